@@ -0,0 +1,116 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[derive(Debug, PartialEq, Eq, uniffi::Record)]
+pub struct CommandDefinition {
+    pub name: String,
+    pub arguments: Vec<CommandArgumentDefinition>,
+}
+
+#[derive(Debug, PartialEq, Eq, uniffi::Record)]
+pub struct CommandArgumentDefinition {
+    pub name: String,
+    pub kind: CommandArgumentKind,
+    pub optional: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum CommandArgumentKind {
+    Word,
+    User,
+    Room,
+    Text,
+}
+
+#[derive(Debug, PartialEq, Eq, uniffi::Record)]
+pub struct ParsedCommand {
+    pub name: String,
+    pub arguments: Vec<ParsedCommandArgument>,
+}
+
+#[derive(Debug, PartialEq, Eq, uniffi::Record)]
+pub struct ParsedCommandArgument {
+    pub name: String,
+    pub value: CommandArgumentValue,
+}
+
+#[derive(Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum CommandArgumentValue {
+    Text {
+        text: String,
+    },
+    User {
+        user_id: String,
+        display_name: String,
+    },
+    Room {
+        room_id_or_alias: String,
+    },
+}
+
+impl From<CommandDefinition> for wysiwyg::CommandDefinition {
+    fn from(command: CommandDefinition) -> Self {
+        Self {
+            name: command.name,
+            arguments: command.arguments.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<CommandArgumentDefinition> for wysiwyg::CommandArgumentDefinition {
+    fn from(argument: CommandArgumentDefinition) -> Self {
+        Self {
+            name: argument.name,
+            kind: match argument.kind {
+                CommandArgumentKind::Word => wysiwyg::CommandArgumentKind::Word,
+                CommandArgumentKind::User => wysiwyg::CommandArgumentKind::User,
+                CommandArgumentKind::Room => wysiwyg::CommandArgumentKind::Room,
+                CommandArgumentKind::Text => wysiwyg::CommandArgumentKind::Text,
+            },
+            optional: argument.optional,
+        }
+    }
+}
+
+impl From<wysiwyg::ParsedCommand> for ParsedCommand {
+    fn from(command: wysiwyg::ParsedCommand) -> Self {
+        Self {
+            name: command.name,
+            arguments: command.arguments.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<wysiwyg::ParsedCommandArgument> for ParsedCommandArgument {
+    fn from(argument: wysiwyg::ParsedCommandArgument) -> Self {
+        Self {
+            name: argument.name,
+            value: match argument.value {
+                wysiwyg::CommandArgumentValue::Text(text) => {
+                    CommandArgumentValue::Text { text }
+                }
+                wysiwyg::CommandArgumentValue::User {
+                    user_id,
+                    display_name,
+                } => CommandArgumentValue::User {
+                    user_id,
+                    display_name,
+                },
+                wysiwyg::CommandArgumentValue::Room { room_id_or_alias } => {
+                    CommandArgumentValue::Room { room_id_or_alias }
+                }
+            },
+        }
+    }
+}
@@ -14,6 +14,7 @@ pub enum ComposerAction {
     Unindent,
     CodeBlock,
     Quote,
+    ClearFormatting,
 }
 
 impl From<&ComposerAction> for wysiwyg::ComposerAction {
@@ -33,6 +34,7 @@ impl From<&ComposerAction> for wysiwyg::ComposerAction {
             ComposerAction::Unindent => Self::Unindent,
             ComposerAction::CodeBlock => Self::CodeBlock,
             ComposerAction::Quote => Self::Quote,
+            ComposerAction::ClearFormatting => Self::ClearFormatting,
         }
     }
 }
@@ -54,6 +56,7 @@ impl From<&wysiwyg::ComposerAction> for ComposerAction {
             wysiwyg::ComposerAction::Unindent => Self::Unindent,
             wysiwyg::ComposerAction::CodeBlock => Self::CodeBlock,
             wysiwyg::ComposerAction::Quote => Self::Quote,
+            wysiwyg::ComposerAction::ClearFormatting => Self::ClearFormatting,
         }
     }
 }
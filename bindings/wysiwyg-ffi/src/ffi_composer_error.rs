@@ -0,0 +1,100 @@
+// Copyright 2023 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, PartialEq, Eq, uniffi::Error)]
+pub enum ComposerError {
+    HtmlParseError { message: String },
+    MarkdownParseError { message: String },
+    MarkdownExportError { message: String },
+    UnknownFormatType { tag: String },
+    InvalidRegex { message: String },
+    InvalidSuggestionPrefix { prefix: String },
+    MissingCommandArgument { name: String },
+    InvalidCommandArgument { name: String },
+    UnexpectedCommandArgument { value: String },
+    Internal { message: String },
+}
+
+impl Display for ComposerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComposerError::HtmlParseError { message }
+            | ComposerError::MarkdownParseError { message }
+            | ComposerError::MarkdownExportError { message }
+            | ComposerError::InvalidRegex { message }
+            | ComposerError::Internal { message } => f.write_str(message),
+            ComposerError::UnknownFormatType { tag } => {
+                write!(f, "unknown format type: {tag}")
+            }
+            ComposerError::InvalidSuggestionPrefix { prefix } => {
+                write!(
+                    f,
+                    "suggestion trigger prefix must be a single character: \
+                     {prefix}"
+                )
+            }
+            ComposerError::MissingCommandArgument { name } => {
+                write!(f, "missing argument: {name}")
+            }
+            ComposerError::InvalidCommandArgument { name } => {
+                write!(f, "invalid argument: {name}")
+            }
+            ComposerError::UnexpectedCommandArgument { value } => {
+                write!(f, "unexpected argument: {value}")
+            }
+        }
+    }
+}
+
+impl From<wysiwyg::ComposerError> for ComposerError {
+    fn from(error: wysiwyg::ComposerError) -> Self {
+        let message = error.to_string();
+        match error {
+            wysiwyg::ComposerError::HtmlParseError(_) => {
+                Self::HtmlParseError { message }
+            }
+            wysiwyg::ComposerError::MarkdownParseError(_) => {
+                Self::MarkdownParseError { message }
+            }
+            wysiwyg::ComposerError::MarkdownExportError(_) => {
+                Self::MarkdownExportError { message }
+            }
+            wysiwyg::ComposerError::UnknownFormatType(tag) => {
+                Self::UnknownFormatType { tag }
+            }
+            wysiwyg::ComposerError::InvalidRegex(_) => {
+                Self::InvalidRegex { message }
+            }
+            wysiwyg::ComposerError::InvalidSuggestionPrefix(prefix) => {
+                Self::InvalidSuggestionPrefix { prefix }
+            }
+            wysiwyg::ComposerError::CommandParseError(error) => match error {
+                wysiwyg::CommandParseError::MissingArgument(name) => {
+                    Self::MissingCommandArgument { name }
+                }
+                wysiwyg::CommandParseError::InvalidArgument(name) => {
+                    Self::InvalidCommandArgument { name }
+                }
+                wysiwyg::CommandParseError::UnexpectedArgument(value) => {
+                    Self::UnexpectedCommandArgument { value }
+                }
+            },
+            wysiwyg::ComposerError::Internal(_) => Self::Internal { message },
+        }
+    }
+}
+
+impl Error for ComposerError {}
@@ -6,11 +6,16 @@ use widestring::Utf16String;
 
 use crate::ffi_composer_state::ComposerState;
 use crate::ffi_composer_update::ComposerUpdate;
-use crate::ffi_dom_creation_error::DomCreationError;
 use crate::ffi_link_actions::LinkAction;
 use crate::ffi_mentions_state::MentionsState;
 use crate::into_ffi::IntoFfi;
-use crate::{ActionState, ComposerAction, SuggestionPattern};
+use crate::{
+    ActionState, CommandDefinition, ComposerAction, ComposerError,
+    ComposerMode, ComposerStats, Direction, DomNodeInfo, FindMatch,
+    FindOptions, Granularity, KeyBinding, KeyEvent, KeymapPreset,
+    MarkdownDialect, MaxLength, ParsedCommand, SuggestionPattern,
+    SuggestionTrigger, TextDirection, TextTransform, TypographyRule,
+};
 
 #[derive(Default, uniffi::Object)]
 pub struct ComposerModel {
@@ -30,7 +35,7 @@ impl ComposerModel {
     pub fn set_content_from_html(
         self: &Arc<Self>,
         html: String,
-    ) -> Result<Arc<ComposerUpdate>, DomCreationError> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let html = Utf16String::from_str(&html);
         let update = self.inner.lock().unwrap().set_content_from_html(&html)?;
         Ok(Arc::new(ComposerUpdate::from(update)))
@@ -39,7 +44,7 @@ impl ComposerModel {
     pub fn set_content_from_markdown(
         self: &Arc<Self>,
         markdown: String,
-    ) -> Result<Arc<ComposerUpdate>, DomCreationError> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let markdown = Utf16String::from_str(&markdown);
         let update = self
             .inner
@@ -59,34 +64,121 @@ impl ComposerModel {
             .set_custom_suggestion_patterns(custom_suggestion_patterns)
     }
 
-    pub fn get_content_as_html(self: &Arc<Self>) -> String {
-        self.inner.lock().unwrap().get_content_as_html().to_string()
+    pub fn set_custom_suggestion_triggers(
+        self: &Arc<Self>,
+        custom_suggestion_triggers: Vec<SuggestionTrigger>,
+    ) -> Result<(), ComposerError> {
+        let triggers = custom_suggestion_triggers
+            .into_iter()
+            .map(wysiwyg::SuggestionTrigger::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.inner
+            .lock()
+            .unwrap()
+            .set_custom_suggestion_triggers(triggers);
+        Ok(())
     }
 
-    pub fn get_content_as_message_html(self: &Arc<Self>) -> String {
+    pub fn set_emoji_suggestions_enabled(self: &Arc<Self>, enabled: bool) {
         self.inner
             .lock()
             .unwrap()
-            .get_content_as_message_html()
-            .to_string()
+            .set_emoji_suggestions_enabled(enabled)
     }
 
-    pub fn get_content_as_markdown(self: &Arc<Self>) -> String {
+    pub fn set_commands(self: &Arc<Self>, commands: Vec<CommandDefinition>) {
         self.inner
             .lock()
             .unwrap()
-            .get_content_as_markdown()
-            .to_string()
+            .set_commands(commands.into_iter().map(Into::into).collect());
+    }
+
+    pub fn is_command(self: &Arc<Self>) -> bool {
+        self.inner.lock().unwrap().is_command()
+    }
+
+    /// Returns None if the content isn't a registered command.
+    pub fn parse_command(
+        self: &Arc<Self>,
+    ) -> Result<Option<ParsedCommand>, ComposerError> {
+        let command = self.inner.lock().unwrap().parse_command()?;
+        Ok(command.map(Into::into))
+    }
+
+    /// Returns None if the key was not consumed and the platform should
+    /// apply its default behaviour.
+    pub fn handle_key(
+        self: &Arc<Self>,
+        event: KeyEvent,
+    ) -> Result<Option<Arc<ComposerUpdate>>, ComposerError> {
+        let Some(event) = event.into_inner() else {
+            return Ok(None);
+        };
+        let update = self.inner.lock().unwrap().handle_key(&event)?;
+        Ok(update.map(|update| Arc::new(ComposerUpdate::from(update))))
+    }
+
+    pub fn set_keymap_preset(self: &Arc<Self>, preset: KeymapPreset) {
+        self.inner
+            .lock()
+            .unwrap()
+            .set_keymap(wysiwyg::Keymap::preset(preset.into()));
+    }
+
+    /// Replace the keymap. Bindings whose key isn't valid are ignored.
+    pub fn set_key_bindings(self: &Arc<Self>, bindings: Vec<KeyBinding>) {
+        let mut keymap = wysiwyg::Keymap::empty();
+        for binding in bindings {
+            if let Some(event) = binding.event.into_inner() {
+                keymap.bind(event, binding.command.into());
+            }
+        }
+        self.inner.lock().unwrap().set_keymap(keymap);
+    }
+
+    pub fn key_bindings(self: &Arc<Self>) -> Vec<KeyBinding> {
+        self.inner
+            .lock()
+            .unwrap()
+            .keymap()
+            .bindings()
+            .map(|(event, command)| KeyBinding {
+                event: event.into(),
+                command: (*command).into(),
+            })
+            .collect()
+    }
+
+    pub fn get_content_as_html(self: &Arc<Self>) -> String {
+        self.inner.lock().unwrap().get_content_as_html().to_string()
     }
 
-    pub fn get_content_as_message_markdown(self: &Arc<Self>) -> String {
+    pub fn get_content_as_message_html(self: &Arc<Self>) -> String {
         self.inner
             .lock()
             .unwrap()
-            .get_content_as_message_markdown()
+            .get_content_as_message_html()
             .to_string()
     }
 
+    pub fn get_content_as_markdown(
+        self: &Arc<Self>,
+    ) -> Result<String, ComposerError> {
+        let markdown = self.inner.lock().unwrap().get_content_as_markdown()?;
+        Ok(markdown.to_string())
+    }
+
+    pub fn get_content_as_message_markdown(
+        self: &Arc<Self>,
+    ) -> Result<String, ComposerError> {
+        let markdown = self
+            .inner
+            .lock()
+            .unwrap()
+            .get_content_as_message_markdown()?;
+        Ok(markdown.to_string())
+    }
+
     pub fn get_content_as_plain_text(self: &Arc<Self>) -> String {
         self.inner
             .lock()
@@ -95,15 +187,70 @@ impl ComposerModel {
             .to_string()
     }
 
-    pub fn clear(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().clear()))
+    pub fn location_to_markdown_offset(
+        self: &Arc<Self>,
+        utf16_codeunit: u32,
+    ) -> Result<u32, ComposerError> {
+        let location =
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap());
+        let offset = self
+            .inner
+            .lock()
+            .unwrap()
+            .location_to_markdown_offset(location)?;
+        Ok(u32::try_from(offset).unwrap())
+    }
+
+    pub fn markdown_offset_to_location(
+        self: &Arc<Self>,
+        offset: u32,
+    ) -> Result<u32, ComposerError> {
+        let offset = usize::try_from(offset).unwrap();
+        let location = self
+            .inner
+            .lock()
+            .unwrap()
+            .markdown_offset_to_location(offset)?;
+        Ok(u32::try_from(usize::from(location)).unwrap())
+    }
+
+    pub fn location_to_plain_text_offset(
+        self: &Arc<Self>,
+        utf16_codeunit: u32,
+    ) -> u32 {
+        let location =
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap());
+        let offset = self
+            .inner
+            .lock()
+            .unwrap()
+            .location_to_plain_text_offset(location);
+        u32::try_from(offset).unwrap()
+    }
+
+    pub fn plain_text_offset_to_location(self: &Arc<Self>, offset: u32) -> u32 {
+        let offset = usize::try_from(offset).unwrap();
+        let location = self
+            .inner
+            .lock()
+            .unwrap()
+            .plain_text_offset_to_location(offset);
+        u32::try_from(usize::from(location)).unwrap()
+    }
+
+    pub fn clear(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().clear()?,
+        )))
     }
 
     pub fn select(
         self: &Arc<Self>,
         start_utf16_codeunit: u32,
         end_utf16_codeunit: u32,
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let start = wysiwyg::Location::from(
             usize::try_from(start_utf16_codeunit).unwrap(),
         );
@@ -111,21 +258,227 @@ impl ComposerModel {
             usize::try_from(end_utf16_codeunit).unwrap(),
         );
 
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().select(start, end),
-        ))
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().select(start, end)?,
+        )))
+    }
+
+    pub fn select_all(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().select_all()?,
+        )))
+    }
+
+    pub fn select_word_at(
+        self: &Arc<Self>,
+        utf16_codeunit: u32,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        let location =
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap());
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().select_word_at(location)?,
+        )))
+    }
+
+    pub fn select_sentence_at(
+        self: &Arc<Self>,
+        utf16_codeunit: u32,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        let location =
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap());
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().select_sentence_at(location)?,
+        )))
+    }
+
+    pub fn select_paragraph_at(
+        self: &Arc<Self>,
+        utf16_codeunit: u32,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        let location =
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap());
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().select_paragraph_at(location)?,
+        )))
+    }
+
+    pub fn move_cursor(
+        self: &Arc<Self>,
+        direction: Direction,
+        granularity: Granularity,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner
+                .lock()
+                .unwrap()
+                .move_cursor(direction.into(), granularity.into())?,
+        )))
+    }
+
+    pub fn extend_selection_by(
+        self: &Arc<Self>,
+        granularity: Granularity,
+        direction: Direction,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner
+                .lock()
+                .unwrap()
+                .extend_selection_by(granularity.into(), direction.into())?,
+        )))
+    }
+
+    pub fn set_typography_rules(self: &Arc<Self>, rules: Vec<TypographyRule>) {
+        self.inner
+            .lock()
+            .unwrap()
+            .set_typography_rules(rules.into_iter().map(Into::into).collect());
+    }
+
+    /// All nodes of the DOM in document order, starting with the root.
+    pub fn dom_nodes(self: &Arc<Self>) -> Vec<DomNodeInfo> {
+        DomNodeInfo::flatten(self.inner.lock().unwrap().dom_tree())
+    }
+
+    /// The deepest node at the given location, in UTF-16 code units. Its descendants follow it in
+    /// [ComposerModel::dom_nodes].
+    pub fn node_at_location(
+        self: &Arc<Self>,
+        location: u32,
+    ) -> Option<DomNodeInfo> {
+        let node = self.inner.lock().unwrap().node_at_location(
+            wysiwyg::Location::from(usize::try_from(location).unwrap()),
+        )?;
+        DomNodeInfo::flatten(node).into_iter().next()
+    }
+
+    pub fn stats(self: &Arc<Self>) -> ComposerStats {
+        ComposerStats::from(self.inner.lock().unwrap().stats())
+    }
+
+    /// Set the markdown syntax used by [ComposerModel::set_content_from_markdown] and
+    /// [ComposerModel::get_content_as_markdown].
+    pub fn set_markdown_dialect(self: &Arc<Self>, dialect: MarkdownDialect) {
+        self.inner
+            .lock()
+            .unwrap()
+            .set_markdown_dialect(dialect.into());
+    }
+
+    pub fn set_max_length(self: &Arc<Self>, max_length: Option<MaxLength>) {
+        self.inner
+            .lock()
+            .unwrap()
+            .set_max_length(max_length.map(Into::into));
+    }
+
+    pub fn find(
+        self: &Arc<Self>,
+        query: String,
+        options: FindOptions,
+    ) -> Result<Vec<FindMatch>, ComposerError> {
+        let matches =
+            self.inner.lock().unwrap().find(&query, &options.into())?;
+        Ok(matches.into_iter().map(FindMatch::from).collect())
+    }
+
+    pub fn replace_match(
+        self: &Arc<Self>,
+        start: u32,
+        end: u32,
+        replacement: String,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        let start = usize::try_from(start).unwrap();
+        let end = usize::try_from(end).unwrap();
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().replace_match(
+                wysiwyg::Location::from(start),
+                wysiwyg::Location::from(end),
+                Utf16String::from_str(&replacement),
+            )?,
+        )))
+    }
+
+    pub fn replace_all(
+        self: &Arc<Self>,
+        query: String,
+        options: FindOptions,
+        replacement: String,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        let update = self.inner.lock().unwrap().replace_all(
+            &query,
+            &options.into(),
+            Utf16String::from_str(&replacement),
+        )?;
+        Ok(Arc::new(ComposerUpdate::from(update)))
+    }
+
+    pub fn clear_formatting(
+        self: &Arc<Self>,
+        include_blocks: bool,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner
+                .lock()
+                .unwrap()
+                .clear_formatting(include_blocks)?,
+        )))
+    }
+
+    pub fn transform_selection(
+        self: &Arc<Self>,
+        transform: TextTransform,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner
+                .lock()
+                .unwrap()
+                .transform_selection(transform.into())?,
+        )))
+    }
+
+    pub fn mode(self: &Arc<Self>) -> ComposerMode {
+        self.inner.lock().unwrap().mode().into()
+    }
+
+    pub fn switch_to_plain(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        let update = self.inner.lock().unwrap().switch_to_plain()?;
+        Ok(Arc::new(ComposerUpdate::from(update)))
+    }
+
+    pub fn switch_to_rich(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        let update = self.inner.lock().unwrap().switch_to_rich()?;
+        Ok(Arc::new(ComposerUpdate::from(update)))
+    }
+
+    pub fn set_text_direction(
+        self: &Arc<Self>,
+        direction: TextDirection,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner
+                .lock()
+                .unwrap()
+                .set_text_direction(direction.into())?,
+        )))
     }
 
     pub fn replace_text(
         self: &Arc<Self>,
         new_text: String,
-    ) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
             self.inner
                 .lock()
                 .unwrap()
-                .replace_text(Utf16String::from_str(&new_text)),
-        ))
+                .replace_text(Utf16String::from_str(&new_text))?,
+        )))
     }
 
     pub fn replace_text_in(
@@ -133,16 +486,16 @@ impl ComposerModel {
         new_text: String,
         start: u32,
         end: u32,
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let start = usize::try_from(start).unwrap();
         let end = usize::try_from(end).unwrap();
-        Arc::new(ComposerUpdate::from(
+        Ok(Arc::new(ComposerUpdate::from(
             self.inner.lock().unwrap().replace_text_in(
                 Utf16String::from_str(&new_text),
                 start,
                 end,
-            ),
-        ))
+            )?,
+        )))
     }
 
     pub fn replace_text_suggestion(
@@ -150,99 +503,169 @@ impl ComposerModel {
         new_text: String,
         suggestion: SuggestionPattern,
         append_space: bool,
-    ) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
             self.inner.lock().unwrap().replace_text_suggestion(
                 Utf16String::from_str(&new_text),
                 wysiwyg::SuggestionPattern::from(suggestion),
                 append_space,
-            ),
-        ))
+            )?,
+        )))
     }
 
-    pub fn backspace(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().backspace()))
+    pub fn backspace(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().backspace()?,
+        )))
     }
 
-    pub fn delete(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().delete()))
+    pub fn delete(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().delete()?,
+        )))
     }
 
     pub fn delete_in(
         self: &Arc<Self>,
         start: u32,
         end: u32,
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let start = usize::try_from(start).unwrap();
         let end = usize::try_from(end).unwrap();
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().delete_in(start, end),
-        ))
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().delete_in(start, end)?,
+        )))
     }
 
-    pub fn enter(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().enter()))
+    pub fn enter(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().enter()?,
+        )))
     }
 
-    pub fn bold(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().bold()))
+    pub fn bold(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().bold()?,
+        )))
     }
 
-    pub fn italic(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().italic()))
+    pub fn italic(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().italic()?,
+        )))
     }
 
-    pub fn strike_through(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().strike_through(),
-        ))
+    pub fn strike_through(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().strike_through()?,
+        )))
     }
 
-    pub fn underline(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().underline()))
+    pub fn underline(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().underline()?,
+        )))
     }
 
-    pub fn inline_code(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().inline_code(),
-        ))
+    pub fn inline_code(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().inline_code()?,
+        )))
     }
 
-    pub fn code_block(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().code_block(),
-        ))
+    pub fn code_block(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().code_block()?,
+        )))
     }
 
-    pub fn quote(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().quote()))
+    pub fn quote(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().quote()?,
+        )))
+    }
+
+    pub fn move_block_up(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().move_block_up()?,
+        )))
+    }
+
+    pub fn move_block_down(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().move_block_down()?,
+        )))
     }
 
-    pub fn ordered_list(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().ordered_list(),
-        ))
+    pub fn duplicate_block(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().duplicate_block()?,
+        )))
     }
 
-    pub fn unordered_list(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().unordered_list(),
-        ))
+    pub fn ordered_list(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().ordered_list()?,
+        )))
     }
 
-    pub fn undo(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().undo()))
+    pub fn unordered_list(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().unordered_list()?,
+        )))
     }
 
-    pub fn redo(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().redo()))
+    pub fn undo(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().undo()?,
+        )))
+    }
+
+    pub fn redo(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().redo()?,
+        )))
     }
 
     pub fn set_link(
         self: &Arc<Self>,
         url: String,
         attributes: Vec<Attribute>,
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let url = Utf16String::from_str(&url);
         let attrs = attributes
             .iter()
@@ -253,9 +676,9 @@ impl ComposerModel {
                 )
             })
             .collect();
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().set_link(url, attrs),
-        ))
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().set_link(url, attrs)?,
+        )))
     }
 
     pub fn set_link_with_text(
@@ -263,7 +686,7 @@ impl ComposerModel {
         url: String,
         text: String,
         attributes: Vec<Attribute>,
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let url = Utf16String::from_str(&url);
         let text = Utf16String::from_str(&html_escape::encode_safe(&text));
         let attrs = attributes
@@ -275,19 +698,21 @@ impl ComposerModel {
                 )
             })
             .collect();
-        Arc::new(ComposerUpdate::from(
+        Ok(Arc::new(ComposerUpdate::from(
             self.inner
                 .lock()
                 .unwrap()
-                .set_link_with_text(url, text, attrs),
-        ))
+                .set_link_with_text(url, text, attrs)?,
+        )))
     }
 
     /// Creates an at-room mention node and inserts it into the composer at the current selection
-    pub fn insert_at_room_mention(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().insert_at_room_mention(vec![]),
-        ))
+    pub fn insert_at_room_mention(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().insert_at_room_mention(vec![])?,
+        )))
     }
 
     /// Creates a mention node and inserts it into the composer at the current selection
@@ -296,13 +721,16 @@ impl ComposerModel {
         url: String,
         text: String,
         _attributes: Vec<Attribute>, // TODO remove attributes
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let url = Utf16String::from_str(&url);
         let text = Utf16String::from_str(&html_escape::encode_safe(&text));
         let attrs = vec![];
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().insert_mention(url, text, attrs),
-        ))
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner
+                .lock()
+                .unwrap()
+                .insert_mention(url, text, attrs)?,
+        )))
     }
 
     /// Creates an at-room mention node and inserts it into the composer, replacing the
@@ -310,15 +738,15 @@ impl ComposerModel {
     pub fn insert_at_room_mention_at_suggestion(
         self: &Arc<Self>,
         suggestion: SuggestionPattern,
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let suggestion = wysiwyg::SuggestionPattern::from(suggestion);
         let attrs = vec![];
-        Arc::new(ComposerUpdate::from(
+        Ok(Arc::new(ComposerUpdate::from(
             self.inner
                 .lock()
                 .unwrap()
-                .insert_at_room_mention_at_suggestion(suggestion, attrs),
-        ))
+                .insert_at_room_mention_at_suggestion(suggestion, attrs)?,
+        )))
     }
 
     /// Creates a mention node and inserts it into the composer, replacing the
@@ -329,31 +757,41 @@ impl ComposerModel {
         text: String,
         suggestion: SuggestionPattern,
         _attributes: Vec<Attribute>, // TODO remove attributes
-    ) -> Arc<ComposerUpdate> {
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
         let url = Utf16String::from_str(&url);
         let text = Utf16String::from_str(&html_escape::encode_safe(&text));
         let suggestion = wysiwyg::SuggestionPattern::from(suggestion);
         let attrs = vec![];
-        Arc::new(ComposerUpdate::from(
+        Ok(Arc::new(ComposerUpdate::from(
             self.inner
                 .lock()
                 .unwrap()
-                .insert_mention_at_suggestion(url, text, suggestion, attrs),
-        ))
+                .insert_mention_at_suggestion(url, text, suggestion, attrs)?,
+        )))
     }
 
-    pub fn remove_links(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(
-            self.inner.lock().unwrap().remove_links(),
-        ))
+    pub fn remove_links(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().remove_links()?,
+        )))
     }
 
-    pub fn indent(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().indent()))
+    pub fn indent(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().indent()?,
+        )))
     }
 
-    pub fn unindent(self: &Arc<Self>) -> Arc<ComposerUpdate> {
-        Arc::new(ComposerUpdate::from(self.inner.lock().unwrap().unindent()))
+    pub fn unindent(
+        self: &Arc<Self>,
+    ) -> Result<Arc<ComposerUpdate>, ComposerError> {
+        Ok(Arc::new(ComposerUpdate::from(
+            self.inner.lock().unwrap().unindent()?,
+        )))
     }
 
     pub fn to_example_format(self: &Arc<Self>) -> String {
@@ -386,12 +824,6 @@ impl ComposerModel {
     pub fn get_mentions_state(self: &Arc<Self>) -> MentionsState {
         self.inner.lock().unwrap().get_mentions_state().into()
     }
-
-    /// Force a panic for test purposes
-    pub fn debug_panic(self: &Arc<Self>) {
-        #[cfg(debug_assertions)]
-        panic!("This should only happen in tests.");
-    }
 }
 
 #[derive(uniffi::Record)]
@@ -3,7 +3,7 @@ use widestring::Utf16String;
 use crate::ffi_link_actions::LinkActionUpdate;
 use crate::ffi_menu_state::MenuState;
 use crate::ffi_text_update::TextUpdate;
-use crate::MenuAction;
+use crate::{MaxLengthExceeded, MenuAction};
 
 #[derive(uniffi::Object)]
 pub struct ComposerUpdate {
@@ -33,6 +33,10 @@ impl ComposerUpdate {
     pub fn link_action(&self) -> LinkActionUpdate {
         LinkActionUpdate::from(self.inner.link_action.clone())
     }
+
+    pub fn max_length_exceeded(&self) -> Option<MaxLengthExceeded> {
+        self.inner.max_length_exceeded.map(MaxLengthExceeded::from)
+    }
 }
 
 #[cfg(test)]
@@ -40,8 +44,9 @@ mod test {
     use std::{collections::HashMap, sync::Arc};
 
     use crate::{
-        ActionState, ComposerAction, ComposerModel, MenuAction, MenuState,
-        SuggestionPattern,
+        ActionState, ComposerAction, ComposerModel, DomNodeKind, Key, KeyEvent,
+        KeymapPreset, MaxLength, MaxLengthBehaviour, MaxLengthExceeded,
+        MenuAction, MenuState, SuggestionPattern, SuggestionTrigger,
     };
 
     #[test]
@@ -51,7 +56,7 @@ mod test {
 
         // Only Redo is disabled
         assert_eq!(
-            update.menu_state(),
+            update.unwrap().menu_state(),
             MenuState::Update {
                 action_states: redo_indent_unindent_disabled()
             }
@@ -75,9 +80,9 @@ mod test {
     #[test]
     fn after_later_set_content_from_html_menu_is_updated() {
         let model = Arc::new(ComposerModel::new());
-        model.replace_text(String::from("foo"));
-        model.replace_text(String::from("bar"));
-        model.undo();
+        model.replace_text(String::from("foo")).unwrap();
+        model.replace_text(String::from("bar")).unwrap();
+        model.undo().unwrap();
         let update = model.set_content_from_html(String::from("")).unwrap();
 
         // Undo and Redo are disabled
@@ -117,7 +122,7 @@ mod test {
         let update = model.replace_text("@alic".into());
 
         assert_eq!(
-            update.menu_action(),
+            update.unwrap().menu_action(),
             MenuAction::Suggestion {
                 suggestion_pattern: SuggestionPattern {
                     key: crate::PatternKey::At,
@@ -136,7 +141,7 @@ mod test {
         let update = model.replace_text("That's great! :)".into());
 
         assert_eq!(
-            update.menu_action(),
+            update.unwrap().menu_action(),
             MenuAction::Suggestion {
                 suggestion_pattern: SuggestionPattern {
                     key: crate::PatternKey::Custom(":)".into()),
@@ -148,6 +153,112 @@ mod test {
         )
     }
 
+    #[test]
+    fn menu_action_is_updated_for_custom_suggestion_trigger() {
+        let model = Arc::new(ComposerModel::new());
+        model
+            .set_custom_suggestion_triggers(vec![SuggestionTrigger {
+                prefix: ":".into(),
+                regex: Some("[a-z_]+".into()),
+                min_length: 0,
+                max_length: None,
+            }])
+            .unwrap();
+        let update = model.replace_text("Nice :smi".into());
+
+        assert_eq!(
+            update.unwrap().menu_action(),
+            MenuAction::Suggestion {
+                suggestion_pattern: SuggestionPattern {
+                    key: crate::PatternKey::Custom(":".into()),
+                    text: "smi".into(),
+                    start: 5,
+                    end: 9,
+                }
+            },
+        )
+    }
+
+    #[test]
+    fn invalid_custom_suggestion_trigger_is_rejected() {
+        let model = Arc::new(ComposerModel::new());
+        let result =
+            model.set_custom_suggestion_triggers(vec![SuggestionTrigger {
+                prefix: "::".into(),
+                regex: None,
+                min_length: 0,
+                max_length: None,
+            }]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn handle_key_reports_whether_the_key_was_consumed() {
+        let model = Arc::new(ComposerModel::new());
+        model.set_keymap_preset(KeymapPreset::Apple);
+        model.replace_text("foo".into()).unwrap();
+        let cmd_z = KeyEvent {
+            key: Key::Character {
+                character: "z".into(),
+            },
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: true,
+        };
+        let ctrl_z = KeyEvent {
+            ctrl: true,
+            meta: false,
+            ..cmd_z.clone()
+        };
+
+        assert!(model.handle_key(ctrl_z).unwrap().is_none());
+        assert!(model.handle_key(cmd_z).unwrap().is_some());
+        assert_eq!(model.get_content_as_html(), "");
+    }
+
+    #[test]
+    fn replace_text_reports_truncated_input() {
+        let model = Arc::new(ComposerModel::new());
+        model.set_max_length(Some(MaxLength {
+            characters: 3,
+            behaviour: MaxLengthBehaviour::Truncate,
+        }));
+        let update = model.replace_text("abcd".into());
+
+        assert_eq!(
+            update.unwrap().max_length_exceeded(),
+            Some(MaxLengthExceeded::Truncated)
+        );
+        assert_eq!(model.get_content_as_html(), "abc");
+        assert_eq!(model.stats().characters, 3);
+    }
+
+    #[test]
+    fn dom_nodes_are_listed_in_document_order() {
+        let model = Arc::new(ComposerModel::new());
+        model
+            .set_content_from_html("<p>a</p><ul><li>b</li></ul>".into())
+            .unwrap();
+
+        let kinds: Vec<_> = model.dom_nodes().iter().map(|n| n.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DomNodeKind::Generic,
+                DomNodeKind::Paragraph,
+                DomNodeKind::Text,
+                DomNodeKind::UnorderedList,
+                DomNodeKind::ListItem,
+                DomNodeKind::Text,
+            ]
+        );
+        let node = model.node_at_location(2).unwrap();
+        assert_eq!(node.handle, vec![1, 0, 0]);
+        assert_eq!(node.text.as_deref(), Some("b"));
+    }
+
     #[test]
     fn test_replace_whole_suggestion_with_mention_ffi() {
         let mut model = Arc::new(ComposerModel::new());
@@ -163,7 +274,7 @@ mod test {
     #[test]
     fn test_replace_end_of_text_node_with_mention_ffi() {
         let mut model = Arc::new(ComposerModel::new());
-        model.replace_text("hello ".into());
+        model.replace_text("hello ".into()).unwrap();
 
         insert_mention_at_cursor(&mut model);
 
@@ -176,8 +287,8 @@ mod test {
     #[test]
     fn test_replace_start_of_text_node_with_mention_ffi() {
         let mut model = Arc::new(ComposerModel::new());
-        model.replace_text(" says hello".into());
-        model.select(0, 0);
+        model.replace_text(" says hello".into()).unwrap();
+        model.select(0, 0).unwrap();
 
         insert_mention_at_cursor(&mut model);
 
@@ -190,8 +301,8 @@ mod test {
     #[test]
     fn test_replace_text_in_middle_of_node_with_mention_ffi() {
         let mut model = Arc::new(ComposerModel::new());
-        model.replace_text("Like  said".into());
-        model.select(5, 5); // "Like | said"
+        model.replace_text("Like  said".into()).unwrap();
+        model.select(5, 5).unwrap(); // "Like | said"
 
         insert_mention_at_cursor(&mut model);
 
@@ -204,8 +315,8 @@ mod test {
     #[test]
     fn test_replace_text_in_second_paragraph_node_with_mention_ffi() {
         let mut model = Arc::new(ComposerModel::new());
-        model.replace_text("hello".into());
-        model.enter();
+        model.replace_text("hello".into()).unwrap();
+        model.enter().unwrap();
         insert_mention_at_cursor(&mut model);
 
         assert_eq!(
@@ -218,9 +329,9 @@ mod test {
     fn test_replace_text_in_second_list_item_start_with_mention_ffi() {
         let mut model = Arc::new(ComposerModel::new());
 
-        model.ordered_list();
-        model.replace_text("hello".into());
-        model.enter();
+        model.ordered_list().unwrap();
+        model.replace_text("hello".into()).unwrap();
+        model.enter().unwrap();
 
         insert_mention_at_cursor(&mut model);
 
@@ -233,10 +344,10 @@ mod test {
     #[test]
     fn test_replace_text_in_second_list_item_end_with_mention_ffi() {
         let mut model = Arc::new(ComposerModel::new());
-        model.ordered_list();
-        model.replace_text("hello".into());
-        model.enter();
-        model.replace_text("there ".into());
+        model.ordered_list().unwrap();
+        model.replace_text("hello".into()).unwrap();
+        model.enter().unwrap();
+        model.replace_text("there ".into()).unwrap();
 
         insert_mention_at_cursor(&mut model);
 
@@ -249,20 +360,22 @@ mod test {
     #[test]
     fn test_replace_text_with_escaped_html_in_mention_ffi() {
         let model = Arc::new(ComposerModel::new());
-        model.replace_text("hello ".into());
+        model.replace_text("hello ".into()).unwrap();
 
         let update = model.replace_text("@alic".into());
         let MenuAction::Suggestion { suggestion_pattern } =
-            update.menu_action()
+            update.unwrap().menu_action()
         else {
             panic!("No suggestion pattern found")
         };
-        model.insert_mention_at_suggestion(
-            "https://matrix.to/#/@alice:matrix.org".into(),
-            ":D</a> a broken mention!".into(),
-            suggestion_pattern,
-            vec![], // TODO remove argument when function signature changes
-        );
+        model
+            .insert_mention_at_suggestion(
+                "https://matrix.to/#/@alice:matrix.org".into(),
+                ":D</a> a broken mention!".into(),
+                suggestion_pattern,
+                vec![], // TODO remove argument when function signature changes
+            )
+            .unwrap();
 
         assert_eq!(
             model.get_content_as_html(),
@@ -275,16 +388,18 @@ mod test {
     fn insert_mention_at_cursor(model: &mut Arc<ComposerModel>) {
         let update = model.replace_text("@alic".into());
         let MenuAction::Suggestion { suggestion_pattern } =
-            update.menu_action()
+            update.unwrap().menu_action()
         else {
             panic!("No suggestion pattern found")
         };
-        model.insert_mention_at_suggestion(
-            "https://matrix.to/#/@alice:matrix.org".into(),
-            "Alice".into(),
-            suggestion_pattern,
-            vec![], // TODO remove argument when function signature changes
-        );
+        model
+            .insert_mention_at_suggestion(
+                "https://matrix.to/#/@alice:matrix.org".into(),
+                "Alice".into(),
+                suggestion_pattern,
+                vec![], // TODO remove argument when function signature changes
+            )
+            .unwrap();
     }
 
     fn redo_indent_unindent_disabled() -> HashMap<ComposerAction, ActionState> {
@@ -303,6 +418,7 @@ mod test {
             (ComposerAction::UnorderedList, ActionState::Enabled),
             (ComposerAction::CodeBlock, ActionState::Enabled),
             (ComposerAction::Quote, ActionState::Enabled),
+            (ComposerAction::ClearFormatting, ActionState::Disabled),
         ])
     }
 
@@ -323,6 +439,7 @@ mod test {
             (ComposerAction::UnorderedList, ActionState::Enabled),
             (ComposerAction::CodeBlock, ActionState::Enabled),
             (ComposerAction::Quote, ActionState::Enabled),
+            (ComposerAction::ClearFormatting, ActionState::Disabled),
         ])
     }
 }
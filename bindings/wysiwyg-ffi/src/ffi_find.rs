@@ -0,0 +1,45 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, uniffi::Record)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+impl From<FindOptions> for wysiwyg::FindOptions {
+    fn from(options: FindOptions) -> Self {
+        Self {
+            case_sensitive: options.case_sensitive,
+            whole_word: options.whole_word,
+            regex: options.regex,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct FindMatch {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl From<(wysiwyg::Location, wysiwyg::Location)> for FindMatch {
+    fn from((start, end): (wysiwyg::Location, wysiwyg::Location)) -> Self {
+        Self {
+            start: u32::try_from(usize::from(start)).unwrap(),
+            end: u32::try_from(usize::from(end)).unwrap(),
+        }
+    }
+}
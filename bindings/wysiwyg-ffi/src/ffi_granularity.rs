@@ -0,0 +1,48 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum Granularity {
+    Character,
+    Word,
+    Sentence,
+    Paragraph,
+    Document,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum Direction {
+    Forwards,
+    Backwards,
+}
+
+impl From<Granularity> for wysiwyg::Granularity {
+    fn from(granularity: Granularity) -> Self {
+        match granularity {
+            Granularity::Character => Self::Character,
+            Granularity::Word => Self::Word,
+            Granularity::Sentence => Self::Sentence,
+            Granularity::Paragraph => Self::Paragraph,
+            Granularity::Document => Self::Document,
+        }
+    }
+}
+
+impl From<Direction> for wysiwyg::Direction {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Forwards => Self::Forwards,
+            Direction::Backwards => Self::Backwards,
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum Key {
+    /// A single character. Events with any other text are ignored.
+    Character {
+        character: String,
+    },
+    Backspace,
+    Delete,
+    Enter,
+    Tab,
+    Escape,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+    Home,
+    End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum KeyCommand {
+    Bold,
+    Italic,
+    StrikeThrough,
+    Underline,
+    InlineCode,
+    Undo,
+    Redo,
+    OrderedList,
+    UnorderedList,
+    Indent,
+    Unindent,
+    CodeBlock,
+    Quote,
+    Backspace,
+    Delete,
+    BackspaceWord,
+    DeleteWord,
+    Enter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum KeymapPreset {
+    Standard,
+    Apple,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct KeyBinding {
+    pub event: KeyEvent,
+    pub command: KeyCommand,
+}
+
+impl KeyEvent {
+    pub(crate) fn into_inner(self) -> Option<wysiwyg::KeyEvent> {
+        let key = match self.key {
+            Key::Character { character } => {
+                let mut chars = character.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    return None;
+                };
+                wysiwyg::Key::Character(c)
+            }
+            Key::Backspace => wysiwyg::Key::Backspace,
+            Key::Delete => wysiwyg::Key::Delete,
+            Key::Enter => wysiwyg::Key::Enter,
+            Key::Tab => wysiwyg::Key::Tab,
+            Key::Escape => wysiwyg::Key::Escape,
+            Key::ArrowLeft => wysiwyg::Key::ArrowLeft,
+            Key::ArrowRight => wysiwyg::Key::ArrowRight,
+            Key::ArrowUp => wysiwyg::Key::ArrowUp,
+            Key::ArrowDown => wysiwyg::Key::ArrowDown,
+            Key::Home => wysiwyg::Key::Home,
+            Key::End => wysiwyg::Key::End,
+        };
+        Some(wysiwyg::KeyEvent::new(
+            key,
+            wysiwyg::KeyModifiers {
+                ctrl: self.ctrl,
+                alt: self.alt,
+                shift: self.shift,
+                meta: self.meta,
+            },
+        ))
+    }
+}
+
+impl From<&wysiwyg::KeyEvent> for KeyEvent {
+    fn from(event: &wysiwyg::KeyEvent) -> Self {
+        let key = match event.key {
+            wysiwyg::Key::Character(c) => Key::Character {
+                character: c.to_string(),
+            },
+            wysiwyg::Key::Backspace => Key::Backspace,
+            wysiwyg::Key::Delete => Key::Delete,
+            wysiwyg::Key::Enter => Key::Enter,
+            wysiwyg::Key::Tab => Key::Tab,
+            wysiwyg::Key::Escape => Key::Escape,
+            wysiwyg::Key::ArrowLeft => Key::ArrowLeft,
+            wysiwyg::Key::ArrowRight => Key::ArrowRight,
+            wysiwyg::Key::ArrowUp => Key::ArrowUp,
+            wysiwyg::Key::ArrowDown => Key::ArrowDown,
+            wysiwyg::Key::Home => Key::Home,
+            wysiwyg::Key::End => Key::End,
+        };
+        Self {
+            key,
+            ctrl: event.modifiers.ctrl,
+            alt: event.modifiers.alt,
+            shift: event.modifiers.shift,
+            meta: event.modifiers.meta,
+        }
+    }
+}
+
+impl From<KeyCommand> for wysiwyg::KeyCommand {
+    fn from(command: KeyCommand) -> Self {
+        match command {
+            KeyCommand::Bold => Self::Bold,
+            KeyCommand::Italic => Self::Italic,
+            KeyCommand::StrikeThrough => Self::StrikeThrough,
+            KeyCommand::Underline => Self::Underline,
+            KeyCommand::InlineCode => Self::InlineCode,
+            KeyCommand::Undo => Self::Undo,
+            KeyCommand::Redo => Self::Redo,
+            KeyCommand::OrderedList => Self::OrderedList,
+            KeyCommand::UnorderedList => Self::UnorderedList,
+            KeyCommand::Indent => Self::Indent,
+            KeyCommand::Unindent => Self::Unindent,
+            KeyCommand::CodeBlock => Self::CodeBlock,
+            KeyCommand::Quote => Self::Quote,
+            KeyCommand::Backspace => Self::Backspace,
+            KeyCommand::Delete => Self::Delete,
+            KeyCommand::BackspaceWord => Self::BackspaceWord,
+            KeyCommand::DeleteWord => Self::DeleteWord,
+            KeyCommand::Enter => Self::Enter,
+        }
+    }
+}
+
+impl From<wysiwyg::KeyCommand> for KeyCommand {
+    fn from(command: wysiwyg::KeyCommand) -> Self {
+        match command {
+            wysiwyg::KeyCommand::Bold => Self::Bold,
+            wysiwyg::KeyCommand::Italic => Self::Italic,
+            wysiwyg::KeyCommand::StrikeThrough => Self::StrikeThrough,
+            wysiwyg::KeyCommand::Underline => Self::Underline,
+            wysiwyg::KeyCommand::InlineCode => Self::InlineCode,
+            wysiwyg::KeyCommand::Undo => Self::Undo,
+            wysiwyg::KeyCommand::Redo => Self::Redo,
+            wysiwyg::KeyCommand::OrderedList => Self::OrderedList,
+            wysiwyg::KeyCommand::UnorderedList => Self::UnorderedList,
+            wysiwyg::KeyCommand::Indent => Self::Indent,
+            wysiwyg::KeyCommand::Unindent => Self::Unindent,
+            wysiwyg::KeyCommand::CodeBlock => Self::CodeBlock,
+            wysiwyg::KeyCommand::Quote => Self::Quote,
+            wysiwyg::KeyCommand::Backspace => Self::Backspace,
+            wysiwyg::KeyCommand::Delete => Self::Delete,
+            wysiwyg::KeyCommand::BackspaceWord => Self::BackspaceWord,
+            wysiwyg::KeyCommand::DeleteWord => Self::DeleteWord,
+            wysiwyg::KeyCommand::Enter => Self::Enter,
+        }
+    }
+}
+
+impl From<KeymapPreset> for wysiwyg::KeymapPreset {
+    fn from(preset: KeymapPreset) -> Self {
+        match preset {
+            KeymapPreset::Standard => Self::Standard,
+            KeymapPreset::Apple => Self::Apple,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct MarkdownDialect {
+    pub flavor: MarkdownFlavor,
+    pub emphasis: EmphasisMarker,
+    pub bullet: BulletMarker,
+    pub fence: FenceStyle,
+    pub hard_break: HardBreakStyle,
+    pub soft_break: SoftBreakPolicy,
+    pub gfm_extensions: bool,
+}
+
+impl From<MarkdownDialect> for wysiwyg::MarkdownDialect {
+    fn from(dialect: MarkdownDialect) -> Self {
+        Self {
+            flavor: dialect.flavor.into(),
+            emphasis: dialect.emphasis.into(),
+            bullet: dialect.bullet.into(),
+            fence: dialect.fence.into(),
+            hard_break: dialect.hard_break.into(),
+            soft_break: dialect.soft_break.into(),
+            gfm_extensions: dialect.gfm_extensions,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum MarkdownFlavor {
+    CommonMark,
+    Gfm,
+}
+
+impl From<MarkdownFlavor> for wysiwyg::MarkdownFlavor {
+    fn from(flavor: MarkdownFlavor) -> Self {
+        match flavor {
+            MarkdownFlavor::CommonMark => Self::CommonMark,
+            MarkdownFlavor::Gfm => Self::Gfm,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum EmphasisMarker {
+    Asterisk,
+    Underscore,
+}
+
+impl From<EmphasisMarker> for wysiwyg::EmphasisMarker {
+    fn from(marker: EmphasisMarker) -> Self {
+        match marker {
+            EmphasisMarker::Asterisk => Self::Asterisk,
+            EmphasisMarker::Underscore => Self::Underscore,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum BulletMarker {
+    Asterisk,
+    Dash,
+    Plus,
+}
+
+impl From<BulletMarker> for wysiwyg::BulletMarker {
+    fn from(marker: BulletMarker) -> Self {
+        match marker {
+            BulletMarker::Asterisk => Self::Asterisk,
+            BulletMarker::Dash => Self::Dash,
+            BulletMarker::Plus => Self::Plus,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum FenceStyle {
+    Backticks,
+    Tildes,
+}
+
+impl From<FenceStyle> for wysiwyg::FenceStyle {
+    fn from(style: FenceStyle) -> Self {
+        match style {
+            FenceStyle::Backticks => Self::Backticks,
+            FenceStyle::Tildes => Self::Tildes,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum HardBreakStyle {
+    Backslash,
+    TrailingSpaces,
+}
+
+impl From<HardBreakStyle> for wysiwyg::HardBreakStyle {
+    fn from(style: HardBreakStyle) -> Self {
+        match style {
+            HardBreakStyle::Backslash => Self::Backslash,
+            HardBreakStyle::TrailingSpaces => Self::TrailingSpaces,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum SoftBreakPolicy {
+    LineBreak,
+    Space,
+}
+
+impl From<SoftBreakPolicy> for wysiwyg::SoftBreakPolicy {
+    fn from(policy: SoftBreakPolicy) -> Self {
+        match policy {
+            SoftBreakPolicy::LineBreak => Self::LineBreak,
+            SoftBreakPolicy::Space => Self::Space,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct ComposerStats {
+    pub characters: u32,
+    pub graphemes: u32,
+    pub words: u32,
+    pub body_bytes: u32,
+    pub formatted_body_bytes: u32,
+}
+
+impl From<wysiwyg::ComposerStats> for ComposerStats {
+    fn from(stats: wysiwyg::ComposerStats) -> Self {
+        let to_u32 = |n: usize| u32::try_from(n).unwrap();
+        Self {
+            characters: to_u32(stats.characters),
+            graphemes: to_u32(stats.graphemes),
+            words: to_u32(stats.words),
+            body_bytes: to_u32(stats.body_bytes),
+            formatted_body_bytes: to_u32(stats.formatted_body_bytes),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct MaxLength {
+    pub characters: u32,
+    pub behaviour: MaxLengthBehaviour,
+}
+
+impl From<MaxLength> for wysiwyg::MaxLength {
+    fn from(max_length: MaxLength) -> Self {
+        Self {
+            characters: usize::try_from(max_length.characters).unwrap(),
+            behaviour: max_length.behaviour.into(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum MaxLengthBehaviour {
+    Truncate,
+    Reject,
+}
+
+impl From<MaxLengthBehaviour> for wysiwyg::MaxLengthBehaviour {
+    fn from(behaviour: MaxLengthBehaviour) -> Self {
+        match behaviour {
+            MaxLengthBehaviour::Truncate => Self::Truncate,
+            MaxLengthBehaviour::Reject => Self::Reject,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum MaxLengthExceeded {
+    Truncated,
+    Rejected,
+}
+
+impl From<wysiwyg::MaxLengthExceeded> for MaxLengthExceeded {
+    fn from(exceeded: wysiwyg::MaxLengthExceeded) -> Self {
+        match exceeded {
+            wysiwyg::MaxLengthExceeded::Truncated => Self::Truncated,
+            wysiwyg::MaxLengthExceeded::Rejected => Self::Rejected,
+        }
+    }
+}
@@ -21,6 +21,25 @@ pub enum MenuAction {
     Suggestion {
         suggestion_pattern: SuggestionPattern,
     },
+    EmojiSuggestion {
+        suggestion_pattern: SuggestionPattern,
+        candidates: Vec<EmojiCandidate>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, uniffi::Record)]
+pub struct EmojiCandidate {
+    pub shortcode: String,
+    pub emoji: String,
+}
+
+impl From<wysiwyg::EmojiCandidate> for EmojiCandidate {
+    fn from(inner: wysiwyg::EmojiCandidate) -> Self {
+        Self {
+            shortcode: inner.shortcode,
+            emoji: inner.emoji,
+        }
+    }
 }
 
 impl MenuAction {
@@ -35,6 +54,18 @@ impl MenuAction {
                     ),
                 }
             }
+            wysiwyg::MenuAction::EmojiSuggestion(suggestion) => {
+                Self::EmojiSuggestion {
+                    suggestion_pattern: SuggestionPattern::from(
+                        suggestion.suggestion_pattern,
+                    ),
+                    candidates: suggestion
+                        .candidates
+                        .into_iter()
+                        .map(EmojiCandidate::from)
+                        .collect(),
+                }
+            }
         }
     }
 }
@@ -0,0 +1,104 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::Attribute;
+
+/// A node of the composer's DOM. Nodes are listed in document order, so a
+/// node's descendants directly follow it; its `handle` is the path of child
+/// indices leading to it from the root.
+#[derive(uniffi::Record)]
+pub struct DomNodeInfo {
+    pub handle: Vec<u32>,
+    pub kind: DomNodeKind,
+    pub attributes: Vec<Attribute>,
+    pub text: Option<String>,
+    pub start: u32,
+    pub end: u32,
+    pub child_count: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum DomNodeKind {
+    Generic,
+    Text,
+    LineBreak,
+    Mention,
+    Bold,
+    Italic,
+    StrikeThrough,
+    Underline,
+    InlineCode,
+    Link,
+    OrderedList,
+    UnorderedList,
+    ListItem,
+    CodeBlock,
+    Quote,
+    Paragraph,
+}
+
+impl DomNodeInfo {
+    /// Flatten `node` and its descendants into a list in document order.
+    pub fn flatten(node: wysiwyg::NodeInfo) -> Vec<Self> {
+        let mut nodes = Vec::new();
+        Self::push_flattened(node, &mut nodes);
+        nodes
+    }
+
+    fn push_flattened(node: wysiwyg::NodeInfo, nodes: &mut Vec<Self>) {
+        let to_u32 = |n: usize| u32::try_from(n).unwrap();
+        nodes.push(Self {
+            handle: node.handle.raw().iter().map(|&i| to_u32(i)).collect(),
+            kind: DomNodeKind::from(node.kind),
+            attributes: node
+                .attributes
+                .into_iter()
+                .map(|(key, value)| Attribute { key, value })
+                .collect(),
+            text: node.text,
+            start: to_u32(node.start.into()),
+            end: to_u32(node.end.into()),
+            child_count: to_u32(node.children.len()),
+        });
+        for child in node.children {
+            Self::push_flattened(child, nodes);
+        }
+    }
+}
+
+impl From<wysiwyg::NodeKind> for DomNodeKind {
+    fn from(kind: wysiwyg::NodeKind) -> Self {
+        use wysiwyg::{InlineFormatType, ListType, NodeKind};
+        match kind {
+            NodeKind::Generic => Self::Generic,
+            NodeKind::Text => Self::Text,
+            NodeKind::LineBreak => Self::LineBreak,
+            NodeKind::Mention => Self::Mention,
+            NodeKind::Formatting(format) => match format {
+                InlineFormatType::Bold => Self::Bold,
+                InlineFormatType::Italic => Self::Italic,
+                InlineFormatType::StrikeThrough => Self::StrikeThrough,
+                InlineFormatType::Underline => Self::Underline,
+                InlineFormatType::InlineCode => Self::InlineCode,
+            },
+            NodeKind::Link => Self::Link,
+            NodeKind::List(ListType::Ordered) => Self::OrderedList,
+            NodeKind::List(ListType::Unordered) => Self::UnorderedList,
+            NodeKind::ListItem => Self::ListItem,
+            NodeKind::CodeBlock => Self::CodeBlock,
+            NodeKind::Quote => Self::Quote,
+            NodeKind::Paragraph => Self::Paragraph,
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::ComposerError;
+
+/// A prefix-based suggestion trigger. If `regex` is set the query typed
+/// after the prefix must match it, otherwise its length must be between
+/// `min_length` and `max_length`.
+#[derive(Debug, PartialEq, Eq, uniffi::Record)]
+pub struct SuggestionTrigger {
+    pub prefix: String,
+    pub regex: Option<String>,
+    pub min_length: u32,
+    pub max_length: Option<u32>,
+}
+
+impl TryFrom<SuggestionTrigger> for wysiwyg::SuggestionTrigger {
+    type Error = ComposerError;
+
+    fn try_from(trigger: SuggestionTrigger) -> Result<Self, Self::Error> {
+        let prefix = Self::parse_prefix(&trigger.prefix)?;
+        Ok(match trigger.regex {
+            Some(regex) => Self::with_regex(prefix, &regex)?,
+            None => Self::with_length(
+                prefix,
+                usize::try_from(trigger.min_length).unwrap(),
+                trigger.max_length.map(|max| usize::try_from(max).unwrap()),
+            ),
+        })
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum TextDirection {
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+impl From<TextDirection> for wysiwyg::TextDirection {
+    fn from(direction: TextDirection) -> Self {
+        match direction {
+            TextDirection::Auto => Self::Auto,
+            TextDirection::Ltr => Self::Ltr,
+            TextDirection::Rtl => Self::Rtl,
+        }
+    }
+}
@@ -15,38 +15,85 @@
 uniffi_macros::include_scaffolding!("wysiwyg_composer");
 
 mod ffi_action_state;
+mod ffi_command;
 mod ffi_composer_action;
+mod ffi_composer_error;
+mod ffi_composer_mode;
 mod ffi_composer_model;
 mod ffi_composer_state;
 mod ffi_composer_update;
-mod ffi_dom_creation_error;
+mod ffi_find;
+mod ffi_granularity;
+mod ffi_keymap;
 mod ffi_link_actions;
+mod ffi_markdown_dialect;
+mod ffi_max_length;
 mod ffi_mention_detector;
 mod ffi_mentions_state;
 mod ffi_menu_action;
 mod ffi_menu_state;
+mod ffi_node_info;
 mod ffi_pattern_key;
 mod ffi_suggestion_pattern;
+mod ffi_suggestion_trigger;
+mod ffi_text_direction;
+mod ffi_text_transform;
 mod ffi_text_update;
+mod ffi_typography;
 mod into_ffi;
 
 use std::sync::Arc;
 
 pub use crate::ffi_action_state::ActionState;
+pub use crate::ffi_command::CommandArgumentDefinition;
+pub use crate::ffi_command::CommandArgumentKind;
+pub use crate::ffi_command::CommandArgumentValue;
+pub use crate::ffi_command::CommandDefinition;
+pub use crate::ffi_command::ParsedCommand;
+pub use crate::ffi_command::ParsedCommandArgument;
 pub use crate::ffi_composer_action::ComposerAction;
+pub use crate::ffi_composer_error::ComposerError;
+pub use crate::ffi_composer_mode::ComposerMode;
 pub use crate::ffi_composer_model::Attribute;
 pub use crate::ffi_composer_model::ComposerModel;
 pub use crate::ffi_composer_state::ComposerState;
 pub use crate::ffi_composer_update::ComposerUpdate;
-pub use crate::ffi_dom_creation_error::DomCreationError;
+
+pub use crate::ffi_find::FindMatch;
+pub use crate::ffi_find::FindOptions;
+pub use crate::ffi_granularity::Direction;
+pub use crate::ffi_granularity::Granularity;
+pub use crate::ffi_keymap::Key;
+pub use crate::ffi_keymap::KeyBinding;
+pub use crate::ffi_keymap::KeyCommand;
+pub use crate::ffi_keymap::KeyEvent;
+pub use crate::ffi_keymap::KeymapPreset;
 pub use crate::ffi_link_actions::LinkAction;
+pub use crate::ffi_markdown_dialect::BulletMarker;
+pub use crate::ffi_markdown_dialect::EmphasisMarker;
+pub use crate::ffi_markdown_dialect::FenceStyle;
+pub use crate::ffi_markdown_dialect::HardBreakStyle;
+pub use crate::ffi_markdown_dialect::MarkdownDialect;
+pub use crate::ffi_markdown_dialect::MarkdownFlavor;
+pub use crate::ffi_markdown_dialect::SoftBreakPolicy;
+pub use crate::ffi_max_length::ComposerStats;
+pub use crate::ffi_max_length::MaxLength;
+pub use crate::ffi_max_length::MaxLengthBehaviour;
+pub use crate::ffi_max_length::MaxLengthExceeded;
 use crate::ffi_mention_detector::MentionDetector;
 pub use crate::ffi_mentions_state::MentionsState;
+pub use crate::ffi_menu_action::EmojiCandidate;
 pub use crate::ffi_menu_action::MenuAction;
 pub use crate::ffi_menu_state::MenuState;
+pub use crate::ffi_node_info::DomNodeInfo;
+pub use crate::ffi_node_info::DomNodeKind;
 pub use crate::ffi_pattern_key::PatternKey;
 pub use crate::ffi_suggestion_pattern::SuggestionPattern;
+pub use crate::ffi_suggestion_trigger::SuggestionTrigger;
+pub use crate::ffi_text_direction::TextDirection;
+pub use crate::ffi_text_transform::TextTransform;
 pub use crate::ffi_text_update::TextUpdate;
+pub use crate::ffi_typography::TypographyRule;
 
 #[uniffi::export]
 pub fn new_composer_model() -> Arc<ComposerModel> {
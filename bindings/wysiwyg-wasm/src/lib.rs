@@ -14,7 +14,7 @@
 
 use std::{
     collections::{HashMap, VecDeque},
-    fmt::Display,
+    str::FromStr,
 };
 
 use wasm_bindgen::prelude::*;
@@ -37,14 +37,14 @@ pub fn new_composer_model_from_html(
     html: &str,
     start_utf16_codeunit: u32,
     end_utf16_codeunit: u32,
-) -> ComposerModel {
-    ComposerModel {
+) -> Result<ComposerModel, ComposerError> {
+    Ok(ComposerModel {
         inner: wysiwyg::ComposerModel::<Utf16String>::from_html(
             html,
             usize::try_from(start_utf16_codeunit).unwrap(),
             usize::try_from(end_utf16_codeunit).unwrap(),
-        ),
-    }
+        )?,
+    })
 }
 
 #[wasm_bindgen]
@@ -106,6 +106,228 @@ impl ToStringVec for js_sys::Array {
     }
 }
 
+trait ToSuggestionTriggerVec {
+    fn into_suggestion_triggers(
+        self,
+    ) -> Result<Vec<wysiwyg::SuggestionTrigger>, ComposerError>;
+}
+
+/// Converts an array of `{ prefix, regex?, min_length?, max_length? }`
+/// objects into suggestion triggers.
+impl ToSuggestionTriggerVec for js_sys::Array {
+    fn into_suggestion_triggers(
+        self,
+    ) -> Result<Vec<wysiwyg::SuggestionTrigger>, ComposerError> {
+        let get = |trigger: &JsValue, key: &str| {
+            js_sys::Reflect::get(trigger, &JsValue::from_str(key))
+                .unwrap_or(JsValue::UNDEFINED)
+        };
+        self.iter()
+            .map(|trigger| {
+                let prefix = wysiwyg::SuggestionTrigger::parse_prefix(
+                    &get(&trigger, "prefix").as_string().unwrap_or_default(),
+                )?;
+                Ok(match get(&trigger, "regex").as_string() {
+                    Some(regex) => {
+                        wysiwyg::SuggestionTrigger::with_regex(prefix, &regex)?
+                    }
+                    None => wysiwyg::SuggestionTrigger::with_length(
+                        prefix,
+                        get(&trigger, "min_length").as_f64().unwrap_or(0.0)
+                            as usize,
+                        get(&trigger, "max_length")
+                            .as_f64()
+                            .map(|max| max as usize),
+                    ),
+                })
+            })
+            .collect()
+    }
+}
+
+trait ToCommandDefinitionVec {
+    fn into_command_definitions(self) -> Vec<wysiwyg::CommandDefinition>;
+}
+
+/// Converts an array of `{ name, arguments: [{ name, kind, optional? }] }`
+/// objects into command definitions. `kind` is one of "word", "user",
+/// "room" or "text", defaulting to "word".
+impl ToCommandDefinitionVec for js_sys::Array {
+    fn into_command_definitions(self) -> Vec<wysiwyg::CommandDefinition> {
+        let get = |object: &JsValue, key: &str| {
+            js_sys::Reflect::get(object, &JsValue::from_str(key))
+                .unwrap_or(JsValue::UNDEFINED)
+        };
+        self.iter()
+            .map(|command| wysiwyg::CommandDefinition {
+                name: get(&command, "name").as_string().unwrap_or_default(),
+                arguments: js_sys::Array::from(&get(&command, "arguments"))
+                    .iter()
+                    .map(|argument| wysiwyg::CommandArgumentDefinition {
+                        name: get(&argument, "name")
+                            .as_string()
+                            .unwrap_or_default(),
+                        kind: match get(&argument, "kind")
+                            .as_string()
+                            .as_deref()
+                        {
+                            Some("user") => wysiwyg::CommandArgumentKind::User,
+                            Some("room") => wysiwyg::CommandArgumentKind::Room,
+                            Some("text") => wysiwyg::CommandArgumentKind::Text,
+                            _ => wysiwyg::CommandArgumentKind::Word,
+                        },
+                        optional: get(&argument, "optional").is_truthy(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// Reads a `KeyboardEvent`, or any object with the same `key`, `ctrlKey`,
+/// `altKey`, `shiftKey` and `metaKey` properties.
+fn key_event_from_js(event: &JsValue) -> Option<wysiwyg::KeyEvent> {
+    let get = |key: &str| {
+        js_sys::Reflect::get(event, &JsValue::from_str(key))
+            .unwrap_or(JsValue::UNDEFINED)
+    };
+    let key = wysiwyg::Key::from_str(&get("key").as_string()?).ok()?;
+    Some(wysiwyg::KeyEvent::new(
+        key,
+        wysiwyg::KeyModifiers {
+            ctrl: get("ctrlKey").is_truthy(),
+            alt: get("altKey").is_truthy(),
+            shift: get("shiftKey").is_truthy(),
+            meta: get("metaKey").is_truthy(),
+        },
+    ))
+}
+
+trait ToKeymap {
+    fn into_keymap(self) -> wysiwyg::Keymap;
+}
+
+/// Converts an array of key event objects with an extra `command` property,
+/// e.g. `{ key: "b", ctrlKey: true, command: "Bold" }`, into a keymap.
+/// Bindings with an unknown key or command are ignored.
+impl ToKeymap for js_sys::Array {
+    fn into_keymap(self) -> wysiwyg::Keymap {
+        let mut keymap = wysiwyg::Keymap::empty();
+        for binding in self.iter() {
+            let command =
+                js_sys::Reflect::get(&binding, &JsValue::from_str("command"))
+                    .ok()
+                    .and_then(|command| command.as_string())
+                    .and_then(|command| {
+                        wysiwyg::KeyCommand::from_str(&command).ok()
+                    });
+            if let (Some(event), Some(command)) =
+                (key_event_from_js(&binding), command)
+            {
+                keymap.bind(event, command);
+            }
+        }
+        keymap
+    }
+}
+
+trait ToTypographyRuleVec {
+    fn into_typography_rules(self) -> Vec<wysiwyg::TypographyRule>;
+}
+
+/// Converts an array of rule names, e.g. `"SmartQuotes"`, `"EmDash"` or
+/// `"Ellipsis"`, and custom `{ from, to }` objects into typography rules.
+/// Unknown entries are ignored.
+impl ToTypographyRuleVec for js_sys::Array {
+    fn into_typography_rules(self) -> Vec<wysiwyg::TypographyRule> {
+        let get = |object: &JsValue, key: &str| {
+            js_sys::Reflect::get(object, &JsValue::from_str(key))
+                .ok()
+                .and_then(|value| value.as_string())
+        };
+        self.iter()
+            .filter_map(|rule| match rule.as_string().as_deref() {
+                Some("SmartQuotes") => {
+                    Some(wysiwyg::TypographyRule::SmartQuotes)
+                }
+                Some("EmDash") => Some(wysiwyg::TypographyRule::EmDash),
+                Some("Ellipsis") => Some(wysiwyg::TypographyRule::Ellipsis),
+                Some(_) => None,
+                None => Some(wysiwyg::TypographyRule::Custom {
+                    from: get(&rule, "from")?,
+                    to: get(&rule, "to")?,
+                }),
+            })
+            .collect()
+    }
+}
+
+trait ToMarkdownDialect {
+    fn into_markdown_dialect(self) -> wysiwyg::MarkdownDialect;
+}
+
+/// Converts a `{ flavor, emphasis, bullet, fence, hard_break, soft_break }`
+/// object of variant names, e.g. `{ flavor: "CommonMark", bullet: "Dash" }`,
+/// and an optional `gfm_extensions` boolean into a markdown dialect. Missing
+/// or unknown entries keep their default.
+impl ToMarkdownDialect for JsValue {
+    fn into_markdown_dialect(self) -> wysiwyg::MarkdownDialect {
+        use wysiwyg::{
+            BulletMarker, EmphasisMarker, FenceStyle, HardBreakStyle,
+            MarkdownFlavor, SoftBreakPolicy,
+        };
+        let get = |key: &str| {
+            js_sys::Reflect::get(&self, &JsValue::from_str(key))
+                .ok()
+                .and_then(|value| value.as_string())
+        };
+        let mut dialect = wysiwyg::MarkdownDialect::default();
+        match get("flavor").as_deref() {
+            Some("CommonMark") => dialect.flavor = MarkdownFlavor::CommonMark,
+            Some("Gfm") => dialect.flavor = MarkdownFlavor::Gfm,
+            _ => {}
+        }
+        match get("emphasis").as_deref() {
+            Some("Asterisk") => dialect.emphasis = EmphasisMarker::Asterisk,
+            Some("Underscore") => dialect.emphasis = EmphasisMarker::Underscore,
+            _ => {}
+        }
+        match get("bullet").as_deref() {
+            Some("Asterisk") => dialect.bullet = BulletMarker::Asterisk,
+            Some("Dash") => dialect.bullet = BulletMarker::Dash,
+            Some("Plus") => dialect.bullet = BulletMarker::Plus,
+            _ => {}
+        }
+        match get("fence").as_deref() {
+            Some("Backticks") => dialect.fence = FenceStyle::Backticks,
+            Some("Tildes") => dialect.fence = FenceStyle::Tildes,
+            _ => {}
+        }
+        match get("hard_break").as_deref() {
+            Some("Backslash") => dialect.hard_break = HardBreakStyle::Backslash,
+            Some("TrailingSpaces") => {
+                dialect.hard_break = HardBreakStyle::TrailingSpaces
+            }
+            _ => {}
+        }
+        match get("soft_break").as_deref() {
+            Some("LineBreak") => {
+                dialect.soft_break = SoftBreakPolicy::LineBreak
+            }
+            Some("Space") => dialect.soft_break = SoftBreakPolicy::Space,
+            _ => {}
+        }
+        if let Some(enabled) =
+            js_sys::Reflect::get(&self, &JsValue::from_str("gfm_extensions"))
+                .ok()
+                .and_then(|value| value.as_bool())
+        {
+            dialect.gfm_extensions = enabled;
+        }
+        dialect
+    }
+}
+
 #[wasm_bindgen]
 #[derive(Default)]
 pub struct ComposerModel {
@@ -138,18 +360,58 @@ impl ComposerModel {
         self.inner.get_content_as_message_html().to_string()
     }
 
-    pub fn get_content_as_markdown(&self) -> String {
-        self.inner.get_content_as_markdown().to_string()
+    pub fn get_content_as_markdown(&self) -> Result<String, ComposerError> {
+        Ok(self.inner.get_content_as_markdown()?.to_string())
     }
 
-    pub fn get_content_as_message_markdown(&self) -> String {
-        self.inner.get_content_as_message_markdown().to_string()
+    pub fn get_content_as_message_markdown(
+        &self,
+    ) -> Result<String, ComposerError> {
+        Ok(self.inner.get_content_as_message_markdown()?.to_string())
     }
 
     pub fn get_content_as_plain_text(&self) -> String {
         self.inner.get_content_as_plain_text().to_string()
     }
 
+    /// The offset in `get_content_as_markdown` matching a location, both
+    /// in UTF-16 code units.
+    pub fn location_to_markdown_offset(
+        &self,
+        utf16_codeunit: u32,
+    ) -> Result<u32, ComposerError> {
+        let location =
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap());
+        let offset = self.inner.location_to_markdown_offset(location)?;
+        Ok(u32::try_from(offset).unwrap())
+    }
+
+    /// The location matching an offset in `get_content_as_markdown`.
+    pub fn markdown_offset_to_location(
+        &self,
+        offset: u32,
+    ) -> Result<u32, ComposerError> {
+        let offset = usize::try_from(offset).unwrap();
+        let location = self.inner.markdown_offset_to_location(offset)?;
+        Ok(u32::try_from(usize::from(location)).unwrap())
+    }
+
+    /// The offset in `get_content_as_plain_text` matching a location, both
+    /// in UTF-16 code units.
+    pub fn location_to_plain_text_offset(&self, utf16_codeunit: u32) -> u32 {
+        let location =
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap());
+        let offset = self.inner.location_to_plain_text_offset(location);
+        u32::try_from(offset).unwrap()
+    }
+
+    /// The location matching an offset in `get_content_as_plain_text`.
+    pub fn plain_text_offset_to_location(&self, offset: u32) -> u32 {
+        let offset = usize::try_from(offset).unwrap();
+        let location = self.inner.plain_text_offset_to_location(offset);
+        u32::try_from(usize::from(location)).unwrap()
+    }
+
     pub fn document(&self) -> DomHandle {
         DomHandle {
             inner: self.inner.state.dom.document().handle(),
@@ -164,14 +426,199 @@ impl ComposerModel {
         &mut self,
         start_utf16_codeunit: u32,
         end_utf16_codeunit: u32,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.select(
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.select(
             wysiwyg::Location::from(
                 usize::try_from(start_utf16_codeunit).unwrap(),
             ),
             wysiwyg::Location::from(
                 usize::try_from(end_utf16_codeunit).unwrap(),
             ),
+        )?))
+    }
+
+    pub fn select_all(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.select_all()?))
+    }
+
+    pub fn select_word_at(
+        &mut self,
+        utf16_codeunit: u32,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.select_word_at(
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap()),
+        )?))
+    }
+
+    pub fn select_sentence_at(
+        &mut self,
+        utf16_codeunit: u32,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.select_sentence_at(
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap()),
+        )?))
+    }
+
+    pub fn select_paragraph_at(
+        &mut self,
+        utf16_codeunit: u32,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.select_paragraph_at(
+            wysiwyg::Location::from(usize::try_from(utf16_codeunit).unwrap()),
+        )?))
+    }
+
+    pub fn move_cursor(
+        &mut self,
+        direction: Direction,
+        granularity: Granularity,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(
+            self.inner
+                .move_cursor(direction.into(), granularity.into())?,
+        ))
+    }
+
+    pub fn extend_selection_by(
+        &mut self,
+        granularity: Granularity,
+        direction: Direction,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.extend_selection_by(
+            granularity.into(),
+            direction.into(),
+        )?))
+    }
+
+    pub fn set_typography_rules(&mut self, rules: js_sys::Array) {
+        self.inner
+            .set_typography_rules(rules.into_typography_rules());
+    }
+
+    /// Set the markdown syntax used by `set_content_from_markdown` and
+    /// `get_content_as_markdown`.
+    pub fn set_markdown_dialect(&mut self, dialect: JsValue) {
+        self.inner
+            .set_markdown_dialect(dialect.into_markdown_dialect());
+    }
+
+    pub fn stats(&self) -> ComposerStats {
+        ComposerStats::from(self.inner.stats())
+    }
+
+    /// Returns the root of the DOM as a `{ handle, kind, attributes, text,
+    /// start, end, children }` object. `handle` is the array of child
+    /// indices leading to the node and `start` and `end` are in UTF-16 code
+    /// units.
+    pub fn dom_tree(&self) -> JsValue {
+        node_info_to_js(self.inner.dom_tree()).into()
+    }
+
+    /// Returns the deepest node at the given location in the same form as
+    /// `dom_tree`, or undefined if the location is beyond the text.
+    pub fn node_at_location(&self, location: u32) -> JsValue {
+        self.inner
+            .node_at_location(wysiwyg::Location::from(location as usize))
+            .map(|node| node_info_to_js(node).into())
+            .unwrap_or(JsValue::UNDEFINED)
+    }
+
+    /// Pass undefined as `characters` to remove the limit.
+    pub fn set_max_length(
+        &mut self,
+        characters: Option<u32>,
+        behaviour: MaxLengthBehaviour,
+    ) {
+        self.inner.set_max_length(characters.map(|characters| {
+            wysiwyg::MaxLength {
+                characters: characters as usize,
+                behaviour: behaviour.into(),
+            }
+        }));
+    }
+
+    /// Returns an array of `FindMatch`es.
+    pub fn find(
+        &self,
+        query: &str,
+        options: FindOptions,
+    ) -> Result<js_sys::Array, ComposerError> {
+        let matches = self.inner.find(query, &options.into())?;
+        Ok(matches
+            .into_iter()
+            .map(|(start, end)| {
+                JsValue::from(FindMatch {
+                    start_utf16_codeunit: usize::from(start) as u32,
+                    end_utf16_codeunit: usize::from(end) as u32,
+                })
+            })
+            .collect())
+    }
+
+    pub fn replace_match(
+        &mut self,
+        start_utf16_codeunit: u32,
+        end_utf16_codeunit: u32,
+        replacement: &str,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.replace_match(
+            wysiwyg::Location::from(start_utf16_codeunit as usize),
+            wysiwyg::Location::from(end_utf16_codeunit as usize),
+            Utf16String::from_str(replacement),
+        )?))
+    }
+
+    pub fn replace_all(
+        &mut self,
+        query: &str,
+        options: FindOptions,
+        replacement: &str,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.replace_all(
+            query,
+            &options.into(),
+            Utf16String::from_str(replacement),
+        )?))
+    }
+
+    pub fn clear_formatting(
+        &mut self,
+        include_blocks: bool,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(
+            self.inner.clear_formatting(include_blocks)?,
+        ))
+    }
+
+    pub fn transform_selection(
+        &mut self,
+        transform: TextTransform,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(
+            self.inner.transform_selection(transform.into())?,
+        ))
+    }
+
+    pub fn mode(&self) -> ComposerMode {
+        self.inner.mode().into()
+    }
+
+    /// Start editing the content as markdown source.
+    pub fn switch_to_plain(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.switch_to_plain()?))
+    }
+
+    /// Parse the markdown source and go back to editing rich text.
+    pub fn switch_to_rich(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.switch_to_rich()?))
+    }
+
+    pub fn set_text_direction(
+        &mut self,
+        direction: TextDirection,
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(
+            self.inner.set_text_direction(direction.into())?,
         ))
     }
 
@@ -185,14 +632,17 @@ impl ComposerModel {
         ret as u32
     }
 
-    pub fn replace_text(&mut self, new_text: &str) -> ComposerUpdate {
+    pub fn replace_text(
+        &mut self,
+        new_text: &str,
+    ) -> Result<ComposerUpdate, ComposerError> {
         // Conversion here to UTF-16, which has presumably just been
         // converted to UTF-8 in the FFI bindings layer.
         // If the performance is a problem, we could fix this.
         // Internal task to track this: PSU-739
-        ComposerUpdate::from(
-            self.inner.replace_text(Utf16String::from_str(new_text)),
-        )
+        Ok(ComposerUpdate::from(
+            self.inner.replace_text(Utf16String::from_str(new_text))?,
+        ))
     }
 
     pub fn replace_text_suggestion(
@@ -200,18 +650,18 @@ impl ComposerModel {
         new_text: &str,
         suggestion: &SuggestionPattern,
         append_space: bool,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.replace_text_suggestion(
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.replace_text_suggestion(
             Utf16String::from_str(new_text),
             wysiwyg::SuggestionPattern::from(suggestion.clone()),
             append_space,
-        ))
+        )?))
     }
 
     pub fn set_content_from_html(
         &mut self,
         text: &str,
-    ) -> Result<ComposerUpdate, DomCreationError> {
+    ) -> Result<ComposerUpdate, ComposerError> {
         let update = self
             .inner
             .set_content_from_html(&Utf16String::from_str(text))?;
@@ -221,87 +671,99 @@ impl ComposerModel {
     pub fn set_content_from_markdown(
         &mut self,
         text: &str,
-    ) -> Result<ComposerUpdate, DomCreationError> {
+    ) -> Result<ComposerUpdate, ComposerError> {
         let markdown = self
             .inner
             .set_content_from_markdown(&Utf16String::from_str(text))?;
         Ok(ComposerUpdate::from(markdown))
     }
 
-    pub fn clear(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.clear())
+    pub fn clear(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.clear()?))
+    }
+
+    pub fn enter(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.enter()?))
+    }
+
+    pub fn backspace(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.backspace()?))
+    }
+
+    pub fn backspace_word(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.backspace_word()?))
     }
 
-    pub fn enter(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.enter())
+    pub fn delete(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.delete()?))
     }
 
-    pub fn backspace(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.backspace())
+    pub fn delete_word(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.delete_word()?))
     }
 
-    pub fn backspace_word(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.backspace_word())
+    pub fn bold(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.bold()?))
     }
 
-    pub fn delete(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.delete())
+    pub fn italic(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.italic()?))
     }
 
-    pub fn delete_word(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.delete_word())
+    pub fn strike_through(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.strike_through()?))
     }
 
-    pub fn bold(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.bold())
+    pub fn underline(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.underline()?))
     }
 
-    pub fn italic(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.italic())
+    pub fn quote(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.quote()?))
     }
 
-    pub fn strike_through(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.strike_through())
+    pub fn move_block_up(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.move_block_up()?))
     }
 
-    pub fn underline(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.underline())
+    pub fn move_block_down(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.move_block_down()?))
     }
 
-    pub fn quote(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.quote())
+    pub fn duplicate_block(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.duplicate_block()?))
     }
 
-    pub fn inline_code(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.inline_code())
+    pub fn inline_code(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.inline_code()?))
     }
 
-    pub fn code_block(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.code_block())
+    pub fn code_block(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.code_block()?))
     }
 
-    pub fn undo(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.undo())
+    pub fn undo(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.undo()?))
     }
 
-    pub fn redo(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.redo())
+    pub fn redo(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.redo()?))
     }
 
-    pub fn ordered_list(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.ordered_list())
+    pub fn ordered_list(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.ordered_list()?))
     }
 
-    pub fn unordered_list(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.unordered_list())
+    pub fn unordered_list(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.unordered_list()?))
     }
 
-    pub fn indent(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.indent())
+    pub fn indent(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.indent()?))
     }
 
-    pub fn unindent(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.unindent())
+    pub fn unindent(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.unindent()?))
     }
 
     pub fn get_link_action(&self) -> LinkAction {
@@ -312,11 +774,11 @@ impl ComposerModel {
         &mut self,
         url: &str,
         attributes: js_sys::Map,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(
-            self.inner
-                .set_link(Utf16String::from_str(url), attributes.into_vec()),
-        )
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.set_link(
+            Utf16String::from_str(url),
+            attributes.into_vec(),
+        )?))
     }
 
     pub fn set_link_with_text(
@@ -324,12 +786,12 @@ impl ComposerModel {
         url: &str,
         text: &str,
         attributes: js_sys::Map,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.set_link_with_text(
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.set_link_with_text(
             Utf16String::from_str(url),
             Utf16String::from_str(&html_escape::encode_safe(&text)),
             attributes.into_vec(),
-        ))
+        )?))
     }
 
     pub fn set_custom_suggestion_patterns(
@@ -341,14 +803,64 @@ impl ComposerModel {
         );
     }
 
+    pub fn set_custom_suggestion_triggers(
+        &mut self,
+        custom_suggestion_triggers: js_sys::Array,
+    ) -> Result<(), ComposerError> {
+        self.inner.set_custom_suggestion_triggers(
+            custom_suggestion_triggers.into_suggestion_triggers()?,
+        );
+        Ok(())
+    }
+
+    pub fn set_emoji_suggestions_enabled(&mut self, enabled: bool) {
+        self.inner.set_emoji_suggestions_enabled(enabled);
+    }
+
+    pub fn set_commands(&mut self, commands: js_sys::Array) {
+        self.inner.set_commands(commands.into_command_definitions());
+    }
+
+    pub fn is_command(&self) -> bool {
+        self.inner.is_command()
+    }
+
+    /// Handles a `KeyboardEvent` according to the keymap. Returns undefined
+    /// if the key was not consumed and the default action should happen.
+    pub fn handle_key(
+        &mut self,
+        event: &JsValue,
+    ) -> Result<Option<ComposerUpdate>, ComposerError> {
+        let Some(event) = key_event_from_js(event) else {
+            return Ok(None);
+        };
+        Ok(self.inner.handle_key(&event)?.map(ComposerUpdate::from))
+    }
+
+    pub fn set_keymap_preset(&mut self, preset: KeymapPreset) {
+        self.inner
+            .set_keymap(wysiwyg::Keymap::preset(preset.into()));
+    }
+
+    pub fn set_key_bindings(&mut self, bindings: js_sys::Array) {
+        self.inner.set_keymap(bindings.into_keymap());
+    }
+
+    /// Returns undefined if the content isn't a registered command.
+    pub fn parse_command(
+        &self,
+    ) -> Result<Option<ParsedCommand>, ComposerError> {
+        Ok(self.inner.parse_command()?.map(ParsedCommand::from))
+    }
+
     /// Creates an at-room mention node and inserts it into the composer at the current selection
     pub fn insert_at_room_mention(
         &mut self,
         attributes: js_sys::Map,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(
-            self.inner.insert_at_room_mention(attributes.into_vec()),
-        )
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(
+            self.inner.insert_at_room_mention(attributes.into_vec())?,
+        ))
     }
 
     /// Creates a mention node and inserts it into the composer at the current selection
@@ -357,12 +869,12 @@ impl ComposerModel {
         url: &str,
         text: &str,
         attributes: js_sys::Map,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.insert_mention(
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.insert_mention(
             Utf16String::from_str(url),
             Utf16String::from_str(&html_escape::encode_safe(&text)),
             attributes.into_vec(),
-        ))
+        )?))
     }
 
     /// Creates an at-room mention node and inserts it into the composer, replacing the
@@ -371,10 +883,12 @@ impl ComposerModel {
         &mut self,
         suggestion: &SuggestionPattern,
         attributes: js_sys::Map,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.insert_at_room_mention_at_suggestion(
-            wysiwyg::SuggestionPattern::from(suggestion.clone()),
-            attributes.into_vec(),
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(
+            self.inner.insert_at_room_mention_at_suggestion(
+                wysiwyg::SuggestionPattern::from(suggestion.clone()),
+                attributes.into_vec(),
+            )?,
         ))
     }
 
@@ -386,17 +900,19 @@ impl ComposerModel {
         text: &str,
         suggestion: &SuggestionPattern,
         attributes: js_sys::Map,
-    ) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.insert_mention_at_suggestion(
-            Utf16String::from_str(url),
-            Utf16String::from_str(&html_escape::encode_safe(&text)),
-            wysiwyg::SuggestionPattern::from(suggestion.clone()),
-            attributes.into_vec(),
+    ) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(
+            self.inner.insert_mention_at_suggestion(
+                Utf16String::from_str(url),
+                Utf16String::from_str(&html_escape::encode_safe(&text)),
+                wysiwyg::SuggestionPattern::from(suggestion.clone()),
+                attributes.into_vec(),
+            )?,
         ))
     }
 
-    pub fn remove_links(&mut self) -> ComposerUpdate {
-        ComposerUpdate::from(self.inner.remove_links())
+    pub fn remove_links(&mut self) -> Result<ComposerUpdate, ComposerError> {
+        Ok(ComposerUpdate::from(self.inner.remove_links()?))
     }
 }
 
@@ -424,44 +940,356 @@ impl ComposerUpdate {
     pub fn menu_action(&self) -> MenuAction {
         MenuAction::from(self.inner.menu_action.clone())
     }
+
+    pub fn max_length_exceeded(&self) -> Option<MaxLengthExceeded> {
+        self.inner.max_length_exceeded.map(MaxLengthExceeded::from)
+    }
 }
 
-#[derive(Clone, Debug)]
-#[wasm_bindgen]
-pub enum DomCreationError {
-    HtmlParseError,
-    MarkdownParseError,
+/// A failed composer operation. It is thrown to JavaScript as an `Error`
+/// whose `name` is the kind of failure, e.g. `HtmlParseError`.
+#[derive(Debug)]
+pub struct ComposerError(wysiwyg::ComposerError);
+
+impl From<wysiwyg::ComposerError> for ComposerError {
+    fn from(error: wysiwyg::ComposerError) -> Self {
+        Self(error)
+    }
 }
 
-impl Display for DomCreationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
-            DomCreationError::HtmlParseError => {
-                "could not create dom from html"
+impl From<ComposerError> for JsValue {
+    fn from(error: ComposerError) -> Self {
+        let js_error = js_sys::Error::new(&error.0.to_string());
+        js_error.set_name(match error.0 {
+            wysiwyg::ComposerError::HtmlParseError(_) => "HtmlParseError",
+            wysiwyg::ComposerError::MarkdownParseError(_) => {
+                "MarkdownParseError"
             }
-            DomCreationError::MarkdownParseError => {
-                "could not create dom from markdown"
+            wysiwyg::ComposerError::MarkdownExportError(_) => {
+                "MarkdownExportError"
             }
-        })
+            wysiwyg::ComposerError::UnknownFormatType(_) => "UnknownFormatType",
+            wysiwyg::ComposerError::InvalidRegex(_) => "InvalidRegex",
+            wysiwyg::ComposerError::InvalidSuggestionPrefix(_) => {
+                "InvalidSuggestionPrefix"
+            }
+            wysiwyg::ComposerError::CommandParseError(_) => "CommandParseError",
+            wysiwyg::ComposerError::Internal(_) => "Internal",
+        });
+        js_error.into()
     }
 }
 
-impl From<wysiwyg::DomCreationError> for DomCreationError {
-    fn from(error: wysiwyg::DomCreationError) -> Self {
-        match error {
-            wysiwyg::DomCreationError::HtmlParseError(_) => {
-                Self::HtmlParseError
-            }
-            wysiwyg::DomCreationError::MarkdownParseError(_) => {
-                Self::MarkdownParseError
-            }
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub struct ComposerStats {
+    pub characters: u32,
+    pub graphemes: u32,
+    pub words: u32,
+    pub body_bytes: u32,
+    pub formatted_body_bytes: u32,
+}
+
+impl From<wysiwyg::ComposerStats> for ComposerStats {
+    fn from(stats: wysiwyg::ComposerStats) -> Self {
+        Self {
+            characters: stats.characters as u32,
+            graphemes: stats.graphemes as u32,
+            words: stats.words as u32,
+            body_bytes: stats.body_bytes as u32,
+            formatted_body_bytes: stats.formatted_body_bytes as u32,
         }
     }
 }
 
-impl From<DomCreationError> for wysiwyg::DomCreationError {
-    fn from(_: DomCreationError) -> Self {
-        unimplemented!("Error is not needed as input")
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum MaxLengthBehaviour {
+    Truncate,
+    Reject,
+}
+
+impl From<MaxLengthBehaviour> for wysiwyg::MaxLengthBehaviour {
+    fn from(behaviour: MaxLengthBehaviour) -> Self {
+        match behaviour {
+            MaxLengthBehaviour::Truncate => Self::Truncate,
+            MaxLengthBehaviour::Reject => Self::Reject,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum MaxLengthExceeded {
+    Truncated,
+    Rejected,
+}
+
+impl From<wysiwyg::MaxLengthExceeded> for MaxLengthExceeded {
+    fn from(exceeded: wysiwyg::MaxLengthExceeded) -> Self {
+        match exceeded {
+            wysiwyg::MaxLengthExceeded::Truncated => Self::Truncated,
+            wysiwyg::MaxLengthExceeded::Rejected => Self::Rejected,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+#[wasm_bindgen]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+#[wasm_bindgen]
+impl FindOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new(case_sensitive: bool, whole_word: bool, regex: bool) -> Self {
+        Self {
+            case_sensitive,
+            whole_word,
+            regex,
+        }
+    }
+}
+
+impl From<FindOptions> for wysiwyg::FindOptions {
+    fn from(options: FindOptions) -> Self {
+        Self {
+            case_sensitive: options.case_sensitive,
+            whole_word: options.whole_word,
+            regex: options.regex,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub struct FindMatch {
+    pub start_utf16_codeunit: u32,
+    pub end_utf16_codeunit: u32,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum Granularity {
+    Character,
+    Word,
+    Sentence,
+    Paragraph,
+    Document,
+}
+
+impl From<Granularity> for wysiwyg::Granularity {
+    fn from(granularity: Granularity) -> Self {
+        match granularity {
+            Granularity::Character => Self::Character,
+            Granularity::Word => Self::Word,
+            Granularity::Sentence => Self::Sentence,
+            Granularity::Paragraph => Self::Paragraph,
+            Granularity::Document => Self::Document,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum Direction {
+    Forwards,
+    Backwards,
+}
+
+impl From<Direction> for wysiwyg::Direction {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Forwards => Self::Forwards,
+            Direction::Backwards => Self::Backwards,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum TextTransform {
+    Uppercase,
+    Lowercase,
+    TitleCase,
+    ToggleCase,
+}
+
+impl From<TextTransform> for wysiwyg::TextTransform {
+    fn from(transform: TextTransform) -> Self {
+        match transform {
+            TextTransform::Uppercase => Self::Uppercase,
+            TextTransform::Lowercase => Self::Lowercase,
+            TextTransform::TitleCase => Self::TitleCase,
+            TextTransform::ToggleCase => Self::ToggleCase,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum ComposerMode {
+    Rich,
+    Plain,
+}
+
+impl From<wysiwyg::ComposerMode> for ComposerMode {
+    fn from(mode: wysiwyg::ComposerMode) -> Self {
+        match mode {
+            wysiwyg::ComposerMode::Rich => Self::Rich,
+            wysiwyg::ComposerMode::Plain => Self::Plain,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum TextDirection {
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+impl From<TextDirection> for wysiwyg::TextDirection {
+    fn from(direction: TextDirection) -> Self {
+        match direction {
+            TextDirection::Auto => Self::Auto,
+            TextDirection::Ltr => Self::Ltr,
+            TextDirection::Rtl => Self::Rtl,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[wasm_bindgen]
+pub enum KeymapPreset {
+    /// Ctrl-based shortcuts, as on Windows and Linux
+    Standard,
+    /// Command-based shortcuts, as on macOS
+    Apple,
+}
+
+impl From<KeymapPreset> for wysiwyg::KeymapPreset {
+    fn from(preset: KeymapPreset) -> Self {
+        match preset {
+            KeymapPreset::Standard => Self::Standard,
+            KeymapPreset::Apple => Self::Apple,
+        }
+    }
+}
+
+fn node_info_to_js(node: wysiwyg::NodeInfo) -> js_sys::Object {
+    use wysiwyg::{InlineFormatType, ListType, NodeKind};
+    let kind = match node.kind {
+        NodeKind::Generic => "generic",
+        NodeKind::Text => "text",
+        NodeKind::LineBreak => "line_break",
+        NodeKind::Mention => "mention",
+        NodeKind::Formatting(InlineFormatType::Bold) => "bold",
+        NodeKind::Formatting(InlineFormatType::Italic) => "italic",
+        NodeKind::Formatting(InlineFormatType::StrikeThrough) => {
+            "strike_through"
+        }
+        NodeKind::Formatting(InlineFormatType::Underline) => "underline",
+        NodeKind::Formatting(InlineFormatType::InlineCode) => "inline_code",
+        NodeKind::Link => "link",
+        NodeKind::List(ListType::Ordered) => "ordered_list",
+        NodeKind::List(ListType::Unordered) => "unordered_list",
+        NodeKind::ListItem => "list_item",
+        NodeKind::CodeBlock => "code_block",
+        NodeKind::Quote => "quote",
+        NodeKind::Paragraph => "paragraph",
+    };
+    let handle: js_sys::Array = node
+        .handle
+        .raw()
+        .iter()
+        .map(|&i| JsValue::from(i as u32))
+        .collect();
+    let attributes = js_sys::Object::new();
+    for (name, value) in &node.attributes {
+        js_sys::Reflect::set(
+            &attributes,
+            &JsValue::from_str(name),
+            &JsValue::from_str(value),
+        )
+        .unwrap();
+    }
+    let text = node
+        .text
+        .as_deref()
+        .map(JsValue::from_str)
+        .unwrap_or(JsValue::UNDEFINED);
+    let children: js_sys::Array = node
+        .children
+        .into_iter()
+        .map(|child| JsValue::from(node_info_to_js(child)))
+        .collect();
+
+    let object = js_sys::Object::new();
+    let set = |key: &str, value: &JsValue| {
+        js_sys::Reflect::set(&object, &JsValue::from_str(key), value).unwrap();
+    };
+    set("handle", &handle);
+    set("kind", &JsValue::from_str(kind));
+    set("attributes", &attributes);
+    set("text", &text);
+    set("start", &JsValue::from(usize::from(node.start) as u32));
+    set("end", &JsValue::from(usize::from(node.end) as u32));
+    set("children", &children);
+    object
+}
+
+#[wasm_bindgen(getter_with_clone)]
+pub struct ParsedCommand {
+    pub name: String,
+    /// An array of `{ name, kind, ... }` objects. Text arguments have a
+    /// `text`, user arguments a `user_id` and `display_name` and room
+    /// arguments a `room_id_or_alias`.
+    pub arguments: js_sys::Array,
+}
+
+impl From<wysiwyg::ParsedCommand> for ParsedCommand {
+    fn from(command: wysiwyg::ParsedCommand) -> Self {
+        let arguments = js_sys::Array::new();
+        for argument in command.arguments {
+            let object = js_sys::Object::new();
+            let set = |key: &str, value: &str| {
+                js_sys::Reflect::set(
+                    &object,
+                    &JsValue::from_str(key),
+                    &JsValue::from_str(value),
+                )
+                .unwrap();
+            };
+            set("name", &argument.name);
+            match argument.value {
+                wysiwyg::CommandArgumentValue::Text(text) => {
+                    set("kind", "text");
+                    set("text", &text);
+                }
+                wysiwyg::CommandArgumentValue::User {
+                    user_id,
+                    display_name,
+                } => {
+                    set("kind", "user");
+                    set("user_id", &user_id);
+                    set("display_name", &display_name);
+                }
+                wysiwyg::CommandArgumentValue::Room { room_id_or_alias } => {
+                    set("kind", "room");
+                    set("room_id_or_alias", &room_id_or_alias);
+                }
+            }
+            arguments.push(&object);
+        }
+        Self {
+            name: command.name,
+            arguments,
+        }
     }
 }
 
@@ -610,6 +1438,28 @@ impl MenuAction {
             _ => None,
         }
     }
+
+    pub fn emoji_suggestion(&self) -> Option<MenuActionEmojiSuggestion> {
+        match &self.inner {
+            wysiwyg::MenuAction::EmojiSuggestion(suggestion) => {
+                Some(MenuActionEmojiSuggestion {
+                    suggestion_pattern: SuggestionPattern::from(
+                        suggestion.suggestion_pattern.clone(),
+                    ),
+                    candidates: suggestion
+                        .candidates
+                        .iter()
+                        .map(|candidate| EmojiCandidate {
+                            shortcode: candidate.shortcode.clone(),
+                            emoji: candidate.emoji.clone(),
+                        })
+                        .map(JsValue::from)
+                        .collect(),
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
 #[wasm_bindgen(getter_with_clone)]
@@ -618,6 +1468,21 @@ pub struct MenuActionSuggestion {
     pub suggestion_pattern: SuggestionPattern,
 }
 
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct MenuActionEmojiSuggestion {
+    pub suggestion_pattern: SuggestionPattern,
+    /// An array of [EmojiCandidate]s
+    pub candidates: js_sys::Array,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct EmojiCandidate {
+    pub shortcode: String,
+    pub emoji: String,
+}
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub enum ComposerAction {
@@ -635,6 +1500,7 @@ pub enum ComposerAction {
     Unindent,
     CodeBlock,
     Quote,
+    ClearFormatting,
 }
 
 impl ComposerAction {
@@ -654,6 +1520,7 @@ impl ComposerAction {
             wysiwyg::ComposerAction::Unindent => Self::Unindent,
             wysiwyg::ComposerAction::CodeBlock => Self::CodeBlock,
             wysiwyg::ComposerAction::Quote => Self::Quote,
+            wysiwyg::ComposerAction::ClearFormatting => Self::ClearFormatting,
         }
     }
 }
@@ -675,6 +1542,7 @@ impl From<&ComposerAction> for wysiwyg::ComposerAction {
             ComposerAction::Unindent => Self::Unindent,
             ComposerAction::CodeBlock => Self::CodeBlock,
             ComposerAction::Quote => Self::Quote,
+            ComposerAction::ClearFormatting => Self::ClearFormatting,
         }
     }
 }
@@ -929,7 +1797,7 @@ mod test {
     #[test]
     fn can_find_types_of_nodes() {
         let mut model = ComposerModel::new();
-        model.replace_text("foo");
+        model.replace_text("foo").unwrap();
 
         assert_eq!(model.document().node_type(&model), "container");
         assert_eq!(
@@ -941,11 +1809,11 @@ mod test {
     #[test]
     fn can_enumerate_children_of_nodes() {
         let mut model = ComposerModel::new();
-        model.replace_text("01234");
-        model.select(1, 4);
-        model.bold();
-        model.select(2, 3);
-        model.italic();
+        model.replace_text("01234").unwrap();
+        model.select(1, 4).unwrap();
+        model.bold().unwrap();
+        model.select(2, 3).unwrap();
+        model.italic().unwrap();
 
         let children = model.document().children(&model).inner;
         let grandchildren = children[1].children(&model).inner;
@@ -0,0 +1,171 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Timings of editing multi-megabyte documents, such as a pasted log.
+//!
+//! Run with `cargo bench -p wysiwyg`. The number of log lines defaults to
+//! 30000 (about 2MB) and can be changed with the `LINES` environment
+//! variable.
+//!
+//! Each timing is shown next to a fixed target, the same whatever the size
+//! of the document: a keystroke should be applied within 100ms, and pasting
+//! or loading a document within a second. A keystroke still costs time in
+//! proportion to the document, as every update carries its whole HTML (see
+//! "Benchmarks" in the README), so large enough documents go over. Timings
+//! depend on the machine, so going over a target is reported rather than
+//! failing the benchmark.
+
+use std::time::{Duration, Instant};
+
+use widestring::Utf16String;
+use wysiwyg::{ComposerModel, Location};
+
+const LOG_LINE: &str =
+    "2024-10-19 12:00:00.000 INFO [sync] Received 3 events for room !a:b.c\n";
+const KEYSTROKES: usize = 21;
+const DEFAULT_LINES: usize = 30000;
+const KEYSTROKE_TARGET: Duration = Duration::from_millis(100);
+const DOCUMENT_TARGET: Duration = Duration::from_secs(1);
+
+fn main() {
+    let lines = std::env::var("LINES")
+        .ok()
+        .and_then(|lines| lines.parse().ok())
+        .unwrap_or(DEFAULT_LINES);
+    let log = LOG_LINE.repeat(lines);
+    println!("{} lines, {} bytes", lines, log.len());
+    let mut report = Report::default();
+
+    let mut model = ComposerModel::new();
+    let paste = time(|| {
+        model.replace_text(Utf16String::from_str(&log)).unwrap();
+    });
+    report.add("paste", paste, DOCUMENT_TARGET);
+
+    let end = model.state.dom.text_len();
+    report.add(
+        "type at the end",
+        type_at(&mut model, end),
+        KEYSTROKE_TARGET,
+    );
+    report.add(
+        "type in the middle",
+        type_at(&mut model, end / 2),
+        KEYSTROKE_TARGET,
+    );
+    report.add(
+        "backspace",
+        edit_at(&mut model, end / 2, |model| {
+            model.backspace().unwrap();
+        }),
+        KEYSTROKE_TARGET,
+    );
+    report.add(
+        "delete",
+        edit_at(&mut model, end / 4, |model| {
+            model.delete().unwrap();
+        }),
+        KEYSTROKE_TARGET,
+    );
+    report.add(
+        "enter",
+        edit_at(&mut model, end / 2, |model| {
+            model.enter().unwrap();
+        }),
+        KEYSTROKE_TARGET,
+    );
+    report.add(
+        "undo",
+        time_per_key(|| {
+            model.undo().unwrap();
+        }),
+        KEYSTROKE_TARGET,
+    );
+
+    let html = model.get_content_as_html();
+    let mut loaded = ComposerModel::new();
+    let load = time(|| {
+        loaded.set_content_from_html(&html).unwrap();
+    });
+    report.add("load html", load, DOCUMENT_TARGET);
+    report.add(
+        "type in loaded html",
+        type_at(&mut loaded, end / 2),
+        KEYSTROKE_TARGET,
+    );
+
+    report.print_summary();
+}
+
+fn type_at(model: &mut ComposerModel<Utf16String>, pos: usize) -> Duration {
+    edit_at(model, pos, |model| {
+        model.replace_text(Utf16String::from_str("x")).unwrap();
+    })
+}
+
+fn edit_at(
+    model: &mut ComposerModel<Utf16String>,
+    pos: usize,
+    mut edit: impl FnMut(&mut ComposerModel<Utf16String>),
+) -> Duration {
+    model
+        .select(Location::from(pos), Location::from(pos))
+        .unwrap();
+    time_per_key(|| edit(model))
+}
+
+/// The median time of a keystroke, so that a few slow ones, e.g. while the
+/// machine is busy with something else, don't change the result.
+fn time_per_key(mut f: impl FnMut()) -> Duration {
+    let mut timings: Vec<Duration> =
+        (0..KEYSTROKES).map(|_| time(&mut f)).collect();
+    timings.sort();
+    timings[KEYSTROKES / 2]
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+#[derive(Default)]
+struct Report {
+    over_target: Vec<&'static str>,
+}
+
+impl Report {
+    fn add(
+        &mut self,
+        name: &'static str,
+        duration: Duration,
+        target: Duration,
+    ) {
+        let verdict = if duration <= target {
+            ""
+        } else {
+            self.over_target.push(name);
+            "  OVER TARGET"
+        };
+        println!("{name:<24}{duration:>12.2?} (target {target:.2?}){verdict}");
+    }
+
+    fn print_summary(&self) {
+        if self.over_target.is_empty() {
+            println!("All timings are within their targets");
+        } else {
+            println!("Over target: {}", self.over_target.join(", "));
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+
+/// A slash command registered by the client, e.g. `/invite <user> [reason]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandDefinition {
+    /// The command name, without the leading slash.
+    pub name: String,
+    pub arguments: Vec<CommandArgumentDefinition>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandArgumentDefinition {
+    pub name: String,
+    pub kind: CommandArgumentKind,
+    /// Optional arguments may be omitted, but only at the end of the command.
+    pub optional: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandArgumentKind {
+    /// A single whitespace-separated word.
+    Word,
+    /// A user mention pill, or a user ID typed as plain text.
+    User,
+    /// A room mention pill, or a room ID or alias typed as plain text.
+    Room,
+    /// The rest of the message. Must be the last argument.
+    Text,
+}
+
+/// A command found in the composer content, with its arguments parsed
+/// according to the matching [CommandDefinition].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedCommand {
+    pub name: String,
+    pub arguments: Vec<ParsedCommandArgument>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsedCommandArgument {
+    pub name: String,
+    pub value: CommandArgumentValue,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandArgumentValue {
+    Text(String),
+    User {
+        user_id: String,
+        display_name: String,
+    },
+    Room {
+        room_id_or_alias: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommandParseError {
+    /// A required argument, named here, was not provided.
+    MissingArgument(String),
+    /// The argument named here doesn't match its expected kind.
+    InvalidArgument(String),
+    /// More arguments than the command accepts were provided. Contains the
+    /// first unexpected one.
+    UnexpectedArgument(String),
+}
+
+impl Display for CommandParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommandParseError::MissingArgument(name) => {
+                write!(f, "missing argument: {name}")
+            }
+            CommandParseError::InvalidArgument(name) => {
+                write!(f, "invalid argument: {name}")
+            }
+            CommandParseError::UnexpectedArgument(value) => {
+                write!(f, "unexpected argument: {value}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommandParseError {}
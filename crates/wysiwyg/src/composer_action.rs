@@ -30,4 +30,7 @@ pub enum ComposerAction {
     Unindent,
     CodeBlock,
     Quote,
+    /// Disabled when the selection has no formatting, links or blocks to
+    /// clear with [crate::ComposerModel::clear_formatting].
+    ClearFormatting,
 }
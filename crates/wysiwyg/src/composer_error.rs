@@ -0,0 +1,121 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::dom::MarkdownError;
+use crate::{
+    CommandParseError, DomCreationError, HtmlParseError, MarkdownParseError,
+    UnicodeString,
+};
+
+/// The errors returned by [ComposerModel](crate::ComposerModel)
+/// operations. These include every [DomCreationError], so content that
+/// can't be parsed is reported the same way as any other failure.
+#[derive(Debug, Eq, PartialEq)]
+pub enum ComposerError {
+    /// The HTML content couldn't be parsed.
+    HtmlParseError(HtmlParseError),
+    /// The markdown content couldn't be parsed.
+    MarkdownParseError(MarkdownParseError),
+    /// The content couldn't be written as markdown, e.g. a list contains
+    /// something other than list items.
+    MarkdownExportError(String),
+    /// A formatting tag, named here, isn't one of the inline formats.
+    UnknownFormatType(String),
+    /// A regular expression, e.g. a find query or the rule of a
+    /// suggestion trigger, is invalid.
+    InvalidRegex(String),
+    /// The prefix of a suggestion trigger, given here, isn't a single
+    /// character.
+    InvalidSuggestionPrefix(String),
+    /// The content is a command whose arguments don't match its
+    /// definition.
+    CommandParseError(CommandParseError),
+    /// The operation failed unexpectedly, e.g. on a bug in the composer.
+    /// The content and selection are left as they were before it.
+    Internal(String),
+}
+
+impl Display for ComposerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComposerError::HtmlParseError(error) => {
+                write!(
+                    f,
+                    "could not parse html: {}",
+                    error.parse_errors.join(", ")
+                )
+            }
+            ComposerError::MarkdownParseError(error) => write!(f, "{error}"),
+            ComposerError::MarkdownExportError(message) => {
+                write!(f, "could not write markdown: {message}")
+            }
+            ComposerError::UnknownFormatType(tag) => {
+                write!(f, "unknown format type: {tag}")
+            }
+            ComposerError::InvalidRegex(message) => {
+                write!(f, "invalid regex: {message}")
+            }
+            ComposerError::InvalidSuggestionPrefix(prefix) => {
+                write!(
+                    f,
+                    "suggestion trigger prefix must be a single character: \
+                     {prefix}"
+                )
+            }
+            ComposerError::CommandParseError(error) => write!(f, "{error}"),
+            ComposerError::Internal(message) => {
+                write!(f, "internal error: {message}")
+            }
+        }
+    }
+}
+
+impl Error for ComposerError {}
+
+impl From<DomCreationError> for ComposerError {
+    fn from(error: DomCreationError) -> Self {
+        match error {
+            DomCreationError::HtmlParseError(error) => {
+                ComposerError::HtmlParseError(error)
+            }
+            DomCreationError::MarkdownParseError(error) => {
+                ComposerError::MarkdownParseError(error)
+            }
+        }
+    }
+}
+
+impl<S> From<MarkdownError<S>> for ComposerError
+where
+    S: UnicodeString,
+{
+    fn from(error: MarkdownError<S>) -> Self {
+        ComposerError::MarkdownExportError(error.to_string())
+    }
+}
+
+impl From<CommandParseError> for ComposerError {
+    fn from(error: CommandParseError) -> Self {
+        ComposerError::CommandParseError(error)
+    }
+}
+
+impl From<regex::Error> for ComposerError {
+    fn from(error: regex::Error) -> Self {
+        ComposerError::InvalidRegex(error.to_string())
+    }
+}
@@ -13,20 +13,31 @@
 // limitations under the License.
 
 pub mod base;
+pub mod block_moves;
+mod boundaries;
 pub mod code_block;
+pub mod commands;
 pub mod delete_text;
 pub mod example_format;
+pub mod find_replace;
 pub mod format;
 mod format_inline_code;
 pub mod hyperlinks;
+pub mod inspection;
+pub mod keymap;
 pub mod lists;
 pub mod mentions;
 pub mod menu_action;
 pub mod menu_state;
+pub mod mode;
 pub mod new_lines;
+pub mod offsets;
 pub mod quotes;
 pub mod replace_text;
 pub mod selection;
+pub mod stats;
+pub mod text_direction;
+pub mod text_transform;
 pub mod undo_redo;
 
 pub use base::ComposerModel;
@@ -14,15 +14,18 @@
 
 use crate::action_state::ActionState;
 use crate::composer_model::menu_state::MenuStateComputeType;
+use crate::composer_model::stats::PlainTextCounts;
 use crate::composer_state::ComposerState;
-use crate::dom::parser::markdown::markdown_html_parser::MarkdownHTMLParser;
+use crate::dom::parser::markdown::parse_markdown_with_dialect;
 use crate::dom::parser::parse;
+use crate::dom::to_markdown::MarkdownOptions;
 use crate::dom::to_plain_text::ToPlainText;
-use crate::dom::{Dom, DomCreationError, UnicodeString};
+use crate::dom::{Dom, UnicodeString};
 use crate::link_action::LinkActionUpdate;
 use crate::{
-    ComposerAction, ComposerUpdate, DomHandle, Location, ToHtml, ToMarkdown,
-    ToTree,
+    CommandDefinition, ComposerAction, ComposerError, ComposerMode,
+    ComposerUpdate, DomHandle, Keymap, Location, MarkdownDialect, MaxLength,
+    SuggestionTrigger, ToHtml, ToMarkdown, ToTree, TypographyRule,
 };
 use std::collections::{HashMap, HashSet};
 
@@ -45,6 +48,37 @@ where
 
     /// Suggestion patterns provided by the client at runtime
     pub(crate) custom_suggestion_patterns: HashSet<String>,
+
+    /// Prefix-based suggestion triggers provided by the client at runtime
+    pub(crate) custom_suggestion_triggers: Vec<SuggestionTrigger>,
+
+    /// Whether `:shortcode`s are suggested from the built-in emoji table
+    pub(crate) emoji_suggestions_enabled: bool,
+
+    /// Slash commands provided by the client at runtime
+    pub(crate) commands: Vec<CommandDefinition>,
+
+    /// Key bindings used by handle_key
+    pub(crate) keymap: Keymap,
+
+    /// Limit applied to text input, if any
+    pub(crate) max_length: Option<MaxLength>,
+
+    /// Plain text length of the blocks of the document, to check the limit
+    pub(crate) plain_text_counts: PlainTextCounts<S>,
+
+    /// Substitutions applied to typed text, e.g. smart quotes
+    pub(crate) typography_rules: Vec<TypographyRule>,
+
+    /// Markdown syntax used to import and export markdown
+    pub(crate) markdown_dialect: MarkdownDialect,
+
+    /// Whether the content is rich text or markdown source
+    pub(crate) mode: ComposerMode,
+
+    /// Whether a public operation is running, so the operations it calls
+    /// don't save the state again. See [Self::try_edit].
+    pub(crate) editing: bool,
 }
 
 impl<S> ComposerModel<S>
@@ -58,6 +92,16 @@ where
             next_states: Vec::new(),
             action_states: HashMap::new(), // TODO: Calculate state based on ComposerState
             custom_suggestion_patterns: HashSet::new(),
+            custom_suggestion_triggers: Vec::new(),
+            emoji_suggestions_enabled: false,
+            commands: Vec::new(),
+            keymap: Keymap::default(),
+            max_length: None,
+            plain_text_counts: PlainTextCounts::default(),
+            typography_rules: Vec::new(),
+            markdown_dialect: MarkdownDialect::default(),
+            mode: ComposerMode::default(),
+            editing: false,
         };
         instance.compute_menu_state(MenuStateComputeType::AlwaysUpdate);
         instance
@@ -70,31 +114,54 @@ where
             next_states: Vec::new(),
             action_states: HashMap::new(), // TODO: Calculate state based on ComposerState
             custom_suggestion_patterns: HashSet::new(),
+            custom_suggestion_triggers: Vec::new(),
+            emoji_suggestions_enabled: false,
+            commands: Vec::new(),
+            keymap: Keymap::default(),
+            max_length: None,
+            plain_text_counts: PlainTextCounts::default(),
+            typography_rules: Vec::new(),
+            markdown_dialect: MarkdownDialect::default(),
+            mode: ComposerMode::default(),
+            editing: false,
         }
     }
 
-    /// Create a UTF-16 model from an HTML string, or panic if HTML parsing
+    /// Create a UTF-16 model from an HTML string, or fail if HTML parsing
     /// fails.
     pub fn from_html(
         html: &str,
         start_codeunit: usize,
         end_codeunit: usize,
-    ) -> Self {
+    ) -> Result<Self, ComposerError> {
         let mut model = Self {
-            state: ComposerState {
-                dom: parse(html).expect("HTML parsing failed"),
-                start: Location::from(start_codeunit),
-                end: Location::from(end_codeunit),
-                toggled_format_types: Vec::new(),
-            },
+            state: ComposerState::default(),
             previous_states: Vec::new(),
             next_states: Vec::new(),
             action_states: HashMap::new(), // TODO: Calculate state based on ComposerState
             custom_suggestion_patterns: HashSet::new(),
+            custom_suggestion_triggers: Vec::new(),
+            emoji_suggestions_enabled: false,
+            commands: Vec::new(),
+            keymap: Keymap::default(),
+            max_length: None,
+            plain_text_counts: PlainTextCounts::default(),
+            typography_rules: Vec::new(),
+            markdown_dialect: MarkdownDialect::default(),
+            mode: ComposerMode::default(),
+            editing: false,
         };
-        model.compute_menu_state(MenuStateComputeType::AlwaysUpdate);
-        Self::post_process_dom(&mut model.state.dom);
-        model
+        model.try_edit(|model| {
+            model.state.dom =
+                parse(html).map_err(ComposerError::HtmlParseError)?;
+            model.state.start = Location::from(start_codeunit);
+            model.state.end = Location::from(end_codeunit);
+            model.clamp_selection();
+            model.compute_menu_state(MenuStateComputeType::AlwaysUpdate);
+            Self::post_process_dom(&mut model.state.dom);
+            Ok(())
+        })?;
+        Ok(model)
     }
 
     /// Replace the entire content of the model with given HTML string.
@@ -103,11 +170,22 @@ where
     pub fn set_content_from_html(
         &mut self,
         html: &S,
-    ) -> Result<ComposerUpdate<S>, DomCreationError> {
-        let dom = parse(&html.to_string())
-            .map_err(DomCreationError::HtmlParseError)?;
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let dom = parse(&html.to_string())
+                .map_err(ComposerError::HtmlParseError)?;
+            model.set_content_from_dom(dom)
+        })
+    }
 
-        self.state.dom = dom;
+    fn set_content_from_dom(
+        &mut self,
+        dom: Dom<S>,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.state.dom = match self.mode {
+            ComposerMode::Rich => dom,
+            ComposerMode::Plain => self.source_dom(&dom)?,
+        };
         self.previous_states.clear();
         self.next_states.clear();
         Self::post_process_dom(&mut self.state.dom);
@@ -116,19 +194,50 @@ where
         Ok(self.create_update_replace_all_with_menu_state())
     }
 
-    fn post_process_dom(dom: &mut Dom<S>) {
+    pub(crate) fn post_process_dom(dom: &mut Dom<S>) {
         dom.wrap_inline_nodes_into_paragraphs_if_needed(&DomHandle::root());
         dom.explicitly_assert_invariants();
     }
 
+    /// Check the invariants of the Dom, and that the selection is inside it.
+    pub fn explicitly_assert_invariants(&self) {
+        #[cfg(any(test, feature = "assert-invariants"))]
+        {
+            self.state.dom.assert_invariants();
+            let len = self.state.dom.text_len();
+            for location in [self.state.start, self.state.end] {
+                if usize::from(location) > len {
+                    panic!(
+                        "Selection {:?} is past the end of the document ({len})!\n{}",
+                        location,
+                        self.state.dom.to_tree()
+                    );
+                }
+            }
+        }
+    }
+
     pub fn set_content_from_markdown(
         &mut self,
         markdown: &S,
-    ) -> Result<ComposerUpdate<S>, DomCreationError> {
-        let html = MarkdownHTMLParser::to_html(markdown)
-            .map_err(DomCreationError::MarkdownParseError)?;
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let parsed =
+                parse_markdown_with_dialect(markdown, &model.markdown_dialect)
+                    .map_err(ComposerError::MarkdownParseError)?;
+            model.set_content_from_dom(parsed.dom)
+        })
+    }
 
-        self.set_content_from_html(&html)
+    /// Replace the markdown syntax used by
+    /// [ComposerModel::set_content_from_markdown] and
+    /// [ComposerModel::get_content_as_markdown].
+    pub fn set_markdown_dialect(&mut self, dialect: MarkdownDialect) {
+        self.markdown_dialect = dialect;
+    }
+
+    pub fn markdown_dialect(&self) -> &MarkdownDialect {
+        &self.markdown_dialect
     }
 
     pub fn set_custom_suggestion_patterns(
@@ -139,6 +248,24 @@ where
             HashSet::from_iter(custom_suggestion_patterns)
     }
 
+    /// Replace the prefix-based suggestion triggers. When several triggers
+    /// share a prefix, the first one whose rule matches wins. Exact custom
+    /// patterns and the built-in `@`, `#` and `/` patterns take precedence
+    /// over triggers.
+    pub fn set_custom_suggestion_triggers(
+        &mut self,
+        custom_suggestion_triggers: Vec<SuggestionTrigger>,
+    ) {
+        self.custom_suggestion_triggers = custom_suggestion_triggers
+    }
+
+    /// Suggest emoji for `:shortcode`s typed by the user, with a
+    /// [crate::MenuAction::EmojiSuggestion]. Off by default, and only available
+    /// with the `emoji` feature.
+    pub fn set_emoji_suggestions_enabled(&mut self, enabled: bool) {
+        self.emoji_suggestions_enabled = enabled
+    }
+
     pub fn action_states(&self) -> &HashMap<ComposerAction, ActionState> {
         &self.action_states
     }
@@ -175,7 +302,23 @@ where
         )
     }
 
+    /// Tidy up the Dom left by the last change before sending it. The text
+    /// doesn't change, so neither does the selection.
+    fn normalize_dom(&mut self) {
+        let (s, e) = self.safe_selection();
+        self.state.dom.normalize(s, e);
+    }
+
     pub(crate) fn create_update_replace_all(&mut self) -> ComposerUpdate<S> {
+        self.normalize_dom();
+        self.create_update_replace_all_as_is()
+    }
+
+    /// Like [Self::create_update_replace_all], for a state from the undo
+    /// history, which was normalized before it was saved.
+    pub(crate) fn create_update_replace_all_as_is(
+        &mut self,
+    ) -> ComposerUpdate<S> {
         #[cfg(any(test, feature = "assert-invariants"))]
         self.state.dom.assert_transaction_not_in_progress();
 
@@ -195,6 +338,7 @@ where
         #[cfg(any(test, feature = "assert-invariants"))]
         self.state.dom.assert_transaction_not_in_progress();
 
+        self.normalize_dom();
         ComposerUpdate::replace_all(
             self.state.dom.to_html(),
             self.state.start,
@@ -205,6 +349,47 @@ where
         )
     }
 
+    /// Run a public operation that can't fail, so nothing needs to be
+    /// saved to put back. See [ComposerModel::try_edit].
+    pub(crate) fn edit<T>(
+        &mut self,
+        edit: impl FnOnce(&mut Self) -> T,
+    ) -> Result<T, ComposerError> {
+        Ok(edit(self))
+    }
+
+    /// Run a public operation. If it fails, the content and selection are
+    /// put back as they were before the operation, and any undo steps it
+    /// added are dropped. Operations called by another one leave this to the
+    /// outermost one. Saving the state is cheap, as the Dom shares its nodes.
+    ///
+    /// Panics aren't caught: the bindings report them as internal errors,
+    /// after which the platforms create a new model.
+    pub(crate) fn try_edit<T>(
+        &mut self,
+        edit: impl FnOnce(&mut Self) -> Result<T, ComposerError>,
+    ) -> Result<T, ComposerError> {
+        if self.editing {
+            return edit(self);
+        }
+        let state = self.state.clone();
+        let previous_states_len = self.previous_states.len();
+        let next_states = self.next_states.clone();
+        let action_states = self.action_states.clone();
+        let mode = self.mode;
+        self.editing = true;
+        let result = edit(self);
+        self.editing = false;
+        if result.is_err() {
+            self.state = state;
+            self.previous_states.truncate(previous_states_len);
+            self.next_states = next_states;
+            self.action_states = action_states;
+            self.mode = mode;
+        }
+        result
+    }
+
     pub fn get_selection(&self) -> (Location, Location) {
         (self.state.start, self.state.end)
     }
@@ -214,15 +399,33 @@ where
     }
 
     pub fn get_content_as_message_html(&self) -> S {
-        self.state.dom.to_message_html()
+        match self.mode {
+            ComposerMode::Rich => self.state.dom.to_message_html(),
+            ComposerMode::Plain => self.plain_message_html(),
+        }
     }
 
-    pub fn get_content_as_markdown(&self) -> S {
-        self.state.dom.to_markdown().unwrap()
+    pub fn get_content_as_markdown(&self) -> Result<S, ComposerError> {
+        self.content_as_markdown(false)
     }
 
-    pub fn get_content_as_message_markdown(&self) -> S {
-        self.state.dom.to_message_markdown().unwrap()
+    pub fn get_content_as_message_markdown(&self) -> Result<S, ComposerError> {
+        self.content_as_markdown(true)
+    }
+
+    fn content_as_markdown(
+        &self,
+        as_message: bool,
+    ) -> Result<S, ComposerError> {
+        if self.mode == ComposerMode::Plain {
+            return self.plain_source(as_message);
+        }
+        let mut markdown = S::default();
+        let options = MarkdownOptions::with_dialect(self.markdown_dialect);
+        self.state
+            .dom
+            .fmt_markdown(&mut markdown, &options, as_message)?;
+        Ok(markdown)
     }
 
     pub fn get_content_as_plain_text(&self) -> S {
@@ -237,9 +440,8 @@ where
         self.state.dom.to_tree()
     }
 
-    pub fn clear(&mut self) -> ComposerUpdate<S> {
-        self.set_content_from_html(&"".into())
-            .expect("empty content")
+    pub fn clear(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| model.set_content_from_dom(Dom::new(Vec::new())))
     }
 }
 
@@ -263,12 +465,39 @@ mod test {
         assert_eq!(model.state.dom.to_string(), "foo <b>bar</b>")
     }
 
+    #[test]
+    fn failed_nested_edit_is_put_back_by_the_outer_edit() {
+        let mut model = cm("a|");
+        let result: Result<(), _> = model.try_edit(|model| {
+            model.push_state_to_history();
+            model.do_replace_text(utf16("b"))?;
+            model.try_edit(|model| {
+                model.do_replace_text(utf16("c"))?;
+                Err(ComposerError::Internal("broken edit".into()))
+            })
+        });
+        assert_eq!(result, Err(ComposerError::Internal("broken edit".into())));
+        assert_eq!(tx(&model), "a|");
+        assert!(model.previous_states.is_empty());
+    }
+
+    #[test]
+    fn failed_edit_leaves_the_model_unchanged() {
+        let mut model = cm("a|");
+        let result: Result<(), _> = model.try_edit(|model| {
+            model.do_replace_text(utf16("b"))?;
+            Err(ComposerError::UnknownFormatType("blink".into()))
+        });
+        assert!(result.is_err());
+        assert_eq!(tx(&model), "a|");
+    }
+
     #[test]
     fn action_states_are_reported() {
         let mut model = ComposerModel::new();
-        model.replace_text(Utf16String::from("a"));
-        model.select(Location::from(0), Location::from(1));
-        model.bold();
+        model.replace_text(Utf16String::from("a")).unwrap();
+        model.select(Location::from(0), Location::from(1)).unwrap();
+        model.bold().unwrap();
 
         assert!(model.action_is_reversed(ComposerAction::Bold));
         assert!(model.action_is_enabled(ComposerAction::StrikeThrough));
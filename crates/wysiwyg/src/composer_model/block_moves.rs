@@ -0,0 +1,196 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dom::nodes::dom_node::DomNodeKind;
+use crate::dom::nodes::{ContainerNodeKind, DomNode};
+use crate::dom::DomLocation;
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, DomHandle, UnicodeString,
+};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// Swap the blocks touched by the selection with the block right before
+    /// them. List items only move within their list and paragraphs within
+    /// their quote, so nothing happens if they're already first in it. The
+    /// selection follows the moved content.
+    pub fn move_block_up(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let Some((parent, first, last)) = model.selected_blocks() else {
+                return Ok(ComposerUpdate::keep());
+            };
+            if first == 0 {
+                return Ok(ComposerUpdate::keep());
+            }
+            model.push_state_to_history();
+            model.move_blocks(&parent, first, first - 1, last, first - 1)
+        })
+    }
+
+    /// Swap the blocks touched by the selection with the block right after
+    /// them. See [Self::move_block_up].
+    pub fn move_block_down(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let Some((parent, first, last)) = model.selected_blocks() else {
+                return Ok(ComposerUpdate::keep());
+            };
+            if last + 1
+                >= model.state.dom.lookup_container(&parent).children().len()
+            {
+                return Ok(ComposerUpdate::keep());
+            }
+            model.push_state_to_history();
+            model.move_blocks(&parent, first, last + 1, first, first + 1)
+        })
+    }
+
+    /// Insert a copy of the blocks touched by the selection right after
+    /// them, and move the selection to the copy.
+    pub fn duplicate_block(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let Some((parent, first, last)) = model.selected_blocks() else {
+                return Ok(ComposerUpdate::keep());
+            };
+            model.push_state_to_history();
+            let old_position =
+                model.block_position(&parent.child_handle(first));
+            let count = last - first + 1;
+            if model.state.dom.lookup_container(&parent).is_list() {
+                model
+                    .state
+                    .dom
+                    .duplicate_list_items(&parent, first, count)?;
+            } else {
+                model.state.dom.duplicate_children(&parent, first, count);
+            }
+            let new_position =
+                model.block_position(&parent.child_handle(last + 1));
+            model.offset_selection(new_position, old_position);
+            Ok(model.create_update_replace_all())
+        })
+    }
+
+    /// Move the sibling block at index `from` to index `to`, so the selected
+    /// blocks that started at index `first` now start at `moved_first`.
+    fn move_blocks(
+        &mut self,
+        parent: &DomHandle,
+        first: usize,
+        from: usize,
+        to: usize,
+        moved_first: usize,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        let old_position = self.block_position(&parent.child_handle(first));
+        if self.state.dom.lookup_container(parent).is_list() {
+            self.state.dom.move_list_item(parent, from, to)?;
+        } else {
+            self.state.dom.move_child(parent, from, to);
+        }
+        let new_position =
+            self.block_position(&parent.child_handle(moved_first));
+        self.offset_selection(new_position, old_position);
+        Ok(self.create_update_replace_all())
+    }
+
+    /// Find the sibling blocks touched by the selection. Returns the handle
+    /// of their parent and the indexes of the first and last of them.
+    fn selected_blocks(&self) -> Option<(DomHandle, usize, usize)> {
+        let (s, e) = self.safe_selection();
+        let collapsed = s == e;
+        let range = self.state.dom.find_range(s, e);
+        let is_empty_container = |l: &DomLocation| {
+            matches!(
+                self.state.dom.lookup_node(&l.node_handle),
+                DomNode::Container(c) if c.children().is_empty()
+            )
+        };
+        let blocks: Vec<DomHandle> = range
+            .locations
+            .iter()
+            .filter(|l| {
+                (l.is_leaf() && l.touches_range(collapsed))
+                    // An empty block holds the selection if it starts or
+                    // ends there
+                    || (is_empty_container(l)
+                        && (l.touches_range(collapsed)
+                            || (s..=e).contains(&l.position)))
+            })
+            .filter_map(|l| self.movable_block_for(&l.node_handle))
+            .collect();
+        let first = blocks.iter().min()?.raw();
+        let last = blocks.iter().max()?.raw();
+
+        let shared = first.iter().zip(last).take_while(|(a, b)| a == b).count();
+        if shared == first.len() || shared == last.len() {
+            // One block contains the other, move the outer one.
+            let outer = if first.len() < last.len() {
+                first
+            } else {
+                last
+            };
+            let outer = DomHandle::from_raw(outer.clone());
+            let index = outer.index_in_parent();
+            return Some((outer.parent_handle(), index, index));
+        }
+        let parent = DomHandle::from_raw(first[..shared].to_vec());
+        Some((parent, first[shared], last[shared]))
+    }
+
+    /// The closest ancestor of the node at `handle`, or the node itself,
+    /// that can be moved. Paragraphs inside list items and code blocks move
+    /// along with them.
+    fn movable_block_for(&self, handle: &DomHandle) -> Option<DomHandle> {
+        let mut handle = handle.clone();
+        while !handle.is_root() {
+            let parent_kind = self.state.dom.parent(&handle).kind();
+            let movable = match self.state.dom.lookup_node(&handle).kind() {
+                DomNodeKind::Paragraph => !matches!(
+                    parent_kind,
+                    ContainerNodeKind::ListItem | ContainerNodeKind::CodeBlock
+                ),
+                DomNodeKind::ListItem
+                | DomNodeKind::CodeBlock
+                | DomNodeKind::Quote => true,
+                _ => false,
+            };
+            if movable {
+                return Some(handle);
+            }
+            handle = handle.parent_handle();
+        }
+        None
+    }
+
+    fn block_position(&self, handle: &DomHandle) -> usize {
+        self.state
+            .dom
+            .find_range(0, usize::MAX)
+            .find_location(handle)
+            .map_or(0, |l| l.position)
+    }
+
+    fn offset_selection(&mut self, new_position: usize, old_position: usize) {
+        let offset = new_position as isize - old_position as isize;
+        self.state.start += offset;
+        self.state.end += offset;
+    }
+}
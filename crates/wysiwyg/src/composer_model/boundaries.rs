@@ -0,0 +1,367 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Word, sentence and paragraph boundaries, computed from the model rather
+//! than from the platform's view of the text.
+
+use std::collections::BTreeMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::composer_model::delete_text::Direction;
+use crate::dom::nodes::{DomNode, TextNode};
+use crate::dom::unicode_string::{UnicodeStr, UnicodeStrExt, UnicodeStringExt};
+use crate::{ComposerModel, Granularity, UnicodeString};
+
+/// Stands in for a mention when segmenting text, so that mentions are
+/// treated as a single, unbreakable unit.
+pub(crate) const MENTION_PLACEHOLDER: char = '\u{FFFC}';
+
+/// A paragraph of the document: the text and mentions between two line
+/// breaks or block boundaries.
+pub(crate) struct TextRun {
+    pub start: usize,
+    pub end: usize,
+    /// The content of the run, with mentions replaced by
+    /// [MENTION_PLACEHOLDER].
+    text: String,
+    /// The byte offset in `text` and code unit position in the model of
+    /// each char in the run.
+    offsets: Vec<(usize, usize)>,
+}
+
+/// A segment of a [TextRun], as code unit positions.
+pub(crate) struct Segment {
+    pub start: usize,
+    pub end: usize,
+    /// Whether the segment contains a letter, digit or mention, as opposed
+    /// to just whitespace or punctuation.
+    pub is_word: bool,
+}
+
+impl TextRun {
+    fn new(start: usize) -> Self {
+        Self {
+            start,
+            end: start,
+            text: String::new(),
+            offsets: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, c: char, len: usize) {
+        self.offsets.push((self.text.len(), self.end));
+        self.text.push(c);
+        self.end += len;
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The code unit position in the model of the char starting at `byte`
+    /// in [TextRun::text], or the end of the run.
+    pub fn position_of_byte(&self, byte: usize) -> usize {
+        match self.offsets.binary_search_by_key(&byte, |(b, _)| *b) {
+            Ok(i) => self.offsets[i].1,
+            Err(_) => self.end,
+        }
+    }
+
+    fn segment(&self, start: usize, text: &str) -> Segment {
+        Segment {
+            start: self.position_of_byte(start),
+            end: self.position_of_byte(start + text.len()),
+            is_word: text
+                .chars()
+                .any(|c| c.is_alphanumeric() || c == MENTION_PLACEHOLDER),
+        }
+    }
+
+    pub fn graphemes(&self) -> Vec<Segment> {
+        self.text
+            .grapheme_indices(true)
+            .map(|(i, g)| self.segment(i, g))
+            .collect()
+    }
+
+    pub fn words(&self) -> Vec<Segment> {
+        self.text
+            .split_word_bound_indices()
+            .map(|(i, w)| self.segment(i, w))
+            .collect()
+    }
+
+    /// Sentences, excluding any whitespace that follows them.
+    pub fn sentences(&self) -> Vec<Segment> {
+        self.text
+            .split_sentence_bound_indices()
+            .map(|(i, s)| self.segment(i, s.trim_end()))
+            .filter(|s| s.start < s.end)
+            .collect()
+    }
+
+    pub fn contains(&self, pos: usize) -> bool {
+        self.start <= pos && pos <= self.end
+    }
+}
+
+/// Find the segment at `pos`, preferring a word that ends at `pos` over
+/// whitespace or punctuation that starts there.
+pub(crate) fn segment_at(segments: &[Segment], pos: usize) -> Option<&Segment> {
+    let index = segments
+        .iter()
+        .position(|s| s.start <= pos && pos < s.end)
+        .or_else(|| segments.iter().rposition(|s| s.end <= pos))?;
+    let segment = &segments[index];
+    if !segment.is_word && segment.start == pos && index > 0 {
+        let previous = &segments[index - 1];
+        if previous.is_word && previous.end == pos {
+            return Some(previous);
+        }
+    }
+    Some(segment)
+}
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// Split the document into runs of text and mentions separated by line
+    /// breaks and block boundaries. There is always at least one run, and
+    /// runs are empty for empty paragraphs.
+    pub(crate) fn text_runs(&self) -> Vec<TextRun> {
+        self.text_runs_in(0, self.state.dom.text_len())
+    }
+
+    /// Like [Self::text_runs], but only for the text from `start` to `end`,
+    /// which must be the edges of blocks.
+    fn text_runs_in(&self, start: usize, end: usize) -> Vec<TextRun> {
+        let range = self.state.dom.find_range(start, end);
+        let mut runs = Vec::new();
+        let mut run = TextRun::new(start);
+        let leaves = range
+            .leaves()
+            .filter(|l| start <= l.position && l.position <= end);
+        for location in leaves {
+            // Any gap before this leaf is made of block boundaries
+            while run.end < location.position {
+                let next = TextRun::new(run.end + 1);
+                runs.push(std::mem::replace(&mut run, next));
+            }
+            match self.state.dom.lookup_node(&location.node_handle) {
+                DomNode::Text(text_node) => {
+                    let data = text_node.data();
+                    for c in data.chars() {
+                        run.push(c, data.char_len(&c));
+                    }
+                }
+                DomNode::Mention(_) => {
+                    run.push(MENTION_PLACEHOLDER, location.length)
+                }
+                DomNode::LineBreak(_) => {
+                    let next = TextRun::new(location.position + 1);
+                    runs.push(std::mem::replace(&mut run, next));
+                }
+                DomNode::Container(_) => {}
+            }
+        }
+        while run.end < end {
+            let next = TextRun::new(run.end + 1);
+            runs.push(std::mem::replace(&mut run, next));
+        }
+        runs.push(run);
+        runs
+    }
+
+    /// The start and end of the deepest block containing `pos`, or of the
+    /// whole document if there are no blocks.
+    fn block_range_at(&self, pos: usize) -> (usize, usize) {
+        let range = self.state.dom.find_range(pos, pos);
+        match range.deepest_block_node(None) {
+            Some(location) => {
+                (location.position, location.position + location.length)
+            }
+            None => (0, self.state.dom.text_len()),
+        }
+    }
+
+    /// The runs of the block containing `pos` and of the blocks either side
+    /// of it, which is as far as any boundary from `pos` can be.
+    fn text_runs_around(&self, pos: usize) -> Vec<TextRun> {
+        let (mut start, mut end) = self.block_range_at(pos);
+        if start > 0 {
+            start = self.block_range_at(start - 1).0;
+        }
+        if end < self.state.dom.text_len() {
+            end = self.block_range_at(end + 1).1;
+        }
+        self.text_runs_in(start, end)
+    }
+
+    /// The run containing `pos`. If `pos` is on a block boundary this is
+    /// the run before it.
+    pub(crate) fn text_run_at(&self, pos: usize) -> TextRun {
+        let (start, end) = self.block_range_at(pos);
+        let mut runs = self.text_runs_in(start, end);
+        let index = runs
+            .iter()
+            .position(|run| run.contains(pos))
+            .unwrap_or(runs.len() - 1);
+        runs.swap_remove(index)
+    }
+
+    /// The positions near `pos` the cursor can stop at when moving by
+    /// `granularity` in `direction`, in ascending order.
+    pub(crate) fn boundaries(
+        &self,
+        pos: usize,
+        granularity: Granularity,
+        direction: &Direction,
+    ) -> Vec<usize> {
+        let runs = self.text_runs_around(pos);
+        let mut boundaries = Vec::new();
+        for run in &runs {
+            let segments = match granularity {
+                Granularity::Character => run.graphemes(),
+                Granularity::Word => run.words(),
+                Granularity::Sentence => run.sentences(),
+                Granularity::Paragraph | Granularity::Document => Vec::new(),
+            };
+            for segment in segments {
+                if granularity == Granularity::Character {
+                    boundaries.push(segment.start);
+                } else if segment.is_word {
+                    boundaries.push(match direction {
+                        Direction::Forwards => segment.end,
+                        Direction::Backwards => segment.start,
+                    });
+                }
+            }
+        }
+        if granularity == Granularity::Document {
+            boundaries.push(0);
+            boundaries.push(self.state.dom.text_len());
+        } else {
+            for run in &runs {
+                if granularity == Granularity::Character {
+                    boundaries.push(run.start);
+                    boundaries.push(run.end);
+                } else {
+                    boundaries.push(match direction {
+                        Direction::Forwards => run.end,
+                        Direction::Backwards => run.start,
+                    });
+                }
+            }
+        }
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        boundaries
+    }
+
+    /// The position reached by moving from `pos` by `granularity` in
+    /// `direction`, or None if there is nowhere to move to.
+    pub(crate) fn next_boundary(
+        &self,
+        pos: usize,
+        granularity: Granularity,
+        direction: Direction,
+    ) -> Option<usize> {
+        if granularity == Granularity::Character {
+            // Outside text, every code unit is a boundary
+            return self.grapheme_boundary(pos, &direction).or_else(|| {
+                match direction {
+                    Direction::Forwards => {
+                        (pos < self.state.dom.text_len()).then_some(pos + 1)
+                    }
+                    Direction::Backwards => pos.checked_sub(1),
+                }
+            });
+        }
+        let boundaries = self.boundaries(pos, granularity, &direction);
+        match direction {
+            Direction::Forwards => boundaries.into_iter().find(|b| *b > pos),
+            Direction::Backwards => {
+                boundaries.into_iter().rev().find(|b| *b < pos)
+            }
+        }
+    }
+
+    /// The grapheme boundary before or after `pos`, found from the text
+    /// nodes at `pos` rather than from the whole document. Returns None if
+    /// there is no text in that direction, e.g. at a block boundary.
+    fn grapheme_boundary(
+        &self,
+        pos: usize,
+        direction: &Direction,
+    ) -> Option<usize> {
+        // Adjacent text nodes by their range, as a grapheme can span them
+        // when e.g. a combining mark is formatted differently to its base
+        let mut nodes = BTreeMap::new();
+        let mut edge = pos;
+        loop {
+            let count = nodes.len();
+            nodes.extend(self.text_nodes_at(edge));
+            if nodes.len() == count {
+                return (edge != pos).then_some(edge);
+            }
+            let (&(start, _), &first) = nodes.iter().next()?;
+            let merged;
+            let text_node = if nodes.len() == 1 {
+                first
+            } else {
+                let mut data = S::default();
+                for node in nodes.values() {
+                    data.push(node.data());
+                }
+                merged = TextNode::from(data);
+                &merged
+            };
+            let len =
+                text_node.grapheme_len_at_offset(pos - start, direction)?;
+            let boundary = match direction {
+                Direction::Forwards => pos + len,
+                Direction::Backwards => pos - len,
+            };
+            let end = start + text_node.data().len();
+            if boundary != start && boundary != end {
+                return Some(boundary);
+            }
+            // The grapheme may continue in the next text node
+            edge = boundary;
+        }
+    }
+
+    /// The text nodes starting at, ending at or containing `pos`, keyed by
+    /// their range.
+    fn text_nodes_at(&self, pos: usize) -> Vec<((usize, usize), &TextNode<S>)> {
+        self.state
+            .dom
+            .find_range(pos, pos)
+            .leaves()
+            .filter_map(|location| {
+                match self.state.dom.lookup_node(&location.node_handle) {
+                    DomNode::Text(text_node) => Some((
+                        (
+                            location.position,
+                            location.position + location.length,
+                        ),
+                        text_node,
+                    )),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
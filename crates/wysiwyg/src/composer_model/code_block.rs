@@ -15,21 +15,33 @@
 use crate::dom::nodes::dom_node::DomNodeKind::*;
 use crate::dom::nodes::{ContainerNode, ContainerNodeKind, DomNode};
 use crate::dom::{DomHandle, DomLocation, Range};
-use crate::{ComposerAction, ComposerModel, ComposerUpdate, UnicodeString};
+use crate::{
+    ComposerAction, ComposerError, ComposerModel, ComposerUpdate,
+    InlineFormatType, UnicodeString,
+};
 
 impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
-    pub fn code_block(&mut self) -> ComposerUpdate<S> {
-        if self.action_is_reversed(ComposerAction::CodeBlock) {
-            self.remove_code_block()
-        } else {
-            self.add_code_block()
-        }
+    pub fn code_block(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.refuses_formatting() {
+                return Ok(ComposerUpdate::keep());
+            }
+            if model.action_is_reversed(ComposerAction::CodeBlock) {
+                Ok(model.remove_code_block())
+            } else {
+                model.add_code_block()
+            }
+        })
     }
 
-    fn add_code_block(&mut self) -> ComposerUpdate<S> {
+    fn add_code_block(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        // Text typed in a code block can't be inline code
+        self.state
+            .toggled_format_types
+            .retain(|format| *format != InlineFormatType::InlineCode);
         let (s, e) = self.safe_selection();
         let Some(wrap_result) =
             self.state.dom.find_nodes_to_wrap_in_block(s, e)
@@ -40,53 +52,54 @@ where
             let node = DomNode::new_code_block(vec![DomNode::new_paragraph(
                 Vec::new(),
             )]);
-            if leaves.is_empty() {
-                if let Some(deepest_block_location) =
-                    range.deepest_block_node(None)
-                {
-                    let mut block_node = self
-                        .state
-                        .dom
-                        .remove(&deepest_block_location.node_handle);
-                    let node = if block_node.is_list_item() {
-                        let list_item = block_node.as_container_mut().unwrap();
-                        let children = list_item.remove_children();
-                        let children =
-                            if children.iter().all(|c| !c.is_block_node()) {
-                                vec![DomNode::new_paragraph(children)]
-                            } else {
-                                children
-                            };
-                        list_item
-                            .append_child(DomNode::new_code_block(children));
-                        block_node
-                    } else {
-                        DomNode::new_code_block(vec![block_node])
-                    };
-                    self.state
-                        .dom
-                        .insert_at(&deepest_block_location.node_handle, node);
-                } else {
-                    self.state.dom.append_at_end_of_document(node);
-                }
-            } else {
-                let first_leaf_loc = leaves.first().unwrap();
+            if let Some(first_leaf_loc) = leaves.first() {
                 let insert_at = if first_leaf_loc.is_start() {
                     first_leaf_loc.node_handle.next_sibling()
                 } else {
                     first_leaf_loc.node_handle.clone()
                 };
                 self.state.dom.insert_at(&insert_at, node);
+            } else if let Some(deepest_block_location) =
+                range.deepest_block_node(None)
+            {
+                let mut block_node =
+                    self.state.dom.remove(&deepest_block_location.node_handle);
+                let node = if let Some(list_item) =
+                    block_node.as_container_mut().filter(|c| c.is_list_item())
+                {
+                    let children = list_item.remove_children();
+                    let children =
+                        if children.iter().all(|c| !c.is_block_node()) {
+                            vec![DomNode::new_paragraph(children)]
+                        } else {
+                            children
+                        };
+                    list_item.append_child(DomNode::new_code_block(children));
+                    block_node
+                } else {
+                    DomNode::new_code_block(vec![block_node])
+                };
+                self.state
+                    .dom
+                    .insert_at(&deepest_block_location.node_handle, node);
+                self.remove_inline_code_in(&deepest_block_location.node_handle);
+            } else {
+                self.state.dom.append_at_end_of_document(node);
             }
-            return self.create_update_replace_all();
+            return Ok(self.create_update_replace_all());
         };
         let parent_handle = wrap_result.ancestor_handle;
         let start_handle = wrap_result.start_handle;
         let end_handle = wrap_result.end_handle;
         let range = wrap_result.range;
         let leaves: Vec<&DomLocation> = range.leaves().collect();
-        let first_leaf = leaves.first().unwrap();
-        let last_leaf = leaves.last().unwrap();
+        let (Some(first_leaf), Some(last_leaf)) =
+            (leaves.first(), leaves.last())
+        else {
+            return Err(ComposerError::Internal(
+                "Nodes to wrap in a code block must have leaves".into(),
+            ));
+        };
 
         let mut subtree = self.state.dom.split_sub_tree_between(
             &start_handle,
@@ -131,7 +144,7 @@ where
         // Merge any nodes that need it
         self.merge_adjacent_code_blocks(&insert_at_handle);
 
-        self.create_update_replace_all()
+        Ok(self.create_update_replace_all())
     }
 
     fn merge_adjacent_code_blocks(&mut self, handle: &DomHandle) -> DomHandle {
@@ -165,12 +178,31 @@ where
             handle = prev_code_block_handle;
         }
 
-        // Join any nodes inside the current code block
+        // Join any nodes inside the current code block, including the text
+        // left next to each other by unwrapping inline code
         self.state.dom.join_nodes_in_container(&handle);
+        self.state.dom.merge_text_nodes_in(&handle);
 
         handle
     }
 
+    /// Code blocks can't hold inline code, so unwrap any left in the empty
+    /// block that was just turned into one.
+    fn remove_inline_code_in(&mut self, handle: &DomHandle) {
+        self.state
+            .dom
+            .lookup_node(handle)
+            .iter_containers()
+            .filter(|c| {
+                c.is_formatting_node_of_type(&InlineFormatType::InlineCode)
+            })
+            .map(|c| c.handle())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .for_each(|h| self.state.dom.remove_and_keep_children(&h));
+    }
+
     fn remove_code_block(&mut self) -> ComposerUpdate<S> {
         let (s, e) = self.safe_selection();
         let range = self.state.dom.find_range(s, e);
@@ -230,8 +262,31 @@ where
             container.kind(),
             ContainerNodeKind::Paragraph | ContainerNodeKind::ListItem
         ) {
-            vec![DomNode::new_paragraph(children)]
-        } else if container.is_block_node() {
+            // Nested blocks have already become paragraphs, only wrap the
+            // inline nodes around them
+            let mut paragraphs = Vec::new();
+            let mut inline_nodes = Vec::new();
+            for child in children {
+                if child.is_block_node() {
+                    if !inline_nodes.is_empty() {
+                        paragraphs.push(DomNode::new_paragraph(
+                            std::mem::take(&mut inline_nodes),
+                        ));
+                    }
+                    paragraphs.push(child);
+                } else {
+                    inline_nodes.push(child);
+                }
+            }
+            if !inline_nodes.is_empty() || paragraphs.is_empty() {
+                paragraphs.push(DomNode::new_paragraph(inline_nodes));
+            }
+            paragraphs
+        } else if container.is_block_node()
+            || container
+                .is_formatting_node_of_type(&InlineFormatType::InlineCode)
+        {
+            // Code blocks can't hold inline code either
             children
         } else {
             vec![DomNode::Container(
@@ -267,21 +322,35 @@ mod test {
     #[test]
     fn add_code_block_to_empty_dom() {
         let mut model = cm("|");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>&nbsp;|</code></pre>");
     }
 
     #[test]
     fn add_code_block_to_simple_text() {
         let mut model = cm("Some text|");
-        model.code_block();
+        model.code_block().unwrap();
+        assert_eq!(tx(&model), "<pre><code>Some text|</code></pre>");
+    }
+
+    #[test]
+    fn add_code_block_removes_inline_code() {
+        let mut model = cm("Some <code>te</code>xt|");
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>Some text|</code></pre>");
     }
 
+    #[test]
+    fn add_code_block_to_empty_inline_code() {
+        let mut model = cm("<p>a</p><p><code>|</code></p>");
+        model.code_block().unwrap();
+        assert_eq!(tx(&model), "<p>a</p><pre><code>&nbsp;|</code></pre>");
+    }
+
     #[test]
     fn add_code_block_to_several_nodes() {
         let mut model = cm("Some text| <b>and bold </b><i>and italic</i>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<pre><code>Some text| <b>and bold </b><i>and italic</i></code></pre>"
@@ -292,7 +361,7 @@ mod test {
     fn add_code_block_to_several_nodes_in_single_paragraph() {
         let mut model =
             cm("<p>Some text| <b>and bold </b></p><p><i>and italic</i></p>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<pre><code>Some text| <b>and bold </b></code></pre><p><i>and italic</i></p>"
@@ -304,7 +373,7 @@ mod test {
         let mut model = cm(
             "<ul><li>Some text <b>and bold </b><i>|and italic</i></li></ul>",
         );
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><pre><code>Some text <b>and bold |</b><i>and italic</i></code></pre></li></ul>"
@@ -316,7 +385,7 @@ mod test {
         let mut model = cm(
             "<ul><li><p>Some text <b>and bold </b></p><p><i>and| italic</i></p></li></ul>",
         );
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><p>Some text <b>and bold&nbsp;</b></p><pre><code><i>and| italic</i></code></pre></li></ul>"
@@ -327,18 +396,26 @@ mod test {
     fn add_code_block_to_several_list_items() {
         let mut model =
             cm("<ul><li>{First item</li><li>Second}| item</li></ul>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<pre><code>{First item\nSecond}| item</code></pre>"
         );
     }
 
+    #[test]
+    fn add_code_block_to_list_items_holding_a_code_block() {
+        let mut model =
+            cm("<ul><li>{a</li><li><pre><code>b}|</code></pre></li></ul>");
+        model.code_block().unwrap();
+        assert_eq!(tx(&model), "<pre><code>{a\nb}|</code></pre>");
+    }
+
     #[test]
     fn add_code_block_to_several_lists() {
         let mut model =
             cm("<ul><li>{First item</li><li>Second item</li></ul><p>Some text</p><ul><li>Third}| item</li><li>Fourth one</li></ul>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>{First item\nSecond item\nSome text\nThird}| item</code></pre><ul><li>Fourth one</li></ul>");
     }
 
@@ -347,7 +424,7 @@ mod test {
         let mut model = cm(
             "<p>{Text</p><ul><li>First item</li><li>Second}| item</li></ul>",
         );
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<pre><code>{Text\nFirst item\nSecond}| item</code></pre>"
@@ -357,7 +434,7 @@ mod test {
     #[test]
     fn add_code_block_to_existing_code_block() {
         let mut model = cm("<p>{Text</p><pre><code>code}|</code></pre>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>{Text\ncode}|</code></pre>");
     }
 
@@ -365,7 +442,7 @@ mod test {
     fn add_code_block_to_existing_code_block_partially_selected() {
         let mut model =
             cm("<p>{Text</p><pre><code><b>code}|</b><i> and italic</i></code></pre>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<pre><code>{Text\n<b>code}|</b><i> and italic</i></code></pre>"
@@ -376,7 +453,7 @@ mod test {
     fn add_code_block_to_nested_item_in_formatting_node() {
         let mut model =
             cm("<p><b>Text</b></p><p><b><i>{in italic}|</i></b></p>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<p><b>Text</b></p><pre><code><b><i>{in italic}|</i></b></code></pre>"
@@ -388,17 +465,17 @@ mod test {
         let mut model = cm(
             "<p><u><b>Text</b></u></p><p><u><b><i>{in italic}|</i></b></u></p>",
         );
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
-            "<p><u><b>Text</b></u></p><pre><code><u><b><i>{in italic}|</i></b></u></code></pre>"
+            "<p><u><b>Text</b></u></p><pre><code><b><i><u>{in italic}|</u></i></b></code></pre>"
         );
     }
 
     #[test]
     fn add_code_block_to_quote() {
         let mut model = cm("<blockquote><p>Quot|e</p></blockquote>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>Quot|e</code></pre>");
     }
 
@@ -406,7 +483,7 @@ mod test {
     fn add_code_block_to_quote_text_before() {
         let mut model =
             cm("<p>Te{xt </p><blockquote><p>Quot}|e</p></blockquote>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>Te{xt \nQuot}|e</code></pre>");
     }
 
@@ -414,7 +491,7 @@ mod test {
     fn add_code_block_to_quote_text_after() {
         let mut model =
             cm("<blockquote><p>Quo{te</p></blockquote><p>Te}|xt</p>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>Quo{te\nTe}|xt</code></pre>");
     }
 
@@ -423,7 +500,7 @@ mod test {
         let mut model = cm(
             "<p>Text</p><pre><code><b>code|</b><i> and italic</i></code></pre>",
         );
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<p>Text</p><p><b>code|</b><i>&nbsp;and italic</i></p>"
@@ -434,7 +511,7 @@ mod test {
     fn remove_code_block_moves_its_children_and_restores_line_breaks() {
         let mut model =
             cm("<p>Text</p><pre><code>with|\nline\nbreaks</code></pre>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<p>Text</p><p>with|</p><p>line</p><p>breaks</p>"
@@ -445,7 +522,7 @@ mod test {
     fn remove_code_block_moves_its_children_and_keeps_selection_in_place() {
         let mut model =
             cm("<p>Text</p><pre><code>wi{th\nline\nbrea}|ks</code></pre>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<p>Text</p><p>wi{th</p><p>line</p><p>brea}|ks</p>"
@@ -455,23 +532,23 @@ mod test {
     #[test]
     fn test_creating_code_block_at_the_end_of_editor() {
         let mut model = cm("<p>Test</p><p>|</p>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<p>Test</p><pre><code>&nbsp;|</code></pre>");
     }
 
     #[test]
     fn creating_and_removing_code_block_works() {
         let mut model = cm("|");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<pre><code>&nbsp;|</code></pre>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;|</p>");
     }
 
     #[test]
     fn add_code_block_to_empty_list_item() {
         let mut model = cm("<ul><li>|</li></ul>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><pre><code>&nbsp;|</code></pre></li></ul>"
@@ -496,7 +573,7 @@ mod test {
         <p>|A</p>\
         <p>B</p>\
         <p>C</p>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "\
@@ -512,7 +589,7 @@ mod test {
         <p>A</p>\
         <p>B|</p>\
         <p>C</p>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "\
@@ -528,7 +605,7 @@ mod test {
         <p>A</p>\
         <p>B</p>\
         <p>|C</p>");
-        model.code_block();
+        model.code_block().unwrap();
         assert_eq!(
             tx(&model),
             "\
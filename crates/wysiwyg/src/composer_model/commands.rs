@@ -0,0 +1,287 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use matrix_mentions::MentionKind;
+
+use crate::dom::nodes::{MentionNode, MentionNodeKind};
+use crate::dom::unicode_string::UnicodeStr;
+use crate::{
+    CommandArgumentDefinition, CommandArgumentKind, CommandArgumentValue,
+    CommandDefinition, CommandParseError, ComposerError, ComposerModel,
+    DomNode, ParsedCommand, ParsedCommandArgument, UnicodeString,
+};
+
+const COMMAND_PREFIX: char = '/';
+
+/// A whitespace-separated part of the composer content.
+enum CommandToken {
+    Text(String),
+    User {
+        user_id: String,
+        display_name: String,
+    },
+    Room {
+        room_id_or_alias: String,
+    },
+}
+
+impl CommandToken {
+    fn from_mention<S: UnicodeString>(mention_node: &MentionNode<S>) -> Self {
+        match mention_node.kind() {
+            MentionNodeKind::MatrixUri { mention } => match mention.kind() {
+                MentionKind::User => Self::User {
+                    user_id: mention.mx_id().to_owned(),
+                    display_name: mention_node.display_text().to_string(),
+                },
+                MentionKind::Room(_) => Self::Room {
+                    room_id_or_alias: mention.mx_id().to_owned(),
+                },
+            },
+            MentionNodeKind::AtRoom => {
+                Self::Text(mention_node.display_text().to_string())
+            }
+        }
+    }
+
+    /// The text of this token as it appears in the plain text message.
+    fn plain_text(&self) -> &str {
+        match self {
+            Self::Text(text) => text,
+            Self::User { display_name, .. } => display_name,
+            Self::Room { room_id_or_alias } => room_id_or_alias,
+        }
+    }
+}
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// Replace the slash commands known to the composer. Only messages
+    /// starting with one of these commands are treated as commands.
+    pub fn set_commands(&mut self, commands: Vec<CommandDefinition>) {
+        self.commands = commands;
+    }
+
+    /// Whether the whole message is a registered slash command. Rich
+    /// formatting can't be applied to command messages.
+    pub fn is_command(&self) -> bool {
+        self.find_command().is_some()
+    }
+
+    /// If the whole message is a registered slash command, parse its
+    /// arguments according to the command definition. Returns None if the
+    /// message isn't a command, and [ComposerError::CommandParseError] if
+    /// its arguments don't match the definition.
+    pub fn parse_command(
+        &self,
+    ) -> Result<Option<ParsedCommand>, ComposerError> {
+        let Some(command) = self.find_command() else {
+            return Ok(None);
+        };
+        let tokens = self.command_tokens(usize::MAX);
+        let arguments =
+            Self::parse_arguments(&command.arguments, &tokens[1..])?;
+        Ok(Some(ParsedCommand {
+            name: command.name.clone(),
+            arguments,
+        }))
+    }
+
+    fn find_command(&self) -> Option<&CommandDefinition> {
+        if self.commands.is_empty() {
+            return None;
+        }
+        // Only the name is needed, so the rest of the content isn't read
+        let Some(CommandToken::Text(first)) =
+            self.command_tokens(1).into_iter().next()
+        else {
+            return None;
+        };
+        let name = first.strip_prefix(COMMAND_PREFIX)?;
+        self.commands.iter().find(|command| command.name == name)
+    }
+
+    /// Split the start of the content into at most `max_tokens` words and
+    /// mentions. Returns no tokens if the content doesn't start with the
+    /// command prefix.
+    fn command_tokens(&self, max_tokens: usize) -> Vec<CommandToken> {
+        let mut tokens = Vec::new();
+        let mut word = String::new();
+        let mut started = false;
+        for node in self.state.dom.iter() {
+            match node {
+                DomNode::Text(text_node) => {
+                    for c in text_node.data().chars() {
+                        if !started {
+                            if c != COMMAND_PREFIX {
+                                return Vec::new();
+                            }
+                            started = true;
+                        }
+                        if c.is_whitespace() {
+                            flush_word(&mut word, &mut tokens);
+                            if tokens.len() >= max_tokens {
+                                return tokens;
+                            }
+                        } else {
+                            word.push(c);
+                        }
+                    }
+                }
+                DomNode::Mention(mention_node) => {
+                    if !started {
+                        return Vec::new();
+                    }
+                    flush_word(&mut word, &mut tokens);
+                    tokens.push(CommandToken::from_mention(mention_node));
+                }
+                DomNode::LineBreak(_) => flush_word(&mut word, &mut tokens),
+                DomNode::Container(container) => {
+                    if container.is_block_node() {
+                        flush_word(&mut word, &mut tokens);
+                    }
+                }
+            }
+            if tokens.len() >= max_tokens {
+                break;
+            }
+        }
+        flush_word(&mut word, &mut tokens);
+        tokens.truncate(max_tokens);
+        tokens
+    }
+
+    fn parse_arguments(
+        definitions: &[CommandArgumentDefinition],
+        tokens: &[CommandToken],
+    ) -> Result<Vec<ParsedCommandArgument>, CommandParseError> {
+        let mut arguments = Vec::new();
+        let mut tokens = tokens.iter().peekable();
+        for definition in definitions {
+            if tokens.peek().is_none() {
+                if definition.optional {
+                    continue;
+                }
+                return Err(CommandParseError::MissingArgument(
+                    definition.name.clone(),
+                ));
+            }
+            let value = match definition.kind {
+                CommandArgumentKind::Text => {
+                    let text: Vec<&str> =
+                        tokens.by_ref().map(|t| t.plain_text()).collect();
+                    Some(CommandArgumentValue::Text(text.join(" ")))
+                }
+                CommandArgumentKind::Word => match tokens.next() {
+                    Some(CommandToken::Text(text)) => {
+                        Some(CommandArgumentValue::Text(text.clone()))
+                    }
+                    _ => None,
+                },
+                CommandArgumentKind::User => match tokens.next() {
+                    Some(CommandToken::User {
+                        user_id,
+                        display_name,
+                    }) => Some(CommandArgumentValue::User {
+                        user_id: user_id.clone(),
+                        display_name: display_name.clone(),
+                    }),
+                    Some(CommandToken::Text(text))
+                        if is_matrix_id(text, &['@']) =>
+                    {
+                        Some(CommandArgumentValue::User {
+                            user_id: text.clone(),
+                            display_name: text.clone(),
+                        })
+                    }
+                    _ => None,
+                },
+                CommandArgumentKind::Room => match tokens.next() {
+                    Some(CommandToken::Room { room_id_or_alias }) => {
+                        Some(CommandArgumentValue::Room {
+                            room_id_or_alias: room_id_or_alias.clone(),
+                        })
+                    }
+                    Some(CommandToken::Text(text))
+                        if is_matrix_id(text, &['#', '!']) =>
+                    {
+                        Some(CommandArgumentValue::Room {
+                            room_id_or_alias: text.clone(),
+                        })
+                    }
+                    _ => None,
+                },
+            };
+            let Some(value) = value else {
+                return Err(CommandParseError::InvalidArgument(
+                    definition.name.clone(),
+                ));
+            };
+            arguments.push(ParsedCommandArgument {
+                name: definition.name.clone(),
+                value,
+            });
+        }
+        if let Some(token) = tokens.next() {
+            return Err(CommandParseError::UnexpectedArgument(
+                token.plain_text().to_owned(),
+            ));
+        }
+        Ok(arguments)
+    }
+}
+
+fn flush_word(word: &mut String, tokens: &mut Vec<CommandToken>) {
+    if !word.is_empty() {
+        tokens.push(CommandToken::Text(std::mem::take(word)));
+    }
+}
+
+/// Whether the text looks like a Matrix ID with one of the given sigils,
+/// e.g. `@alice:matrix.org`.
+fn is_matrix_id(text: &str, sigils: &[char]) -> bool {
+    let mut chars = text.chars();
+    let Some(sigil) = chars.next() else {
+        return false;
+    };
+    sigils.contains(&sigil)
+        && chars
+            .as_str()
+            .split_once(':')
+            .is_some_and(|(local, server)| {
+                !local.is_empty() && !server.is_empty()
+            })
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_matrix_id;
+
+    #[test]
+    fn matrix_ids_are_detected() {
+        assert!(is_matrix_id("@alice:matrix.org", &['@']));
+        assert!(is_matrix_id("#room:matrix.org", &['#', '!']));
+        assert!(is_matrix_id("!abc:matrix.org", &['#', '!']));
+    }
+
+    #[test]
+    fn non_matrix_ids_are_rejected() {
+        assert!(!is_matrix_id("alice", &['@']));
+        assert!(!is_matrix_id("@alice", &['@']));
+        assert!(!is_matrix_id("@:matrix.org", &['@']));
+        assert!(!is_matrix_id("#room:matrix.org", &['@']));
+        assert!(!is_matrix_id("", &['@']));
+    }
+}
@@ -12,14 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dom::nodes::dom_node::DomNodeKind::{Link, ListItem};
+use crate::dom::nodes::dom_node::DomNodeKind::{
+    CodeBlock, Link, ListItem, Quote,
+};
 use crate::dom::nodes::text_node::CharType;
-use crate::dom::nodes::{DomNode, TextNode};
-use crate::dom::unicode_string::UnicodeStrExt;
+use crate::dom::nodes::DomNode;
 use crate::dom::{DomHandle, DomLocation, Range};
-use crate::{ComposerModel, ComposerUpdate, Location, UnicodeString};
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, Granularity, Location,
+    UnicodeString,
+};
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Direction {
     Forwards,
     Backwards,
@@ -32,19 +36,17 @@ impl Direction {
             Direction::Backwards => index - 1,
         }
     }
-    pub fn get_index_from_cursor(&self, index: usize) -> usize {
-        match self {
-            Direction::Forwards => index,
-            Direction::Backwards => index - 1,
-        }
-    }
 }
 
 impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
-    pub fn backspace(&mut self) -> ComposerUpdate<S> {
+    pub fn backspace(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| model.remove_backwards())
+    }
+
+    fn remove_backwards(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
         self.push_state_to_history();
         self.handle_non_editable_selection(&Direction::Backwards);
 
@@ -62,32 +64,49 @@ where
 
     /// Deletes the current selection, will return a keep in case where
     /// we don't have a selection
-    fn delete_selection(&mut self) -> ComposerUpdate<S> {
+    fn delete_selection(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
         if self.has_cursor() {
-            return ComposerUpdate::keep();
+            return Ok(ComposerUpdate::keep());
         }
 
         let (s, e) = self.safe_selection();
-        self.delete_in(s, e)
+        self.do_delete_in(s, e)
     }
 
     /// Allows deletion between two positions, regardless of argument order
-    fn delete_to_cursor(&mut self, position: usize) -> ComposerUpdate<S> {
+    fn delete_to_cursor(
+        &mut self,
+        position: usize,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         if self.has_selection() {
-            panic!("Can't delete from a position to a selection")
+            return Err(ComposerError::Internal(
+                "Can't delete from a position to a selection".into(),
+            ));
         }
 
         let (s, _) = self.safe_selection();
 
         if s < position {
-            self.delete_in(s, position)
+            self.do_delete_in(s, position)
         } else {
-            self.delete_in(position, s)
+            self.do_delete_in(position, s)
         }
     }
 
     /// Deletes text in an arbitrary start..end range.
-    pub fn delete_in(&mut self, start: usize, end: usize) -> ComposerUpdate<S> {
+    pub fn delete_in(
+        &mut self,
+        start: usize,
+        end: usize,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| model.do_delete_in(start, end))
+    }
+
+    fn do_delete_in(
+        &mut self,
+        start: usize,
+        end: usize,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         self.push_state_to_history();
         self.state.end = Location::from(start);
         self.do_replace_text_in(S::default(), start, end)
@@ -134,7 +153,7 @@ where
                     .lookup_container(&link.node_handle)
                     .is_immutable_link()
                 {
-                    self.select(
+                    self.do_select(
                         Location::from(link.position),
                         Location::from(link.position + link.length),
                     );
@@ -144,42 +163,47 @@ where
     }
 
     /// Deletes the character after the current cursor position.
-    pub fn delete(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.do_delete()
+    pub fn delete(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            model.push_state_to_history();
+            model.do_delete()
+        })
     }
 
-    pub fn do_delete(&mut self) -> ComposerUpdate<S> {
+    pub fn do_delete(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
         self.handle_non_editable_selection(&Direction::Forwards);
 
         if self.state.start == self.state.end {
             let (s, _) = self.safe_selection();
-            // If we're dealing with complex graphemes, this value might not be 1
-            let next_char_len =
-                if let Some((text_node, loc)) = self.get_selected_text_node() {
-                    let selection_start_in_str = s - loc.position;
-                    Self::find_next_char_len(
-                        selection_start_in_str,
-                        text_node.data(),
-                    ) as isize
-                } else {
-                    1
-                };
-            // Go forward `next_char_len` positions from the current location
-            self.state.end += next_char_len;
+            // Delete a whole grapheme cluster, which may span several code
+            // units or even several nodes
+            match self.next_boundary(
+                s,
+                Granularity::Character,
+                Direction::Forwards,
+            ) {
+                Some(next) => self.state.end = Location::from(next),
+                None => self.state.end += 1,
+            }
         }
 
         self.do_replace_text(S::default())
     }
 
     /// Remove a single word when user does ctrl/opt + delete
-    pub fn delete_word(&mut self) -> ComposerUpdate<S> {
-        self.remove_word_in_direction(Direction::Forwards)
+    pub fn delete_word(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            model.remove_word_in_direction(Direction::Forwards)
+        })
     }
 
     /// Remove a single word when user does ctrl/opt + backspace
-    pub fn backspace_word(&mut self) -> ComposerUpdate<S> {
-        self.remove_word_in_direction(Direction::Backwards)
+    pub fn backspace_word(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            model.remove_word_in_direction(Direction::Backwards)
+        })
     }
 
     /// Given a direction will get the remove word arguments and then run 'remove_word'
@@ -187,7 +211,7 @@ where
     fn remove_word_in_direction(
         &mut self,
         direction: Direction,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         // if we have a selection, only remove the selection
         if self.has_selection() {
             return self.delete_selection();
@@ -195,7 +219,7 @@ where
 
         let args = self.get_remove_word_arguments(&direction);
         match args {
-            None => ComposerUpdate::keep(),
+            None => Ok(ComposerUpdate::keep()),
             Some(arguments) => {
                 // here we have a non-split cursor, a single location, and a textlike node
                 let (location, start_type) = arguments;
@@ -210,7 +234,7 @@ where
         start_type: CharType,
         direction: Direction,
         location: DomLocation,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         // we could have entered a non-editable node during this run, if this is the
         // case, we handle it by calling the relecant method once which will adjust the
         // selection to cover that node and then remove it, ending the recursive calls
@@ -220,7 +244,7 @@ where
             // backspace and delete be equivalent, as well as the do_* functions
             return match direction {
                 Direction::Forwards => self.do_delete(),
-                Direction::Backwards => self.backspace(),
+                Direction::Backwards => self.remove_backwards(),
             };
         }
         match self.state.dom.lookup_node_mut(&location.node_handle) {
@@ -228,31 +252,25 @@ where
                 CharType::Whitespace => self.delete_to_cursor(
                     direction.increment(location.index_in_dom()),
                 ),
-                _ => ComposerUpdate::keep(),
+                _ => Ok(ComposerUpdate::keep()),
             },
             DomNode::Mention(_) => self
                 .delete_to_cursor(direction.increment(location.index_in_dom())),
             DomNode::Text(node) => {
-                // we are guaranteed to get valid chars here, so can use unwrap
+                // step over whole grapheme clusters of the starting type
                 let mut current_offset = location.start_offset;
-                let mut current_type = node
-                    .char_type_at_offset(current_offset, &direction)
-                    .unwrap();
-
-                while node.offset_is_inside_node(current_offset, &direction)
-                    && current_type == start_type
+                while node.char_type_at_offset(current_offset, &direction)
+                    == Some(start_type)
                 {
-                    let next_offset = direction.increment(current_offset);
-                    let next_type = node
-                        .char_type_at_offset(current_offset, &direction)
-                        .unwrap();
-
-                    if next_type != current_type {
+                    let Some(len) =
+                        node.grapheme_len_at_offset(current_offset, &direction)
+                    else {
                         break;
-                    }
-
-                    current_offset = next_offset;
-                    current_type = next_type;
+                    };
+                    current_offset = match direction {
+                        Direction::Forwards => current_offset + len,
+                        Direction::Backwards => current_offset - len,
+                    };
                 }
 
                 // determine our current position in the dom
@@ -261,17 +279,17 @@ where
                     node.offset_is_inside_node(current_offset, &direction);
 
                 // delete to the cursor
-                let update = self.delete_to_cursor(current_position);
+                let update = self.delete_to_cursor(current_position)?;
 
                 // if we have stopped inside the node and we didn't start at whitespace, stop
                 if offset_is_inside_node && start_type != CharType::Whitespace {
-                    return update;
+                    return Ok(update);
                 }
 
                 // otherwise make a recursive call
                 let next_args = self.get_remove_word_arguments(&direction);
                 match next_args {
-                    None => update,
+                    None => Ok(update),
                     Some(args) => {
                         let (location, next_type) = args;
                         let type_argument = if offset_is_inside_node {
@@ -366,7 +384,10 @@ where
         }
     }
 
-    fn backspace_single_cursor(&mut self, range: Range) -> ComposerUpdate<S> {
+    fn backspace_single_cursor(
+        &mut self,
+        range: Range,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         // Find the first leaf node in this selection - note there
         // should only be one because s == e, so we don't have a
         // selection that spans multiple leaves.
@@ -380,6 +401,28 @@ where
             let parent_list_item_loc =
                 range.deepest_node_of_kind(ListItem, Some(&leaf.node_handle));
             if let Some(list_item_loc) = parent_list_item_loc {
+                // A quote or code block nested in the list item is unwrapped
+                // first, so its content stays inside the list item.
+                let nested_block_loc = [Quote, CodeBlock]
+                    .into_iter()
+                    .filter_map(|kind| {
+                        range
+                            .deepest_node_of_kind(kind, Some(&leaf.node_handle))
+                    })
+                    .filter(|loc| {
+                        list_item_loc
+                            .node_handle
+                            .is_ancestor_of(&loc.node_handle)
+                    })
+                    .max();
+                if let Some(block_loc) = nested_block_loc {
+                    if block_loc.start_offset == 0 {
+                        self.state
+                            .dom
+                            .remove_and_keep_children(&block_loc.node_handle);
+                        return Ok(self.create_update_replace_all());
+                    }
+                }
                 if list_item_loc.start_offset == 0 {
                     self.do_backspace_in_list(&list_item_loc.node_handle)
                 } else {
@@ -411,65 +454,23 @@ where
         }
     }
 
-    pub(crate) fn do_backspace(&mut self) -> ComposerUpdate<S> {
+    pub(crate) fn do_backspace(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         if self.state.start == self.state.end {
             let (_, e) = self.safe_selection();
-            // If we're dealing with complex graphemes, this value might not be 1
-            let prev_char_len =
-                if let Some((text_node, loc)) = self.get_selected_text_node() {
-                    let selection_end_in_str = e - loc.position;
-                    Self::find_previous_char_len(
-                        selection_end_in_str,
-                        text_node.data(),
-                    ) as isize
-                } else {
-                    1
-                };
-            // Go back `prev_char_len` positions from the current location
-            self.state.start -= prev_char_len;
-        }
-
-        self.do_replace_text(S::default())
-    }
-
-    /// Returns the currently selected TextNode if it's the only leaf node and the cursor is inside
-    /// its range.
-    fn get_selected_text_node(&self) -> Option<(&TextNode<S>, DomLocation)> {
-        let (s, e) = self.safe_selection();
-        let range = self.state.dom.find_range(s, e);
-        let leaves: Vec<&DomLocation> = range.leaves().collect();
-        if s == e && leaves.len() == 1 {
-            let leaf = leaves[0];
-            if let DomNode::Text(text_node) =
-                self.state.dom.lookup_node(&leaf.node_handle)
-            {
-                return Some((text_node, leaf.clone()));
+            // Delete a whole grapheme cluster, which may span several code
+            // units or even several nodes
+            match self.next_boundary(
+                e,
+                Granularity::Character,
+                Direction::Backwards,
+            ) {
+                Some(prev) => self.state.start = Location::from(prev),
+                None => self.state.start -= 1,
             }
         }
-        None
-    }
-
-    /// Returns the length of the [char] for the current [S] string encoding before the given [pos].
-    fn find_previous_char_len(pos: usize, str: &S::Str) -> usize {
-        let graphemes = str.find_graphemes_at(pos);
-        // Take the grapheme before the position
-        if let Some(last_grapheme) = graphemes.0 {
-            last_grapheme.len()
-        } else {
-            // Default length for characters
-            1
-        }
-    }
 
-    /// Returns the length of the [char] for the current [S] string encoding after the given [pos].
-    fn find_next_char_len(pos: usize, str: &S::Str) -> usize {
-        let graphemes = str.find_graphemes_at(pos);
-        // Take the grapheme after the position
-        if let Some(first_grapheme) = graphemes.1 {
-            first_grapheme.len()
-        } else {
-            // Default length for characters
-            1
-        }
+        self.do_replace_text(S::default())
     }
 }
@@ -71,7 +71,7 @@ impl ComposerModel<Utf16String> {
     /// assert_eq!(model.state.dom.to_html().to_string(), "aabbcc");
     /// assert_eq!(model.state.start, 2);
     /// assert_eq!(model.state.end, 4);
-    /// model.select(Location::from(1), Location::from(5));
+    /// model.select(Location::from(1), Location::from(5)).unwrap();
     /// assert_eq!(model.to_example_format(), "a{abbc}|c");
     /// ```
     pub fn from_example_format(text: &str) -> Self {
@@ -0,0 +1,171 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use regex::{Regex, RegexBuilder};
+
+use crate::composer_model::boundaries::MENTION_PLACEHOLDER;
+use crate::dom::nodes::DomNode;
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, FindOptions, Location,
+    UnicodeString,
+};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// Find all the non-overlapping matches of `query` in the document, as
+    /// (start, end) pairs. Matches may span formatting boundaries, but never
+    /// line breaks, blocks or mentions.
+    ///
+    /// Returns [ComposerError::InvalidRegex] if `options.regex` is set and
+    /// `query` isn't a valid regular expression.
+    pub fn find(
+        &self,
+        query: &str,
+        options: &FindOptions,
+    ) -> Result<Vec<(Location, Location)>, ComposerError> {
+        Ok(self
+            .find_matches(query, options)?
+            .into_iter()
+            .map(|(s, e)| (Location::from(s), Location::from(e)))
+            .collect())
+    }
+
+    /// Replace the text between `start` and `end`, usually a match returned
+    /// by [ComposerModel::find], with `replacement`. The replacement takes
+    /// the formatting of the text at `start`. Ranges containing a mention
+    /// are left untouched.
+    pub fn replace_match(
+        &mut self,
+        start: Location,
+        end: Location,
+        replacement: S,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let (start, end) = (
+                usize::from(model.char_boundary_before(start)),
+                usize::from(model.char_boundary_before(end)),
+            );
+            let len = model.state.dom.text_len();
+            if start > end || end > len || model.contains_mention(start, end) {
+                return Ok(ComposerUpdate::keep());
+            }
+            model.push_state_to_history();
+            model.do_replace_match(replacement, start, end)
+        })
+    }
+
+    /// Replace every match of `query` with `replacement`, as a single undo
+    /// step. The replacement is inserted literally, even for regex queries.
+    pub fn replace_all(
+        &mut self,
+        query: &str,
+        options: &FindOptions,
+        replacement: S,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let matches = model.find_matches(query, options)?;
+            if matches.is_empty() {
+                return Ok(ComposerUpdate::keep());
+            }
+            model.push_state_to_history();
+            // Replace from the end so the earlier positions remain valid
+            for (start, end) in matches.into_iter().rev() {
+                model.do_replace_match(replacement.clone(), start, end)?;
+            }
+            Ok(model.create_update_replace_all())
+        })
+    }
+
+    fn do_replace_match(
+        &mut self,
+        replacement: S,
+        start: usize,
+        end: usize,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        // Select the match first, as multi-line replacements are inserted
+        // at the selection.
+        self.state.start = Location::from(start);
+        self.state.end = Location::from(end);
+        self.do_replace_text_in(replacement, start, end)
+    }
+
+    fn find_matches(
+        &self,
+        query: &str,
+        options: &FindOptions,
+    ) -> Result<Vec<(usize, usize)>, ComposerError> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let regex = build_regex(query, options)?;
+        let mut matches = Vec::new();
+        for run in self.text_runs() {
+            let text = run.text();
+            for m in regex.find_iter(text) {
+                if m.is_empty() || m.as_str().contains(MENTION_PLACEHOLDER) {
+                    continue;
+                }
+                if options.whole_word
+                    && (ends_with_word_char(&text[..m.start()])
+                        || starts_with_word_char(&text[m.end()..]))
+                {
+                    continue;
+                }
+                matches.push((
+                    run.position_of_byte(m.start()),
+                    run.position_of_byte(m.end()),
+                ));
+            }
+        }
+        Ok(matches)
+    }
+
+    pub(crate) fn contains_mention(&self, start: usize, end: usize) -> bool {
+        self.state.dom.find_range(start, end).leaves().any(|l| {
+            l.start_offset < l.end_offset
+                && matches!(
+                    self.state.dom.lookup_node(&l.node_handle),
+                    DomNode::Mention(_)
+                )
+        })
+    }
+}
+
+fn build_regex(
+    query: &str,
+    options: &FindOptions,
+) -> Result<Regex, regex::Error> {
+    let pattern = if options.regex {
+        query.to_owned()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .build()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn ends_with_word_char(text: &str) -> bool {
+    text.chars().next_back().is_some_and(is_word_char)
+}
+
+fn starts_with_word_char(text: &str) -> bool {
+    text.chars().next().is_some_and(is_word_char)
+}
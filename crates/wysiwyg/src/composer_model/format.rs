@@ -12,17 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::composer_model::menu_state::MenuStateComputeType;
 use crate::dom::action_list::DomActionList;
+use crate::dom::nodes::dom_node::DomNodeKind;
 use crate::dom::nodes::{ContainerNodeKind, DomNode};
 use crate::dom::unicode_string::UnicodeStrExt;
 use crate::dom::{Dom, DomHandle, DomLocation, Range};
 use crate::{
-    ComposerModel, ComposerUpdate, InlineFormatType, MenuAction, UnicodeString,
+    ComposerAction, ComposerError, ComposerModel, ComposerUpdate,
+    InlineFormatType, MenuAction, UnicodeString,
 };
 
+const INLINE_FORMAT_TYPES: [InlineFormatType; 5] = [
+    InlineFormatType::Bold,
+    InlineFormatType::Italic,
+    InlineFormatType::StrikeThrough,
+    InlineFormatType::Underline,
+    InlineFormatType::InlineCode,
+];
+
 #[derive(Eq, PartialEq, Debug)]
 enum FormatSelectionType {
     Extend,
@@ -33,34 +43,120 @@ impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
-    pub fn bold(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.format_or_unformat(InlineFormatType::Bold)
+    pub fn bold(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if model.refuses_formatting() {
+                return ComposerUpdate::keep();
+            }
+            model.push_state_to_history();
+            model.format_or_unformat(InlineFormatType::Bold)
+        })
     }
 
-    pub fn italic(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.format_or_unformat(InlineFormatType::Italic)
+    pub fn italic(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if model.refuses_formatting() {
+                return ComposerUpdate::keep();
+            }
+            model.push_state_to_history();
+            model.format_or_unformat(InlineFormatType::Italic)
+        })
     }
 
-    pub fn strike_through(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.format_or_unformat(InlineFormatType::StrikeThrough)
+    pub fn strike_through(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if model.refuses_formatting() {
+                return ComposerUpdate::keep();
+            }
+            model.push_state_to_history();
+            model.format_or_unformat(InlineFormatType::StrikeThrough)
+        })
     }
 
-    pub fn underline(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.format_or_unformat(InlineFormatType::Underline)
-    }
+    pub fn underline(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if model.refuses_formatting() {
+                return ComposerUpdate::keep();
+            }
+            model.push_state_to_history();
+            model.format_or_unformat(InlineFormatType::Underline)
+        })
+    }
+
+    pub fn inline_code(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let format_type = InlineFormatType::InlineCode;
+            // Also disabled inside code blocks, which can't hold inline code
+            if model.refuses_formatting()
+                || model
+                    .compute_disabled_actions()
+                    .contains(&format_type.action())
+            {
+                return ComposerUpdate::keep();
+            }
+            model.push_state_to_history();
+            if model.action_is_reversed(format_type.action()) {
+                model.unformat(format_type)
+            } else {
+                model.add_inline_code()
+            }
+        })
+    }
+
+    /// Remove all inline formatting and links from the selection. If
+    /// `include_blocks` is true, the lists, quotes and code blocks it
+    /// touches are turned back into paragraphs too.
+    ///
+    /// Nodes only partially covered by the selection are split at its
+    /// edges, so the text outside of it keeps its formatting. With a
+    /// collapsed selection, the formats active at the cursor are turned off
+    /// for the next typed text instead.
+    pub fn clear_formatting(
+        &mut self,
+        include_blocks: bool,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.refuses_formatting() {
+                return Ok(ComposerUpdate::keep());
+            }
+            let (s, e) = model.safe_selection();
+            let range = model.state.dom.find_range(s, e);
+            let reversed = model.compute_reversed_actions_from_range(&range);
+            if !Self::has_formatting_to_clear(&range, &reversed, include_blocks)
+            {
+                return Ok(ComposerUpdate::keep());
+            }
+            model.push_state_to_history();
 
-    pub fn inline_code(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        let format_type = InlineFormatType::InlineCode;
-        if self.action_is_reversed(format_type.action()) {
-            self.unformat(format_type)
-        } else {
-            self.add_inline_code()
-        }
+            if s == e {
+                for format in INLINE_FORMAT_TYPES.iter() {
+                    if reversed.contains(&format.action()) {
+                        model.toggle_zero_length_format(format);
+                    }
+                }
+            } else {
+                for format in INLINE_FORMAT_TYPES.iter() {
+                    model.unformat_range(s, e, format);
+                }
+                model.clear_links_in(s, e);
+            }
+            if include_blocks {
+                model.clear_block_formatting_in(s, e)?;
+            }
+
+            if s == e && !include_blocks {
+                Ok(ComposerUpdate::update_menu_state(
+                    model.compute_menu_state(
+                        MenuStateComputeType::KeepIfUnchanged,
+                    ),
+                    MenuAction::Keep,
+                ))
+            } else {
+                Ok(model.create_update_replace_all())
+            }
+        })
     }
 
     /// Finds the closest structure node ancestor for each leaf node handle and groups it with other
@@ -97,6 +193,10 @@ where
     }
 
     pub(crate) fn apply_pending_formats(&mut self, start: usize, end: usize) {
+        if start == end {
+            // Nothing was typed, keep the formats for the next text
+            return;
+        }
         // Reverse to pop and apply in expected order.
         self.state.toggled_format_types.reverse();
         while let Some(format) = self.state.toggled_format_types.pop() {
@@ -300,12 +400,9 @@ where
         // Go through the locations in reverse order to prevent Dom modification issues
         for loc in sorted_locations.into_iter().rev() {
             let mut loc = loc.clone();
-            let moved_handle =
-                action_list.find_moved_parent_or_self(&loc.node_handle);
-            if let Some((from_handle, to_handle)) = moved_handle {
-                // Careful here, the location's position is no longer valid
-                loc.node_handle.replace_ancestor(from_handle, to_handle);
-            }
+            // Careful here, the location's position is no longer valid if
+            // merging moved the node
+            loc.node_handle = action_list.follow_moves(&loc.node_handle);
             if Self::needs_format(&self.state.dom, &loc, format) {
                 let parent = self.state.dom.parent_mut(&loc.node_handle);
                 let index = loc.node_handle.index_in_parent();
@@ -408,32 +505,179 @@ where
                 if text_node.data().is_empty() {
                     (None, None)
                 } else {
-                    let split_data_orig =
-                        text_node.data()[..position].to_owned();
-                    let split_data_new =
-                        text_node.data()[position..].to_owned();
-                    let before = if split_data_orig.is_empty() {
+                    let len = text_node.data().len();
+                    let before = if position == 0 {
                         None
                     } else {
-                        let mut node = DomNode::new_text(split_data_orig);
+                        let mut node = text_node.clone_with_range(0..position);
                         node.set_handle(text_node.handle());
-                        Some(node)
+                        Some(DomNode::Text(node))
                     };
-                    let after = if split_data_new.is_empty() {
+                    let after = if position == len {
                         None
                     } else {
-                        Some(DomNode::new_text(split_data_new))
+                        Some(DomNode::Text(
+                            text_node.clone_with_range(position..len),
+                        ))
                     };
                     (before, after)
                 }
             } else {
-                panic!("Node was not a text node so can't be split!");
+                // Only text can be split, anything else stays in one piece
+                (Some(node), None)
             }
         } else {
             (None, None)
         }
     }
 
+    /// Whether [Self::clear_formatting] would change anything in the given
+    /// range, `reversed` being the reversed actions computed for it.
+    pub(crate) fn has_formatting_to_clear(
+        range: &Range,
+        reversed: &HashSet<ComposerAction>,
+        include_blocks: bool,
+    ) -> bool {
+        let collapsed = range.start() == range.end();
+        let has_inline_formatting = if collapsed {
+            INLINE_FORMAT_TYPES
+                .iter()
+                .any(|format| reversed.contains(&format.action()))
+        } else {
+            range.locations.iter().any(|l| {
+                matches!(l.kind, DomNodeKind::Formatting(_) | DomNodeKind::Link)
+                    && l.start_offset < l.end_offset
+            })
+        };
+        has_inline_formatting
+            || include_blocks
+                && range.locations.iter().any(|l| {
+                    is_clearable_block(&l.kind) && l.touches_range(collapsed)
+                })
+    }
+
+    /// Remove the links in the given range. The parts of partially covered
+    /// links outside of it are wrapped in new links with the same attributes.
+    fn clear_links_in(&mut self, start: usize, end: usize) {
+        let range = self.state.dom.find_range(start, end);
+        let mut slices_to_relink = Vec::new();
+        for loc in range.locations.iter().rev() {
+            if loc.kind != DomNodeKind::Link
+                || loc.start_offset == loc.end_offset
+            {
+                continue;
+            }
+            let DomNode::Container(link) =
+                self.state.dom.lookup_node(&loc.node_handle)
+            else {
+                continue;
+            };
+            let link = link.clone_with_new_children(Vec::new());
+            let link_end = loc.position + loc.length;
+            if loc.position < start {
+                slices_to_relink.push((link.clone(), loc.position, start));
+            }
+            if link_end > end {
+                slices_to_relink.push((link, end, link_end));
+            }
+            self.state
+                .dom
+                .replace_node_with_its_children(&loc.node_handle);
+        }
+        for (link, from, to) in slices_to_relink {
+            let range = self.state.dom.find_range(from, to);
+            self.state
+                .dom
+                .insert_parent(&range, DomNode::Container(link));
+        }
+    }
+
+    /// Turn the lists, quotes and code blocks touched by the given range
+    /// into paragraphs, innermost first. Blocks are split so their children
+    /// outside of the range stay in place.
+    fn clear_block_formatting_in(
+        &mut self,
+        start: usize,
+        end: usize,
+    ) -> Result<(), ComposerError> {
+        let collapsed = start == end;
+        loop {
+            let range = self.state.dom.find_range(start, end);
+            let next = range
+                .locations
+                .iter()
+                .filter(|l| {
+                    is_clearable_block(&l.kind) && l.touches_range(collapsed)
+                })
+                .find_map(|block| {
+                    let indexes: Vec<usize> = range
+                        .locations
+                        .iter()
+                        .filter(|l| {
+                            !l.node_handle.is_root()
+                                && l.node_handle.parent_handle()
+                                    == block.node_handle
+                                && l.touches_range(collapsed)
+                        })
+                        .map(|l| l.node_handle.index_in_parent())
+                        .collect();
+                    let first = *indexes.iter().min()?;
+                    let last = *indexes.iter().max()?;
+                    Some((block.clone(), first, last - first + 1))
+                });
+            let Some((block, first, count)) = next else {
+                return Ok(());
+            };
+            if block.kind == DomNodeKind::List {
+                self.state.dom.extract_list_items(
+                    &block.node_handle,
+                    first,
+                    count,
+                )?;
+            } else {
+                self.unwrap_block_children(&block.node_handle, first, count)?;
+            }
+        }
+    }
+
+    /// Move `count` children of the block at `handle` out of it, starting
+    /// at `first`. The block is split around them, keeping its remaining
+    /// children on both sides.
+    fn unwrap_block_children(
+        &mut self,
+        handle: &DomHandle,
+        first: usize,
+        count: usize,
+    ) -> Result<(), ComposerError> {
+        let DomNode::Container(block) = self.state.dom.lookup_node_mut(handle)
+        else {
+            return Err(ComposerError::Internal(format!(
+                "Block at {handle:?} is not a container"
+            )));
+        };
+        let after = block.take_children_after(first + count);
+        let unwrapped = block.take_children_after(first);
+        let before = block.remove_children();
+
+        let mut nodes = Vec::new();
+        if !before.is_empty() {
+            nodes.push(DomNode::Container(
+                block.clone_with_new_children(before),
+            ));
+        }
+        if unwrapped.iter().any(|n| !n.is_block_node()) {
+            nodes.push(DomNode::new_paragraph(unwrapped));
+        } else {
+            nodes.extend(unwrapped);
+        }
+        if !after.is_empty() {
+            nodes
+                .push(DomNode::Container(block.clone_with_new_children(after)));
+        }
+        self.state.dom.replace(handle, nodes);
+        Ok(())
+    }
+
     pub(crate) fn merge_formatting_node_with_siblings(
         &mut self,
         handle: &DomHandle,
@@ -456,6 +700,13 @@ where
     }
 }
 
+fn is_clearable_block(kind: &DomNodeKind) -> bool {
+    matches!(
+        kind,
+        DomNodeKind::List | DomNodeKind::Quote | DomNodeKind::CodeBlock
+    )
+}
+
 #[cfg(test)]
 mod test {
     use crate::tests::testutils_composer_model::{cm, tx};
@@ -616,23 +867,23 @@ mod test {
     #[test]
     fn partially_formatted_selection_triggers_format() {
         let mut model = cm("<em>a{bc</em>de}|f");
-        model.italic();
+        model.italic().unwrap();
         assert_eq!(model.state.dom.to_string(), "<em>abcde</em>f");
     }
 
     #[test]
     fn completely_formatted_selection_triggers_unformat() {
         let mut model = cm("<del>a{bcd}|ef</del>");
-        model.strike_through();
+        model.strike_through().unwrap();
         assert_eq!(model.state.dom.to_string(), "<del>a</del>bcd<del>ef</del>");
     }
 
     #[test]
     fn format_and_unformat_empty_selection() {
         let mut model = cm("AAA |");
-        model.bold();
+        model.bold().unwrap();
         assert_eq!(tx(&model), "AAA&nbsp;|");
-        model.bold();
+        model.bold().unwrap();
         assert_eq!(tx(&model), "AAA&nbsp;|");
     }
 }
@@ -40,6 +40,10 @@ where
     }
 
     pub(crate) fn add_inline_code_in(&mut self, start: usize, end: usize) {
+        if self.contains_mention(start, end) {
+            // Mentions can't be written inside inline code
+            return;
+        }
         let range = self.state.dom.find_range(start, end);
         let leaves: Vec<&DomLocation> = range.leaves().collect();
         // We'll iterate through the leaves finding their closest structural node ancestor and
@@ -92,9 +96,9 @@ where
                         insert_text_at = Some(ancestor_child_handle);
                         cur_text = S::default();
                     }
-                    _ => panic!(
-                        "Leaf should be either a line break or a text node"
-                    ),
+                    // A mention touching the edge of the range, none of it
+                    // is selected
+                    _ => {}
                 }
             }
 
@@ -118,6 +122,8 @@ where
                 self.merge_formatting_node_with_siblings(&insert_text_at);
             }
         }
+        // Merged inline code nodes can leave their text nodes side by side
+        self.state.dom.merge_text_nodes_in(&DomHandle::root());
     }
 
     fn process_text_node_for_inline_code(
@@ -202,7 +208,7 @@ mod test {
     #[test]
     fn inline_code_replacing_formatting_removes_formatting() {
         let mut model = cm("<b>{bold</b><i>text}|</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<code>{boldtext}|</code>");
     }
 
@@ -210,14 +216,14 @@ mod test {
     fn inline_code_replacing_partial_formatting_removes_overlapping_formatting()
     {
         let mut model = cm("<b>bo{ld</b><i>te}|xt</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<b>bo</b><code>{ldte}|</code><i>xt</i>");
     }
 
     #[test]
     fn inline_code_with_formatting_preserves_line_breaks() {
         let mut model = cm("<b>{bold</b><br /><i>text}|</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(
             tx(&model),
             "<p><code>{bold</code></p><p><code>text}|</code></p>"
@@ -227,7 +233,7 @@ mod test {
     #[test]
     fn inline_code_replacing_complex_formatting_removes_formatting() {
         let mut model = cm("<b><u>{bold</u></b><i>text}|</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<code>{boldtext}|</code>");
     }
 
@@ -235,21 +241,21 @@ mod test {
     fn inline_code_replacing_nested_and_complex_formatting_removes_formatting()
     {
         let mut model = cm("<b><u>{bold</u><i>italic</i></b><i>text}|</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<code>{bolditalictext}|</code>");
     }
 
     #[test]
     fn inline_code_partially_replacing_formatting_removes_overlap() {
         let mut model = cm("<b><u>bo{ld</u></b><i>te}|xt</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<b><u>bo</u></b><code>{ldte}|</code><i>xt</i>");
     }
 
     #[test]
     fn inline_code_on_partial_nested_line_break_removes_formatting() {
         let mut model = cm("<b><u>bo{ld</u></b><br /><i>te}|xt</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(
             tx(&model),
             "<p><b><u>bo</u></b><code>{ld</code></p><p><code>te}|</code><i>xt</i></p>",
@@ -261,7 +267,7 @@ mod test {
     fn inline_code_on_partial_nested_line_break_within_parent_removes_formatting(
     ) {
         let mut model = cm("<b><u>bo{ld</u><br /></b><i>te}|xt</i>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(
             tx(&model),
             "<p><b><u>bo</u></b><code>{ld</code></p><p><code>te}|</code><i>xt</i></p>",
@@ -271,7 +277,7 @@ mod test {
     #[test]
     fn format_inline_code_in_list_item() {
         let mut model = cm("<ul><li><b>bo{ld</b><i>text}|</i></li></ul>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><b>bo</b><code>{ldtext}|</code></li></ul>"
@@ -282,7 +288,7 @@ mod test {
     fn format_inline_code_in_several_list_items() {
         let mut model =
             cm("<ul><li><b>bo{ld</b></li><li><i>text}|</i></li></ul>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><b>bo</b><code>{ld</code></li><li><code>text}|</code></li></ul>"
@@ -293,7 +299,7 @@ mod test {
     fn format_inline_code_in_several_list_items_and_text() {
         let mut model =
             cm("<p>Text {before</p><ul><li><b>bo}|ld</b></li><li><i>text</i></li></ul>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(
             tx(&model),
             "<p>Text <code>{before</code></p><ul><li><code>bo}|</code><b>ld</b></li><li><i>text</i></li></ul>"
@@ -303,35 +309,35 @@ mod test {
     #[test]
     fn format_inline_code_with_existing_inline_code_start() {
         let mut model = cm("{Some <code>co}|de</code>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<code>{Some co}|de</code>");
     }
 
     #[test]
     fn format_inline_code_with_existing_inline_code_end() {
         let mut model = cm("<code>So{me </code>code}|");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<code>So{me code}|</code>");
     }
 
     #[test]
     fn format_inline_code_with_existing_inline_code_side_to_side_start() {
         let mut model = cm("<code>Some </code>{code}|");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<code>Some {code}|</code>");
     }
 
     #[test]
     fn format_inline_code_with_existing_inline_code_side_to_side_end() {
         let mut model = cm("{Some }|<code>code</code>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<code>{Some }|code</code>");
     }
 
     #[test]
     fn unformat_inline_code_same_row_with_line_breaks() {
         let mut model = cm("<code>{bold<br />text}|</code>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(tx(&model), "<p>{bold</p><p>text}|</p>");
     }
 
@@ -339,7 +345,7 @@ mod test {
     fn unformat_inline_code_in_several_list_items_and_text() {
         let mut model =
             cm("<p>Text <code>{before</code></p><ul><li><code>bo}|</code><b>ld</b></li><li><i>text</i></li></ul>");
-        model.inline_code();
+        model.inline_code().unwrap();
         assert_eq!(
             tx(&model),
             "<p>Text {before</p><ul><li>bo}|<b>ld</b></li><li><i>text</i></li></ul>"
@@ -351,35 +357,35 @@ mod test {
     #[test]
     fn disable_inline_code_then_write_text() {
         let mut model = cm("<code>code|</code>");
-        model.inline_code();
-        model.replace_text(" plain text".into());
+        model.inline_code().unwrap();
+        model.replace_text(" plain text".into()).unwrap();
         assert_eq!(tx(&model), "<code>code</code> plain text|");
     }
 
     #[test]
     fn test_creating_inline_code_inside_format_node() {
         let mut model = cm("<i>Test |</i>");
-        model.inline_code();
-        model.replace_text("code".into());
+        model.inline_code().unwrap();
+        model.replace_text("code".into()).unwrap();
         assert_eq!(tx(&model), "<i>Test&nbsp;</i><code>code|</code>");
     }
 
     #[test]
     fn test_enabling_and_disabling_inline_code_then_adding_text() {
         let mut model = cm("<i>Test </i><code>code|</code>");
-        model.inline_code();
-        model.replace_text(" plain".into());
+        model.inline_code().unwrap();
+        model.replace_text(" plain".into()).unwrap();
         assert_eq!(tx(&model), "<i>Test&nbsp;</i><code>code</code> plain|");
     }
 
     #[test]
     fn test_inline_code_disables_current_formatting() {
         let mut model = cm("|");
-        model.bold();
-        model.replace_text("bold".into());
-        model.italic();
-        model.inline_code();
-        model.replace_text("code".into());
+        model.bold().unwrap();
+        model.replace_text("bold".into()).unwrap();
+        model.italic().unwrap();
+        model.inline_code().unwrap();
+        model.replace_text("code".into()).unwrap();
         assert_eq!(tx(&model), "<strong>bold</strong><code>code|</code>");
     }
 }
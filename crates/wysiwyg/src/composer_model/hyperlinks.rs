@@ -21,7 +21,8 @@ use crate::dom::nodes::DomNode;
 use crate::dom::unicode_string::UnicodeStrExt;
 use crate::dom::Range;
 use crate::{
-    ComposerModel, ComposerUpdate, DomHandle, LinkAction, UnicodeString,
+    ComposerError, ComposerModel, ComposerUpdate, DomHandle, LinkAction,
+    UnicodeString,
 };
 use email_address::*;
 use url::{ParseError, Url};
@@ -53,7 +54,9 @@ where
                 LinkAction::Disabled
             } else {
                 // Otherwise we edit the first link of the selection.
-                LinkAction::Edit(first_link.get_link_url().unwrap())
+                first_link
+                    .get_link_url()
+                    .map_or(LinkAction::Disabled, LinkAction::Edit)
             }
         } else if s == e || self.is_blank_selection(range) {
             LinkAction::CreateWithText
@@ -66,14 +69,14 @@ where
         for leaf in range.leaves() {
             match leaf.kind {
                 DomNodeKind::Text => {
-                    let text_node = self
+                    let selection_range = leaf.start_offset..leaf.end_offset;
+                    let is_blank = self
                         .state
                         .dom
                         .lookup_node(&leaf.node_handle)
                         .as_text()
-                        .unwrap();
-                    let selection_range = leaf.start_offset..leaf.end_offset;
-                    if !text_node.is_blank_in_range(selection_range) {
+                        .is_some_and(|t| t.is_blank_in_range(selection_range));
+                    if !is_blank {
                         return false;
                     }
                 }
@@ -99,26 +102,36 @@ where
         url: S,
         text: S,
         attributes: Vec<(S, S)>,
-    ) -> ComposerUpdate<S> {
-        let (s, _) = self.safe_selection();
-        self.push_state_to_history();
-        self.do_replace_text(text.clone());
-        let e = s + text.len();
-        let range = self.state.dom.find_range(s, e);
-        self.set_link_in_range(url, range, attributes)
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.refuses_formatting() {
+                return Ok(ComposerUpdate::keep());
+            }
+            let (s, _) = model.safe_selection();
+            model.push_state_to_history();
+            model.do_replace_text(text.clone())?;
+            let e = s + text.len();
+            let range = model.state.dom.find_range(s, e);
+            Ok(model.set_link_in_range(url, range, attributes))
+        })
     }
 
     pub fn set_link(
         &mut self,
         url: S,
         attributes: Vec<(S, S)>,
-    ) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        let (s, e) = self.safe_selection();
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if model.refuses_formatting() {
+                return ComposerUpdate::keep();
+            }
+            model.push_state_to_history();
+            let (s, e) = model.safe_selection();
 
-        let range = self.state.dom.find_range(s, e);
+            let range = model.state.dom.find_range(s, e);
 
-        self.set_link_in_range(url, range, attributes)
+            model.set_link_in_range(url, range, attributes)
+        })
     }
 
     fn set_link_in_range(
@@ -206,8 +219,24 @@ where
             }
         }
 
-        for (_, s, e) in split_points.into_iter() {
+        // Links are nested until the inner ones are removed, so only check
+        // the Dom once they all are
+        #[cfg(any(test, feature = "assert-invariants"))]
+        self.state.dom.start_transaction();
+
+        // Go backwards, as converting mentions to text moves what follows
+        split_points.sort_by_key(|(_, s, _)| *s);
+        for (_, s, e) in split_points.into_iter().rev() {
             let range = self.state.dom.find_range(s, e);
+            // Nodes only touching the edges of the range stay where they are
+            let range =
+                Range::new(range.locations.iter().filter(|l| {
+                    l.start_offset != l.end_offset || l.length == 0
+                }));
+            if range.leaves().next().is_none() {
+                // Only empty containers, there's no text to link
+                continue;
+            }
 
             // Create a new link node containing the passed range
             let inserted = self.state.dom.insert_parent(
@@ -220,6 +249,9 @@ where
             self.convert_child_mentions_to_text(&inserted);
         }
 
+        #[cfg(any(test, feature = "assert-invariants"))]
+        self.state.dom.end_transaction();
+
         self.create_update_replace_all()
     }
 
@@ -278,6 +310,10 @@ where
                     &handle,
                     vec![DomNode::new_text(display_text.clone())],
                 );
+                // The new text may be next to other text nodes
+                self.state
+                    .dom
+                    .join_nodes_in_container(&handle.parent_handle());
                 let selection_length_change: isize =
                     (display_text.len() - 1).try_into().unwrap_or(0);
                 self.state.extend_selection(selection_length_change)
@@ -304,25 +340,28 @@ where
         None
     }
 
-    pub fn remove_links(&mut self) -> ComposerUpdate<S> {
-        let mut has_found_link = false;
-        let (s, e) = self.safe_selection();
-        let range = self.state.dom.find_range(s, e);
-        let iter = range.locations.into_iter().rev();
-        for loc in iter {
-            if loc.kind == DomNodeKind::Link {
-                if !has_found_link {
-                    has_found_link = true;
-                    self.push_state_to_history();
+    pub fn remove_links(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let mut has_found_link = false;
+            let (s, e) = model.safe_selection();
+            let range = model.state.dom.find_range(s, e);
+            let iter = range.locations.into_iter().rev();
+            for loc in iter {
+                if loc.kind == DomNodeKind::Link {
+                    if !has_found_link {
+                        has_found_link = true;
+                        model.push_state_to_history();
+                    }
+                    model
+                        .state
+                        .dom
+                        .replace_node_with_its_children(&loc.node_handle);
                 }
-                self.state
-                    .dom
-                    .replace_node_with_its_children(&loc.node_handle);
             }
-        }
-        if !has_found_link {
-            return ComposerUpdate::keep();
-        }
-        self.create_update_replace_all()
+            if !has_found_link {
+                return ComposerUpdate::keep();
+            }
+            model.create_update_replace_all()
+        })
     }
 }
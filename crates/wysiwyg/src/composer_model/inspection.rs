@@ -0,0 +1,67 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::{ComposerModel, DomHandle, Location, NodeInfo, UnicodeString};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// A read-only snapshot of the whole document, starting at the root.
+    pub fn dom_tree(&self) -> NodeInfo {
+        NodeInfo::from_node(self.state.dom.document_node(), &self.positions())
+    }
+
+    /// The deepest node containing `location`, or None if it's beyond the
+    /// end of the text. At the boundary between two nodes, the node after
+    /// it wins, except at the very end of the text.
+    pub fn node_at_location(&self, location: Location) -> Option<NodeInfo> {
+        let loc = usize::from(location);
+        if loc > self.state.dom.text_len() {
+            return None;
+        }
+        let range = self.state.dom.find_range(loc, loc);
+        let leaves: Vec<_> = range.leaves().collect();
+        let handle = leaves
+            .iter()
+            .find(|l| l.position <= loc && loc < l.position + l.length)
+            .or_else(|| leaves.last())
+            .map(|l| l.node_handle.clone())
+            .or_else(|| {
+                // No leaves, e.g. in an empty paragraph or list item.
+                range
+                    .locations
+                    .iter()
+                    .filter(|l| l.kind.is_block_kind() && l.is_empty())
+                    .max()
+                    .map(|l| l.node_handle.clone())
+            })
+            .unwrap_or_else(DomHandle::root);
+        let node = self.state.dom.lookup_node(&handle);
+        Some(NodeInfo::from_node(node, &self.positions()))
+    }
+
+    /// The start of the text of every node, by handle.
+    fn positions(&self) -> HashMap<DomHandle, usize> {
+        self.state
+            .dom
+            .find_range(0, usize::MAX)
+            .locations
+            .into_iter()
+            .map(|l| (l.node_handle, l.position))
+            .collect()
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+    ComposerAction, ComposerError, ComposerModel, ComposerUpdate, KeyCommand,
+    KeyEvent, Keymap, UnicodeString,
+};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// Replace the keymap used by [ComposerModel::handle_key].
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    pub fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Run the command bound to `event` in the keymap.
+    ///
+    /// Returns None if the key was not consumed, in which case the platform
+    /// should apply its default behaviour. Unbound keys are never consumed,
+    /// and neither are indent and unindent when they're disabled, so that
+    /// Tab can still move focus out of the composer.
+    pub fn handle_key(
+        &mut self,
+        event: &KeyEvent,
+    ) -> Result<Option<ComposerUpdate<S>>, ComposerError> {
+        self.try_edit(|model| {
+            let Some(command) = model.keymap.command_for(event) else {
+                return Ok(None);
+            };
+            let update = match command {
+                KeyCommand::Bold => model.bold()?,
+                KeyCommand::Italic => model.italic()?,
+                KeyCommand::StrikeThrough => model.strike_through()?,
+                KeyCommand::Underline => model.underline()?,
+                KeyCommand::InlineCode => model.inline_code()?,
+                KeyCommand::Undo => model.undo()?,
+                KeyCommand::Redo => model.redo()?,
+                KeyCommand::OrderedList => model.ordered_list()?,
+                KeyCommand::UnorderedList => model.unordered_list()?,
+                KeyCommand::Indent => {
+                    if model
+                        .compute_disabled_actions()
+                        .contains(&ComposerAction::Indent)
+                    {
+                        return Ok(None);
+                    }
+                    model.indent()?
+                }
+                KeyCommand::Unindent => {
+                    if model
+                        .compute_disabled_actions()
+                        .contains(&ComposerAction::Unindent)
+                    {
+                        return Ok(None);
+                    }
+                    model.unindent()?
+                }
+                KeyCommand::CodeBlock => model.code_block()?,
+                KeyCommand::Quote => model.quote()?,
+                KeyCommand::Backspace => model.backspace()?,
+                KeyCommand::Delete => model.delete()?,
+                KeyCommand::BackspaceWord => model.backspace_word()?,
+                KeyCommand::DeleteWord => model.delete_word()?,
+                KeyCommand::Enter => model.enter()?,
+            };
+            Ok(Some(update))
+        })
+    }
+}
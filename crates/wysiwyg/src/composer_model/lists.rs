@@ -18,52 +18,70 @@ use crate::dom::nodes::DomNode;
 use crate::dom::range::DomLocationPosition;
 use crate::dom::range::DomLocationPosition::Before;
 use crate::dom::{DomHandle, DomLocation, Range};
-use crate::{ComposerModel, ComposerUpdate, ListType, UnicodeString};
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, ListType, UnicodeString,
+};
 
 impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
-    pub fn ordered_list(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.toggle_list(ListType::Ordered)
+    pub fn ordered_list(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.refuses_formatting() {
+                return Ok(ComposerUpdate::keep());
+            }
+            model.push_state_to_history();
+            model.toggle_list(ListType::Ordered)
+        })
     }
 
-    pub fn unordered_list(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.toggle_list(ListType::Unordered)
+    pub fn unordered_list(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.refuses_formatting() {
+                return Ok(ComposerUpdate::keep());
+            }
+            model.push_state_to_history();
+            model.toggle_list(ListType::Unordered)
+        })
     }
 
-    pub fn indent(&mut self) -> ComposerUpdate<S> {
-        // push_state_to_history is called if we can indent
-        let (s, e) = self.safe_selection();
-        let range = self.state.dom.find_range(s, e);
-        let top_most_locations =
-            self.find_top_most_list_item_locations(&range.locations);
-        if !top_most_locations.is_empty()
-            && self.can_indent(&top_most_locations)
-        {
-            self.push_state_to_history();
-            self.indent_locations(&top_most_locations);
-            self.create_update_replace_all()
-        } else {
-            ComposerUpdate::keep()
-        }
+    pub fn indent(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            // push_state_to_history is called if we can indent
+            let (s, e) = model.safe_selection();
+            let range = model.state.dom.find_range(s, e);
+            let top_most_locations =
+                model.find_top_most_list_item_locations(&range.locations);
+            if !top_most_locations.is_empty()
+                && model.can_indent(&top_most_locations)
+            {
+                model.push_state_to_history();
+                model.indent_locations(&top_most_locations)?;
+                Ok(model.create_update_replace_all())
+            } else {
+                Ok(ComposerUpdate::keep())
+            }
+        })
     }
 
-    pub fn unindent(&mut self) -> ComposerUpdate<S> {
-        // push_state_to_history is called if we can unindent
-        let (s, e) = self.safe_selection();
-        let range = self.state.dom.find_range(s, e);
-        let top_most_locations =
-            self.find_top_most_list_item_locations(&range.locations);
-        if self.can_unindent(&top_most_locations) {
-            self.push_state_to_history();
-            self.unindent_locations(&top_most_locations);
-            self.create_update_replace_all()
-        } else {
-            ComposerUpdate::keep()
-        }
+    pub fn unindent(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            // push_state_to_history is called if we can unindent
+            let (s, e) = model.safe_selection();
+            let range = model.state.dom.find_range(s, e);
+            let top_most_locations =
+                model.find_top_most_list_item_locations(&range.locations);
+            if model.can_unindent(&top_most_locations) {
+                model.push_state_to_history();
+                model.unindent_locations(&top_most_locations)?;
+                Ok(model.create_update_replace_all())
+            } else {
+                Ok(ComposerUpdate::keep())
+            }
+        })
     }
 
     pub fn can_indent(&self, locations: &[DomLocation]) -> bool {
@@ -141,23 +159,19 @@ where
     pub(crate) fn do_backspace_in_list(
         &mut self,
         list_item_handle: &DomHandle,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let list_item_node = self.state.dom.lookup_node(list_item_handle);
         let list_node_handle = list_item_node.handle().parent_handle();
-        if let DomNode::Container(list_item) = list_item_node {
-            if list_item.has_no_text()
-                || list_item_handle.index_in_parent() == 0
-            {
-                self.state.dom.extract_list_items(
-                    &list_node_handle,
-                    list_item_handle.index_in_parent(),
-                    1,
-                );
-            }
-            self.do_backspace()
-        } else {
-            panic!("No list item found")
+        if list_item_node.has_no_text()
+            || list_item_handle.index_in_parent() == 0
+        {
+            self.state.dom.extract_list_items(
+                &list_node_handle,
+                list_item_handle.index_in_parent(),
+                1,
+            )?;
         }
+        self.do_backspace()
     }
 
     /// Insert a newline in some text.
@@ -178,7 +192,10 @@ where
         self.create_update_replace_all()
     }
 
-    fn toggle_list(&mut self, list_type: ListType) -> ComposerUpdate<S> {
+    fn toggle_list(
+        &mut self,
+        list_type: ListType,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let (s, e) = self.safe_selection();
         let range = self.state.dom.find_extended_range(s, e);
         self.toggle_list_range(list_type, range)
@@ -188,9 +205,15 @@ where
         &mut self,
         list_type: ListType,
         range: Range,
-    ) -> ComposerUpdate<S> {
-        let list_loc_in_range =
-            range.locations.iter().find(|l| l.kind == DomNodeKind::List);
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        // Prefer a list around the selection to one ending right before it
+        let list_loc_in_range = range
+            .locations
+            .iter()
+            .filter(|l| l.kind == DomNodeKind::List)
+            .min_by_key(|l| {
+                l.relative_position() == DomLocationPosition::Before
+            });
         let list_is_before_selection = list_loc_in_range.map_or(false, |l| {
             l.relative_position() == DomLocationPosition::Before
         });
@@ -200,17 +223,27 @@ where
             } else {
                 false
             };
-        if list_loc_in_range.is_some()
-            && (!list_is_before_selection || list_is_last_node_in_selection)
-        {
-            if let Some(block_location) = range.deepest_block_node(None) {
+        if let Some(list_loc) = list_loc_in_range.filter(|_| {
+            !list_is_before_selection || list_is_last_node_in_selection
+        }) {
+            // Only look inside the list, the selection may go past its end
+            let block_location = range
+                .locations
+                .iter()
+                .filter(|l| {
+                    list_loc.node_handle.is_ancestor_of(&l.node_handle)
+                        && (l.kind.is_block_kind()
+                            || l.kind.is_structure_kind())
+                })
+                .max();
+            if let Some(block_location) = block_location {
                 self.single_leaf_list_toggle(
                     list_type,
                     &block_location.node_handle,
                 )
             } else {
                 // TODO: handle cases where a list is already present in the extended selection.
-                panic!("Partially creating/removing list is not handled yet")
+                Ok(ComposerUpdate::keep())
             }
         } else {
             self.create_list_from_range(list_type, range)
@@ -222,7 +255,7 @@ where
         &mut self,
         list_type: ListType,
         handle: &DomHandle,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let parent_list_item_handle =
             self.state.dom.find_ancestor_list_item_or_self(handle);
         if let Some(list_item_handle) = parent_list_item_handle {
@@ -232,14 +265,14 @@ where
                     &list_item_handle.parent_handle(),
                     list_item_handle.index_in_parent(),
                     1,
-                );
-                self.create_update_replace_all()
+                )?;
+                Ok(self.create_update_replace_all())
             } else {
                 let list_node_handle = list.handle();
                 self.update_list_type(&list_node_handle, list_type)
             }
         } else {
-            unreachable!("No list in range. Should have been catched by toggle_list_range")
+            Ok(ComposerUpdate::keep())
         }
     }
 
@@ -247,7 +280,7 @@ where
         &mut self,
         list_type: ListType,
         range: Range,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let nodes_iterator =
             if range.has_single_top_level_node(Some(DomNodeKind::Quote)) {
                 // Single top level quote means we're trying to create a list inside
@@ -259,19 +292,19 @@ where
 
         let handles = nodes_iterator.map(|l| &l.node_handle).collect();
         self.state.dom.wrap_nodes_in_list(list_type, handles);
-        self.create_update_replace_all()
+        Ok(self.create_update_replace_all())
     }
 
     fn update_list_type(
         &mut self,
         list_handle: &DomHandle,
         list_type: ListType,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let list_node = self.state.dom.lookup_node_mut(list_handle);
         if let DomNode::Container(list) = list_node {
             list.set_list_type(list_type);
         }
-        self.create_update_replace_all()
+        Ok(self.create_update_replace_all())
     }
 
     pub(crate) fn can_indent_list_item_handle(
@@ -301,35 +334,41 @@ where
         }
     }
 
-    fn indent_locations(&mut self, locations: &[DomLocation]) {
+    fn indent_locations(
+        &mut self,
+        locations: &[DomLocation],
+    ) -> Result<(), ComposerError> {
         let handles: Vec<DomHandle> =
             locations.iter().map(|l| l.node_handle.clone()).collect();
-        self.indent_list_item_handles(&handles);
+        self.indent_list_item_handles(&handles)
     }
 
-    fn indent_list_item_handles(&mut self, handles: &[DomHandle]) {
+    fn indent_list_item_handles(
+        &mut self,
+        handles: &[DomHandle],
+    ) -> Result<(), ComposerError> {
         // Pre-checks
         if handles.is_empty() {
-            return;
+            return Ok(());
         }
         let can_indent =
             handles.iter().all(|h| self.can_indent_list_item_handle(h));
         if !can_indent {
-            return;
+            return Ok(());
         }
 
         let parent_handle = handles[0].parent_handle();
         let all_have_same_parent =
             handles.iter().all(|h| h.parent_handle() == parent_handle);
         if !all_have_same_parent {
-            return;
+            return Ok(());
         }
 
         // Sort handles to avoid issues where we delete a former handle so the rest become invalid
         let mut sorted_handles = handles.to_owned();
         sorted_handles.sort();
 
-        let first_handle = sorted_handles.first().unwrap();
+        let first_handle = &sorted_handles[0];
         let insert_into_handle = first_handle.prev_sibling();
 
         let parent_list_type = self
@@ -337,8 +376,12 @@ where
             .dom
             .parent(first_handle)
             .get_list_type()
-            .unwrap()
-            .clone();
+            .cloned()
+            .ok_or_else(|| {
+                ComposerError::Internal(
+                    "List items to indent must be in a list".into(),
+                )
+            })?;
 
         // Remove ListItems to indent from the parent List
         let mut removed_list_items = Vec::new();
@@ -349,10 +392,9 @@ where
         if let DomNode::Container(dest_list_item) =
             &mut self.state.dom.lookup_node_mut(&insert_into_handle)
         {
-            // Wrap any existing inline nodes inside the destination ListItem into a paragraph
-            if dest_list_item.children().len() == 1
-                && !dest_list_item.get_child(0).unwrap().is_block_node()
-            {
+            // Wrap any existing inline nodes inside the destination ListItem into a paragraph,
+            // an empty one if there are none, so it keeps its line
+            if dest_list_item.children().iter().all(|c| !c.is_block_node()) {
                 let children = dest_list_item.remove_children();
                 let paragraph = DomNode::new_paragraph(children);
                 dest_list_item.append_child(paragraph);
@@ -364,27 +406,36 @@ where
                 removed_list_items,
             ));
         } else {
-            panic!("Destination list item must be a container");
+            return Err(ComposerError::Internal(
+                "Destination list item must be a container".into(),
+            ));
         }
         // We'll join adjacent Lists, so even if we appended a new List above, this would be the
         // same as pushing new ListItems to that List
         self.state.dom.join_nodes_in_container(&insert_into_handle);
+        Ok(())
     }
 
-    fn unindent_locations(&mut self, locations: &[DomLocation]) {
+    fn unindent_locations(
+        &mut self,
+        locations: &[DomLocation],
+    ) -> Result<(), ComposerError> {
         let handles: Vec<DomHandle> =
             locations.iter().map(|l| l.node_handle.clone()).collect();
-        self.unindent_handles(&handles);
+        self.unindent_handles(&handles)
     }
 
-    fn unindent_handles(&mut self, handles: &[DomHandle]) {
+    fn unindent_handles(
+        &mut self,
+        handles: &[DomHandle],
+    ) -> Result<(), ComposerError> {
         // Pre-checks
         if handles.is_empty() {
-            return;
+            return Ok(());
         }
         let can_unindent = handles.iter().all(|h| self.can_unindent_handle(h));
         if !can_unindent {
-            return;
+            return Ok(());
         }
 
         let first_handle = handles[0].clone();
@@ -392,7 +443,7 @@ where
         let all_have_same_parent =
             handles.iter().all(|h| h.parent_handle() == parent_handle);
         if !all_have_same_parent {
-            return;
+            return Ok(());
         }
 
         // Sort handles to avoid issues where we delete a former handle so the rest become invalid
@@ -413,8 +464,12 @@ where
             .dom
             .lookup_container(&parent_handle)
             .get_list_type()
-            .unwrap()
-            .clone();
+            .cloned()
+            .ok_or_else(|| {
+                ComposerError::Internal(
+                    "List items to unindent must be in a list".into(),
+                )
+            })?;
         let remaining_list_child_count = self
             .state
             .dom
@@ -458,8 +513,16 @@ where
             //
             // 'Fourth' here would be inside `list_items_after_removed_ones` and will be added
             // to the last un-indented ListItem, 'Third'.
-            let mut last_removed_list_item = removed_list_items.pop().unwrap();
-            let container = last_removed_list_item.as_container_mut().unwrap();
+            let mut last_removed_list_item =
+                removed_list_items.pop().ok_or_else(|| {
+                    ComposerError::Internal("No list items to unindent".into())
+                })?;
+            let container =
+                last_removed_list_item.as_container_mut().ok_or_else(|| {
+                    ComposerError::Internal(
+                        "Unindented list item must be a container".into(),
+                    )
+                })?;
             let needs_paragraph =
                 container.children().iter().any(|n| !n.is_block_node());
             if needs_paragraph {
@@ -495,6 +558,7 @@ where
             .insert(&insert_into_handle, removed_list_items);
 
         self.state.dom.join_nodes_in_container(&insert_into_handle);
+        Ok(())
     }
 }
 
@@ -536,14 +600,38 @@ mod tests {
     #[test]
     fn indent_list_item_works() {
         let mut model = cm("<ul><li>First item</li><li>Second item</li><li>Third item|</li></ul>");
-        model.indent_list_item_handles(&vec![DomHandle::from_raw(vec![0, 1])]);
+        model
+            .indent_list_item_handles(&vec![DomHandle::from_raw(vec![0, 1])])
+            .unwrap();
         assert_eq!(tx(&model), "<ul><li><p>First item</p><ul><li>Second item</li></ul></li><li>Third item|</li></ul>");
     }
 
+    #[test]
+    fn indent_list_item_wraps_several_inline_nodes() {
+        let mut model =
+            cm("<ul><li>First <b>item</b></li><li>Second item|</li></ul>");
+        model
+            .indent_list_item_handles(&[DomHandle::from_raw(vec![0, 1])])
+            .unwrap();
+        assert_eq!(tx(&model), "<ul><li><p>First <b>item</b></p><ul><li>Second item|</li></ul></li></ul>");
+    }
+
+    #[test]
+    fn indent_list_item_after_an_empty_one() {
+        let mut model = cm("<ol><li></li><li>|</li></ol>");
+        model.indent().unwrap();
+        assert_eq!(
+            tx(&model),
+            "<ol><li><p>&nbsp;</p><ol><li>|</li></ol></li></ol>"
+        );
+    }
+
     #[test]
     fn indent_list_item_to_previous_works() {
         let mut model = cm("<ul><li><p>First item</p><ul><li>Second item</li></ul></li><li>Third item|</li></ul>");
-        model.indent_list_item_handles(&vec![DomHandle::from_raw(vec![0, 1])]);
+        model
+            .indent_list_item_handles(&vec![DomHandle::from_raw(vec![0, 1])])
+            .unwrap();
         assert_eq!(tx(&model), "<ul><li><p>First item</p><ul><li>Second item</li><li>Third item|</li></ul></li></ul>");
     }
 
@@ -577,7 +665,7 @@ mod tests {
         let mut model =
             cm("<ul><li><p>First item</p><ul><li>{Second item}|</li></ul></li></ul>");
         let handles = vec![DomHandle::from_raw(vec![0, 0, 1, 0])];
-        model.unindent_handles(&handles);
+        model.unindent_handles(&handles).unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li>First item</li><li>{Second item}|</li></ul>"
@@ -17,8 +17,8 @@ use crate::{
         nodes::{MentionNode, MentionNodeKind},
         DomLocation,
     },
-    ComposerModel, ComposerUpdate, DomNode, Location, MentionsState,
-    SuggestionPattern, UnicodeString,
+    ComposerError, ComposerModel, ComposerUpdate, DomNode, Location,
+    MentionsState, SuggestionPattern, UnicodeString,
 };
 
 impl<S> ComposerModel<S>
@@ -67,24 +67,28 @@ where
         text: S,
         suggestion: SuggestionPattern,
         attributes: Vec<(S, S)>,
-    ) -> ComposerUpdate<S> {
-        if self.range_contains_link_or_code_leaves() {
-            return ComposerUpdate::keep();
-        }
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.range_contains_link_or_code_leaves() {
+                return Ok(ComposerUpdate::keep());
+            }
 
-        if let Ok(mention_node) = DomNode::new_mention(url, text, attributes) {
-            self.push_state_to_history();
-            self.do_replace_text_in(
-                S::default(),
-                suggestion.start,
-                suggestion.end,
-            );
-            self.state.start = Location::from(suggestion.start);
-            self.state.end = self.state.start;
-            self.do_insert_mention(mention_node)
-        } else {
-            ComposerUpdate::keep()
-        }
+            if let Ok(mention_node) =
+                DomNode::new_mention(url, text, attributes)
+            {
+                model.push_state_to_history();
+                model.do_replace_text_in(
+                    S::default(),
+                    suggestion.start,
+                    suggestion.end,
+                )?;
+                model.state.start = Location::from(suggestion.start);
+                model.state.end = model.state.start;
+                model.do_insert_mention(mention_node)
+            } else {
+                Ok(ComposerUpdate::keep())
+            }
+        })
     }
 
     /// Checks to see if the mention should be inserted and also if the mention can be created.
@@ -94,20 +98,24 @@ where
         url: S,
         text: S,
         attributes: Vec<(S, S)>,
-    ) -> ComposerUpdate<S> {
-        if self.range_contains_link_or_code_leaves() {
-            return ComposerUpdate::keep();
-        }
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.range_contains_link_or_code_leaves() {
+                return Ok(ComposerUpdate::keep());
+            }
 
-        if let Ok(mention_node) = DomNode::new_mention(url, text, attributes) {
-            self.push_state_to_history();
-            if self.has_selection() {
-                self.do_replace_text(S::default());
+            if let Ok(mention_node) =
+                DomNode::new_mention(url, text, attributes)
+            {
+                model.push_state_to_history();
+                if model.has_selection() {
+                    model.do_replace_text(S::default())?;
+                }
+                model.do_insert_mention(mention_node)
+            } else {
+                Ok(ComposerUpdate::keep())
             }
-            self.do_insert_mention(mention_node)
-        } else {
-            ComposerUpdate::keep()
-        }
+        })
     }
 
     /// Checks to see if the at-room mention should be inserted.
@@ -116,18 +124,24 @@ where
         &mut self,
         suggestion: SuggestionPattern,
         attributes: Vec<(S, S)>,
-    ) -> ComposerUpdate<S> {
-        if self.range_contains_link_or_code_leaves() {
-            return ComposerUpdate::keep();
-        }
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.range_contains_link_or_code_leaves() {
+                return Ok(ComposerUpdate::keep());
+            }
 
-        self.push_state_to_history();
-        self.do_replace_text_in(S::default(), suggestion.start, suggestion.end);
-        self.state.start = Location::from(suggestion.start);
-        self.state.end = self.state.start;
+            model.push_state_to_history();
+            model.do_replace_text_in(
+                S::default(),
+                suggestion.start,
+                suggestion.end,
+            )?;
+            model.state.start = Location::from(suggestion.start);
+            model.state.end = model.state.start;
 
-        let mention_node = DomNode::new_at_room_mention(attributes);
-        self.do_insert_mention(mention_node)
+            let mention_node = DomNode::new_at_room_mention(attributes);
+            model.do_insert_mention(mention_node)
+        })
     }
 
     /// Checks to see if the at-room mention should be inserted.
@@ -135,18 +149,20 @@ where
     pub fn insert_at_room_mention(
         &mut self,
         attributes: Vec<(S, S)>,
-    ) -> ComposerUpdate<S> {
-        if self.range_contains_link_or_code_leaves() {
-            return ComposerUpdate::keep();
-        }
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.range_contains_link_or_code_leaves() {
+                return Ok(ComposerUpdate::keep());
+            }
 
-        self.push_state_to_history();
-        if self.has_selection() {
-            self.do_replace_text(S::default());
-        }
+            model.push_state_to_history();
+            if model.has_selection() {
+                model.do_replace_text(S::default())?;
+            }
 
-        let mention_node = DomNode::new_at_room_mention(attributes);
-        self.do_insert_mention(mention_node)
+            let mention_node = DomNode::new_at_room_mention(attributes);
+            model.do_insert_mention(mention_node)
+        })
     }
 
     /// Inserts the node at the cursor position. It adds a trailing space when the inserted
@@ -154,7 +170,7 @@ where
     fn do_insert_mention(
         &mut self,
         mention_node: MentionNode<S>,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let (start, end) = self.safe_selection();
         let range = self.state.dom.find_range(start, end);
 
@@ -173,7 +189,7 @@ where
         if self.state.dom.is_last_in_parent(&handle) {
             self.do_replace_text(" ".into())
         } else {
-            self.create_update_replace_all()
+            Ok(self.create_update_replace_all())
         }
     }
 
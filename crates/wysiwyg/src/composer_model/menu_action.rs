@@ -19,8 +19,11 @@ use crate::{
         unicode_string::{UnicodeStr, UnicodeStringExt},
         Range,
     },
-    ComposerModel, MenuAction, PatternKey, SuggestionPattern, UnicodeString,
+    ComposerModel, MenuAction, PatternKey, SuggestionPattern,
+    SuggestionTrigger, UnicodeString,
 };
+#[cfg(feature = "emoji")]
+use crate::{emoji, EmojiSuggestion};
 
 impl<S> ComposerModel<S>
 where
@@ -41,9 +44,10 @@ where
         let (raw_text, start, end) = self.extended_text(range);
 
         if let Some((key, text)) = Self::pattern_for_text(
-            raw_text,
+            raw_text.clone(),
             start,
             &self.custom_suggestion_patterns,
+            &self.custom_suggestion_triggers,
         ) {
             MenuAction::Suggestion(SuggestionPattern {
                 key,
@@ -51,11 +55,40 @@ where
                 start,
                 end,
             })
+        } else if self.emoji_suggestions_enabled {
+            Self::emoji_action_for_text(raw_text, start, end)
         } else {
             MenuAction::None
         }
     }
 
+    /// Compute an emoji suggestion from the built-in emoji table if the
+    /// given text is a (partial) `:shortcode` with at least one candidate.
+    #[cfg(feature = "emoji")]
+    fn emoji_action_for_text(text: S, start: usize, end: usize) -> MenuAction {
+        let Some(query) = emoji::shortcode_query(&text.to_string()) else {
+            return MenuAction::None;
+        };
+        let candidates = emoji::candidates_for_query(&query);
+        if candidates.is_empty() {
+            return MenuAction::None;
+        }
+        MenuAction::EmojiSuggestion(EmojiSuggestion {
+            suggestion_pattern: SuggestionPattern {
+                key: PatternKey::Custom(emoji::SHORTCODE_PREFIX.to_string()),
+                text: query,
+                start,
+                end,
+            },
+            candidates,
+        })
+    }
+
+    #[cfg(not(feature = "emoji"))]
+    fn emoji_action_for_text(_: S, _: usize, _: usize) -> MenuAction {
+        MenuAction::None
+    }
+
     /// Compute extended text from a range. Text is extended up
     /// to the leading/trailing of the text nodes, or up to the
     /// first whitespace found.
@@ -87,12 +120,16 @@ where
         mut text: S,
         start_location: usize,
         custom_suggestion_patterns: &HashSet<String>,
+        custom_suggestion_triggers: &[SuggestionTrigger],
     ) -> Option<(PatternKey, String)> {
         let Some(key) = PatternKey::from_string_and_suggestions(
             text.to_string(),
             custom_suggestion_patterns,
         ) else {
-            return None;
+            return Self::pattern_for_triggers(
+                text,
+                custom_suggestion_triggers,
+            );
         };
 
         if key.is_static_pattern() {
@@ -109,4 +146,22 @@ where
             Some((key, text.to_string()))
         }
     }
+
+    /// Compute a custom pattern from the first trigger matching the given
+    /// text. The pattern key is the trigger prefix and the associated text
+    /// is the captured query.
+    fn pattern_for_triggers(
+        text: S,
+        custom_suggestion_triggers: &[SuggestionTrigger],
+    ) -> Option<(PatternKey, String)> {
+        if text.chars().any(|c| c.is_whitespace()) {
+            return None;
+        }
+        let text = text.to_string();
+        custom_suggestion_triggers.iter().find_map(|trigger| {
+            trigger.captured_query(&text).map(|query| {
+                (PatternKey::Custom(trigger.prefix().to_string()), query)
+            })
+        })
+    }
 }
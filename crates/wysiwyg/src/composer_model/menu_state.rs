@@ -64,7 +64,10 @@ where
         let mut action_states = HashMap::new();
 
         let reversed = self.compute_reversed_actions_from_range(range);
-        let disabled = self.compute_disabled_actions();
+        let mut disabled = self.compute_disabled_actions();
+        if !Self::has_formatting_to_clear(range, &reversed, true) {
+            disabled.insert(ComposerAction::ClearFormatting);
+        }
 
         for action in ComposerAction::iter() {
             let state = if disabled.contains(&action) {
@@ -80,7 +83,7 @@ where
         action_states
     }
 
-    fn compute_reversed_actions_from_range(
+    pub(crate) fn compute_reversed_actions_from_range(
         &self,
         range: &Range,
     ) -> HashSet<ComposerAction> {
@@ -185,7 +188,7 @@ where
         }
     }
 
-    fn compute_disabled_actions(&self) -> HashSet<ComposerAction> {
+    pub(crate) fn compute_disabled_actions(&self) -> HashSet<ComposerAction> {
         let mut disabled_actions = HashSet::new();
         if self.previous_states.is_empty() {
             disabled_actions.insert(ComposerAction::Undo);
@@ -199,6 +202,23 @@ where
         disabled_actions.extend(
             self.compute_disabled_actions_for_locations(&range.locations),
         );
+        // Commands are sent as plain text and markdown source has no rich
+        // formatting.
+        if self.refuses_formatting() {
+            disabled_actions.extend(vec![
+                ComposerAction::Bold,
+                ComposerAction::Italic,
+                ComposerAction::StrikeThrough,
+                ComposerAction::Underline,
+                ComposerAction::InlineCode,
+                ComposerAction::Link,
+                ComposerAction::OrderedList,
+                ComposerAction::UnorderedList,
+                ComposerAction::CodeBlock,
+                ComposerAction::Quote,
+                ComposerAction::ClearFormatting,
+            ])
+        }
         disabled_actions
     }
 
@@ -0,0 +1,232 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Switching between rich text and markdown source editing.
+//!
+//! In [ComposerMode::Plain] the DOM holds the markdown source as one
+//! paragraph per line, so the usual editing operations work on it. Mentions
+//! are kept as mention nodes rather than the HTML they are written as, so
+//! they still behave as pills.
+
+use crate::dom::nodes::DomNode;
+use crate::dom::parser::markdown::parse_markdown_with_dialect;
+use crate::dom::to_markdown::MarkdownError;
+use crate::dom::to_markdown::MarkdownOptions;
+use crate::dom::to_plain_text::ToPlainText;
+use crate::dom::unicode_string::UnicodeStringExt;
+use crate::dom::Dom;
+use crate::{
+    ComposerError, ComposerMode, ComposerModel, ComposerUpdate, Location,
+    ToHtml, ToMarkdown, UnicodeString,
+};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    pub fn mode(&self) -> ComposerMode {
+        self.mode
+    }
+
+    /// Start editing the content as markdown source, written in the
+    /// current [crate::MarkdownDialect]. The selection is moved to the
+    /// matching text in the source. This clears the undo history.
+    pub fn switch_to_plain(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.mode == ComposerMode::Plain {
+                return Ok(ComposerUpdate::keep());
+            }
+            let rich = model.markdown_offsets()?;
+            let start = rich.offset_of(model.state.start.into());
+            let end = rich.offset_of(model.state.end.into());
+            model.state.dom = model.source_dom(&model.state.dom)?;
+            model.mode = ComposerMode::Plain;
+            let plain = model.markdown_offsets()?;
+            Ok(model.finish_switch(
+                plain.location_of(start),
+                plain.location_of(end),
+            ))
+        })
+    }
+
+    /// Parse the markdown source being edited and go back to editing rich
+    /// text. The selection is moved to the matching text in the result.
+    /// This clears the undo history.
+    pub fn switch_to_rich(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            if model.mode == ComposerMode::Rich {
+                return Ok(ComposerUpdate::keep());
+            }
+            let plain = model.markdown_offsets()?;
+            let start = plain.offset_of(model.state.start.into());
+            let end = plain.offset_of(model.state.end.into());
+            let parsed = parse_markdown_with_dialect(
+                &model.plain_source(false)?,
+                &model.markdown_dialect,
+            )
+            .map_err(ComposerError::MarkdownParseError)?;
+            let start = parsed.location_for_source_offset(start);
+            let end = parsed.location_for_source_offset(end);
+            model.state.dom = parsed.dom;
+            Self::post_process_dom(&mut model.state.dom);
+            model.mode = ComposerMode::Rich;
+            Ok(model.finish_switch(start, end))
+        })
+    }
+
+    fn finish_switch(&mut self, start: usize, end: usize) -> ComposerUpdate<S> {
+        self.state.start = Location::from(start);
+        self.state.end = Location::from(end);
+        self.state.toggled_format_types.clear();
+        self.previous_states.clear();
+        self.next_states.clear();
+        self.create_update_replace_all_with_menu_state()
+    }
+
+    /// Whether rich formatting operations should do nothing, because the
+    /// message is a slash command or the content is markdown source.
+    pub(crate) fn refuses_formatting(&self) -> bool {
+        self.mode == ComposerMode::Plain || self.is_command()
+    }
+
+    /// The markdown source being edited in [ComposerMode::Plain], with
+    /// mentions written as they would be by [ToMarkdown].
+    pub(crate) fn plain_source(
+        &self,
+        as_message: bool,
+    ) -> Result<S, ComposerError> {
+        let options = MarkdownOptions::with_dialect(self.markdown_dialect);
+        let mut source = S::default();
+        self.fmt_plain_source(&mut source, &options, as_message)?;
+        Ok(source)
+    }
+
+    pub(crate) fn fmt_plain_source(
+        &self,
+        buffer: &mut S,
+        options: &MarkdownOptions,
+        as_message: bool,
+    ) -> Result<(), MarkdownError<S>> {
+        let track_offsets = options.contains(MarkdownOptions::TRACK_OFFSETS);
+        let dom = &self.state.dom;
+        let len = dom.text_len();
+        let mut pos = 0;
+        for location in dom.find_range(0, len).leaves() {
+            // Any gap before this leaf is made of block boundaries
+            for _ in pos..location.position {
+                buffer.push('\n');
+            }
+            match dom.lookup_node(&location.node_handle) {
+                DomNode::Text(text) => {
+                    text.fmt_plain_text(buffer, track_offsets)
+                }
+                DomNode::Mention(mention) => {
+                    mention.fmt_markdown(buffer, options, as_message)?
+                }
+                DomNode::LineBreak(line_break) => {
+                    line_break.fmt_plain_text(buffer, track_offsets)
+                }
+                DomNode::Container(_) => {}
+            }
+            pos = location.position + location.length;
+        }
+        for _ in pos..len {
+            buffer.push('\n');
+        }
+        Ok(())
+    }
+
+    /// The HTML of the message written in [ComposerMode::Plain]. If the
+    /// source can't be parsed, the source itself is sent.
+    pub(crate) fn plain_message_html(&self) -> S {
+        let parsed = self.plain_source(false).ok().and_then(|source| {
+            parse_markdown_with_dialect(&source, &self.markdown_dialect).ok()
+        });
+        match parsed {
+            Some(parsed) => parsed.dom.to_message_html(),
+            None => self.state.dom.to_message_html(),
+        }
+    }
+
+    /// A DOM holding the markdown source of `dom`, one paragraph per line,
+    /// with the mentions of `dom` kept as mention nodes.
+    pub(crate) fn source_dom(
+        &self,
+        dom: &Dom<S>,
+    ) -> Result<Dom<S>, ComposerError> {
+        let options = MarkdownOptions::with_dialect(self.markdown_dialect);
+        let mut markdown = S::default();
+        dom.fmt_markdown(&mut markdown, &options, false)?;
+        let markdown = markdown.to_string();
+
+        let mut lines = SourceLines::default();
+        let mut rest = markdown.as_str();
+        for mention in dom.iter_mentions() {
+            let mut written = S::default();
+            mention.fmt_markdown(&mut written, &options, false)?;
+            let written = written.to_string();
+            if let Some(index) = rest.find(&written) {
+                lines.push_text(&rest[..index]);
+                lines.push_node(DomNode::Mention(mention.clone()));
+                rest = &rest[index + written.len()..];
+            }
+        }
+        lines.push_text(rest);
+        Ok(Dom::new(lines.finish()))
+    }
+}
+
+/// Builds the paragraphs of a DOM in [ComposerMode::Plain].
+#[derive(Default)]
+struct SourceLines<S>
+where
+    S: UnicodeString,
+{
+    paragraphs: Vec<DomNode<S>>,
+    line: Vec<DomNode<S>>,
+}
+
+impl<S> SourceLines<S>
+where
+    S: UnicodeString,
+{
+    fn push_text(&mut self, text: &str) {
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                self.end_line();
+            }
+            if !line.is_empty() {
+                self.line.push(DomNode::new_text(S::from(line)));
+            }
+        }
+    }
+
+    fn push_node(&mut self, node: DomNode<S>) {
+        self.line.push(node);
+    }
+
+    fn end_line(&mut self) {
+        let line = std::mem::take(&mut self.line);
+        self.paragraphs.push(DomNode::new_paragraph(line));
+    }
+
+    fn finish(mut self) -> Vec<DomNode<S>> {
+        self.end_line();
+        self.paragraphs
+    }
+}
@@ -3,19 +3,32 @@ use crate::dom::nodes::dom_node::DomNodeKind::{
     Generic, Link, List, ListItem, Paragraph,
 };
 use crate::dom::{Dom, DomLocation};
-use crate::{ComposerModel, ComposerUpdate, DomNode, UnicodeString};
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, DomNode, UnicodeString,
+};
 
 impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
     /// Adds a new line break by creating a paragraph.
-    pub fn enter(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.do_enter()
+    pub fn enter(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            model.push_state_to_history();
+            model.do_enter()
+        })
+    }
+
+    pub(crate) fn do_enter(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.enter_in_dom()?;
+        Ok(self.create_update_replace_all())
     }
 
-    pub(crate) fn do_enter(&mut self) -> ComposerUpdate<S> {
+    /// Internal: add a new line without creating an update, so several
+    /// changes can be sent as one.
+    pub(crate) fn enter_in_dom(&mut self) -> Result<(), ComposerError> {
         let (s, e) = self.safe_selection();
         let range = self.state.dom.find_range(s, e);
 
@@ -28,48 +41,48 @@ where
                 DomNode::new_paragraph(Vec::new()),
             ]);
             self.state.advance_selection();
-            return self.create_update_replace_all();
+            return Ok(());
         }
 
-        // If the selection covered several characters, remove them first
+        // If the selection covered several characters, remove them first and
+        // start again from the collapsed cursor, as the range no longer
+        // matches the DOM
         if range.is_selection() {
-            self.do_replace_text(S::default());
+            self.replace_text_in_dom(S::default(), s, e)?;
+            if self.state.dom.document().children().is_empty() {
+                // Everything was removed, the new line is all that's left
+                self.state.dom.append_at_end_of_document(
+                    DomNode::new_paragraph(Vec::new()),
+                );
+                return Ok(());
+            }
+            return self.enter_in_dom();
         }
 
-        let block_location = range.deepest_block_node(None).expect(
-            "No block node selected (at least the root one should be here)",
-        );
+        // At least the root one should be there
+        let block_location =
+            range.deepest_block_node(None).ok_or_else(|| {
+                ComposerError::Internal("No block node selected".into())
+            })?;
         let block_handle = block_location.node_handle.clone();
 
-        // TODO: what if a block node was removed and the next one has the same type?
-        // If we the block node was removed, just insert an empty paragraph
-        if !self.state.dom.contains(&block_handle)
-            || self.state.dom.lookup_node(&block_handle).kind()
-                != block_location.kind
+        // An empty paragraph in a list item without text ends the list, the
+        // same as an empty list item would
+        let block_location = match range.deepest_block_node(Some(&block_handle))
         {
-            let mut insert_at = block_handle.clone();
-            loop {
-                let has_prev = insert_at.index_in_parent() > 0;
-                if self.state.dom.contains(&insert_at)
-                    || (has_prev
-                        && self.state.dom.contains(&insert_at.prev_sibling()))
-                {
-                    break;
-                }
-                if insert_at.has_parent() {
-                    insert_at = insert_at.parent_handle();
-                } else {
-                    break;
-                }
+            Some(list_item_location)
+                if block_location.kind == Paragraph
+                    && list_item_location.kind == ListItem
+                    && self
+                        .state
+                        .dom
+                        .lookup_node(&list_item_location.node_handle)
+                        .has_no_text() =>
+            {
+                list_item_location
             }
-            let paragraph = DomNode::new_paragraph(Vec::new());
-            if insert_at.is_root() {
-                self.state.dom.append_at_end_of_document(paragraph);
-            } else {
-                self.state.dom.insert_at(&block_handle, paragraph);
-            }
-            return self.create_update_replace_all();
-        }
+            _ => block_location,
+        };
 
         let first_leaf = range.leaves().next();
         match block_location.kind {
@@ -88,10 +101,10 @@ where
                         self.do_new_line_in_paragraph(
                             first_leaf,
                             block_location,
-                        );
+                        )?;
                     }
                 } else {
-                    self.do_new_line_in_paragraph(first_leaf, block_location);
+                    self.do_new_line_in_paragraph(first_leaf, block_location)?;
                 }
             }
             ListItem => {
@@ -119,15 +132,25 @@ where
                         self.state.dom.insert_at(&insert_at, li);
                     } else {
                         // Otherwise, add new paragraph after the current list
-                        let DomNode::Container(list_item) = li else {
-                            panic!("List item is not a container")
-                        };
                         // A list item without text might still contain some formatting nodes that
                         // should be transferred to the new paragraph.
-                        self.state.dom.insert_at(
-                            &list_handle.next_sibling(),
-                            DomNode::new_paragraph(list_item.take_children()),
-                        );
+                        let children = match li {
+                            DomNode::Container(list_item) => {
+                                list_item.take_children()
+                            }
+                            node => vec![node],
+                        };
+                        let paragraphs =
+                            if children.iter().any(|c| c.is_block_node()) {
+                                children
+                            } else {
+                                vec![DomNode::new_paragraph(children)]
+                            };
+                        let mut insert_at = list_handle.next_sibling();
+                        for paragraph in paragraphs {
+                            self.state.dom.insert_at(&insert_at, paragraph);
+                            insert_at = insert_at.next_sibling();
+                        }
                     }
                     // If list becomes empty, remove it too
                     if self.state.dom.lookup_container(&list_handle).is_empty()
@@ -157,7 +180,11 @@ where
                         DomNode::new_list_item(Vec::new()),
                     );
                 } else {
-                    let first_leaf = first_leaf.unwrap();
+                    let first_leaf = first_leaf.ok_or_else(|| {
+                        ComposerError::Internal(
+                            "No leaf to split the list item at".into(),
+                        )
+                    })?;
                     let mut sub_tree = self.state.dom.split_sub_tree_from(
                         &first_leaf.node_handle,
                         first_leaf.start_offset,
@@ -173,21 +200,23 @@ where
                 }
             }
             Generic => {
-                self.do_new_line_in_paragraph(first_leaf, block_location);
+                self.do_new_line_in_paragraph(first_leaf, block_location)?;
+            }
+            _ => {
+                return Err(ComposerError::Internal(format!(
+                    "Unexpected kind {:?} with inline contents",
+                    block_location.kind
+                )))
             }
-            _ => panic!(
-                "Unexpected kind {:?} with inline contents",
-                block_location.kind
-            ),
         }
-        self.create_update_replace_all()
+        Ok(())
     }
 
     fn do_new_line_in_paragraph(
         &mut self,
         first_leaf: Option<&DomLocation>,
         paragraph_location: &DomLocation,
-    ) {
+    ) -> Result<(), ComposerError> {
         if let Some(first_leaf) = first_leaf {
             let block_node_handle = paragraph_location.node_handle.clone();
             let block_node_is_paragraph =
@@ -201,6 +230,11 @@ where
                 .len();
             let last_child_handle =
                 block_node_handle.child_handle(child_count - 1);
+            let text_direction = self
+                .state
+                .dom
+                .lookup_container(&block_node_handle)
+                .text_direction();
 
             // Wrap the contents of the "right" sub tree into a paragraph and insert it
             let mut sub_tree = self.state.dom.split_sub_tree_between(
@@ -217,12 +251,17 @@ where
                 !self.state.dom.contains(&block_node_handle);
 
             let mut children = sub_tree_container.remove_children();
-            let new_paragraph =
+            let mut new_paragraph =
                 if children.first().map_or(false, |n| n.kind() == Paragraph) {
                     children.remove(0)
                 } else {
                     DomNode::new_paragraph(children)
                 };
+            if block_node_is_paragraph {
+                if let DomNode::Container(paragraph) = &mut new_paragraph {
+                    paragraph.set_text_direction(text_direction);
+                }
+            }
             let depth = if block_node_is_paragraph {
                 block_node_handle.depth()
             } else {
@@ -243,7 +282,9 @@ where
                 let DomNode::Container(block_container) =
                     self.state.dom.lookup_node_mut(&block_node_handle)
                 else {
-                    panic!("Block container must be a container node");
+                    return Err(ComposerError::Internal(
+                        "Block container must be a container node".into(),
+                    ));
                 };
                 let mut children = Vec::new();
                 for _ in 0..new_paragraph_handle.index_in_parent() {
@@ -270,6 +311,7 @@ where
             );
             self.state.advance_selection();
         }
+        Ok(())
     }
 
     fn do_new_line_in_block_node(
@@ -312,9 +354,14 @@ where
                 .insert_at(&insert_at, sub_tree.take_document());
         }
 
-        self.state
-            .dom
-            .insert_at(&insert_at, DomNode::new_paragraph(Vec::new()));
+        // Lists can only hold list items
+        let paragraph = DomNode::new_paragraph(Vec::new());
+        let new_node = if ancestor_block_location.kind == ListItem {
+            DomNode::new_list_item(vec![paragraph])
+        } else {
+            paragraph
+        };
+        self.state.dom.insert_at(&insert_at, new_node);
     }
 }
 
@@ -333,49 +380,49 @@ mod test {
     #[test]
     fn test_new_line_in_empty_dom() {
         let mut model = cm("|");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;</p><p>&nbsp;|</p>");
     }
 
     #[test]
     fn test_new_line_in_plain_text() {
         let mut model = cm("Test| lines");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>Test</p><p>|&nbsp;lines</p>");
     }
 
     #[test]
     fn test_new_line_at_start() {
         let mut model = cm("|Test lines");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;</p><p>|Test lines</p>");
     }
 
     #[test]
     fn test_new_line_at_end() {
         let mut model = cm("Test lines|");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>Test lines</p><p>&nbsp;|</p>");
     }
 
     #[test]
     fn test_new_line_after_emoji() {
         let mut model = cm("🤗|");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>🤗</p><p>&nbsp;|</p>");
     }
 
     #[test]
     fn test_new_line_in_formatted_text() {
         let mut model = cm("<b>Test| lines</b>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p><b>Test</b></p><p><b>|&nbsp;lines</b></p>");
     }
 
     #[test]
     fn test_new_line_in_paragraph() {
         let mut model = cm("<p>Test| lines</p>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>Test</p><p>|&nbsp;lines</p>");
     }
 
@@ -412,36 +459,36 @@ mod test {
     #[test]
     fn add_line_at_start_of_paragraph() {
         let mut model = cm("<p>|Test</p>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;</p><p>|Test</p>");
-        model.select(0.into(), 0.into());
+        model.select(0.into(), 0.into()).unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;|</p><p>Test</p>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;</p><p>&nbsp;|</p><p>Test</p>");
     }
 
     #[test]
     fn add_line_at_start_of_empty_paragraph() {
         let mut model = cm("<p>|</p><p>Test</p>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;</p><p>&nbsp;|</p><p>Test</p>");
     }
 
     #[test]
     fn repeated_line_breaks_in_quote_split_it() {
         let mut model = cm("<blockquote><p>First|Second</p></blockquote>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p>First</p><p>|Second</p></blockquote>"
         );
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p>First</p><p>&nbsp;</p><p>|Second</p></blockquote>"
         );
-        model.select(6.into(), 6.into());
-        model.enter();
+        model.select(6.into(), 6.into()).unwrap();
+        model.enter().unwrap();
         assert_eq!(tx(&model), "<blockquote><p>First</p></blockquote><p>&nbsp;|</p><blockquote><p>Second</p></blockquote>");
     }
 
@@ -449,7 +496,7 @@ mod test {
     fn line_break_in_empty_paragraph_inside_quote_splits_quote() {
         let mut model =
             cm("<blockquote><p>First</p><p>|</p><p>Second</p></blockquote>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p>First</p></blockquote><p>&nbsp;|</p><blockquote><p>Second</p></blockquote>"
@@ -459,7 +506,7 @@ mod test {
     #[test]
     fn write_text_in_empty_paragraph() {
         let mut model = cm("<p>|</p>");
-        model.replace_text("Testing".into());
+        model.replace_text("Testing".into()).unwrap();
         assert_eq!(tx(&model), "<p>Testing|</p>");
     }
 
@@ -472,7 +519,7 @@ mod test {
                 <ul><li>|</li></ul>\
             </li>\
         </ul>");
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(
             tx(&model),
             "\
@@ -488,7 +535,7 @@ mod test {
         let mut model = cm(
             r#"|<a data-mention-type="user" href="https://matrix.to/#/@carol:matrix.org" contenteditable="false">@carol</a>"#,
         );
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(
             tx(&model),
             r#"<p>&nbsp;</p><p>|<a data-mention-type="user" href="https://matrix.to/#/@carol:matrix.org" contenteditable="false">@carol</a></p>"#
@@ -500,7 +547,7 @@ mod test {
         let mut model = cm(
             r#"<a data-mention-type="user" href="https://matrix.to/#/@carol:matrix.org" contenteditable="false">@carol</a>|"#,
         );
-        model.enter();
+        model.enter().unwrap();
         assert_eq!(
             tx(&model),
             r#"<p><a data-mention-type="user" href="https://matrix.to/#/@carol:matrix.org" contenteditable="false">@carol</a></p><p>&nbsp;|</p>"#
@@ -0,0 +1,227 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between [Location]s and offsets in the markdown and plain
+//! text versions of the content.
+
+use std::collections::HashMap;
+
+use crate::dom::nodes::{DomNode, MentionNode};
+use crate::dom::offset_markers::take_markers;
+use crate::dom::to_markdown::MarkdownOptions;
+use crate::dom::to_plain_text::ToPlainText;
+use crate::dom::unicode_string::{UnicodeStr, UnicodeStrExt};
+use crate::{
+    ComposerError, ComposerMode, ComposerModel, DomHandle, Location,
+    ToMarkdown, UnicodeString,
+};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// The offset in [ComposerModel::get_content_as_markdown] matching
+    /// `location`, in code units.
+    pub fn location_to_markdown_offset(
+        &self,
+        location: Location,
+    ) -> Result<usize, ComposerError> {
+        Ok(self.markdown_offsets()?.offset_of(location.into()))
+    }
+
+    /// The [Location] matching an offset in
+    /// [ComposerModel::get_content_as_markdown]. Offsets inside markdown
+    /// syntax map to the nearest text on the same line.
+    pub fn markdown_offset_to_location(
+        &self,
+        offset: usize,
+    ) -> Result<Location, ComposerError> {
+        Ok(Location::from(self.markdown_offsets()?.location_of(offset)))
+    }
+
+    /// The offset in [ComposerModel::get_content_as_plain_text] matching
+    /// `location`, in code units.
+    pub fn location_to_plain_text_offset(&self, location: Location) -> usize {
+        self.plain_text_offsets().offset_of(location.into())
+    }
+
+    /// The [Location] matching an offset in
+    /// [ComposerModel::get_content_as_plain_text].
+    pub fn plain_text_offset_to_location(&self, offset: usize) -> Location {
+        Location::from(self.plain_text_offsets().location_of(offset))
+    }
+
+    pub(crate) fn markdown_offsets(&self) -> Result<OffsetMap, ComposerError> {
+        let options = MarkdownOptions::with_dialect(self.markdown_dialect);
+        let mut mention_lens = HashMap::new();
+        for mention in self.state.dom.iter_mentions() {
+            let mut written = S::default();
+            mention.fmt_markdown(&mut written, &options, false)?;
+            mention_lens.insert(mention.handle(), written.len());
+        }
+        let mention_len = |mention: &MentionNode<S>| {
+            mention_lens
+                .get(&mention.handle())
+                .copied()
+                .unwrap_or_default()
+        };
+        let mut tracked = options;
+        tracked.insert(MarkdownOptions::TRACK_OFFSETS);
+        let mut marked = S::default();
+        match self.mode {
+            ComposerMode::Rich => {
+                self.state.dom.fmt_markdown(&mut marked, &tracked, false)?
+            }
+            ComposerMode::Plain => {
+                self.fmt_plain_source(&mut marked, &tracked, false)?
+            }
+        }
+        Ok(self.offset_map(&marked, mention_len))
+    }
+
+    fn plain_text_offsets(&self) -> OffsetMap {
+        let mut marked = S::default();
+        self.state.dom.fmt_plain_text(&mut marked, true);
+        self.offset_map(&marked, |mention| mention.display_text().len())
+    }
+
+    /// Find where each leaf ended up in `marked` text, written with offset
+    /// markers.
+    fn offset_map(
+        &self,
+        marked: &S,
+        mention_len: impl Fn(&MentionNode<S>) -> usize,
+    ) -> OffsetMap {
+        let dom = &self.state.dom;
+        let positions: HashMap<DomHandle, usize> = dom
+            .find_range(0, dom.text_len())
+            .leaves()
+            .map(|l| (l.node_handle.clone(), l.position))
+            .collect();
+        let (text, markers) = take_markers(marked);
+        let mut chars = Vec::new();
+        let mut offset = 0;
+        for c in text.chars() {
+            chars.push((c, offset));
+            offset += text.char_len(&c);
+        }
+        let line_breaks = chars
+            .iter()
+            .filter(|(c, _)| *c == '\n')
+            .map(|(_, o)| *o)
+            .collect();
+
+        let mut pairs = Vec::new();
+        for (index, (handle, start)) in markers.iter().enumerate() {
+            let Some(&position) = positions.get(handle) else {
+                continue;
+            };
+            let end = markers.get(index + 1).map_or(text.len(), |(_, o)| *o);
+            let first = chars.partition_point(|(_, o)| o < start);
+            let last = chars.partition_point(|(_, o)| *o < end);
+            let written = &chars[first..last];
+            match dom.lookup_node(handle) {
+                DomNode::Text(node) => {
+                    // Escapes, entities and indentation may have been
+                    // written between the chars of the text.
+                    let data = node.data();
+                    let mut location = position;
+                    let mut next = 0;
+                    let mut end_offset = None;
+                    for c in data.chars() {
+                        let found =
+                            written[next..].iter().position(|(w, _)| *w == c);
+                        if let Some(found) = found {
+                            let (_, offset) = written[next + found];
+                            pairs.push((location, offset));
+                            end_offset = Some(offset + data.char_len(&c));
+                            next += found + 1;
+                        }
+                        location += data.char_len(&c);
+                    }
+                    if let Some(end_offset) = end_offset {
+                        pairs.push((location, end_offset));
+                    }
+                }
+                DomNode::Mention(mention) => {
+                    pairs.push((position, *start));
+                    pairs.push((position + 1, start + mention_len(mention)));
+                }
+                DomNode::LineBreak(_) => {
+                    let after = written
+                        .iter()
+                        .find(|(c, _)| *c == '\n')
+                        .map_or(start + 1, |(_, o)| o + 1);
+                    pairs.push((position, *start));
+                    pairs.push((position + 1, after));
+                }
+                DomNode::Container(_) => {}
+            }
+        }
+        pairs.sort();
+        OffsetMap {
+            pairs,
+            line_breaks,
+            len: text.len(),
+        }
+    }
+}
+
+/// Matching positions in the model and in a serialized form of its
+/// content, in code units.
+pub(crate) struct OffsetMap {
+    /// Locations and their offsets, in ascending order of both.
+    pairs: Vec<(usize, usize)>,
+    /// The offsets of the `\n` chars.
+    line_breaks: Vec<usize>,
+    len: usize,
+}
+
+impl OffsetMap {
+    /// The offset of `location`. Locations between blocks are counted
+    /// from the end of the previous text.
+    pub fn offset_of(&self, location: usize) -> usize {
+        let index = self.pairs.partition_point(|(l, _)| *l <= location);
+        let Some(&(previous, offset)) =
+            index.checked_sub(1).and_then(|index| self.pairs.get(index))
+        else {
+            return 0;
+        };
+        let next = self.pairs.get(index).map_or(self.len, |(_, o)| *o);
+        (offset + location - previous).min(next).min(self.len)
+    }
+
+    /// The location of `offset`. Offsets in syntax between two texts map
+    /// to the end of the first one, unless there's a line break before the
+    /// offset.
+    pub fn location_of(&self, offset: usize) -> usize {
+        let index = self.pairs.partition_point(|(_, o)| *o <= offset);
+        let Some(&(location, previous)) =
+            index.checked_sub(1).and_then(|index| self.pairs.get(index))
+        else {
+            return self.pairs.first().map_or(0, |(l, _)| *l);
+        };
+        match self.pairs.get(index) {
+            Some(&(next, _))
+                if self
+                    .line_breaks
+                    .iter()
+                    .any(|o| previous <= *o && *o < offset) =>
+            {
+                next
+            }
+            _ => location,
+        }
+    }
+}
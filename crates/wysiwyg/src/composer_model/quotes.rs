@@ -12,26 +12,52 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::dom::nodes::dom_node::DomNodeKind::{Generic, ListItem, Quote};
+use crate::dom::nodes::dom_node::DomNodeKind::{
+    CodeBlock, Generic, ListItem, Quote,
+};
 use crate::dom::DomLocation;
 use crate::{
-    ComposerAction, ComposerModel, ComposerUpdate, DomNode, UnicodeString,
+    ComposerAction, ComposerError, ComposerModel, ComposerUpdate, DomNode,
+    UnicodeString,
 };
 
 impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
-    pub fn quote(&mut self) -> ComposerUpdate<S> {
-        if self.action_is_reversed(ComposerAction::Quote) {
-            self.remove_quote()
-        } else {
-            self.add_quote()
-        }
+    pub fn quote(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if model.refuses_formatting() {
+                return ComposerUpdate::keep();
+            }
+            if model.action_is_reversed(ComposerAction::Quote) {
+                model.remove_quote()
+            } else {
+                model.add_quote()
+            }
+        })
     }
 
     fn add_quote(&mut self) -> ComposerUpdate<S> {
         let (s, e) = self.safe_selection();
+        let range = self.state.dom.find_range(s, e);
+        if let Some(code_block_loc) =
+            range.deepest_node_of_kind(CodeBlock, None)
+        {
+            // Code blocks can't contain quotes, so wrap the whole code block
+            // when the selection is inside a single one.
+            let is_inside_code_block = range.leaves().all(|l| {
+                code_block_loc.node_handle.is_ancestor_of(&l.node_handle)
+            });
+            if is_inside_code_block {
+                let handle = code_block_loc.node_handle.clone();
+                let code_block = self.state.dom.remove(&handle);
+                self.state
+                    .dom
+                    .insert_at(&handle, DomNode::new_quote(vec![code_block]));
+                return self.create_update_replace_all();
+            }
+        }
         let Some(wrap_result) =
             self.state.dom.find_nodes_to_wrap_in_block(s, e)
         else {
@@ -41,36 +67,32 @@ where
             let leaves: Vec<&DomLocation> = range.leaves().collect();
             let node =
                 DomNode::new_quote(vec![DomNode::new_paragraph(Vec::new())]);
-            if leaves.is_empty() {
-                if let Some(deepest_block_location) =
-                    range.deepest_block_node(None)
-                {
-                    let mut block_node = self
-                        .state
-                        .dom
-                        .remove(&deepest_block_location.node_handle);
-                    let node = if block_node.is_list_item() {
-                        let list_item = block_node.as_container_mut().unwrap();
-                        let children = list_item.remove_children();
-                        list_item.append_child(DomNode::new_quote(children));
-                        block_node
-                    } else {
-                        DomNode::new_quote(vec![block_node])
-                    };
-                    self.state
-                        .dom
-                        .insert_at(&deepest_block_location.node_handle, node);
-                } else {
-                    self.state.dom.append_at_end_of_document(node);
-                }
-            } else {
-                let first_leaf_loc = leaves.first().unwrap();
+            if let Some(first_leaf_loc) = leaves.first() {
                 let insert_at = if first_leaf_loc.is_start() {
                     first_leaf_loc.node_handle.next_sibling()
                 } else {
                     first_leaf_loc.node_handle.clone()
                 };
                 self.state.dom.insert_at(&insert_at, node);
+            } else if let Some(deepest_block_location) =
+                range.deepest_block_node(None)
+            {
+                let mut block_node =
+                    self.state.dom.remove(&deepest_block_location.node_handle);
+                let node = if let Some(list_item) =
+                    block_node.as_container_mut().filter(|c| c.is_list_item())
+                {
+                    let children = list_item.remove_children();
+                    list_item.append_child(DomNode::new_quote(children));
+                    block_node
+                } else {
+                    DomNode::new_quote(vec![block_node])
+                };
+                self.state
+                    .dom
+                    .insert_at(&deepest_block_location.node_handle, node);
+            } else {
+                self.state.dom.append_at_end_of_document(node);
             }
             return self.create_update_replace_all();
         };
@@ -153,21 +175,21 @@ mod test {
     #[test]
     fn apply_quote_to_empty_dom() {
         let mut model = cm("|");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(tx(&model), "<blockquote><p>&nbsp;|</p></blockquote>")
     }
 
     #[test]
     fn apply_quote_to_simple_text() {
         let mut model = cm("Some text|");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(tx(&model), "<blockquote><p>Some text|</p></blockquote>")
     }
 
     #[test]
     fn apply_quote_to_formatted_text() {
         let mut model = cm("<i>Some text|</i>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p><i>Some text|</i></p></blockquote>"
@@ -177,27 +199,27 @@ mod test {
     #[test]
     fn apply_quote_to_nested_formatted_text() {
         let mut model = cm("<i><b>Some text|</b></i>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
-            "<blockquote><p><i><b>Some text|</b></i></p></blockquote>"
+            "<blockquote><p><b><i>Some text|</i></b></p></blockquote>"
         )
     }
 
     #[test]
     fn apply_quote_to_text_and_formatted_text() {
         let mut model = cm("Plain text and <i><b>Some formatted text|</b></i>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
-            "<blockquote><p>Plain text and <i><b>Some formatted text|</b></i></p></blockquote>"
+            "<blockquote><p>Plain text and <b><i>Some formatted text|</i></b></p></blockquote>"
         )
     }
 
     #[test]
     fn apply_quote_to_single_list_item() {
         let mut model = cm("<ul><li>List item|</li></ul>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><blockquote><p>List item|</p></blockquote></li></ul>"
@@ -208,7 +230,7 @@ mod test {
     fn apply_quote_to_list() {
         let mut model =
             cm("<ul><li>First {item</li><li>Second}| item</li></ul>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><ul><li>First {item</li><li>Second}| item</li></ul></blockquote>"
@@ -219,7 +241,7 @@ mod test {
     fn apply_quote_to_list_and_formatted_text() {
         let mut model =
             cm("<p>Plain text <b>bold {text</b></p><ul><li>First item</li><li>Second}| item</li></ul>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p>Plain text <b>bold {text</b></p><ul><li>First item</li><li>Second}| item</li></ul></blockquote>"
@@ -229,7 +251,7 @@ mod test {
     #[test]
     fn apply_quote_to_simple_text_with_paragraphs() {
         let mut model = cm("<p>Some |text</p><p>Next line</p>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p>Some |text</p></blockquote><p>Next line</p>"
@@ -239,7 +261,7 @@ mod test {
     #[test]
     fn apply_quote_to_several_nodes_with_line_breaks() {
         let mut model = cm("<p>Some {text</p><p><b>Next}| line</b></p>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p>Some {text</p><p><b>Next}| line</b></p></blockquote>"
@@ -249,7 +271,7 @@ mod test {
     #[test]
     fn apply_quote_to_formatted_text_with_line_breaks() {
         let mut model = cm("<b>Some |text<br />Next line</b>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><p><b>Some |text</b></p></blockquote><p><b>Next line</b></p>"
@@ -261,7 +283,7 @@ mod test {
         let mut model = cm(
             "<ul><li><p><b>Some |text</b></p><p><b>Next line</b></p></li></ul>",
         );
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><blockquote><p><b>Some |text</b></p></blockquote><p><b>Next line</b></p></li></ul>"
@@ -272,7 +294,7 @@ mod test {
     fn apply_quote_to_several_list_items_with_formatted_text_and_line_breaks() {
         let mut model =
             cm("<ul><li><b>Some {text<br />Next line</b></li><li><i>Second}| item</i></li><li>Third item</li></ul>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><ul><li><p><b>Some {text</b></p><p><b>Next line</b></p></li><li><i>Second}| item</i></li></ul></blockquote><ul><li>Third item</li></ul>"
@@ -282,7 +304,7 @@ mod test {
     #[test]
     fn apply_quote_to_code_block() {
         let mut model = cm("<pre><code>Some| code</code></pre>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<blockquote><pre><code>Some| code</code></pre></blockquote>"
@@ -292,7 +314,7 @@ mod test {
     #[test]
     fn remove_quote_with_simple_text() {
         let mut model = cm("<blockquote><p>Text|</p></blockquote>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(tx(&model), "<p>Text|</p>");
     }
 
@@ -300,14 +322,14 @@ mod test {
     fn remove_quote_with_simple_text_with_adjacent_nodes() {
         let mut model =
             cm("<blockquote><p>Text|</p></blockquote><p>with plain text</p>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(tx(&model), "<p>Text|</p><p>with plain text</p>");
     }
 
     #[test]
     fn remove_quote_with_nested_formatted_text() {
         let mut model = cm("<blockquote><p><b><i>Text|</i></b></p><p><b><i>Some other text</i></b></p></blockquote>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<p><b><i>Text|</i></b></p><p><b><i>Some other text</i></b></p>"
@@ -317,7 +339,7 @@ mod test {
     #[test]
     fn remove_quote_with_list() {
         let mut model = cm("<blockquote><p><b><i>Text|</i></b></p><ul><li>Fist item</li></ul></blockquote>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<p><b><i>Text|</i></b></p><ul><li>Fist item</li></ul>"
@@ -327,16 +349,16 @@ mod test {
     #[test]
     fn create_and_remove_quote() {
         let mut model = cm("|");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(tx(&model), "<blockquote><p>&nbsp;|</p></blockquote>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(tx(&model), "<p>&nbsp;|</p>");
     }
 
     #[test]
     fn add_quote_to_empty_list_item() {
         let mut model = cm("<ul><li>|</li></ul>");
-        model.quote();
+        model.quote().unwrap();
         assert_eq!(
             tx(&model),
             "<ul><li><blockquote><p>&nbsp;|</p></blockquote></li></ul>"
@@ -15,12 +15,14 @@
 use crate::dom::nodes::dom_node::DomNodeKind;
 use crate::dom::nodes::DomNode;
 use crate::dom::unicode_string::UnicodeStrExt;
-use crate::dom::{DomLocation, Range};
+use crate::dom::{Dom, DomLocation, Range};
 use crate::{
-    ComposerModel, ComposerUpdate, DomHandle, Location, SuggestionPattern,
+    ComposerError, ComposerModel, ComposerState, ComposerUpdate, DomHandle,
+    InlineFormatType, Location, SuggestionPattern, TypographyRule,
     UnicodeString,
 };
 use std::cmp::min;
+use std::mem;
 
 impl<S> ComposerModel<S>
 where
@@ -29,20 +31,67 @@ where
     /// Replaces text in the current selection with new_text.
     /// Treats its input as plain text, so any HTML code will show up in
     /// the document (i.e. it will be escaped).
-    pub fn replace_text(&mut self, new_text: S) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.do_replace_text(new_text)
+    /// If a max length is set, the text may be truncated or rejected.
+    pub fn replace_text(
+        &mut self,
+        new_text: S,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let (s, e) = model.safe_selection();
+            let (new_text, max_length_exceeded) =
+                match model.limit_input(new_text, s, e) {
+                    Ok(limited) => limited,
+                    Err(rejected) => {
+                        return Ok(model
+                            .create_update_replace_all()
+                            .with_max_length_exceeded(Some(rejected)))
+                    }
+                };
+            model.push_state_to_history();
+            let typed = new_text.to_string();
+            let update = model.do_replace_text(new_text)?;
+            let update = match model.apply_typography_rules(&typed) {
+                Some(replaced) => replaced?,
+                None => update,
+            };
+            Ok(update.with_max_length_exceeded(max_length_exceeded))
+        })
+    }
+
+    /// Set the substitutions applied to text typed through
+    /// [ComposerModel::replace_text], e.g. [TypographyRule::defaults]. No
+    /// substitutions are made by default.
+    pub fn set_typography_rules(&mut self, rules: Vec<TypographyRule>) {
+        self.typography_rules = rules;
     }
 
     /// Replaces text in the an arbitrary start..end range with new_text.
+    /// If a max length is set, the text may be truncated or rejected.
     pub fn replace_text_in(
         &mut self,
         new_text: S,
         start: usize,
         end: usize,
-    ) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.do_replace_text_in(new_text, start, end)
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            let (start, end) = model.safe_locations_from(
+                model.char_boundary_before(Location::from(start)),
+                model.char_boundary_before(Location::from(end)),
+            );
+            let (new_text, max_length_exceeded) =
+                match model.limit_input(new_text, start, end) {
+                    Ok(limited) => limited,
+                    Err(rejected) => {
+                        return Ok(model
+                            .create_update_replace_all()
+                            .with_max_length_exceeded(Some(rejected)))
+                    }
+                };
+            model.push_state_to_history();
+            Ok(model
+                .do_replace_text_in(new_text, start, end)?
+                .with_max_length_exceeded(max_length_exceeded))
+        })
     }
 
     pub fn replace_text_suggestion(
@@ -50,32 +99,43 @@ where
         new_text: S,
         suggestion: SuggestionPattern,
         append_space: bool,
-    ) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        let replace_suggestion_update =
-            self.do_replace_text_in(new_text, suggestion.start, suggestion.end);
-        if append_space {
-            self.do_replace_text(" ".into())
-        } else {
-            replace_suggestion_update
-        }
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            model.push_state_to_history();
+            let replace_suggestion_update = model.do_replace_text_in(
+                new_text,
+                suggestion.start,
+                suggestion.end,
+            )?;
+            if append_space {
+                model.do_replace_text(" ".into())
+            } else {
+                Ok(replace_suggestion_update)
+            }
+        })
     }
 
     #[deprecated(since = "0.20.0")]
-    pub fn add_line_break(&mut self) -> ComposerUpdate<S> {
-        self.push_state_to_history();
-        self.do_add_line_break()
+    pub fn add_line_break(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.try_edit(|model| {
+            model.push_state_to_history();
+            model.do_add_line_break()
+        })
     }
 
-    fn do_add_line_break(&mut self) -> ComposerUpdate<S> {
+    fn do_add_line_break(
+        &mut self,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let (s, e) = self.safe_selection();
 
         if s == e {
             let range = self.state.dom.find_range(s, e);
-            self.enter_with_zero_length_selection(range)
+            Ok(self.enter_with_zero_length_selection(range))
         } else {
             // Clear selection then enter.
-            self.do_replace_text_in("".into(), s, e);
+            self.do_replace_text_in("".into(), s, e)?;
             self.do_add_line_break()
         }
     }
@@ -190,7 +250,76 @@ where
         self.state.end = self.state.start;
     }
 
-    pub(crate) fn do_replace_text(&mut self, new_text: S) -> ComposerUpdate<S> {
+    /// If `typed`, a single character just inserted before the cursor,
+    /// completes the pattern of a typography rule, replace the pattern.
+    /// This is a separate undo step, so undoing it restores the text as
+    /// typed. Code is left untouched.
+    fn apply_typography_rules(
+        &mut self,
+        typed: &str,
+    ) -> Option<Result<ComposerUpdate<S>, ComposerError>> {
+        let mut chars = typed.chars();
+        let (Some(typed), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        let (s, e) = self.safe_selection();
+        if self.typography_rules.is_empty() || s != e || s == 0 {
+            return None;
+        }
+        let range = self.state.dom.find_range(e - 1, e);
+        let in_code = range
+            .locations
+            .iter()
+            .filter(|l| l.start_offset < l.end_offset)
+            .any(|l| {
+                matches!(
+                    l.kind,
+                    DomNodeKind::CodeBlock
+                        | DomNodeKind::Formatting(InlineFormatType::InlineCode)
+                )
+            });
+        if in_code {
+            return None;
+        }
+        // Only the text node that was typed into is replaced, so the
+        // patterns don't cross formatting or mentions
+        let leaf = range.leaves().find(|l| l.start_offset < l.end_offset)?;
+        let DomNode::Text(text_node) =
+            self.state.dom.lookup_node(&leaf.node_handle)
+        else {
+            return None;
+        };
+        let own_text = text_node.data()[..leaf.end_offset].to_string();
+        // The character before the text node still decides e.g. which
+        // quote to use
+        let mut text = String::new();
+        if let Some(c) = self.char_before(leaf.position) {
+            text.push(c);
+        }
+        text.push_str(&own_text);
+        let (matched, replacement) =
+            self.typography_rules.iter().find_map(|rule| {
+                rule.substitution(&text, typed)
+                    .filter(|(matched, _)| matched.len() <= own_text.len())
+            })?;
+        let start = e - S::from(matched).len();
+        let replacement = S::from(replacement.as_str());
+        // Custom replacements can be longer than the text they replace, so
+        // the text is kept as typed if the replacement doesn't fit
+        if !matches!(
+            self.limit_input(replacement.clone(), start, e),
+            Ok((_, None))
+        ) {
+            return None;
+        }
+        self.push_state_to_history();
+        Some(self.do_replace_text_in(replacement, start, e))
+    }
+
+    pub(crate) fn do_replace_text(
+        &mut self,
+        new_text: S,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
         let (s, e) = self.safe_selection();
         self.do_replace_text_in(new_text, s, e)
     }
@@ -201,7 +330,21 @@ where
         new_text: S,
         start: usize,
         end: usize,
-    ) -> ComposerUpdate<S> {
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.replace_text_in_dom(new_text, start, end)?;
+        // TODO: for now, we replace every time, to check ourselves, but
+        // at least some of the time we should not
+        Ok(self.create_update_replace_all())
+    }
+
+    /// Internal: replace some text without creating an update, so that
+    /// the lines of a paste aren't each converted to HTML.
+    pub(crate) fn replace_text_in_dom(
+        &mut self,
+        new_text: S,
+        start: usize,
+        end: usize,
+    ) -> Result<(), ComposerError> {
         let text_string = new_text.to_string();
         // If passed start, end don't match the model's state, we can't fix them
         let (s, e) = self.safe_selection();
@@ -210,19 +353,31 @@ where
         // insert each slice while simulating calls to the
         // enter function in betweeen.
         if text_string.contains('\n') {
-            let mut slices = text_string.split('\n').peekable();
-            while let Some(slice) = slices.next() {
+            let slices: Vec<&str> = text_string.split('\n').collect();
+            let mut i = 0;
+            while i < slices.len() {
+                let is_last = i + 1 == slices.len();
                 let (s, e) = self.safe_selection();
-                if !slice.is_empty() && slice != "\u{A0}" {
-                    self.do_replace_text_in(S::from(slice), s, e);
+                if !is_last {
+                    let inserted =
+                        self.insert_lines_into_paragraph(&slices[i..], s, e)?;
+                    if inserted > 0 {
+                        i += inserted;
+                        continue;
+                    }
                 }
-                if slices.peek().is_some() {
-                    self.do_enter();
+                let slice = slices[i];
+                if !is_blank_line(slice) {
+                    self.replace_text_in_dom(S::from(slice), s, e)?;
                 }
+                if !is_last {
+                    self.enter_in_dom()?;
+                }
+                i += 1;
             }
         } else {
             let len = new_text.len();
-            self.state.dom.replace_text_in(new_text, start, end);
+            self.state.dom.replace_text_in(new_text, start, end)?;
             self.apply_pending_formats(start, start + len);
             let start = if needs_to_recalculate_selection {
                 let (new_start, _) = self.safe_selection();
@@ -234,13 +389,88 @@ where
                 Location::from(min(start + len, self.state.dom.text_len()));
             self.state.end = self.state.start;
         }
+        Ok(())
+    }
 
-        // TODO: for now, we replace every time, to check ourselves, but
-        // at least some of the time we should not
-        self.create_update_replace_all()
+    /// Insert the lines at the start of `lines` that have text, followed by
+    /// new lines, if the cursor is at the start of a paragraph. Returns how
+    /// many lines were inserted.
+    ///
+    /// This gives the same result as inserting them one by one, but each
+    /// line is split off a copy of the paragraph that has no siblings, so
+    /// pasting many lines doesn't look through the whole document for
+    /// every one of them.
+    fn insert_lines_into_paragraph(
+        &mut self,
+        lines: &[&str],
+        start: usize,
+        end: usize,
+    ) -> Result<usize, ComposerError> {
+        // Never the last line, which isn't followed by a new line
+        let count = lines[..lines.len() - 1]
+            .iter()
+            .take_while(|l| !is_blank_line(l))
+            .count();
+        if count == 0 || start != end {
+            return Ok(0);
+        }
+        let range = self.state.dom.find_range(start, end);
+        let Some(paragraph) = range.deepest_block_node(None) else {
+            return Ok(0);
+        };
+        if paragraph.kind != DomNodeKind::Paragraph
+            || paragraph.start_offset != 0
+        {
+            return Ok(0);
+        }
+
+        // Keep the ancestors, as a new line depends on where it is
+        let handle = paragraph.node_handle.clone();
+        let mut node = self.state.dom.lookup_node(&handle).clone();
+        let mut ancestor = handle.parent_handle();
+        while !ancestor.is_root() {
+            let container = self.state.dom.lookup_container(&ancestor);
+            node = DomNode::Container(
+                container.clone_with_new_children(vec![node]),
+            );
+            ancestor = ancestor.parent_handle();
+        }
+        let mut copy = ComposerModel::from_state(ComposerState {
+            dom: Dom::new(vec![node]),
+            start: Location::from(0),
+            end: Location::from(0),
+            toggled_format_types: mem::take(
+                &mut self.state.toggled_format_types,
+            ),
+        });
+        let copy_handle = DomHandle::from_raw(vec![0; handle.depth()]);
+
+        let mut paragraphs = Vec::new();
+        let mut cursor = start;
+        for line in &lines[..count] {
+            copy.replace_text_in_dom(S::from(*line), 0, 0)?;
+            copy.enter_in_dom()?;
+            let line_paragraph = copy.state.dom.remove(&copy_handle);
+            cursor += line_paragraph.text_len() + 1;
+            paragraphs.push(line_paragraph);
+            copy.state.start = Location::from(0);
+            copy.state.end = copy.state.start;
+        }
+        paragraphs.push(copy.state.dom.remove(&copy_handle));
+
+        self.state.dom.replace(&handle, paragraphs);
+        self.state.toggled_format_types = copy.state.toggled_format_types;
+        self.state.start = Location::from(cursor);
+        self.state.end = self.state.start;
+        Ok(count)
     }
 }
 
+/// Lines of pasted text without any text only add a new line.
+fn is_blank_line(line: &str) -> bool {
+    line.is_empty() || line == "\u{A0}"
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
@@ -261,7 +491,7 @@ mod test {
     #[test]
     fn composer_update_contains_escaped_html() {
         let mut model = cm("|");
-        let update = model.replace_text(Utf16String::from_str("<"));
+        let update = model.replace_text(Utf16String::from_str("<")).unwrap();
         assert_eq!(
             update,
             ComposerUpdate::replace_all(
@@ -284,6 +514,7 @@ mod test {
                 ComposerAction::Redo
                     | ComposerAction::Indent
                     | ComposerAction::Unindent
+                    | ComposerAction::ClearFormatting
             ) {
                 (action, ActionState::Disabled)
             } else {
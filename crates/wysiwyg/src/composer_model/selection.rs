@@ -12,19 +12,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{ComposerModel, ComposerUpdate, Location, UnicodeString};
+use crate::composer_model::boundaries::segment_at;
+use crate::composer_model::delete_text::Direction;
+use crate::dom::unicode_string::UnicodeStr;
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, DomNode, Granularity,
+    Location, UnicodeString,
+};
 
 impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
     /// Select the text at the supplied code unit positions.
-    /// The cursor is at end.
+    /// The cursor is at end. Positions past the end of the text are moved to
+    /// its end, and positions inside a character to its start.
     pub fn select(
         &mut self,
         start: Location,
         end: Location,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| model.do_select(start, end))
+    }
+
+    pub(crate) fn do_select(
+        &mut self,
+        start: Location,
+        end: Location,
     ) -> ComposerUpdate<S> {
+        let start = Location::from(self.clamped_position(start));
+        let end = Location::from(self.clamped_position(end));
         if self.state.start == start && self.state.end == end {
             return ComposerUpdate::keep();
         }
@@ -35,6 +52,144 @@ where
         self.create_update_update_selection()
     }
 
+    /// Select the whole document.
+    pub fn select_all(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let len = model.state.dom.text_len();
+            model.do_select(Location::from(0), Location::from(len))
+        })
+    }
+
+    /// Select the word at the supplied location, e.g. on double click. If
+    /// the location is between a word and whitespace or punctuation, the
+    /// word is selected. Mentions are selected as a whole.
+    pub fn select_word_at(
+        &mut self,
+        location: Location,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let pos = model.clamped_position(location);
+            let words = model.text_run_at(pos).words();
+            match segment_at(&words, pos) {
+                Some(word) => model.select_range(word.start, word.end),
+                None => model.select_range(pos, pos),
+            }
+        })
+    }
+
+    /// Select the sentence at the supplied location, without any
+    /// whitespace that follows it.
+    pub fn select_sentence_at(
+        &mut self,
+        location: Location,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let pos = model.clamped_position(location);
+            let sentences = model.text_run_at(pos).sentences();
+            match segment_at(&sentences, pos) {
+                Some(sentence) => {
+                    model.select_range(sentence.start, sentence.end)
+                }
+                None => model.select_range(pos, pos),
+            }
+        })
+    }
+
+    /// Select the paragraph at the supplied location, e.g. on triple click.
+    /// Paragraphs are delimited by line breaks and block boundaries.
+    pub fn select_paragraph_at(
+        &mut self,
+        location: Location,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let run = model.text_run_at(model.clamped_position(location));
+            model.select_range(run.start, run.end)
+        })
+    }
+
+    /// Move the end of the selection by `granularity` in `direction`,
+    /// keeping its start where it is.
+    pub fn extend_selection_by(
+        &mut self,
+        granularity: Granularity,
+        direction: Direction,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let end = model.clamped_position(model.state.end);
+            match model.next_boundary(end, granularity, direction) {
+                Some(end) => {
+                    model.do_select(model.state.start, Location::from(end))
+                }
+                None => ComposerUpdate::keep(),
+            }
+        })
+    }
+
+    /// Move the cursor by `granularity` in `direction`. If there is a
+    /// selection, moving by character collapses it to its start or end,
+    /// and moving by a larger unit starts from that end.
+    pub fn move_cursor(
+        &mut self,
+        direction: Direction,
+        granularity: Granularity,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let (s, e) = model.safe_selection();
+            let from = match direction {
+                Direction::Forwards => e,
+                Direction::Backwards => s,
+            };
+            if s != e && granularity == Granularity::Character {
+                return model.select_range(from, from);
+            }
+            match model.next_boundary(from, granularity, direction) {
+                Some(to) => model.select_range(to, to),
+                None => model.select_range(from, from),
+            }
+        })
+    }
+
+    fn select_range(&mut self, start: usize, end: usize) -> ComposerUpdate<S> {
+        self.do_select(Location::from(start), Location::from(end))
+    }
+
+    /// Move the selection back inside the document, e.g. when it was given
+    /// for content that turned out to be shorter.
+    pub(crate) fn clamp_selection(&mut self) {
+        let len = self.state.dom.text_len();
+        self.state.start =
+            Location::from(usize::from(self.state.start).min(len));
+        self.state.end = Location::from(usize::from(self.state.end).min(len));
+    }
+
+    fn clamped_position(&self, location: Location) -> usize {
+        let len = self.state.dom.text_len();
+        usize::from(self.char_boundary_before(location)).min(len)
+    }
+
+    /// Move `location` back to the start of the character it points
+    /// into, if any, so text is never split inside a character.
+    pub(crate) fn char_boundary_before(&self, location: Location) -> Location {
+        let pos = usize::from(location);
+        let range = self.state.dom.find_range(pos, pos);
+        for leaf in range.leaves() {
+            let DomNode::Text(text) =
+                self.state.dom.lookup_node(&leaf.node_handle)
+            else {
+                continue;
+            };
+            let mut offset = 0;
+            for c in text.data().chars() {
+                let next = offset + text.data().char_len(&c);
+                if next > leaf.start_offset {
+                    return Location::from(pos - (leaf.start_offset - offset));
+                }
+                offset = next;
+            }
+        }
+        location
+    }
+
     /// Return the start and end of the selection, ensuring the first number
     /// returned is <= the second, and they are both between 0 and the number
     /// of code units in the string representation of the Dom.
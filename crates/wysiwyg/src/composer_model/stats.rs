@@ -0,0 +1,166 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::dom::nodes::ContainerNode;
+use crate::dom::to_plain_text::ToPlainText;
+use crate::dom::unicode_string::UnicodeStr;
+use crate::{
+    ComposerModel, ComposerStats, DomNode, MaxLength, MaxLengthBehaviour,
+    MaxLengthExceeded, ToHtml, UnicodeString,
+};
+
+/// The number of plain text characters of each block at the top of the
+/// document, so that only blocks changed since the last count are counted
+/// again. Each block is kept with its count, which keeps its children
+/// shared with the document until either is changed.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PlainTextCounts<S>
+where
+    S: UnicodeString,
+{
+    blocks: HashMap<usize, (ContainerNode<S>, usize)>,
+}
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    pub fn stats(&self) -> ComposerStats {
+        let body = self.state.dom.to_plain_text().to_string();
+        let formatted_body = self.state.dom.to_message_html().to_string();
+        ComposerStats {
+            characters: body.chars().count(),
+            graphemes: body.graphemes(true).count(),
+            words: body.unicode_words().count(),
+            body_bytes: body.len(),
+            formatted_body_bytes: formatted_body.len(),
+        }
+    }
+
+    /// Limit the length of text typed or pasted through
+    /// [ComposerModel::replace_text] and [ComposerModel::replace_text_in].
+    /// Content that is already longer is kept, but can't grow any further.
+    pub fn set_max_length(&mut self, max_length: Option<MaxLength>) {
+        self.max_length = max_length;
+        self.plain_text_counts = PlainTextCounts::default();
+    }
+
+    pub fn max_length(&self) -> Option<MaxLength> {
+        self.max_length
+    }
+
+    /// Apply the max length to `new_text`, about to replace the text between
+    /// `start` and `end`. Returns the text to insert, with the reason if it
+    /// was truncated, or an error if none of it should be inserted.
+    pub(crate) fn limit_input(
+        &mut self,
+        new_text: S,
+        start: usize,
+        end: usize,
+    ) -> Result<(S, Option<MaxLengthExceeded>), MaxLengthExceeded> {
+        let Some(max_length) = self.max_length else {
+            return Ok((new_text, None));
+        };
+        let before = self.plain_text_chars();
+        let after = (before + new_text.chars().count())
+            .saturating_sub(self.plain_text_chars_in(start, end));
+        // Edits are allowed as long as they don't make the message longer
+        // than the limit, or than it already was.
+        let allowed = max_length.characters.max(before);
+        if after <= allowed {
+            return Ok((new_text, None));
+        }
+        if max_length.behaviour == MaxLengthBehaviour::Reject {
+            return Err(MaxLengthExceeded::Rejected);
+        }
+        let text = new_text.to_string();
+        let mut fits = text.chars().count().saturating_sub(after - allowed);
+        let truncated: String = text
+            .graphemes(true)
+            .take_while(|g| {
+                let len = g.chars().count();
+                if len > fits {
+                    return false;
+                }
+                fits -= len;
+                true
+            })
+            .collect();
+        if truncated.is_empty() {
+            Err(MaxLengthExceeded::Rejected)
+        } else {
+            Ok((
+                S::from(truncated.as_str()),
+                Some(MaxLengthExceeded::Truncated),
+            ))
+        }
+    }
+
+    /// The number of characters in the plain text of the document.
+    fn plain_text_chars(&mut self) -> usize {
+        let mut cached = std::mem::take(&mut self.plain_text_counts.blocks);
+        let mut chars = 0;
+        for child in self.state.dom.children() {
+            // The plain text of the document is that of each of its children
+            // in turn
+            let DomNode::Container(container) = child else {
+                chars += child.to_plain_text().chars().count();
+                continue;
+            };
+            let id = container.children_id();
+            let count = match cached.remove(&id) {
+                Some((block, count)) if block.kind() == container.kind() => {
+                    count
+                }
+                _ => container.to_plain_text().chars().count(),
+            };
+            self.plain_text_counts
+                .blocks
+                .insert(id, (container.clone(), count));
+            chars += count;
+        }
+        chars
+    }
+
+    /// The number of characters of plain text between `start` and `end`.
+    /// Positions outside any leaf are the ends of blocks, each written as a
+    /// line break.
+    fn plain_text_chars_in(&self, start: usize, end: usize) -> usize {
+        if start >= end {
+            return 0;
+        }
+        let range = self.state.dom.find_range(start, end);
+        let mut covered = 0;
+        let mut chars = 0;
+        for leaf in range.leaves() {
+            let length = leaf.end_offset - leaf.start_offset;
+            covered += length;
+            chars += match self.state.dom.lookup_node(&leaf.node_handle) {
+                DomNode::Text(text_node) => text_node.data()
+                    [leaf.start_offset..leaf.end_offset]
+                    .chars()
+                    .count(),
+                DomNode::Mention(mention) if length > 0 => {
+                    mention.display_text().chars().count()
+                }
+                _ => length,
+            };
+        }
+        chars + (end - start).saturating_sub(covered)
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::dom::nodes::ContainerNodeKind;
+use crate::dom::DomLocation;
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, DomHandle, DomNode,
+    TextDirection, UnicodeString,
+};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// Set the direction of every block touched by the selection. Inline
+    /// content at the root of the document is wrapped into a paragraph
+    /// first, so that it has a block to carry the direction.
+    pub fn set_text_direction(
+        &mut self,
+        direction: TextDirection,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            model.push_state_to_history();
+            model.wrap_root_inline_nodes_into_paragraph();
+
+            let (s, e) = model.safe_selection();
+            let range = model.state.dom.find_range(s, e);
+            let mut handles: Vec<DomHandle> = range
+                .locations
+                .iter()
+                .filter(|l| is_leaf_or_empty_block(l))
+                .filter_map(|l| model.text_direction_block_for(&l.node_handle))
+                .collect();
+            handles.sort();
+            handles.dedup();
+
+            for handle in handles {
+                if let DomNode::Container(block) =
+                    model.state.dom.lookup_node_mut(&handle)
+                {
+                    block.set_text_direction(Some(direction));
+                }
+            }
+            model.create_update_replace_all()
+        })
+    }
+
+    /// The deepest node containing `handle` (or `handle` itself) that can
+    /// carry a text direction. Code blocks are treated as a single block, as
+    /// their paragraphs are output as lines of the same `<pre>` tag.
+    fn text_direction_block_for(
+        &self,
+        handle: &DomHandle,
+    ) -> Option<DomHandle> {
+        let mut block = None;
+        for ancestor in handle.with_ancestors() {
+            if let DomNode::Container(c) = self.state.dom.lookup_node(&ancestor)
+            {
+                if c.supports_text_direction() {
+                    block = Some(ancestor);
+                }
+                if c.kind() == &ContainerNodeKind::CodeBlock {
+                    break;
+                }
+            }
+        }
+        block
+    }
+
+    fn wrap_root_inline_nodes_into_paragraph(&mut self) {
+        let document = self.state.dom.document_mut();
+        if document.children().iter().any(|c| c.is_block_node()) {
+            return;
+        }
+        let children = document.remove_children();
+        document.append_child(DomNode::new_paragraph(children));
+    }
+}
+
+fn is_leaf_or_empty_block(location: &DomLocation) -> bool {
+    location.is_leaf()
+        || (location.kind.is_block_kind()
+            && location.is_empty()
+            && !location.node_handle.is_root())
+}
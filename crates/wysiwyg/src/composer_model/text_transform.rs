@@ -0,0 +1,133 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::composer_model::boundaries::MENTION_PLACEHOLDER;
+use crate::dom::nodes::DomNode;
+use crate::dom::unicode_string::{UnicodeStr, UnicodeStrExt};
+use crate::dom::DomLocation;
+use crate::{
+    ComposerError, ComposerModel, ComposerUpdate, Location, TextTransform,
+    UnicodeString,
+};
+
+impl<S> ComposerModel<S>
+where
+    S: UnicodeString,
+{
+    /// Change the case of the selected text in place, keeping its
+    /// formatting. Mentions are left untouched. The selection still covers
+    /// the transformed text afterwards, even if its length changed (e.g.
+    /// "ß" becomes "SS").
+    pub fn transform_selection(
+        &mut self,
+        transform: TextTransform,
+    ) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            let (s, e) = model.safe_selection();
+            if s == e {
+                return ComposerUpdate::keep();
+            }
+            let mut leaves: Vec<DomLocation> = model
+                .state
+                .dom
+                .find_range(s, e)
+                .leaves()
+                .filter(|l| l.start_offset < l.end_offset)
+                .cloned()
+                .collect();
+            leaves.sort_by_key(|l| l.position);
+
+            // Title case depends on the character before the selection
+            let mut previous = model.char_before(s);
+            let mut last_end = s;
+            let mut delta: isize = 0;
+            let mut changed = Vec::new();
+            for leaf in leaves {
+                if leaf.position + leaf.start_offset > last_end {
+                    // There was a block boundary since the previous leaf
+                    previous = None;
+                }
+                last_end = leaf.position + leaf.end_offset;
+                let text_node =
+                    match model.state.dom.lookup_node(&leaf.node_handle) {
+                        DomNode::Text(text_node) => text_node,
+                        // Mentions are immutable, and line breaks start a new line
+                        DomNode::Mention(_) => {
+                            previous = Some(MENTION_PLACEHOLDER);
+                            continue;
+                        }
+                        _ => {
+                            previous = None;
+                            continue;
+                        }
+                    };
+                let data = text_node.data();
+                let mut transformed = String::new();
+                let mut pos = 0;
+                for c in data.chars() {
+                    if leaf.start_offset <= pos && pos < leaf.end_offset {
+                        transform.apply(c, previous, &mut transformed);
+                        previous = Some(c);
+                    } else {
+                        transformed.push(c);
+                    }
+                    pos += data.char_len(&c);
+                }
+                let transformed = S::from(transformed.as_str());
+                if *transformed != *data {
+                    delta += transformed.len() as isize - data.len() as isize;
+                    changed.push((leaf.node_handle, transformed));
+                }
+            }
+            if changed.is_empty() {
+                return ComposerUpdate::keep();
+            }
+
+            model.push_state_to_history();
+            for (handle, transformed) in changed {
+                if let DomNode::Text(text_node) =
+                    model.state.dom.lookup_node_mut(&handle)
+                {
+                    text_node.set_data(transformed);
+                }
+            }
+
+            let new_end = Location::from((e as isize + delta) as usize);
+            if model.state.start <= model.state.end {
+                model.state.start = Location::from(s);
+                model.state.end = new_end;
+            } else {
+                model.state.start = new_end;
+                model.state.end = Location::from(s);
+            }
+            model.create_update_replace_all()
+        })
+    }
+
+    /// The character just before `pos` in the same block, if any.
+    pub(crate) fn char_before(&self, pos: usize) -> Option<char> {
+        if pos == 0 {
+            return None;
+        }
+        let range = self.state.dom.find_range(pos - 1, pos);
+        let leaf = range.leaves().find(|l| l.start_offset < l.end_offset)?;
+        match self.state.dom.lookup_node(&leaf.node_handle) {
+            DomNode::Text(text_node) => {
+                text_node.data()[..leaf.end_offset].chars().last()
+            }
+            DomNode::Mention(_) => Some(MENTION_PLACEHOLDER),
+            _ => None,
+        }
+    }
+}
@@ -12,30 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{ComposerModel, ComposerUpdate, UnicodeString};
+use crate::{ComposerError, ComposerModel, ComposerUpdate, UnicodeString};
 
 impl<S> ComposerModel<S>
 where
     S: UnicodeString,
 {
-    pub fn undo(&mut self) -> ComposerUpdate<S> {
-        if let Some(prev) = self.previous_states.pop() {
-            self.next_states.push(self.state.clone());
-            self.state = prev;
-            self.create_update_replace_all()
-        } else {
-            ComposerUpdate::keep()
-        }
+    pub fn undo(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if let Some(prev) = model.previous_states.pop() {
+                model.next_states.push(model.state.clone());
+                model.state = prev;
+                model.create_update_replace_all_as_is()
+            } else {
+                ComposerUpdate::keep()
+            }
+        })
     }
 
-    pub fn redo(&mut self) -> ComposerUpdate<S> {
-        if let Some(next) = self.next_states.pop() {
-            self.previous_states.push(self.state.clone());
-            self.state = next;
-            self.create_update_replace_all()
-        } else {
-            ComposerUpdate::keep()
-        }
+    pub fn redo(&mut self) -> Result<ComposerUpdate<S>, ComposerError> {
+        self.edit(|model| {
+            if let Some(next) = model.next_states.pop() {
+                model.previous_states.push(model.state.clone());
+                model.state = next;
+                model.create_update_replace_all_as_is()
+            } else {
+                ComposerUpdate::keep()
+            }
+        })
     }
 
     pub(crate) fn push_state_to_history(&mut self) {
@@ -0,0 +1,28 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Counts describing the content of the composer, e.g. to show a character
+/// counter or warn that a message is too large to send.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComposerStats {
+    /// Unicode scalar values in the plain text of the message.
+    pub characters: usize,
+    /// User-perceived characters in the plain text of the message.
+    pub graphemes: usize,
+    pub words: usize,
+    /// Size in bytes of the UTF-8 encoded plain text `body`.
+    pub body_bytes: usize,
+    /// Size in bytes of the UTF-8 encoded HTML `formatted_body`.
+    pub formatted_body_bytes: usize,
+}
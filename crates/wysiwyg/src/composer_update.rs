@@ -15,7 +15,8 @@
 use crate::dom::UnicodeString;
 use crate::link_action::LinkActionUpdate;
 use crate::{
-    Location, MenuAction, MenuState, ReplaceAll, Selection, TextUpdate,
+    Location, MaxLengthExceeded, MenuAction, MenuState, ReplaceAll, Selection,
+    TextUpdate,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,6 +28,8 @@ where
     pub menu_state: MenuState,
     pub menu_action: MenuAction,
     pub link_action: LinkActionUpdate<S>,
+    /// Set if the input was truncated or rejected because of the max length.
+    pub max_length_exceeded: Option<MaxLengthExceeded>,
 }
 
 impl<S> ComposerUpdate<S>
@@ -39,6 +42,7 @@ where
             menu_state: MenuState::Keep,
             menu_action: MenuAction::Keep,
             link_action: LinkActionUpdate::Keep,
+            max_length_exceeded: None,
         }
     }
 
@@ -51,6 +55,7 @@ where
             menu_state,
             menu_action,
             link_action: LinkActionUpdate::Keep,
+            max_length_exceeded: None,
         }
     }
 
@@ -66,6 +71,7 @@ where
             menu_state,
             menu_action,
             link_action,
+            max_length_exceeded: None,
         }
     }
 
@@ -86,6 +92,15 @@ where
             menu_state,
             menu_action,
             link_action,
+            max_length_exceeded: None,
         }
     }
+
+    pub(crate) fn with_max_length_exceeded(
+        mut self,
+        max_length_exceeded: Option<MaxLengthExceeded>,
+    ) -> Self {
+        self.max_length_exceeded = max_length_exceeded;
+        self
+    }
 }
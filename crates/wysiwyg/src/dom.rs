@@ -19,6 +19,8 @@ pub mod dom_handle;
 pub mod dom_invariants;
 pub mod dom_list_methods;
 pub mod dom_methods;
+pub mod dom_normalize;
+pub mod dom_repair;
 pub mod dom_struct;
 pub mod find_extended_range;
 pub mod find_range;
@@ -28,6 +30,7 @@ pub mod insert_parent;
 pub mod iter;
 pub mod join_nodes;
 pub mod nodes;
+pub mod offset_markers;
 pub mod parser;
 pub mod range;
 pub mod to_html;
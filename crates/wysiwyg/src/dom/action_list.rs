@@ -148,22 +148,25 @@ impl<S: UnicodeString> DomActionList<S> {
         self.actions = new_actions;
     }
 
-    pub fn find_moved_parent_or_self(
-        &self,
-        handle: &DomHandle,
-    ) -> Option<(DomHandle, DomHandle)> {
-        self.actions.iter().find_map(|action| match action {
-            DomAction::Move(a) => {
-                if a.from_handle.is_ancestor_of(handle)
-                    || a.from_handle == *handle
+    /// Returns where the node at [handle] is after the moves in this list.
+    /// Moves are applied in order, so a node moved several times, e.g. by
+    /// merging its parent into a sibling and then that sibling into another
+    /// one, ends up at its final position.
+    pub fn follow_moves(&self, handle: &DomHandle) -> DomHandle {
+        let mut handle = handle.clone();
+        for action in &self.actions {
+            if let DomAction::Move(a) = action {
+                if a.from_handle.is_ancestor_of(&handle)
+                    || a.from_handle == handle
                 {
-                    Some((a.from_handle.clone(), a.to_handle.clone()))
-                } else {
-                    None
+                    handle.replace_ancestor(
+                        a.from_handle.clone(),
+                        a.to_handle.clone(),
+                    );
                 }
             }
-            _ => None,
-        })
+        }
+        handle
     }
 }
 
@@ -269,21 +272,43 @@ mod test {
     }
 
     #[test]
-    fn find_moved_parent_or_self_looks_for_it_in_move_actions() {
+    fn follow_moves_moves_the_handle_and_its_descendants() {
         let actions = vec![DomAction::move_node(
             DomHandle::from_raw(vec![1, 0]),
             DomHandle::from_raw(vec![0, 0]),
         )];
         let list = ActionList::new(actions);
-        let found_parent =
-            list.find_moved_parent_or_self(&DomHandle::from_raw(vec![1, 0, 0]));
-        assert_eq!(found_parent.unwrap().0, DomHandle::from_raw(vec![1, 0]));
-        let found_self =
-            list.find_moved_parent_or_self(&DomHandle::from_raw(vec![1, 0]));
-        assert_eq!(found_self.unwrap().0, DomHandle::from_raw(vec![1, 0]));
-        let not_found =
-            list.find_moved_parent_or_self(&DomHandle::from_raw(vec![1]));
-        assert!(not_found.is_none());
+        assert_eq!(
+            list.follow_moves(&DomHandle::from_raw(vec![1, 0, 0])),
+            DomHandle::from_raw(vec![0, 0, 0])
+        );
+        assert_eq!(
+            list.follow_moves(&DomHandle::from_raw(vec![1, 0])),
+            DomHandle::from_raw(vec![0, 0])
+        );
+        assert_eq!(
+            list.follow_moves(&DomHandle::from_raw(vec![1])),
+            DomHandle::from_raw(vec![1])
+        );
+    }
+
+    #[test]
+    fn follow_moves_applies_every_move_in_order() {
+        let actions = vec![
+            DomAction::move_node(
+                DomHandle::from_raw(vec![0, 3, 0]),
+                DomHandle::from_raw(vec![0, 2, 1]),
+            ),
+            DomAction::move_node(
+                DomHandle::from_raw(vec![0, 2, 1, 0]),
+                DomHandle::from_raw(vec![0, 2, 0, 1]),
+            ),
+        ];
+        let list = ActionList::new(actions);
+        assert_eq!(
+            list.follow_moves(&DomHandle::from_raw(vec![0, 3, 0, 0])),
+            DomHandle::from_raw(vec![0, 2, 0, 1])
+        );
     }
 
     fn default_actions() -> Vec<DomAction<Utf16String>> {
@@ -135,6 +135,38 @@ where
             && (node.kind() != ListItem || range.contains(&node.handle()))
     }
 
+    /// Move the child of the container at `parent_handle` found at index
+    /// `from` so it ends up at index `to`.
+    pub(crate) fn move_child(
+        &mut self,
+        parent_handle: &DomHandle,
+        from: usize,
+        to: usize,
+    ) {
+        let DomNode::Container(parent) = self.lookup_node_mut(parent_handle)
+        else {
+            panic!("Parent node should be a container");
+        };
+        let node = parent.remove_child(from);
+        parent.insert_child(to, node);
+    }
+
+    /// Insert a copy of `count` children of the container at
+    /// `parent_handle`, starting at `first`, right after them.
+    pub(crate) fn duplicate_children(
+        &mut self,
+        parent_handle: &DomHandle,
+        first: usize,
+        count: usize,
+    ) {
+        let DomNode::Container(parent) = self.lookup_node_mut(parent_handle)
+        else {
+            panic!("Parent node should be a container");
+        };
+        let copies = parent.children()[first..first + count].to_vec();
+        parent.insert_children(first + count, copies);
+    }
+
     pub(crate) fn find_block_ancestor_to_split(
         &self,
         handle: &DomHandle,
@@ -29,14 +29,13 @@
 //! "assert-invariants".
 //!
 //! TODO: build the demo app with these assertions enabled
-//! TODO: add more assertions - see the code of assert_invariants for ideas
 
 #[cfg(any(test, feature = "assert-invariants"))]
 use crate::dom::unicode_string::UnicodeStrExt;
 use crate::dom::Dom;
 use crate::UnicodeString;
 #[cfg(any(test, feature = "assert-invariants"))]
-use crate::{DomNode, ToTree};
+use crate::{dom::nodes::dom_node::DomNodeKind, DomNode, ToTree};
 
 impl<S> Dom<S>
 where
@@ -62,15 +61,12 @@ where
         self.assert_no_adjacent_text_nodes();
         self.assert_exactly_one_generic_container();
         self.assert_all_nodes_in_containers_are_block_or_inline();
-
-        // We probably want some more asserts like these:
-        // self.assert_document_node_is_a_container();
-        // self.assert_no_empty_containers_except_at_root();
-        // self.assert_inline_code_contains_no_tags_except_line_breaks
-        // self.assert_code_blocks_do_not_contain_structure_tags
-        // self.assert_links_do_not_contain_structure_tags
-        // self.assert_links_do_not_contain_links
-        // self.assert_zero_width_spaces_are_only_in_empty_list_item_tags
+        self.assert_valid_block_nesting();
+        self.assert_no_blocks_in_inline_containers();
+        self.assert_no_empty_formatting_containers();
+        self.assert_lists_only_contain_list_items();
+        self.assert_links_do_not_contain_links();
+        self.assert_mentions_are_whole();
     }
 
     #[cfg(any(test, feature = "assert-invariants"))]
@@ -139,6 +135,136 @@ where
             }
         }
     }
+
+    /// Check block containers are nested in a way we can render and edit:
+    /// list items only live in lists and code blocks don't contain
+    /// structure nodes. Quotes, lists and code blocks can otherwise be
+    /// nested in any order.
+    #[cfg(any(test, feature = "assert-invariants"))]
+    fn assert_valid_block_nesting(&self) {
+        use super::nodes::dom_node::DomNodeKind::*;
+
+        for container in self.iter_containers() {
+            let kind = DomNode::Container(container.clone()).kind();
+            for child in container.children() {
+                let child_kind = child.kind();
+                let is_valid = match kind {
+                    List => matches!(child_kind, ListItem | List),
+                    CodeBlock => !matches!(
+                        child_kind,
+                        List | ListItem | CodeBlock | Quote
+                    ),
+                    _ => child_kind != ListItem,
+                };
+                if !is_valid {
+                    panic!(
+                        "Invalid block nesting! {:?} can't contain {:?}. handle: {:?}\n{}",
+                        kind,
+                        child_kind,
+                        child.handle(),
+                        self.to_tree()
+                    );
+                }
+            }
+        }
+    }
+
+    /// Paragraphs, links and formatting nodes can only hold inline nodes.
+    #[cfg(any(test, feature = "assert-invariants"))]
+    fn assert_no_blocks_in_inline_containers(&self) {
+        for container in self.iter_containers() {
+            let kind = DomNode::Container(container.clone()).kind();
+            if !matches!(
+                kind,
+                DomNodeKind::Paragraph
+                    | DomNodeKind::Link
+                    | DomNodeKind::Formatting(_)
+            ) {
+                continue;
+            }
+            if let Some(block) =
+                container.children().iter().find(|n| n.is_block_node())
+            {
+                panic!(
+                    "{:?} found inside a {:?}! handle: {:?}\n{}",
+                    block.kind(),
+                    kind,
+                    block.handle(),
+                    self.to_tree()
+                );
+            }
+        }
+    }
+
+    /// Formatting nodes can only be empty in an empty block, where they
+    /// hold the format of the text typed next.
+    #[cfg(any(test, feature = "assert-invariants"))]
+    fn assert_no_empty_formatting_containers(&self) {
+        for container in self.iter_containers() {
+            if container.is_formatting_node()
+                && container.children().is_empty()
+                && !self.is_in_empty_block(&container.handle())
+            {
+                panic!(
+                    "Empty formatting container found! handle: {:?}\n{}",
+                    container.handle(),
+                    self.to_tree()
+                );
+            }
+        }
+    }
+
+    /// Older HTML nests lists directly inside lists, [Dom::repair] moves
+    /// them into list items.
+    #[cfg(any(test, feature = "assert-invariants"))]
+    fn assert_lists_only_contain_list_items(&self) {
+        for list in self.iter_containers().filter(|c| c.is_list()) {
+            if let Some(child) =
+                list.children().iter().find(|n| !n.is_list_item())
+            {
+                panic!(
+                    "List contains a {:?} instead of a list item! handle: {:?}\n{}",
+                    child.kind(),
+                    child.handle(),
+                    self.to_tree()
+                );
+            }
+        }
+    }
+
+    /// Mentions are links too, so they can't be inside a link either.
+    #[cfg(any(test, feature = "assert-invariants"))]
+    fn assert_links_do_not_contain_links(&self) {
+        for link in self.iter_containers().filter(|c| c.is_link()) {
+            let inner_link = link.children().iter().find_map(|child| {
+                child.iter_subtree().find(|n| {
+                    matches!(n.kind(), DomNodeKind::Link | DomNodeKind::Mention)
+                })
+            });
+            if let Some(inner_link) = inner_link {
+                panic!(
+                    "Link nested in a link found! handle: {:?}\n{}",
+                    inner_link.handle(),
+                    self.to_tree()
+                );
+            }
+        }
+    }
+
+    /// Mentions are edited as a single character, so they can only exist as
+    /// a whole, with all of their display text.
+    #[cfg(any(test, feature = "assert-invariants"))]
+    fn assert_mentions_are_whole(&self) {
+        for mention in self.iter_mentions() {
+            if mention.display_text().is_empty() {
+                panic!(
+                    "Mention without display text found! handle: {:?}\n{}",
+                    mention.handle(),
+                    self.to_tree()
+                );
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -147,7 +273,7 @@ mod test {
 
     use crate::dom::nodes::{ContainerNode, TextNode};
     use crate::dom::Dom;
-    use crate::{DomHandle, DomNode, InlineFormatType};
+    use crate::{DomHandle, DomNode, InlineFormatType, ListType};
 
     #[test]
     fn should_not_panic_if_transaction_in_progress() {
@@ -194,6 +320,124 @@ mod test {
         dom.assert_invariants();
     }
 
+    #[test]
+    #[should_panic(
+        expected = "Invalid block nesting! CodeBlock can't contain Quote"
+    )]
+    fn quote_inside_code_block_fails_invariants() {
+        let dom =
+            Dom::new(vec![DomNode::new_code_block(vec![DomNode::new_quote(
+                vec![DomNode::new_paragraph(vec![DomNode::Text(
+                    TextNode::from(Utf16String::from("a")),
+                )])],
+            )])]);
+
+        dom.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid block nesting! List can't contain Paragraph"
+    )]
+    fn paragraph_inside_list_fails_invariants() {
+        let dom = Dom::new(vec![DomNode::new_list(
+            ListType::Ordered,
+            vec![DomNode::new_paragraph(vec![DomNode::Text(TextNode::from(
+                Utf16String::from("a"),
+            ))])],
+        )]);
+
+        dom.assert_invariants();
+    }
+
+    #[test]
+    fn nested_quotes_lists_and_code_blocks_are_fine() {
+        let dom = Dom::new(vec![DomNode::new_list(
+            ListType::Unordered,
+            vec![DomNode::new_list_item(vec![DomNode::new_quote(vec![
+                DomNode::new_list(
+                    ListType::Ordered,
+                    vec![DomNode::new_list_item(vec![
+                        DomNode::new_code_block(vec![DomNode::new_paragraph(
+                            vec![DomNode::Text(TextNode::from(
+                                Utf16String::from("a"),
+                            ))],
+                        )]),
+                    ])],
+                ),
+            ])])],
+        )]);
+
+        dom.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "Empty formatting container found")]
+    fn empty_formatting_fails_invariants() {
+        let dom = Dom::new(vec![
+            DomNode::Text(TextNode::from(Utf16String::from("a"))),
+            DomNode::new_formatting(InlineFormatType::Bold, vec![]),
+        ]);
+
+        dom.assert_invariants();
+    }
+
+    #[test]
+    fn empty_formatting_in_an_empty_block_is_fine() {
+        let dom = Dom::new(vec![
+            DomNode::new_paragraph(vec![DomNode::Text(TextNode::from(
+                Utf16String::from("a"),
+            ))]),
+            DomNode::new_paragraph(vec![DomNode::new_formatting(
+                InlineFormatType::Bold,
+                vec![],
+            )]),
+        ]);
+
+        dom.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "Paragraph found inside a Formatting(Bold)")]
+    fn paragraph_inside_formatting_fails_invariants() {
+        let dom = Dom::new(vec![DomNode::new_formatting(
+            InlineFormatType::Bold,
+            vec![DomNode::new_paragraph(vec![DomNode::Text(TextNode::from(
+                Utf16String::from("a"),
+            ))])],
+        )]);
+
+        dom.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "List contains a List instead of a list item")]
+    fn list_inside_list_fails_invariants() {
+        let dom = Dom::new(vec![DomNode::new_list(
+            ListType::Ordered,
+            vec![DomNode::new_list(
+                ListType::Ordered,
+                vec![DomNode::new_list_item(vec![DomNode::Text(
+                    TextNode::from(Utf16String::from("a")),
+                )])],
+            )],
+        )]);
+
+        dom.assert_invariants();
+    }
+
+    #[test]
+    #[should_panic(expected = "Link nested in a link found")]
+    fn mention_inside_link_fails_invariants() {
+        let dom = Dom::new(vec![DomNode::new_link(
+            Utf16String::from("https://matrix.org"),
+            vec![DomNode::Mention(DomNode::new_at_room_mention(vec![]))],
+            vec![],
+        )]);
+
+        dom.assert_invariants();
+    }
+
     #[test]
     #[should_panic(
         expected = "More than one generic container node found. Handle: [1]"
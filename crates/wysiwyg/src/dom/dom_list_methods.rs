@@ -15,12 +15,9 @@
 //! Methods on Dom that modify its contents and are guaranteed to conform to
 //! our invariants e.g. no empty text nodes, no adjacent text nodes.
 
-use core::panic;
+use crate::{ComposerError, DomHandle, DomNode, ListType, UnicodeString};
 
-use crate::{DomHandle, DomNode, ListType, UnicodeString};
-
-use super::nodes::dom_node::DomNodeKind::{CodeBlock, Quote};
-use super::nodes::ContainerNode;
+use super::nodes::{ContainerNode, ContainerNodeKind};
 use super::Dom;
 
 impl<S> Dom<S>
@@ -46,22 +43,23 @@ where
         let first_handle = handles[0];
         let mut removed_nodes = Vec::new();
         for handle in handles.iter().rev() {
-            let removed = self.remove(handle);
             // Quotes and code block contains paragraphs as direct children
             // We need to wrap these instead of the quote/code block
             //
             // Note: this behaviour might change if we want to handle
             // quote/code blocks inside list items.
-            if removed.kind() == Quote || removed.kind() == CodeBlock {
-                if let DomNode::Container(c) = removed {
+            match self.remove(handle) {
+                DomNode::Container(c)
+                    if matches!(
+                        c.kind(),
+                        ContainerNodeKind::Quote | ContainerNodeKind::CodeBlock
+                    ) =>
+                {
                     let mut children = c.take_children();
                     children.reverse();
                     removed_nodes.append(&mut children);
-                } else {
-                    panic!("Quote/code block is not a container!")
                 }
-            } else {
-                removed_nodes.push(removed);
+                removed => removed_nodes.push(removed),
             }
         }
         removed_nodes.reverse();
@@ -69,12 +67,13 @@ where
         let mut list_items = Vec::new();
         if removed_nodes.iter().all(|n| n.is_block_node()) {
             for block_node in removed_nodes {
-                let DomNode::Container(block_node) = block_node else {
-                    panic!("Block node must be a container node")
+                let children = match block_node {
+                    DomNode::Container(block_node) => {
+                        block_node.take_children()
+                    }
+                    block_node => vec![block_node],
                 };
-                let children = block_node.take_children();
-                let list_item = DomNode::new_list_item(children);
-                list_items.push(list_item);
+                list_items.push(DomNode::new_list_item(children));
             }
         } else {
             let mut list_item = ContainerNode::new_list_item(removed_nodes);
@@ -116,12 +115,12 @@ where
     /// separated by line breaks.
     ///
     /// * `handle` - the list handle.
-    pub fn extract_from_list(&mut self, handle: &DomHandle) {
-        let list = self.lookup_node(handle);
-        let DomNode::Container(list) = list else {
-            panic!("List is not a container")
-        };
-        self.extract_list_items(handle, 0, list.children().len());
+    pub fn extract_from_list(
+        &mut self,
+        handle: &DomHandle,
+    ) -> Result<(), ComposerError> {
+        let count = self.list_at(handle)?.children().len();
+        self.extract_list_items(handle, 0, count)
     }
 
     /// Extract items from the list at given handle and positions
@@ -136,7 +135,7 @@ where
         handle: &DomHandle,
         child_index: usize,
         count: usize,
-    ) {
+    ) -> Result<(), ComposerError> {
         fn wrap_children<S: UnicodeString>(
             children: Vec<DomNode<S>>,
         ) -> Vec<DomNode<S>> {
@@ -148,18 +147,20 @@ where
                 children
             }
         }
-        let list = self.lookup_node_mut(handle);
-        let DomNode::Container(list) = list else {
-            panic!("List is not a container")
-        };
+        let list = self.list_at_mut(handle)?;
+        if child_index + count > list.children().len() {
+            return Err(ComposerError::Internal(format!(
+                "Can't extract {count} items from {child_index} in a list of {}",
+                list.children().len()
+            )));
+        }
 
         let mut nodes_to_insert = Vec::new();
         for _index in child_index..child_index + count {
-            let list_item = list.remove_child(child_index);
-            let DomNode::Container(list_item) = list_item else {
-                panic!("List item is not a container")
+            let children = match list.remove_child(child_index) {
+                DomNode::Container(list_item) => list_item.take_children(),
+                node => vec![node],
             };
-            let children = list_item.take_children();
             nodes_to_insert.append(&mut wrap_children(children));
         }
         if child_index == 0 {
@@ -175,7 +176,9 @@ where
             if list.children().len() > child_index {
                 let new_list_children = list.take_children_after(child_index);
                 let new_list = DomNode::new_list(
-                    list.get_list_type().expect("Node is not a list").clone(),
+                    list.get_list_type()
+                        .cloned()
+                        .unwrap_or(ListType::Unordered),
                     new_list_children,
                 );
                 nodes_to_insert.push(new_list);
@@ -184,6 +187,41 @@ where
             self.insert(&handle.next_sibling(), nodes_to_insert);
         }
         self.join_nodes_in_container(&handle.parent_handle());
+        Ok(())
+    }
+
+    /// Move the list item at index `from` of the list at given handle so it
+    /// ends up at index `to`. Nested lists move along with their item.
+    ///
+    /// * `handle` - the list handle.
+    /// * `from` - current child index of the list item.
+    /// * `to` - child index of the list item after the move.
+    pub fn move_list_item(
+        &mut self,
+        handle: &DomHandle,
+        from: usize,
+        to: usize,
+    ) -> Result<(), ComposerError> {
+        self.list_at(handle)?;
+        self.move_child(handle, from, to);
+        Ok(())
+    }
+
+    /// Duplicate list items of the list at given handle, inserting the
+    /// copies right after the originals.
+    ///
+    /// * `handle` - the list handle.
+    /// * `child_index` - child index of the first list item to duplicate.
+    /// * `count` - number of list items that should be duplicated.
+    pub fn duplicate_list_items(
+        &mut self,
+        handle: &DomHandle,
+        child_index: usize,
+        count: usize,
+    ) -> Result<(), ComposerError> {
+        self.list_at(handle)?;
+        self.duplicate_children(handle, child_index, count);
+        Ok(())
     }
 
     /// Slice list item at given handle and offset.
@@ -194,18 +232,49 @@ where
         &mut self,
         handle: &DomHandle,
         offset: usize,
-    ) {
+    ) -> Result<(), ComposerError> {
+        self.list_at(&handle.parent_handle())?;
         let list_item = self.lookup_node_mut(handle);
         let slice = list_item.slice_after(offset);
-        let list = self.lookup_node_mut(&handle.parent_handle());
-        let DomNode::Container(list) = list else {
-            panic!("List node is not a container")
-        };
-        list.insert_child(handle.index_in_parent() + 1, slice);
+        self.parent_mut(handle)
+            .insert_child(handle.index_in_parent() + 1, slice);
         self.join_nodes_in_container(&handle.parent_handle());
+        Ok(())
+    }
+
+    /// The list at `handle`, or an error if the node there isn't one.
+    fn list_at(
+        &self,
+        handle: &DomHandle,
+    ) -> Result<&ContainerNode<S>, ComposerError> {
+        match self.lookup_node(handle) {
+            DomNode::Container(list) if list.is_list() => Ok(list),
+            node => Err(not_a_list(handle, node)),
+        }
+    }
+
+    fn list_at_mut(
+        &mut self,
+        handle: &DomHandle,
+    ) -> Result<&mut ContainerNode<S>, ComposerError> {
+        self.list_at(handle)?;
+        match self.lookup_node_mut(handle) {
+            DomNode::Container(list) => Ok(list),
+            node => Err(not_a_list(handle, node)),
+        }
     }
 }
 
+fn not_a_list<S: UnicodeString>(
+    handle: &DomHandle,
+    node: &DomNode<S>,
+) -> ComposerError {
+    ComposerError::Internal(format!(
+        "Expected a list at {handle:?}, found {:?}",
+        node.kind()
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use widestring::Utf16String;
@@ -230,7 +299,8 @@ mod test {
             "<ol><li>abc<strong>de<em>f</em></strong></li><li><strong><em>gh</em>i</strong>jkl</li></ol>",
         );
 
-        dom.extract_from_list(&DomHandle::from_raw(vec![0]));
+        dom.extract_from_list(&DomHandle::from_raw(vec![0]))
+            .unwrap();
 
         assert_eq!(ds(&dom), "<p>abc<strong>de<em>f</em></strong></p><p><strong><em>gh</em>i</strong>jkl</p>",);
     }
@@ -244,7 +314,8 @@ mod test {
         );
         assert_eq!(ds(&dom), "<ol><li>abc</li><li>def</li></ol>");
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 1);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 1)
+            .unwrap();
         assert_eq!(ds(&dom), "<p>abc</p><ol><li>def</li></ol>");
     }
 
@@ -261,7 +332,8 @@ mod test {
         );
         assert_eq!(ds(&dom), "<ol><li>abc</li><li>def</li><li>ghi</li></ol>");
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 2);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 2)
+            .unwrap();
         assert_eq!(ds(&dom), "<p>abc</p><p>def</p><ol><li>ghi</li></ol>");
     }
 
@@ -274,7 +346,8 @@ mod test {
         );
         assert_eq!(ds(&dom), "<ol><li>abc</li><li>def</li></ol>");
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 1);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 1)
+            .unwrap();
         assert_eq!(ds(&dom), "<ol><li>abc</li></ol><p>def</p>");
     }
 
@@ -291,7 +364,8 @@ mod test {
         );
         assert_eq!(ds(&dom), "<ol><li>abc</li><li>def</li><li>ghi</li></ol>");
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 2);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 2)
+            .unwrap();
         assert_eq!(ds(&dom), "<ol><li>abc</li></ol><p>def</p><p>ghi</p>");
     }
 
@@ -312,7 +386,8 @@ mod test {
             "<ol><li>abc</li><li>def</li><li>ghi</li><li>jkl</li></ol>"
         );
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 1);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 1)
+            .unwrap();
         assert_eq!(
             ds(&dom),
             "<ol><li>abc</li></ol><p>def</p><ol><li>ghi</li><li>jkl</li></ol>"
@@ -336,7 +411,8 @@ mod test {
             "<ol><li>abc</li><li>def</li><li>ghi</li><li>jkl</li></ol>"
         );
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 2);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 1, 2)
+            .unwrap();
         assert_eq!(
             ds(&dom),
             "<ol><li>abc</li></ol><p>def</p><p>ghi</p><ol><li>jkl</li></ol>"
@@ -352,7 +428,8 @@ mod test {
         );
         assert_eq!(ds(&dom), "<ol><li>abc</li></ol>");
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 1);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 1)
+            .unwrap();
         assert_eq!(ds(&dom), "<p>abc</p>");
     }
 
@@ -369,10 +446,54 @@ mod test {
         );
         assert_eq!(ds(&dom), "<ol><li>abc</li><li>def</li><li>ghi</li></ol>");
 
-        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 3);
+        dom.extract_list_items(&DomHandle::from_raw(vec![0]), 0, 3)
+            .unwrap();
         assert_eq!(ds(&dom), "<p>abc</p><p>def</p><p>ghi</p>");
     }
 
+    #[test]
+    fn move_list_item_down() {
+        let mut model = cm("<ol><li>a|</li><li>b</li><li>c</li></ol>");
+        model
+            .state
+            .dom
+            .move_list_item(&DomHandle::from_raw(vec![0]), 0, 2)
+            .unwrap();
+        assert_eq!(
+            ds(&model.state.dom),
+            "<ol><li>b</li><li>c</li><li>a</li></ol>"
+        );
+    }
+
+    #[test]
+    fn move_list_item_keeps_nested_list() {
+        let mut model =
+            cm("<ul><li><p>a|</p><ul><li>b</li></ul></li><li>c</li></ul>");
+        model
+            .state
+            .dom
+            .move_list_item(&DomHandle::from_raw(vec![0]), 1, 0)
+            .unwrap();
+        assert_eq!(
+            ds(&model.state.dom),
+            "<ul><li>c</li><li><p>a</p><ul><li>b</li></ul></li></ul>"
+        );
+    }
+
+    #[test]
+    fn duplicate_list_items() {
+        let mut model = cm("<ol><li>a|</li><li>b</li><li>c</li></ol>");
+        model
+            .state
+            .dom
+            .duplicate_list_items(&DomHandle::from_raw(vec![0]), 0, 2)
+            .unwrap();
+        assert_eq!(
+            ds(&model.state.dom),
+            "<ol><li>a</li><li>b</li><li>a</li><li>b</li><li>c</li></ol>"
+        );
+    }
+
     #[test]
     fn slice_list_item() {
         let mut dom = cm("<em>abcd</em>ef|").state.dom;
@@ -382,7 +503,8 @@ mod test {
         );
         assert_eq!(ds(&dom), "<ol><li><em>abcd</em>ef</li></ol>");
 
-        dom.slice_list_item(&DomHandle::from_raw(vec![0, 0]), 3);
+        dom.slice_list_item(&DomHandle::from_raw(vec![0, 0]), 3)
+            .unwrap();
         assert_eq!(
             ds(&dom),
             "<ol><li><em>abc</em></li><li><em>d</em>ef</li></ol>"
@@ -415,7 +537,7 @@ mod test {
             .state
             .dom
             .wrap_nodes_in_list(ListType::Ordered, handles);
-        model.state.dom.extract_from_list(&first_handle);
+        model.state.dom.extract_from_list(&first_handle).unwrap();
         assert_eq!(tx(&model), text);
     }
 
@@ -18,7 +18,7 @@
 use crate::dom::nodes::dom_node::DomNodeKind::{Generic, ListItem, Paragraph};
 use crate::dom::range::DomLocationPosition::After;
 use crate::dom::DomLocation;
-use crate::{DomHandle, DomNode, UnicodeString};
+use crate::{ComposerError, DomHandle, DomNode, UnicodeString};
 
 use super::action_list::{DomAction, DomActionList};
 use super::nodes::dom_node::DomNodeKind;
@@ -60,7 +60,12 @@ where
         self.assert_invariants();
     }
 
-    pub fn replace_text_in(&mut self, new_text: S, start: usize, end: usize) {
+    pub fn replace_text_in(
+        &mut self,
+        new_text: S,
+        start: usize,
+        end: usize,
+    ) -> Result<(), ComposerError> {
         #[cfg(any(test, feature = "assert-invariants"))]
         self.assert_invariants();
 
@@ -80,26 +85,41 @@ where
         {
             // We replace and delete as normal with an empty string on the current range
             let deleted_handles =
-                self.replace_multiple_nodes(&range, "".into());
+                self.replace_multiple_nodes(&range, "".into())?;
             let mut moved_handles = Vec::new();
+            // Deleting may have moved the link, e.g. if the block before it
+            // was removed, so look it up again at the cursor
+            let is_start = starting_link.is_start();
+            let link_handle = self
+                .find_range(start, start)
+                .locations
+                .into_iter()
+                .find(|loc| {
+                    loc.kind == DomNodeKind::Link
+                        && if is_start {
+                            loc.start_offset == loc.length
+                        } else {
+                            loc.start_offset == 0
+                        }
+                })
+                .map_or(starting_link.node_handle.clone(), |loc| {
+                    loc.node_handle
+                });
             // Then we set the new text value in the previous/next sibling node (or create a new one if none exists)
-            if starting_link.is_start() {
-                self.set_new_text_in_next_sibling_node(
-                    starting_link.node_handle.clone(),
-                    new_text,
-                );
+            if is_start {
+                self.set_new_text_in_next_sibling_node(link_handle, new_text);
             } else {
                 // `leading_is_end` case, as filtered by `first_shrinkable_link_node_handle`
                 self.set_new_text_in_prev_sibling_node(
-                    starting_link.node_handle.clone(),
+                    link_handle.clone(),
                     new_text,
                 );
-                moved_handles.push(starting_link.node_handle.clone());
+                moved_handles.push(link_handle);
             }
 
             (deleted_handles, moved_handles)
         } else {
-            (self.replace_multiple_nodes(&range, new_text), Vec::new())
+            (self.replace_multiple_nodes(&range, new_text)?, Vec::new())
         };
 
         // If text was replaced, not inserted
@@ -129,18 +149,27 @@ where
             {
                 // If there are adjacent block nodes as a result of replacing text
                 if start_block_loc != end_block_loc {
-                    let end_block = self.remove(&end_block_loc.node_handle);
-                    let DomNode::Container(end_block) = end_block else {
-                        panic!("Ending block node must be a container node")
-                    };
-                    let removed_items = end_block.take_children();
+                    if !self
+                        .lookup_node(&start_block_loc.node_handle)
+                        .is_container_node()
+                    {
+                        return Err(ComposerError::Internal(
+                            "Starting block node must be a container node"
+                                .into(),
+                        ));
+                    }
+                    let removed_items =
+                        match self.remove(&end_block_loc.node_handle) {
+                            DomNode::Container(end_block) => {
+                                end_block.take_children()
+                            }
+                            end_block => vec![end_block],
+                        };
                     if let DomNode::Container(start_block) =
                         self.lookup_node_mut(&start_block_loc.node_handle)
                     {
                         // Merge contents in `start_block`
                         start_block.append_children(removed_items);
-                    } else {
-                        panic!("Starting block node must be a container node");
                     }
 
                     if end_block_loc.node_handle.has_parent() {
@@ -160,6 +189,7 @@ where
 
         #[cfg(any(test, feature = "assert-invariants"))]
         self.assert_invariants();
+        Ok(())
     }
 
     /// Removes paragraph from the closest list item ancestor, if
@@ -392,9 +422,10 @@ where
         &mut self,
         range: &Range,
         new_text: S,
-    ) -> Vec<DomHandle> {
+    ) -> Result<Vec<DomHandle>, ComposerError> {
         let len = new_text.len();
-        let action_list = self.replace_in_text_nodes(range.clone(), new_text);
+        let action_list =
+            self.replace_in_text_nodes(range.clone(), new_text)?;
 
         let (to_add, to_delete, _) = action_list.grouped();
         let to_delete: Vec<DomHandle> =
@@ -410,7 +441,9 @@ where
             if let DomNode::Container(parent) = parent {
                 parent.insert_child(add_action.index, add_action.node);
             } else {
-                panic!("Parent was not a container!");
+                return Err(ComposerError::Internal(format!(
+                    "Can't add a node to {parent_handle:?}, not a container"
+                )));
             }
         }
 
@@ -446,7 +479,7 @@ where
             }
         }
 
-        deleted_handles
+        Ok(deleted_handles)
     }
 
     fn find_first_non_matching_ancestor_in(
@@ -476,13 +509,16 @@ where
         &mut self,
         container_handle: &DomHandle,
     ) {
-        let child_count = if let DomNode::Container(container) =
-            self.lookup_node(container_handle)
-        {
-            container.children().len()
-        } else {
-            panic!("Parent node should be a container");
+        if !self.contains(container_handle) {
+            // The container was removed along with its content
+            return;
+        }
+        let DomNode::Container(container) = self.lookup_node(container_handle)
+        else {
+            // Deleting nodes moved something else to this position
+            return;
         };
+        let child_count = container.children().len();
 
         if child_count > 0 {
             for i in (0..child_count - 1).rev() {
@@ -500,6 +536,34 @@ where
         }
     }
 
+    /// Merges any adjacent text nodes inside the container at
+    /// [container_handle] and its descendants.
+    pub(crate) fn merge_text_nodes_in(&mut self, container_handle: &DomHandle) {
+        // Only these are looked up mutably, so the others stay shared with
+        // copies of the Dom
+        let containers: Vec<DomHandle> = self
+            .lookup_node(container_handle)
+            .iter_subtree()
+            .filter(|n| {
+                n.as_container().is_some_and(|c| {
+                    c.children().windows(2).any(|pair| {
+                        matches!(pair, [DomNode::Text(_), DomNode::Text(_)])
+                    })
+                })
+            })
+            .map(|n| n.handle())
+            .collect();
+        // Deepest and last first, so merging doesn't move the others
+        for handle in containers.into_iter().rev() {
+            if let DomNode::Container(container) = self.lookup_node_mut(&handle)
+            {
+                for i in (0..container.children().len()).rev() {
+                    merge_if_adjacent_text_nodes(container, i);
+                }
+            }
+        }
+    }
+
     /// Given a range to replace and some new text, modify the nodes in the
     /// range to replace the text with the supplied text.
     /// Returns a list of actions to be done to the Dom (add or remove nodes).
@@ -510,9 +574,47 @@ where
         &mut self,
         range: Range,
         new_text: S,
-    ) -> DomActionList<S> {
+    ) -> Result<DomActionList<S>, ComposerError> {
         let mut action_list = DomActionList::default();
         let mut first_text_node = true;
+        let mut skipped_text_node = false;
+        // Blocks removed as a whole, the new text can't go inside them
+        let mut removed_blocks: Vec<DomHandle> = range
+            .locations
+            .iter()
+            .filter(|l| {
+                l.kind.is_block_kind()
+                    && l.kind != Generic
+                    && !l.is_empty()
+                    && l.is_covered()
+            })
+            .map(|l| l.node_handle.clone())
+            .collect();
+        let is_removed = |blocks: &[DomHandle], handle: &DomHandle| {
+            blocks
+                .iter()
+                .any(|h| h == handle || h.is_ancestor_of(handle))
+        };
+        // Somewhere outside the removed blocks to put the new text
+        let has_kept_location = range.locations.iter().any(|l| {
+            !is_removed(&removed_blocks, &l.node_handle)
+                && (l.kind == DomNodeKind::Text
+                    || (l.kind.is_block_kind() && l.kind != Generic))
+        });
+        if !has_kept_location && !new_text.is_empty() {
+            // Everything is removed, keep the blocks around the first text
+            if let Some(first_text) =
+                range.leaves().find(|l| l.kind == DomNodeKind::Text)
+            {
+                removed_blocks
+                    .retain(|h| !h.is_ancestor_of(&first_text.node_handle));
+            }
+        }
+        let in_removed_block = |handle: &DomHandle| {
+            removed_blocks.iter().any(|h| h.is_ancestor_of(handle))
+        };
+        let is_kept =
+            |l: &DomLocation| !is_removed(&removed_blocks, &l.node_handle);
 
         for loc in range.locations.iter() {
             let mut node = self.lookup_node_mut(&loc.node_handle);
@@ -527,25 +629,31 @@ where
                                 ));
                                 first_text_node = false;
                             } else if first_text_node {
-                                match loc.kind {
-                                    Paragraph | ListItem => {
-                                        let text_node = DomNode::new_text(new_text.clone());
-                                        action_list.push(DomAction::add_node(
-                                            loc.node_handle.clone(),
-                                            0,
-                                            text_node,
-                                        ));
-                                        first_text_node = false;
-                                    },
-                                    _ => panic!("A block node that can't contain inline nodes was selected, text can't be added to it."),
-                                }
+                                let text_node =
+                                    DomNode::new_text(new_text.clone());
+                                // Blocks that can't contain inline nodes get
+                                // a new block to hold the text
+                                let node = match loc.kind {
+                                    Paragraph | ListItem => text_node,
+                                    DomNodeKind::List => {
+                                        DomNode::new_list_item(vec![text_node])
+                                    }
+                                    _ => {
+                                        DomNode::new_paragraph(vec![text_node])
+                                    }
+                                };
+                                action_list.push(DomAction::add_node(
+                                    loc.node_handle.clone(),
+                                    0,
+                                    node,
+                                ));
+                                first_text_node = false;
                             }
-                        } else if !loc.is_empty() && loc.is_covered() {
+                        } else if removed_blocks.contains(&loc.node_handle) {
                             action_list.push(DomAction::remove_node(
                                 loc.node_handle.clone(),
                             ));
-                            first_text_node = false;
-                        } else if !first_text_node
+                        } else if (!first_text_node || skipped_text_node)
                             && matches!(loc.kind, Paragraph)
                         {
                             // FIXME: Workaround for empty paragraphs. This is a hack, but trying to fix the root cause
@@ -558,11 +666,12 @@ where
                                 action_list.push(DomAction::remove_node(
                                     loc.node_handle.clone(),
                                 ));
-                                first_text_node = false;
                             }
                         }
                     } else if container_node.is_formatting_node()
                         && container_node.is_empty()
+                        && first_text_node
+                        && !new_text.is_empty()
                     {
                         // do a special case here for when we split a formatting node and create empty
                         // formatting nodes inside the next paragraph tag
@@ -596,15 +705,24 @@ where
                                 first_text_node = false;
                             }
                         }
-                        _ => panic!(
-                            "Tried to insert text into a line break or mention with offset != 0 or 1. \
-                            Start offset: {}, end offset: {}",
-                            loc.start_offset,
-                            loc.end_offset,
-                        ),
+                        _ => {
+                            return Err(ComposerError::Internal(format!(
+                                "Tried to insert text into a line break or mention with offset != 0 or 1. \
+                                Start offset: {}, end offset: {}",
+                                loc.start_offset, loc.end_offset,
+                            )))
+                        }
                     }
                 }
                 DomNode::Text(node) => {
+                    if first_text_node
+                        && !new_text.is_empty()
+                        && in_removed_block(&loc.node_handle)
+                    {
+                        // The new text goes in a node that is kept
+                        skipped_text_node = true;
+                        continue;
+                    }
                     let old_data = node.data();
 
                     // If this is not the first node, and the selections spans
@@ -646,7 +764,9 @@ where
 
         // If text wasn't added in any previous iteration, just append it next to the last leaf
         if first_text_node && !new_text.is_empty() {
-            if let Some(last_leaf) = range.leaves().last() {
+            if let Some(last_leaf) =
+                range.leaves().filter(|l| is_kept(l)).last()
+            {
                 action_list.push(DomAction::add_node(
                     last_leaf.node_handle.parent_handle(),
                     last_leaf.node_handle.index_in_parent() + 1,
@@ -655,17 +775,33 @@ where
             } else if let Some(block_node) = sorted_locations
                 .into_iter()
                 .rev()
-                .find(|l| l.kind.is_block_kind())
+                .find(|l| l.kind.is_block_kind() && is_kept(l))
             {
-                action_list.push(DomAction::add_node(
-                    block_node.node_handle,
-                    0,
-                    DomNode::new_text(new_text),
-                ));
+                let first_child = block_node.node_handle.child_handle(0);
+                let first_text = if self.contains(&first_child) {
+                    match self.lookup_node_mut(&first_child) {
+                        DomNode::Text(text) => Some(text),
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+                if let Some(text) = first_text {
+                    // Join the text the block starts with
+                    let mut data = new_text;
+                    data.push(text.data());
+                    text.set_data(data);
+                } else {
+                    action_list.push(DomAction::add_node(
+                        block_node.node_handle,
+                        0,
+                        DomNode::new_text(new_text),
+                    ));
+                }
             }
         }
 
-        action_list
+        Ok(action_list)
     }
 
     fn merge_adjacent_text_nodes_after_replace(
@@ -676,7 +812,11 @@ where
         inserted_length: usize,
     ) {
         // If we've ended up with adjacent text nodes, merge them
-        if let Some(first_location) = replaced_range.locations.first() {
+        let first_location = replaced_range
+            .leaves()
+            .next()
+            .or(replaced_range.locations.first());
+        if let Some(first_location) = first_location {
             let first_handle = &first_location.node_handle;
             if deleted_handles.contains(first_handle) {
                 // If we deleted the first node in the range ...
@@ -694,9 +834,9 @@ where
                 let location = replaced_range.start();
                 let range =
                     self.find_range(location, location + inserted_length);
-                self.merge_text_nodes_around(
-                    &range.locations.first().unwrap().node_handle,
-                )
+                if let Some(location) = range.locations.first() {
+                    self.merge_text_nodes_around(&location.node_handle);
+                }
             } else {
                 // If the first node of the range still exists, then
                 // merge it with the next, and potentially also the
@@ -712,6 +852,10 @@ where
         // beginning!
         // Instead, move another method into here, and make it call this one.
 
+        if !self.contains(handle) {
+            // The structure around the node changed during the replacement
+            return;
+        }
         let parent = self.parent_mut(handle);
         let idx = handle.index_in_parent();
         if idx > 0 {
@@ -948,7 +1092,15 @@ where
             // Mentions only have 1 char length:
             // If the offset is 0 the selection was before the node and the mention should be part of the new subtree.
             // If it's 1 it should be kept in the current DOM (do nothing).
-            if start_offset == 0 {
+            // The offsets only apply to the node the split starts or ends
+            // at, any other mention is part of the new subtree.
+            let is_split_start = cur_handle == *from_handle
+                || (from_handle.is_ancestor_of(&cur_handle)
+                    && cur_handle.index_in_parent() == 0);
+            let is_split_end = to_handle.as_ref() == Some(&cur_handle);
+            let keep = (is_split_start && start_offset == 1)
+                || (is_split_end && end_offset == 1);
+            if !keep {
                 nodes.push(self.remove(&cur_handle));
             }
         } else {
@@ -1038,28 +1190,23 @@ where
     ) -> Vec<DomNode<S>> {
         let mut nodes = Vec::new();
         let DomNode::Text(text_node) = self.lookup_node_mut(&cur_handle) else {
-            panic!("Found node must be a TextNode");
+            return vec![self.remove(&cur_handle)];
         };
         if (cur_handle == *from_handle
             || (from_handle.is_ancestor_of(&cur_handle)
                 && cur_handle.index_in_parent() == 0))
             && (1..=text_node.data().len()).contains(&start_offset)
         {
-            let left_data = text_node.data()[..start_offset].to_owned();
-            let right_data = text_node.data()[start_offset..].to_owned();
-            text_node.set_data(left_data);
-            if !right_data.is_empty() {
-                nodes.push(DomNode::new_text(right_data));
+            let right = text_node.slice_after(start_offset);
+            if !right.data().is_empty() {
+                nodes.push(DomNode::Text(right));
             }
-        } else if to_handle.is_some()
-            && cur_handle == to_handle.unwrap()
+        } else if to_handle.as_ref() == Some(&cur_handle)
             && (1..=text_node.data().len()).contains(&end_offset)
         {
-            let left_data = text_node.data()[..end_offset].to_owned();
-            let right_data = text_node.data()[end_offset..].to_owned();
-            text_node.set_data(left_data);
-            if !right_data.is_empty() {
-                nodes.push(DomNode::new_text(right_data));
+            let right = text_node.slice_after(end_offset);
+            if !right.data().is_empty() {
+                nodes.push(DomNode::Text(right));
             }
         } else {
             nodes.push(self.remove(&cur_handle));
@@ -1098,8 +1245,10 @@ where
             if needs_removal {
                 self.remove_and_keep_children(&cur);
                 cur = cur.parent_handle();
+            } else if let Some(prev) = self.prev_node(&cur) {
+                cur = prev.handle();
             } else {
-                cur = self.prev_node(&cur).unwrap().handle();
+                break;
             }
         }
     }
@@ -1114,17 +1263,14 @@ where
 {
     let previous_child = parent.children().get(index);
     let after_child = parent.children().get(index + 1);
-    if let (Some(DomNode::Text(t1)), Some(DomNode::Text(t2))) =
+    if let (Some(DomNode::Text(_)), Some(DomNode::Text(t2))) =
         (previous_child, after_child)
     {
-        let mut data = t1.data().to_owned();
-        data.push(t2.data());
-        if let Some(DomNode::Text(t1_mut)) = parent.get_child_mut(index) {
-            t1_mut.set_data(data);
-            parent.remove_child(index + 1);
-        } else {
-            unreachable!("t1 was a text node but t1_mut was not!");
+        let t2 = t2.clone();
+        if let Some(DomNode::Text(t1)) = parent.get_child_mut(index) {
+            t1.push(&t2);
         }
+        parent.remove_child(index + 1);
     }
 }
 
@@ -1314,7 +1460,7 @@ mod test {
     #[test]
     fn delete_text_at_end_of_code_block_appends_next_content() {
         let mut model = cm("<pre><code>Te{st</code></pre>AA}|BB");
-        model.delete();
+        model.delete().unwrap();
         assert_eq!(tx(&model), "<pre><code>Te|BB</code></pre>");
     }
 }
@@ -0,0 +1,344 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rewrites inline content into its simplest form after an edit, so that
+//! e.g. `<b>a</b><b>b</b>` is sent as `<b>ab</b>`.
+
+use crate::dom::nodes::{ContainerNode, ContainerNodeKind, DomNode};
+use crate::dom::{Dom, DomHandle, Range};
+use crate::{InlineFormatType, UnicodeString};
+
+impl<S> Dom<S>
+where
+    S: UnicodeString,
+{
+    /// Remove inline containers holding no leaves, put nested formatting in
+    /// a fixed order, and join adjacent identical inline containers and text
+    /// nodes, in the blocks holding the selection from `start` to `end`. The
+    /// rest of the Dom isn't touched.
+    ///
+    /// None of this changes the text, so locations stay valid. Empty nodes
+    /// in an empty block touching the selection are kept, as they hold the
+    /// formatting of text typed there.
+    pub fn normalize(&mut self, start: usize, end: usize) {
+        let range = self.find_range(start, end);
+        let in_selection: Vec<DomHandle> = range
+            .locations
+            .iter()
+            .map(|l| l.node_handle.clone())
+            .collect();
+        // Last first, so normalizing a block doesn't move the others
+        for block in self.blocks_in(&range).into_iter().rev() {
+            self.remove_empty_inline_containers(&block, &in_selection);
+            self.sort_nested_formatting(&block);
+            self.join_identical_inline_containers(&block);
+            self.merge_text_nodes_in(&block);
+        }
+    }
+
+    /// Put formatting nodes that only hold another formatting node in the
+    /// order of [InlineFormatType], outermost first, in the container at
+    /// `handle` and below it. This lets `<i><b>a</b></i><b><i>b</i></b>` be
+    /// joined.
+    fn sort_nested_formatting(&mut self, handle: &DomHandle) {
+        let outermost: Vec<DomHandle> = self
+            .lookup_node(handle)
+            .iter_containers()
+            .filter(|c| {
+                c.is_formatting_node()
+                    && !c.handle().is_root()
+                    && !self.holds_only_formatting(&c.handle().parent_handle())
+                    && self.holds_only_formatting(&c.handle())
+            })
+            .map(|c| c.handle())
+            .collect();
+        for handle in outermost {
+            let DomNode::Container(container) = self.lookup_node(&handle)
+            else {
+                continue;
+            };
+            let mut chain = vec![container];
+            let mut innermost = container;
+            while let [DomNode::Container(inner)] =
+                innermost.children().as_slice()
+            {
+                if !inner.is_formatting_node() {
+                    break;
+                }
+                chain.push(inner);
+                innermost = inner;
+            }
+            let mut sorted = chain.clone();
+            sorted.sort_by_key(|c| format_order(c.kind()));
+            if sorted == chain {
+                continue;
+            }
+            let innermost = innermost.children().clone();
+            let node = sorted.iter().rev().fold(innermost, |children, c| {
+                vec![DomNode::Container(c.clone_with_new_children(children))]
+            });
+            self.replace(&handle, node);
+        }
+    }
+
+    /// The closest block ancestor of each inline node in `range`, leaving
+    /// out blocks inside others, in document order. A range without inline
+    /// nodes is in its deepest block.
+    fn blocks_in(&self, range: &Range) -> Vec<DomHandle> {
+        let mut blocks: Vec<DomHandle> = range
+            .locations
+            .iter()
+            .filter(|l| !l.kind.is_block_kind())
+            .map(|l| {
+                self.find_structure_ancestor(&l.node_handle)
+                    .unwrap_or_else(DomHandle::root)
+            })
+            .collect();
+        if blocks.is_empty() {
+            blocks.push(
+                range
+                    .deepest_block_node(None)
+                    .map_or_else(DomHandle::root, |l| l.node_handle.clone()),
+            );
+        }
+        blocks.sort();
+        blocks.dedup();
+        blocks
+            .iter()
+            .filter(|b| !blocks.iter().any(|other| other.is_ancestor_of(b)))
+            .cloned()
+            .collect()
+    }
+
+    fn remove_empty_inline_containers(
+        &mut self,
+        block: &DomHandle,
+        in_selection: &[DomHandle],
+    ) {
+        let empty: Vec<DomHandle> = self
+            .lookup_node(block)
+            .iter_containers()
+            .filter(|c| {
+                is_inline_container(c)
+                    && holds_no_leaves(c)
+                    && !(in_selection.contains(&c.handle())
+                        && self.is_in_empty_block(&c.handle()))
+            })
+            .map(|c| c.handle())
+            .collect();
+        // Last first, so removing them doesn't move the others
+        for handle in empty.into_iter().rev() {
+            self.remove(&handle);
+        }
+    }
+
+    /// Join adjacent formatting nodes of the same type, and adjacent links
+    /// to the same place, in the container at `handle` and below it. The
+    /// children of joined nodes are checked after the join, so anything that
+    /// becomes adjacent and identical inside them is joined too.
+    fn join_identical_inline_containers(&mut self, handle: &DomHandle) {
+        let DomNode::Container(container) = self.lookup_node(handle) else {
+            return;
+        };
+        // Last first, so joining doesn't move the children still to check
+        for i in (1..container.children().len()).rev() {
+            let (prev, next) =
+                (handle.child_handle(i - 1), handle.child_handle(i));
+            if self.can_join_inline_containers(&prev, &next) {
+                self.move_children_and_delete_parent(&next, &prev);
+            }
+        }
+        let DomNode::Container(container) = self.lookup_node(handle) else {
+            return;
+        };
+        let children: Vec<DomHandle> = container
+            .children()
+            .iter()
+            .filter(|child| child.is_container_node())
+            .map(|child| child.handle())
+            .collect();
+        for child in children {
+            self.join_identical_inline_containers(&child);
+        }
+    }
+
+    fn can_join_inline_containers(
+        &self,
+        prev: &DomHandle,
+        next: &DomHandle,
+    ) -> bool {
+        matches!(
+            (self.lookup_node(prev), self.lookup_node(next)),
+            (DomNode::Container(prev), DomNode::Container(next))
+                if is_inline_container(next) && is_identical(prev, next)
+        )
+    }
+
+    fn holds_only_formatting(&self, handle: &DomHandle) -> bool {
+        matches!(
+            self.lookup_node(handle),
+            DomNode::Container(c) if c.is_formatting_node()
+                && matches!(
+                    c.children().as_slice(),
+                    [DomNode::Container(inner)] if inner.is_formatting_node()
+                )
+        )
+    }
+}
+
+fn is_inline_container<S: UnicodeString>(container: &ContainerNode<S>) -> bool {
+    container.is_formatting_node() || container.is_link()
+}
+
+fn holds_no_leaves<S: UnicodeString>(container: &ContainerNode<S>) -> bool {
+    container
+        .children()
+        .iter()
+        .all(|child| child.iter_subtree().all(DomNode::is_container_node))
+}
+
+/// Whether two containers only differ by their children.
+fn is_identical<S: UnicodeString>(
+    a: &ContainerNode<S>,
+    b: &ContainerNode<S>,
+) -> bool {
+    a.clone_with_new_children(Vec::new())
+        == b.clone_with_new_children(Vec::new())
+}
+
+fn format_order<S: UnicodeString>(kind: &ContainerNodeKind<S>) -> usize {
+    match kind {
+        ContainerNodeKind::Formatting(format) => match format {
+            InlineFormatType::Bold => 0,
+            InlineFormatType::Italic => 1,
+            InlineFormatType::StrikeThrough => 2,
+            InlineFormatType::Underline => 3,
+            InlineFormatType::InlineCode => 4,
+        },
+        _ => usize::MAX,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use widestring::Utf16String;
+
+    use crate::dom::parser::parse;
+    use crate::tests::testutils_composer_model::{cm, tx};
+    use crate::ToHtml;
+
+    fn normalized(html: &str, start: usize, end: usize) -> String {
+        let mut dom = parse::<Utf16String>(html).unwrap();
+        dom.normalize(start, end);
+        dom.to_html().to_string()
+    }
+
+    #[test]
+    fn adjacent_identical_formatting_is_joined() {
+        assert_eq!(normalized("<b>a</b><b>b</b>", 0, 0), "<b>ab</b>");
+        assert_eq!(
+            normalized("<b><i>a</i></b><b><i>b</i>c</b>", 0, 0),
+            "<b><i>ab</i>c</b>"
+        );
+    }
+
+    #[test]
+    fn different_formatting_is_not_joined() {
+        assert_eq!(normalized("<b>a</b><i>b</i>", 0, 0), "<b>a</b><i>b</i>");
+    }
+
+    #[test]
+    fn adjacent_links_are_joined_only_if_identical() {
+        assert_eq!(
+            normalized(
+                r#"<a href="https://x.org">a</a><a href="https://x.org">b</a>"#,
+                0,
+                0
+            ),
+            r#"<a href="https://x.org">ab</a>"#
+        );
+        assert_eq!(
+            normalized(
+                r#"<a href="https://x.org">a</a><a href="https://y.org">b</a>"#,
+                0,
+                0
+            ),
+            r#"<a href="https://x.org">a</a><a href="https://y.org">b</a>"#
+        );
+    }
+
+    #[test]
+    fn nested_formatting_is_sorted() {
+        assert_eq!(
+            normalized("<u><i><b>a</b></i></u>", 0, 0),
+            "<b><i><u>a</u></i></b>"
+        );
+        assert_eq!(
+            normalized("<i><b>a</b></i><b><i>b</i></b>", 0, 0),
+            "<b><i>ab</i></b>"
+        );
+    }
+
+    #[test]
+    fn formatting_holding_more_than_one_node_is_not_sorted() {
+        assert_eq!(normalized("<i><b>a</b>b</i>", 0, 0), "<i><b>a</b>b</i>");
+    }
+
+    #[test]
+    fn nested_formatting_is_only_sorted_in_the_blocks_holding_the_selection() {
+        assert_eq!(
+            normalized("<p><i><b>a</b></i></p><p><i><b>b</b></i></p>", 3, 3),
+            "<p><i><b>a</b></i></p><p><b><i>b</i></b></p>"
+        );
+    }
+
+    #[test]
+    fn empty_formatting_at_the_cursor_is_kept() {
+        let mut model = cm("<p>a</p><p><strong>|</strong></p>");
+        model.state.dom.normalize(2, 2);
+        assert_eq!(tx(&model), "<p>a</p><p><strong>|</strong></p>");
+    }
+
+    #[test]
+    fn empty_formatting_in_a_block_with_text_is_removed() {
+        assert_eq!(normalized("<p><b></b>a</p>", 1, 1), "<p>a</p>");
+        assert_eq!(
+            normalized(r#"<p><a href="https://x.org"><b></b></a>a</p>"#, 0, 0),
+            "<p>a</p>"
+        );
+    }
+
+    #[test]
+    fn only_the_blocks_holding_the_selection_are_normalized() {
+        assert_eq!(
+            normalized("<p><b>a</b><b>b</b></p><p><b>c</b><b>d</b></p>", 4, 4),
+            "<p><b>a</b><b>b</b></p><p><b>cd</b></p>"
+        );
+        assert_eq!(
+            normalized(
+                "<p><b>a</b><b>b</b></p><p>c</p><p><b>d</b><b>e</b></p>",
+                1,
+                6
+            ),
+            "<p><b>ab</b></p><p>c</p><p><b>de</b></p>"
+        );
+    }
+
+    #[test]
+    fn composer_updates_are_normalized() {
+        let mut model = cm("<b>a</b>{b}|");
+        model.bold().unwrap();
+        assert_eq!(tx(&model), "<b>a{b}|</b>");
+    }
+}
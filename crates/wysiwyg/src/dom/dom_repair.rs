@@ -0,0 +1,326 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Passes that rewrite a Dom until it satisfies the invariants checked by
+//! [Dom::explicitly_assert_invariants].
+//!
+//! HTML written by other clients can nest nodes in ways we can't edit, so
+//! the parser runs [Dom::repair] on everything it reads.
+
+use std::mem;
+
+use crate::dom::nodes::dom_node::DomNodeKind;
+use crate::dom::nodes::{ContainerNode, ContainerNodeKind};
+use crate::dom::unicode_string::UnicodeStrExt;
+use crate::dom::Dom;
+use crate::{DomHandle, DomNode, UnicodeString};
+
+impl<S> Dom<S>
+where
+    S: UnicodeString,
+{
+    /// Apply every repair pass. Each pass only creates nodes that the
+    /// passes after it accept, so running them once is enough.
+    pub(crate) fn repair(&mut self) {
+        self.unwrap_nested_links();
+        self.move_blocks_out_of_inline_containers();
+        self.move_lists_into_list_items();
+        // The root can still mix blocks and inline nodes, the model wraps
+        // them into paragraphs when it takes the Dom
+        for i in 0..self.children().len() {
+            self.wrap_inline_nodes_into_paragraphs_if_needed(
+                &DomHandle::root().child_handle(i),
+            );
+        }
+        self.remove_empty_formatting_containers();
+        self.remove_mentions_without_text();
+        self.merge_text_nodes_in(&DomHandle::root());
+    }
+
+    /// Links can't hold links, and mentions are links too. Inner links are
+    /// replaced by their contents and inner mentions by their text.
+    fn unwrap_nested_links(&mut self) {
+        while let Some(handle) = self.last_handle_where(|dom, node| {
+            matches!(node.kind(), DomNodeKind::Link | DomNodeKind::Mention)
+                && dom.has_link_ancestor(&node.handle())
+        }) {
+            let replacement = match self.lookup_node(&handle) {
+                DomNode::Mention(mention) => {
+                    vec![DomNode::new_text(mention.display_text())]
+                }
+                link => link
+                    .as_container()
+                    .map_or_else(Vec::new, |link| link.children().clone()),
+            };
+            self.replace(&handle, replacement);
+        }
+    }
+
+    /// Paragraphs, links and formatting nodes can only hold inline nodes.
+    /// Blocks inside them are moved out, and links and formatting nodes
+    /// are copied into the blocks so their text keeps its formatting.
+    fn move_blocks_out_of_inline_containers(&mut self) {
+        while let Some(handle) = self.last_handle_where(|_, node| {
+            matches!(
+                node.kind(),
+                DomNodeKind::Paragraph
+                    | DomNodeKind::Link
+                    | DomNodeKind::Formatting(_)
+            ) && node
+                .as_container()
+                .is_some_and(|c| c.children().iter().any(|n| n.is_block_node()))
+        }) {
+            let Some(container) = self.lookup_node(&handle).as_container()
+            else {
+                break;
+            };
+            let nodes = split_around_blocks(container);
+            self.replace(&handle, nodes);
+        }
+    }
+
+    /// Lists can only hold list items. Anything else is moved into the list
+    /// item before it, or into a new list item if there is none.
+    fn move_lists_into_list_items(&mut self) {
+        while let Some(handle) = self.last_handle_where(|_, node| {
+            node.as_container().is_some_and(|c| {
+                c.is_list() && c.children().iter().any(|n| !n.is_list_item())
+            })
+        }) {
+            let Some(index) =
+                self.lookup_node(&handle).as_container().and_then(|list| {
+                    list.children().iter().rposition(|n| !n.is_list_item())
+                })
+            else {
+                break;
+            };
+            let child_handle = handle.child_handle(index);
+            let child = self.remove(&child_handle);
+            let prev_list_item = (index > 0)
+                .then(|| child_handle.prev_sibling())
+                .filter(|h| self.lookup_node(h).is_list_item());
+            if let Some(list_item) = prev_list_item
+                .and_then(|h| self.lookup_node_mut(&h).as_container_mut())
+            {
+                list_item.append_child(child);
+            } else {
+                self.insert_at(
+                    &child_handle,
+                    DomNode::new_list_item(vec![child]),
+                );
+            }
+        }
+    }
+
+    /// Empty formatting is only kept in an empty block, where it holds the
+    /// format of the text typed next.
+    fn remove_empty_formatting_containers(&mut self) {
+        while let Some(handle) = self.last_handle_where(|dom, node| {
+            node.is_formatting_node()
+                && node.as_container().is_some_and(|c| c.children().is_empty())
+                && !dom.is_in_empty_block(&node.handle())
+        }) {
+            self.remove(&handle);
+        }
+    }
+
+    /// A mention is edited as a single character, so one without any text
+    /// can't be displayed or selected.
+    fn remove_mentions_without_text(&mut self) {
+        while let Some(handle) = self.last_handle_where(|_, node| {
+            matches!(node, DomNode::Mention(m) if m.display_text().is_empty())
+        }) {
+            self.remove(&handle);
+        }
+    }
+
+    /// Whether the closest block around `handle` holds no text, e.g. a
+    /// paragraph only made of empty formatting nodes.
+    pub(crate) fn is_in_empty_block(&self, handle: &DomHandle) -> bool {
+        let block = handle
+            .with_ancestors()
+            .into_iter()
+            .rev()
+            .find(|h| h != handle && self.lookup_node(h).is_block_node())
+            .unwrap_or_else(DomHandle::root);
+        self.lookup_node(&block).text_len() == 0
+    }
+
+    fn has_link_ancestor(&self, handle: &DomHandle) -> bool {
+        let mut ancestors = handle.with_ancestors();
+        ancestors.pop();
+        ancestors
+            .iter()
+            .any(|h| self.lookup_node(h).kind() == DomNodeKind::Link)
+    }
+
+    /// The last node in depth-first order that matches `condition`. Fixing
+    /// it never moves the nodes before it, or any of its ancestors.
+    fn last_handle_where(
+        &self,
+        condition: impl Fn(&Self, &DomNode<S>) -> bool,
+    ) -> Option<DomHandle> {
+        self.iter()
+            .filter(|node| condition(self, node))
+            .map(|node| node.handle())
+            .last()
+    }
+}
+
+/// Replace `container` by its block children, with its inline children
+/// wrapped in copies of it. Copies of links and formatting nodes also wrap
+/// the contents of each block.
+fn split_around_blocks<S: UnicodeString>(
+    container: &ContainerNode<S>,
+) -> Vec<DomNode<S>> {
+    let is_paragraph = matches!(container.kind(), ContainerNodeKind::Paragraph);
+    let mut nodes = Vec::new();
+    let mut inline_nodes = Vec::new();
+    for child in container.children().iter().cloned() {
+        if !child.is_block_node() {
+            inline_nodes.push(child);
+            continue;
+        }
+        if !inline_nodes.is_empty() {
+            nodes.push(DomNode::Container(
+                container.clone_with_new_children(mem::take(&mut inline_nodes)),
+            ));
+        }
+        match child {
+            DomNode::Container(block)
+                if !is_paragraph && !block.children().is_empty() =>
+            {
+                let inner =
+                    container.clone_with_new_children(block.children().clone());
+                nodes.push(DomNode::Container(
+                    block.clone_with_new_children(vec![DomNode::Container(
+                        inner,
+                    )]),
+                ));
+            }
+            _ => nodes.push(child),
+        }
+    }
+    if !inline_nodes.is_empty() {
+        nodes.push(DomNode::Container(
+            container.clone_with_new_children(inline_nodes),
+        ));
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod test {
+    use widestring::Utf16String;
+
+    use crate::dom::parser::parse;
+    use crate::dom::Dom;
+    use crate::{DomNode, ToHtml};
+
+    fn repaired(html: &str) -> String {
+        let dom = parse::<Utf16String>(html).unwrap();
+        dom.explicitly_assert_invariants();
+        dom.to_html().to_string()
+    }
+
+    #[test]
+    fn empty_formatting_is_removed() {
+        assert_eq!(repaired("a<b></b>c"), "ac");
+        assert_eq!(repaired("<p>a<b><i></i></b></p>"), "<p>a</p>");
+    }
+
+    #[test]
+    fn empty_formatting_in_an_empty_block_is_kept() {
+        assert_eq!(
+            repaired("<p>a</p><p><b><i></i></b></p>"),
+            "<p>a</p><p><b><i></i></b></p>"
+        );
+    }
+
+    #[test]
+    fn nested_lists_are_moved_into_list_items() {
+        assert_eq!(
+            repaired("<ul><li>a</li><ul><li>b</li></ul></ul>"),
+            "<ul><li><p>a</p><ul><li>b</li></ul></li></ul>"
+        );
+        assert_eq!(
+            repaired("<ol><ol><li>b</li></ol></ol>"),
+            "<ol><li><ol><li>b</li></ol></li></ol>"
+        );
+    }
+
+    #[test]
+    fn blocks_are_moved_out_of_formatting() {
+        assert_eq!(
+            repaired("<b><p>a</p><p>b</p></b>"),
+            "<p><b>a</b></p><p><b>b</b></p>"
+        );
+        assert_eq!(
+            repaired(
+                "<blockquote><em>x<blockquote><p>a</p></blockquote></em></blockquote>"
+            ),
+            "<blockquote><p><em>x</em></p><blockquote><p><em>a</em></p></blockquote></blockquote>"
+        );
+    }
+
+    #[test]
+    fn blocks_are_moved_out_of_links() {
+        assert_eq!(
+            repaired(
+                r#"<blockquote><a href="https://x.org">x<b><p>a</p></b></a></blockquote>"#
+            ),
+            r#"<blockquote><p><a href="https://x.org">x</a></p><p><a href="https://x.org"><b>a</b></a></p></blockquote>"#
+        );
+    }
+
+    #[test]
+    fn links_inside_links_are_unwrapped() {
+        let mut dom = Dom::new(vec![DomNode::new_link(
+            Utf16String::from("https://x.org"),
+            vec![
+                DomNode::new_text(Utf16String::from("a")),
+                DomNode::new_link(
+                    Utf16String::from("https://y.org"),
+                    vec![DomNode::new_text(Utf16String::from("b"))],
+                    vec![],
+                ),
+            ],
+            vec![],
+        )]);
+        dom.repair();
+        dom.explicitly_assert_invariants();
+        assert_eq!(
+            dom.to_html().to_string(),
+            r#"<a href="https://x.org">ab</a>"#
+        );
+    }
+
+    #[test]
+    fn mentions_inside_links_become_text() {
+        let mut dom = Dom::new(vec![DomNode::new_link(
+            Utf16String::from("https://x.org"),
+            vec![
+                DomNode::new_text(Utf16String::from("hi ")),
+                DomNode::Mention(DomNode::new_at_room_mention(vec![])),
+            ],
+            vec![],
+        )]);
+        dom.repair();
+        dom.explicitly_assert_invariants();
+        assert_eq!(
+            dom.to_html().to_string(),
+            r#"<a href="https://x.org">hi @room</a>"#
+        );
+    }
+}
@@ -479,11 +479,8 @@ where
                 // Splice new_node in between this text node and a new one
                 let old_node = self.lookup_node_mut(handle);
                 if let DomNode::Text(old_text_node) = old_node {
-                    let data = old_text_node.data();
-                    let before_text = data[..offset].to_owned();
-                    let after_text = data[offset..].to_owned();
-                    old_text_node.set_data(before_text);
-                    let new_text_node = DomNode::new_text(after_text);
+                    let new_text_node =
+                        DomNode::Text(old_text_node.slice_after(offset));
                     let parent = self.parent_mut(handle);
                     let inserted_handle = parent
                         .insert_child(handle.index_in_parent() + 1, new_node)
@@ -647,8 +644,8 @@ impl<S> ToPlainText<S> for Dom<S>
 where
     S: UnicodeString,
 {
-    fn to_plain_text(&self) -> S {
-        self.document.to_plain_text()
+    fn fmt_plain_text(&self, buffer: &mut S, track_offsets: bool) {
+        self.document.fmt_plain_text(buffer, track_offsets)
     }
 }
 
@@ -717,7 +714,8 @@ mod test {
             DomNode::new_formatting_from_tag(
                 utf16("b"),
                 vec![DomNode::new_text(utf16("b"))],
-            ),
+            )
+            .unwrap(),
         ]);
 
         // The DOM was created successfully
@@ -732,7 +730,8 @@ mod test {
             DomNode::new_formatting_from_tag(
                 utf16("b"),
                 vec![DomNode::new_text(utf16("b"))],
-            ),
+            )
+            .unwrap(),
         ]);
 
         let child0 = &dom.children()[0];
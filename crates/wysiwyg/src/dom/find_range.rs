@@ -68,7 +68,15 @@ where
     S: UnicodeString,
 {
     let mut offset = 0;
-    let locations = do_find_pos(dom, node_handle, start, end, &mut offset);
+    let is_last_in_parent =
+        node_handle.is_root() || dom.is_last_in_parent(node_handle);
+    let locations = do_find_pos(
+        dom.lookup_node(node_handle),
+        is_last_in_parent,
+        start,
+        end,
+        &mut offset,
+    );
 
     if locations.is_empty() {
         FindResult::NotFound
@@ -78,8 +86,8 @@ where
 }
 
 fn do_find_pos<S>(
-    dom: &Dom<S>,
-    node_handle: &DomHandle,
+    node: &DomNode<S>,
+    is_last_in_parent: bool,
     start: usize,
     end: usize,
     offset: &mut usize,
@@ -87,7 +95,6 @@ fn do_find_pos<S>(
 where
     S: UnicodeString,
 {
-    let node = dom.lookup_node(node_handle);
     let mut locations = Vec::new();
     if *offset > end {
         *offset += node.text_len();
@@ -113,16 +120,21 @@ where
             }
         }
         DomNode::Container(n) => {
-            locations
-                .extend(process_container_node(dom, n, start, end, offset));
+            locations.extend(process_container_node(
+                n,
+                is_last_in_parent,
+                start,
+                end,
+                offset,
+            ));
         }
     }
     locations
 }
 
 fn process_container_node<S>(
-    dom: &Dom<S>,
     node: &ContainerNode<S>,
+    is_last_in_parent: bool,
     start: usize,
     end: usize,
     offset: &mut usize,
@@ -132,10 +144,10 @@ where
 {
     let mut results = Vec::new();
     let container_start = *offset;
-    for child in node.children() {
-        let child_handle = child.handle();
-        assert!(!child_handle.is_root(), "Incorrect child handle!");
-        let locations = do_find_pos(dom, &child_handle, start, end, offset);
+    let children = node.children();
+    for (i, child) in children.iter().enumerate() {
+        let is_last = i + 1 == children.len();
+        let locations = do_find_pos(child, is_last, start, end, offset);
         if !locations.is_empty() {
             results.extend(locations);
         }
@@ -144,7 +156,7 @@ where
     let mut container_end = *offset;
     if node.is_block_node() && !node.handle().is_root() {
         container_end += 1;
-        if !dom.is_last_in_parent(&node.handle()) {
+        if !is_last_in_parent {
             *offset = container_end;
         }
     }
@@ -175,7 +187,7 @@ where
     S: UnicodeString,
 {
     process_textlike_node(
-        node.handle(),
+        || node.handle(),
         node.data().len(),
         start,
         end,
@@ -195,7 +207,7 @@ where
 {
     // Line breaks are like 1-character text nodes
     process_textlike_node(
-        node.handle(),
+        || node.handle(),
         1,
         start,
         end,
@@ -215,7 +227,7 @@ where
 {
     // Mentions are like 1-character text nodes
     process_textlike_node(
-        node.handle(),
+        || node.handle(),
         1,
         start,
         end,
@@ -224,8 +236,10 @@ where
     )
 }
 
+/// `handle` is only called for nodes in the range, as most nodes of a large
+/// document aren't.
 fn process_textlike_node(
-    handle: DomHandle,
+    handle: impl FnOnce() -> DomHandle,
     node_len: usize,
     start: usize,
     end: usize,
@@ -254,7 +268,7 @@ fn process_textlike_node(
         let end_offset = min(end, node_end) - node_start;
 
         Some(DomLocation {
-            node_handle: handle,
+            node_handle: handle(),
             position: node_start,
             start_offset,
             end_offset,
@@ -60,9 +60,16 @@ where
                 )
             }
         } else {
-            // if we don't have a leaf, try to find the first container that we're inside
-            let first_location: Option<&DomLocation> =
-                range.locations.iter().find(|l| l.start_offset < l.length);
+            // if we don't have a leaf, go inside any empty formatting at the
+            // cursor so the new node takes its format, or else try to find
+            // the first container that we're inside
+            let first_location: Option<&DomLocation> = range
+                .locations
+                .iter()
+                .find(|l| self.is_empty_formatting_node(&l.node_handle))
+                .or_else(|| {
+                    range.locations.iter().find(|l| l.start_offset < l.length)
+                });
             match first_location {
                 // if we haven't found anything, we're inserting into an empty dom
                 None => {
@@ -80,6 +87,13 @@ where
 
         inserted_handle
     }
+
+    fn is_empty_formatting_node(&self, handle: &DomHandle) -> bool {
+        matches!(
+            self.lookup_node(handle),
+            DomNode::Container(c) if c.is_formatting_node() && c.children().is_empty()
+        )
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +143,23 @@ mod test {
         assert_eq!(model.state.dom.to_html(), "<p><a href=\"href\"></a></p>")
     }
 
+    #[test]
+    fn inserts_node_into_empty_formatting() {
+        let mut model = cm("<p>a</p><p><u>|</u></p>");
+        let (start, end) = model.safe_selection();
+        let range = model.state.dom.find_range(start, end);
+
+        model.state.dom.insert_node_at_cursor(
+            &range,
+            DomNode::new_link(utf16("href"), vec![], vec![]),
+        );
+
+        assert_eq!(
+            model.state.dom.to_html(),
+            "<p>a</p><p><u><a href=\"href\"></a></u></p>"
+        )
+    }
+
     #[test]
     fn inserts_node_into_leaf_start() {
         let mut model = cm("<p>|this is a leaf</p>");
@@ -57,12 +57,8 @@ where
         if level >= handle.raw().len() {
             return;
         }
-        let mut handle = handle.clone();
         // If the handle was moved, use updated value instead
-        let moved_handle = action_list.find_moved_parent_or_self(&handle);
-        if let Some((from_handle, to_handle)) = moved_handle {
-            handle.replace_ancestor(from_handle, to_handle);
-        }
+        let handle = action_list.follow_moves(handle);
         // Get the node handle at the current depth level
         let cur_handle = DomHandle::from_raw(handle.raw()[..=level].to_vec());
         let index_in_parent = if cur_handle.is_root() {
@@ -13,19 +13,23 @@
 // limitations under the License.
 
 use std::ops::ControlFlow;
+use std::sync::Arc;
 
 use crate::char::CharExt;
 use crate::composer_model::example_format::SelectionWriter;
 use crate::dom::dom_handle::DomHandle;
 use crate::dom::nodes::dom_node::{DomNode, DomNodeKind};
+use crate::dom::offset_markers::is_only_markers;
 use crate::dom::to_html::{ToHtml, ToHtmlExt, ToHtmlState};
-use crate::dom::to_markdown::{MarkdownError, MarkdownOptions, ToMarkdown};
+use crate::dom::to_markdown::{
+    MarkdownError, MarkdownOptions, MarkdownSyntax, ToMarkdown,
+};
 use crate::dom::to_plain_text::ToPlainText;
 use crate::dom::to_raw_text::ToRawText;
 use crate::dom::to_tree::ToTree;
 use crate::dom::unicode_string::{UnicodeStr, UnicodeStrExt, UnicodeStringExt};
 use crate::dom::{self, UnicodeString};
-use crate::{InlineFormatType, ListType};
+use crate::{ComposerError, InlineFormatType, ListType, TextDirection};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ContainerNode<S>
@@ -35,8 +39,11 @@ where
     name: S,
     kind: ContainerNodeKind<S>,
     attrs: Option<Vec<(S, S)>>,
-    children: Vec<DomNode<S>>,
+    /// Shared between clones until one of them changes, so keeping a copy
+    /// of the Dom in the undo history doesn't copy the whole document.
+    children: Arc<Vec<DomNode<S>>>,
     handle: DomHandle,
+    markdown_syntax: Option<MarkdownSyntax>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -87,8 +94,9 @@ where
             name,
             kind,
             attrs,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -97,23 +105,25 @@ where
             name: "p".into(),
             kind: ContainerNodeKind::Paragraph,
             attrs: None,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
     pub fn new_formatting_from_tag(
         format: S,
         children: Vec<DomNode<S>>,
-    ) -> Self {
-        let format_type = InlineFormatType::from(format.clone());
-        Self {
+    ) -> Result<Self, ComposerError> {
+        let format_type = format.to_string().parse()?;
+        Ok(Self {
             name: format,
             kind: ContainerNodeKind::Formatting(format_type),
             attrs: None,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
-        }
+            markdown_syntax: None,
+        })
     }
 
     pub fn new_formatting(
@@ -124,8 +134,9 @@ where
             name: format.tag().into(),
             kind: ContainerNodeKind::Formatting(format),
             attrs: None,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -134,8 +145,9 @@ where
             name: list_type.tag().into(),
             kind: ContainerNodeKind::List(list_type),
             attrs: None,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -144,8 +156,9 @@ where
             name: "li".into(),
             kind: ContainerNodeKind::ListItem,
             attrs: None,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -154,8 +167,9 @@ where
             name: "codeblock".into(),
             kind: ContainerNodeKind::CodeBlock,
             attrs: None,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -164,8 +178,9 @@ where
             name: "blockquote".into(),
             kind: ContainerNodeKind::Quote,
             attrs: None,
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -175,7 +190,7 @@ where
         let child_index = self.children.len();
         let child_handle = self.handle.child_handle(child_index);
         child.set_handle(child_handle.clone());
-        self.children.push(child);
+        self.children_mut().push(child);
         child_handle
     }
 
@@ -189,11 +204,11 @@ where
         assert!(self.handle.is_set());
         assert!(index < self.children().len());
 
-        let ret = self.children.remove(index);
+        let ret = self.children_mut().remove(index);
 
         for child_index in index..self.children.len() {
             let new_handle = self.handle.child_handle(child_index);
-            self.children[child_index].set_handle(new_handle);
+            self.children_mut()[child_index].set_handle(new_handle);
         }
 
         ret
@@ -212,13 +227,13 @@ where
         for mut node in nodes {
             let child_handle = self.handle.child_handle(current_index);
             node.set_handle(child_handle);
-            self.children.insert(current_index, node);
+            self.children_mut().insert(current_index, node);
             current_index += 1;
         }
 
         for child_index in current_index..self.children.len() {
             let new_handle = self.handle.child_handle(child_index);
-            self.children[child_index].set_handle(new_handle.clone());
+            self.children_mut()[child_index].set_handle(new_handle.clone());
             handles.push(new_handle);
         }
         handles
@@ -234,12 +249,12 @@ where
         assert!(self.handle.is_set());
         assert!(index < self.children().len());
 
-        self.children.remove(index);
+        self.children_mut().remove(index);
         self.insert_children(index, nodes)
     }
 
     pub fn get_child_mut(&mut self, idx: usize) -> Option<&mut DomNode<S>> {
-        self.children.get_mut(idx)
+        self.children_mut().get_mut(idx)
     }
 
     pub fn get_child(&self, idx: usize) -> Option<&DomNode<S>> {
@@ -251,7 +266,7 @@ where
     }
 
     pub fn last_child_mut(&mut self) -> Option<&mut DomNode<S>> {
-        self.children.last_mut()
+        self.children_mut().last_mut()
     }
 
     pub fn insert_child(
@@ -262,11 +277,11 @@ where
         assert!(self.handle.is_set());
         assert!(index <= self.children().len());
 
-        self.children.insert(index, node);
+        self.children_mut().insert(index, node);
 
         for i in index..self.children.len() {
             let new_handle = self.handle.child_handle(i);
-            self.children[i].set_handle(new_handle);
+            self.children_mut()[i].set_handle(new_handle);
         }
 
         self.children.get(index).unwrap()
@@ -278,7 +293,18 @@ where
 
     pub fn set_handle(&mut self, handle: DomHandle) {
         self.handle = handle;
-        for (i, child) in self.children.iter_mut().enumerate() {
+        // A child with the right handle has the right handles below it too,
+        // so children that are all up to date can stay shared with clones
+        let is_up_to_date =
+            self.children.iter().enumerate().all(|(i, child)| {
+                child.handle() == self.handle.child_handle(i)
+            });
+        if is_up_to_date {
+            return;
+        }
+        for (i, child) in
+            Arc::make_mut(&mut self.children).iter_mut().enumerate()
+        {
             child.set_handle(self.handle.child_handle(i))
         }
     }
@@ -295,20 +321,32 @@ where
         &self.children
     }
 
+    /// Identifies the children of this node. Clones share their children,
+    /// and so this id, until one of them is changed.
+    pub(crate) fn children_id(&self) -> usize {
+        Arc::as_ptr(&self.children) as usize
+    }
+
+    /// The children, copied first if they are shared with a clone of this
+    /// node.
+    fn children_mut(&mut self) -> &mut Vec<DomNode<S>> {
+        Arc::make_mut(&mut self.children)
+    }
+
     pub(crate) fn take_children(self) -> Vec<DomNode<S>> {
-        self.children
+        Arc::unwrap_or_clone(self.children)
     }
 
     /// Removes all children from the container and returns them
     pub(crate) fn remove_children(&mut self) -> Vec<DomNode<S>> {
-        self.children.drain(..).collect()
+        std::mem::take(self.children_mut())
     }
 
     pub(crate) fn take_children_after(
         &mut self,
         position: usize,
     ) -> Vec<DomNode<S>> {
-        return self.children.drain(position..self.children.len()).collect();
+        self.children_mut().drain(position..).collect()
     }
 
     pub fn kind(&self) -> &ContainerNodeKind<S> {
@@ -362,6 +400,77 @@ where
         DomNodeKind::from_container_kind(&self.kind).is_block_kind()
     }
 
+    /// Whether this node can carry a `dir` attribute.
+    pub fn supports_text_direction(&self) -> bool {
+        use ContainerNodeKind::*;
+
+        matches!(self.kind, Paragraph | ListItem | CodeBlock | Quote)
+    }
+
+    /// The direction explicitly set on this node, if any.
+    pub fn text_direction(&self) -> Option<TextDirection> {
+        self.attrs
+            .as_ref()?
+            .iter()
+            .find(|(name, _)| name.to_string() == "dir")
+            .and_then(|(_, value)| value.to_string().parse().ok())
+    }
+
+    /// Set or, if `direction` is None, remove the `dir` attribute.
+    pub fn set_text_direction(&mut self, direction: Option<TextDirection>) {
+        let attrs = self.attrs.get_or_insert_with(Vec::new);
+        attrs.retain(|(name, _)| name.to_string() != "dir");
+        if let Some(direction) = direction {
+            attrs.push(("dir".into(), direction.as_str().into()));
+        }
+        if attrs.is_empty() {
+            self.attrs = None;
+        }
+    }
+
+    /// Whether this block should be output with `dir="auto"` even though
+    /// no direction was set, because its text starts with a right-to-left
+    /// character. Only the innermost blocks are marked, so that nested
+    /// blocks can each pick their own direction.
+    fn needs_auto_direction(&self) -> bool {
+        self.text_direction().is_none() && self.detects_auto_direction()
+    }
+
+    /// Whether `dir="auto"` is added to the HTML of this block when no
+    /// direction is set on it.
+    pub(crate) fn detects_auto_direction(&self) -> bool {
+        self.supports_text_direction()
+            && (matches!(self.kind, ContainerNodeKind::CodeBlock)
+                || !self.children.iter().any(|c| c.is_block_node()))
+            && self.first_strong_direction() == Some(TextDirection::Rtl)
+    }
+
+    /// The direction of the first strongly directional character in this
+    /// node, read in place as this runs for every block of every update.
+    fn first_strong_direction(&self) -> Option<TextDirection> {
+        self.children.iter().find_map(|child| match child {
+            DomNode::Container(container) => container.first_strong_direction(),
+            DomNode::Text(text) => {
+                TextDirection::detect_in_chars(text.data().chars())
+            }
+            DomNode::Mention(mention) => {
+                TextDirection::detect(&mention.display_text().to_string())
+            }
+            DomNode::LineBreak(_) => None,
+        })
+    }
+
+    /// The attributes to output as HTML, including any detected direction.
+    fn html_attrs(&self) -> Option<Vec<(S, S)>> {
+        if self.needs_auto_direction() {
+            let mut attrs = self.attrs.clone().unwrap_or_default();
+            attrs.push(("dir".into(), TextDirection::Auto.as_str().into()));
+            Some(attrs)
+        } else {
+            self.attrs.clone()
+        }
+    }
+
     pub fn text_len(&self) -> usize {
         let children_len: usize =
             self.children.iter().map(|child| child.text_len()).sum();
@@ -392,8 +501,9 @@ where
             name: "a".into(),
             kind: ContainerNodeKind::Link(url),
             attrs: Some(attributes),
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -423,6 +533,14 @@ where
         Some(url)
     }
 
+    pub(crate) fn markdown_syntax(&self) -> Option<&MarkdownSyntax> {
+        self.markdown_syntax.as_ref()
+    }
+
+    pub(crate) fn set_markdown_syntax(&mut self, syntax: MarkdownSyntax) {
+        self.markdown_syntax = Some(syntax);
+    }
+
     /// Creates a container with the same kind & attributes
     /// as self, with given children and an unset handle.
     pub(crate) fn clone_with_new_children(
@@ -433,15 +551,16 @@ where
             name: self.name.clone(),
             kind: self.kind.clone(),
             attrs: self.attrs.clone(),
-            children,
+            children: Arc::new(children),
             handle: DomHandle::new_unset(),
+            markdown_syntax: self.markdown_syntax.clone(),
         }
     }
 
     /// Remove leading Line break char from this container.
     /// Returns false if no updates were done.
     pub fn remove_leading_line_break(&mut self) -> bool {
-        let Some(first_child) = self.children.get_mut(0) else {
+        let Some(first_child) = self.children_mut().get_mut(0) else {
             return false;
         };
         match first_child {
@@ -450,7 +569,7 @@ where
                 if self.handle().is_set() {
                     self.remove_child(0);
                 } else {
-                    self.children.remove(0);
+                    self.children_mut().remove(0);
                 }
                 true
             }
@@ -473,11 +592,14 @@ where
         if other_node.kind != self.kind {
             panic!("Trying to push a non-matching container kind");
         }
-        let last_child = self.children.last().unwrap();
-        let other_node_first_child = other_node.get_child(0).unwrap();
-        if last_child.can_push(other_node_first_child) {
-            let mut next_child = other_node.remove_child(0);
-            self.last_child_mut().unwrap().push(&mut next_child);
+        // Either node may be an empty formatting node
+        if let (Some(last_child), Some(other_node_first_child)) =
+            (self.children.last(), other_node.get_child(0))
+        {
+            if last_child.can_push(other_node_first_child) {
+                let mut next_child = other_node.remove_child(0);
+                self.last_child_mut().unwrap().push(&mut next_child);
+            }
         }
         while !other_node.children().is_empty() {
             let child = other_node.remove_child(0);
@@ -510,7 +632,7 @@ where
                 }
                 while self.children.len() > index_to_remove {
                     removed_children
-                        .push(self.children.remove(index_to_remove));
+                        .push(self.children_mut().remove(index_to_remove));
                 }
                 self.clone_with_new_children(removed_children)
             }
@@ -537,7 +659,7 @@ where
                     removed_children.push(sliced);
                 }
                 for i in (0..child_index).rev() {
-                    removed_children.insert(0, self.children.remove(i));
+                    removed_children.insert(0, self.children_mut().remove(i));
                 }
                 self.clone_with_new_children(removed_children)
             }
@@ -643,7 +765,7 @@ impl<S: UnicodeString> ContainerNode<S> {
     ) {
         let name = self.name();
         if !name.is_empty() {
-            self.fmt_tag_open(name, formatter, &self.attrs);
+            self.fmt_tag_open(name, formatter, &self.html_attrs());
         }
 
         self.fmt_children_html(formatter, selection_writer, state, as_message);
@@ -675,7 +797,12 @@ impl<S: UnicodeString> ContainerNode<S> {
                 state,
             )
         } else {
-            self.fmt_default_paragraph_html(formatter, selection_writer, state)
+            self.fmt_default_paragraph_html(
+                formatter,
+                selection_writer,
+                state,
+                as_message,
+            )
         }
     }
 
@@ -684,12 +811,12 @@ impl<S: UnicodeString> ContainerNode<S> {
         formatter: &mut S,
         selection_writer: Option<&mut SelectionWriter>,
         state: &ToHtmlState,
+        as_message: bool,
     ) {
-        let as_message = false;
         assert!(matches!(self.kind, ContainerNodeKind::Paragraph));
         let name = self.name();
 
-        self.fmt_tag_open(name, formatter, &self.attrs);
+        self.fmt_tag_open(name, formatter, &self.html_attrs());
         if self.is_empty() {
             formatter.push(char::nbsp());
         }
@@ -716,7 +843,20 @@ impl<S: UnicodeString> ContainerNode<S> {
             formatter.push("<br />");
         }
 
+        // Messages always send paragraphs as lines, so any direction goes on
+        // a span around the line rather than on a `<p>` tag.
+        let direction = self.text_direction().or_else(|| {
+            self.needs_auto_direction().then_some(TextDirection::Auto)
+        });
+        let span = S::from("span");
+        if let Some(direction) = direction {
+            let attrs = vec![("dir".into(), direction.as_str().into())];
+            self.fmt_tag_open(&span, formatter, &Some(attrs));
+        }
         self.fmt_children_html(formatter, selection_writer, state, as_message);
+        if direction.is_some() {
+            self.fmt_tag_close(&span, formatter);
+        }
 
         // If the next node is a block node, no need to add a line break as
         // one is implicitly added.
@@ -756,7 +896,7 @@ impl<S: UnicodeString> ContainerNode<S> {
         as_message: bool,
     ) {
         assert!(matches!(self.kind, ContainerNodeKind::CodeBlock));
-        self.fmt_tag_open(&S::from("pre"), formatter, &self.attrs);
+        self.fmt_tag_open(&S::from("pre"), formatter, &self.html_attrs());
         let mut state = state.clone();
         state.is_inside_code_block = true;
 
@@ -817,7 +957,7 @@ where
 {
     fn to_raw_text(&self) -> S {
         let mut text = S::default();
-        for child in &self.children {
+        for child in self.children.iter() {
             text.push(child.to_raw_text());
         }
         text
@@ -828,25 +968,31 @@ impl<S> ToPlainText<S> for ContainerNode<S>
 where
     S: UnicodeString,
 {
-    fn to_plain_text(&self) -> S {
+    fn fmt_plain_text(&self, buffer: &mut S, track_offsets: bool) {
         let mut text = S::default();
         match self.kind {
-            ContainerNodeKind::List(_) => fmt_list(self, &mut text),
-            ContainerNodeKind::ListItem => fmt_list_item(self, &mut text),
-            _ => fmt_default(self, &mut text),
+            ContainerNodeKind::List(_) => {
+                fmt_list(self, &mut text, track_offsets)
+            }
+            ContainerNodeKind::ListItem => {
+                fmt_list_item(self, &mut text, track_offsets)
+            }
+            _ => fmt_default(self, &mut text, track_offsets),
         }
-        return text;
+        buffer.push(text);
+        return;
 
         #[inline(always)]
         fn fmt_list<S: UnicodeString>(
             container: &ContainerNode<S>,
             text: &mut S,
+            track_offsets: bool,
         ) {
             for (index, child) in container.children.iter().enumerate() {
                 if index != 0 && !matches!(text.chars().last(), Some('\n')) {
                     text.push("\n");
                 }
-                text.push(child.to_plain_text());
+                child.fmt_plain_text(text, track_offsets);
             }
             text.push("\n");
         }
@@ -855,9 +1001,10 @@ where
         fn fmt_list_item<S: UnicodeString>(
             container: &ContainerNode<S>,
             text: &mut S,
+            track_offsets: bool,
         ) {
             for child in container.children() {
-                text.push(child.to_plain_text());
+                child.fmt_plain_text(text, track_offsets);
             }
         }
 
@@ -865,9 +1012,10 @@ where
         fn fmt_default<S: UnicodeString>(
             container: &ContainerNode<S>,
             text: &mut S,
+            track_offsets: bool,
         ) {
-            for child in &container.children {
-                text.push(child.to_plain_text());
+            for child in container.children.iter() {
+                child.fmt_plain_text(text, track_offsets);
             }
             if container.is_block_node()
                 && !container.handle.is_root()
@@ -992,7 +1140,20 @@ where
         {
             for (nth, child) in this.children.iter().enumerate() {
                 if nth > 0 && child.is_block_node() {
-                    buffer.push("\n");
+                    // A paragraph made from a line break is separated
+                    // from the previous one by the same break.
+                    let separator = match child {
+                        DomNode::Container(container) => {
+                            match container.markdown_syntax() {
+                                Some(MarkdownSyntax::LineBreak { source }) => {
+                                    source.as_str()
+                                }
+                                _ => "\n",
+                            }
+                        }
+                        _ => "\n",
+                    };
+                    buffer.push(separator);
                 }
 
                 child.fmt_markdown(buffer, options, as_message)?;
@@ -1015,11 +1176,14 @@ where
             // simple emphasis to `*` to avoid unwanted emphasis
             // in words containing internal underscores, like
             // `foo_bar_baz`. We reckon it's good to follow this
-            // trend to avoid unexpected behaviours for our users.
+            // trend to avoid unexpected behaviours for our users,
+            // unless the dialect asks for `_`.
 
-            buffer.push("*");
+            let (delimiter, _) = options.dialect().emphasis_delimiters();
+            let (open, close) = delimiters(this, delimiter, delimiter);
+            buffer.push(open);
             fmt_children(this, buffer, options, as_message)?;
-            buffer.push("*");
+            buffer.push(close);
 
             Ok(())
         }
@@ -1034,19 +1198,21 @@ where
         where
             S: UnicodeString,
         {
-            // `Formatting(Italic)` already uses `*` to represent
-            // a simple emphasis.
+            // `Formatting(Italic)` already uses `*` (or `_`) to
+            // represent a simple emphasis.
             //
-            // We reckon it is better to use `_` to represent a
-            // strong emphasis instead of `*` so that
+            // We reckon it is better to use the other character to
+            // represent a strong emphasis so that
             // `<em><strong>…</strong></em>` does _not_ produce
             // `***…***` or `___…___` which can be ambigiously
             // interpreted by various Markdown compilers out
             // there. Instead, it will produce `*__…__*`.
 
-            buffer.push("__");
+            let (_, delimiter) = options.dialect().emphasis_delimiters();
+            let (open, close) = delimiters(this, delimiter, delimiter);
+            buffer.push(open);
             fmt_children(this, buffer, options, as_message)?;
-            buffer.push("__");
+            buffer.push(close);
 
             Ok(())
         }
@@ -1066,10 +1232,18 @@ where
             // ambiguous behaviours for users that manipulate
             // filesystem paths, or with Markdown compilers that
             // do not support this format extension.
-
-            buffer.push("~~");
+            //
+            // Strict CommonMark doesn't have strikethrough at all,
+            // so raw HTML is used instead.
+
+            let (open, close) = if options.dialect().is_gfm() {
+                delimiters(this, "~~", "~~")
+            } else {
+                ("<del>", "</del>")
+            };
+            buffer.push(open);
             fmt_children(this, buffer, options, as_message)?;
-            buffer.push("~~");
+            buffer.push(close);
 
             Ok(())
         }
@@ -1116,12 +1290,13 @@ where
             // backtick. Those spaces are removed during
             // normalization.
 
-            buffer.push("`` ");
+            let (open, close) = delimiters(this, "`` ", " ``");
+            buffer.push(open);
 
             options.insert(MarkdownOptions::IGNORE_LINE_BREAK);
             fmt_children(this, buffer, options, as_message)?;
 
-            buffer.push(" ``");
+            buffer.push(close);
 
             Ok(())
         }
@@ -1137,6 +1312,35 @@ where
         where
             S: UnicodeString,
         {
+            let url = url.to_string();
+            match this.markdown_syntax() {
+                Some(MarkdownSyntax::InlineLink {
+                    url: imported_url,
+                    suffix,
+                }) if *imported_url == url => {
+                    buffer.push('[');
+                    fmt_children(this, buffer, options, as_message)?;
+                    buffer.push(suffix.as_str());
+                    return Ok(());
+                }
+                // Autolinks can only be kept while their text is still
+                // the URL.
+                Some(MarkdownSyntax::Autolink)
+                    if is_url_text(&this.to_raw_text().to_string(), &url) =>
+                {
+                    buffer.push('<');
+                    fmt_children(this, buffer, options, as_message)?;
+                    buffer.push('>');
+                    return Ok(());
+                }
+                Some(MarkdownSyntax::BareUrl)
+                    if is_url_text(&this.to_raw_text().to_string(), &url) =>
+                {
+                    return fmt_children(this, buffer, options, as_message);
+                }
+                _ => {}
+            }
+
             buffer.push('[');
 
             fmt_children(this, buffer, options, as_message)?;
@@ -1151,8 +1355,7 @@ where
 
             buffer.push("](<");
             buffer.push(
-                url.to_string()
-                    .replace('<', "\\<")
+                url.replace('<', "\\<")
                     .replace('>', "\\>")
                     .replace('(', "\\(")
                     .replace(')', "\\)")
@@ -1177,7 +1380,13 @@ where
             let ordered_list_name = "ol";
             let expected_list_item_name = &S::from("li");
             let number_of_children = this.children.len();
-            let mut ordered_list_counter = 0i32;
+            let (mut ordered_list_counter, delimiter) =
+                match this.markdown_syntax() {
+                    Some(MarkdownSyntax::OrderedList { start, delimiter }) => {
+                        (start.saturating_sub(1), *delimiter)
+                    }
+                    _ => (0, '.'),
+                };
 
             for (nth, child) in this.children.iter().enumerate() {
                 // Verify the list item is correct.
@@ -1223,69 +1432,36 @@ where
                     }
                 };
 
-                // What's the current indentation, for this specific list only.
-                let mut indentation = 0;
-
-                // It's an ordered list.
-                if list_type == ordered_list_name {
+                // The item's marker, e.g. `1. ` or `* `.
+                let marker = if list_type == ordered_list_name {
                     // Update the counter.
                     ordered_list_counter += 1;
 
                     // Generate something like `1.` (arabic numbers only,
                     // as requested by the specification).
-                    let counter = ordered_list_counter.to_string();
-
-                    buffer.push(counter.as_str());
-                    buffer.push('.');
-
-                    // Indentation will match the counter size.
-                    indentation += counter.len();
-                }
-                // It's an unordered list.
-                else {
+                    format!("{ordered_list_counter}{delimiter} ")
+                } else if let Some(MarkdownSyntax::BulletList { marker }) =
+                    this.markdown_syntax()
+                {
+                    format!("{marker} ")
+                } else {
                     // Generate something like `*`.
-                    buffer.push('*');
-
-                    // Indentation will match the counter size.
-                    indentation += 1;
-                }
-
-                // Insert a space between the counter and the item's content.
-                buffer.push(' ');
-
-                // And update the indentation.
-                indentation += 1;
+                    options.dialect().bullet_marker().to_owned()
+                };
 
-                {
-                    // Let's create a new buffer for the child formatting.
-                    let mut child_buffer = S::default();
-                    child.fmt_markdown(
-                        &mut child_buffer,
-                        options,
-                        as_message,
-                    )?;
-
-                    // Generate the indentation of form `\n` followed by
-                    // $x$ spaces where $x$ is `indentation`.
-                    let indentation = {
-                        let spaces = " ".repeat(indentation);
-                        let mut indentation =
-                            String::with_capacity(1 /* `\n` */ + indentation);
-                        indentation.push('\n');
-                        indentation.push_str(&spaces);
-
-                        indentation
-                    };
+                // Let's create a new buffer for the child formatting.
+                let mut child_buffer = S::default();
+                child.fmt_markdown(&mut child_buffer, options, as_message)?;
 
-                    // Insert the child's buffer after `\n`s have been
-                    // replaced by `\n` followed by spaces for indentation.
-                    buffer.push(
-                        child_buffer
-                            .to_string()
-                            .replace('\n', &indentation)
-                            .as_str(),
-                    );
-                }
+                // Nested blocks are indented to align with the item's
+                // content, so they stay part of the item.
+                let indentation = " ".repeat(marker.len());
+                push_prefixed_lines(
+                    buffer,
+                    &child_buffer.to_string(),
+                    &marker,
+                    &indentation,
+                );
 
                 let is_last = nth == number_of_children - 1;
 
@@ -1322,9 +1498,38 @@ where
         where
             S: UnicodeString,
         {
-            buffer.push("```\n");
+            let (fence, info, closing_fence) = match this.markdown_syntax() {
+                Some(MarkdownSyntax::FencedCode {
+                    fence,
+                    info,
+                    closing_fence,
+                }) => (fence.as_str(), info.as_str(), closing_fence.as_str()),
+                Some(MarkdownSyntax::IndentedCode) => {
+                    let mut child_buffer = S::default();
+                    fmt_children(this, &mut child_buffer, options, as_message)?;
+                    push_prefixed_lines(
+                        buffer,
+                        &child_buffer.to_string(),
+                        "    ",
+                        "    ",
+                    );
+                    buffer.push("\n");
+                    return Ok(());
+                }
+                _ => {
+                    let fence = options.dialect().fence();
+                    (fence, "", fence)
+                }
+            };
+            buffer.push(fence);
+            buffer.push(info);
+            buffer.push("\n");
             fmt_children(this, buffer, options, as_message)?;
-            buffer.push("\n```\n");
+            buffer.push("\n");
+            if !closing_fence.is_empty() {
+                buffer.push(closing_fence);
+                buffer.push("\n");
+            }
 
             Ok(())
         }
@@ -1339,8 +1544,12 @@ where
         where
             S: UnicodeString,
         {
-            buffer.push("> ");
-            fmt_children(this, buffer, options, as_message)?;
+            let mut child_buffer = S::default();
+            fmt_children(this, &mut child_buffer, options, as_message)?;
+
+            // Every line of the quote, including the ones of nested
+            // blocks, needs its own marker.
+            push_prefixed_lines(buffer, &child_buffer.to_string(), "> ", "> ");
             buffer.push("\n");
 
             Ok(())
@@ -1356,10 +1565,75 @@ where
         where
             S: UnicodeString,
         {
+            if let Some(MarkdownSyntax::Heading { prefix }) =
+                this.markdown_syntax()
+            {
+                buffer.push(prefix.as_str());
+            }
             fmt_children(this, buffer, options, as_message)?;
 
             Ok(())
         }
+
+        /// The delimiters the node was imported with, or the given ones.
+        #[inline(always)]
+        fn delimiters<'a, S>(
+            this: &'a ContainerNode<S>,
+            open: &'a str,
+            close: &'a str,
+        ) -> (&'a str, &'a str)
+        where
+            S: UnicodeString,
+        {
+            match this.markdown_syntax() {
+                Some(MarkdownSyntax::Delimiters { open, close }) => {
+                    (open.as_str(), close.as_str())
+                }
+                _ => (open, close),
+            }
+        }
+
+        /// Whether a link's text is its URL, give or take the scheme
+        /// implied by an email address or a `www.` domain.
+        #[inline(always)]
+        fn is_url_text(text: &str, url: &str) -> bool {
+            text == url
+                || url.strip_prefix("mailto:") == Some(text)
+                || url.strip_prefix("http://") == Some(text)
+        }
+
+        /// Push the lines of `text` to the buffer, ignoring trailing line
+        /// breaks. The first line is prefixed with `first_prefix`, the
+        /// others with `prefix`. Empty lines don't get any trailing
+        /// whitespace.
+        #[inline(always)]
+        fn push_prefixed_lines<S>(
+            buffer: &mut S,
+            text: &str,
+            first_prefix: &str,
+            prefix: &str,
+        ) where
+            S: UnicodeString,
+        {
+            let empty_line_prefix = prefix.trim_end();
+            // Offset markers don't make a line any less empty.
+            let is_empty = is_only_markers;
+            for (nth, line) in
+                text.trim_end_matches('\n').split('\n').enumerate()
+            {
+                if nth == 0 {
+                    buffer.push(first_prefix);
+                } else {
+                    buffer.push('\n');
+                    buffer.push(if is_empty(line) {
+                        empty_line_prefix
+                    } else {
+                        prefix
+                    });
+                }
+                buffer.push(line);
+            }
+        }
     }
 }
 
@@ -1541,6 +1815,27 @@ mod test {
         c1.push(&mut c2);
     }
 
+    #[test]
+    fn setting_an_unchanged_handle_keeps_the_children_shared() {
+        let mut container =
+            format_container_with_handle(InlineFormatType::Bold, &[0, 0]);
+        container.append_child(text_node("abc"));
+        let copy = container.clone();
+        container.set_handle(DomHandle::from_raw(vec![0, 0]));
+        assert!(std::ptr::eq(container.children(), copy.children()));
+    }
+
+    #[test]
+    fn setting_a_new_handle_moves_the_children() {
+        let mut container =
+            format_container_with_handle(InlineFormatType::Bold, &[0, 0]);
+        container.append_child(text_node("abc"));
+        let copy = container.clone();
+        container.set_handle(DomHandle::from_raw(vec![1]));
+        assert_eq!(container.children()[0].handle().raw(), &vec![1, 0]);
+        assert_eq!(copy.children()[0].handle().raw(), &vec![0, 0, 0]);
+    }
+
     #[test]
     fn slicing_container_before() {
         let mut bold = create_container_with_nested_children();
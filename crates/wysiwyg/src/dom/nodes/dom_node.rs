@@ -24,7 +24,7 @@ use crate::dom::to_raw_text::ToRawText;
 use crate::dom::to_tree::ToTree;
 use crate::dom::unicode_string::UnicodeStrExt;
 use crate::dom::{self, UnicodeString};
-use crate::{InlineFormatType, ListType};
+use crate::{ComposerError, InlineFormatType, ListType};
 
 use super::mention_node::UriParseError;
 use super::MentionNode;
@@ -72,11 +72,9 @@ where
     pub fn new_formatting_from_tag(
         format: S,
         children: Vec<DomNode<S>>,
-    ) -> DomNode<S> {
-        DomNode::Container(ContainerNode::new_formatting_from_tag(
-            format.clone(),
-            children,
-        ))
+    ) -> Result<DomNode<S>, ComposerError> {
+        ContainerNode::new_formatting_from_tag(format, children)
+            .map(DomNode::Container)
     }
 
     pub fn new_list(
@@ -461,12 +459,12 @@ impl<S> ToPlainText<S> for DomNode<S>
 where
     S: UnicodeString,
 {
-    fn to_plain_text(&self) -> S {
+    fn fmt_plain_text(&self, buffer: &mut S, track_offsets: bool) {
         match self {
-            DomNode::Container(n) => n.to_plain_text(),
-            DomNode::LineBreak(n) => n.to_plain_text(),
-            DomNode::Text(n) => n.to_plain_text(),
-            DomNode::Mention(n) => n.to_plain_text(),
+            DomNode::Container(n) => n.fmt_plain_text(buffer, track_offsets),
+            DomNode::LineBreak(n) => n.fmt_plain_text(buffer, track_offsets),
+            DomNode::Text(n) => n.fmt_plain_text(buffer, track_offsets),
+            DomNode::Mention(n) => n.fmt_plain_text(buffer, track_offsets),
         }
     }
 }
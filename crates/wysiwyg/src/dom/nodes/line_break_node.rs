@@ -14,13 +14,17 @@
 
 use crate::composer_model::example_format::SelectionWriter;
 use crate::dom::dom_handle::DomHandle;
+use crate::dom::offset_markers::push_marker;
 use crate::dom::to_html::{ToHtml, ToHtmlState};
-use crate::dom::to_markdown::{MarkdownError, MarkdownOptions, ToMarkdown};
+use crate::dom::to_markdown::{
+    MarkdownError, MarkdownOptions, MarkdownSyntax, ToMarkdown,
+};
 use crate::dom::to_plain_text::ToPlainText;
 use crate::dom::to_raw_text::ToRawText;
 use crate::dom::to_tree::ToTree;
 use crate::dom::unicode_string::{UnicodeStrExt, UnicodeStringExt};
 use crate::dom::UnicodeString;
+use crate::HardBreakStyle;
 use std::marker::PhantomData;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -30,6 +34,7 @@ where
 {
     _phantom_data: PhantomData<S>,
     handle: DomHandle,
+    markdown_syntax: Option<MarkdownSyntax>,
 }
 
 impl<S> Default for LineBreakNode<S>
@@ -44,6 +49,7 @@ where
         Self {
             _phantom_data: PhantomData {},
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 }
@@ -64,6 +70,14 @@ where
         self.handle.clone()
     }
 
+    pub(crate) fn markdown_syntax(&self) -> Option<&MarkdownSyntax> {
+        self.markdown_syntax.as_ref()
+    }
+
+    pub(crate) fn set_markdown_syntax(&mut self, syntax: MarkdownSyntax) {
+        self.markdown_syntax = Some(syntax);
+    }
+
     // A br tag is always treated as 1 character, so this always returns 1
     pub fn text_len(&self) -> usize {
         1
@@ -102,8 +116,11 @@ impl<S> ToPlainText<S> for LineBreakNode<S>
 where
     S: UnicodeString,
 {
-    fn to_plain_text(&self) -> S {
-        "\n".into()
+    fn fmt_plain_text(&self, buffer: &mut S, track_offsets: bool) {
+        if track_offsets {
+            push_marker(buffer, &self.handle);
+        }
+        buffer.push("\n");
     }
 }
 
@@ -130,9 +147,26 @@ where
         options: &MarkdownOptions,
         _as_message: bool,
     ) -> Result<(), MarkdownError<S>> {
+        // Trailing spaces can't be used on an otherwise empty line, which
+        // would be blank and end the paragraph.
+        let is_line_start = buffer
+            .pop_last()
+            .map(|last| {
+                buffer.push(last);
+                last == '\n'
+            })
+            .unwrap_or(true);
+        if options.contains(MarkdownOptions::TRACK_OFFSETS) {
+            push_marker(buffer, &self.handle);
+        }
         if options.contains(MarkdownOptions::IGNORE_LINE_BREAK) {
             // Replace the line break by a single space.
             buffer.push(' ');
+        } else if let Some(MarkdownSyntax::LineBreak { source }) =
+            &self.markdown_syntax
+        {
+            // Keep the break it was imported from.
+            buffer.push(source.as_str());
         } else {
             // A line break is a `\n` in Markdown. Two or more line breaks
             // usually generate a new block (i.e. a new paragraph). To
@@ -154,7 +188,13 @@ where
             // \
             // jkl
             // ```
-            buffer.push("\\\n");
+            //
+            match options.dialect().hard_break {
+                HardBreakStyle::TrailingSpaces if !is_line_start => {
+                    buffer.push("  \n")
+                }
+                _ => buffer.push("\\\n"),
+            }
         }
 
         Ok(())
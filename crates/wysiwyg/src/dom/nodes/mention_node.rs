@@ -15,6 +15,7 @@ use matrix_mentions::{Mention, MentionKind};
 
 use crate::composer_model::example_format::SelectionWriter;
 use crate::dom::dom_handle::DomHandle;
+use crate::dom::offset_markers::write_leaf;
 use crate::dom::to_html::{ToHtml, ToHtmlExt, ToHtmlState};
 use crate::dom::to_markdown::{MarkdownError, MarkdownOptions, ToMarkdown};
 use crate::dom::to_plain_text::ToPlainText;
@@ -126,6 +127,10 @@ where
     pub fn kind(&self) -> &MentionNodeKind {
         &self.kind
     }
+
+    pub fn attributes(&self) -> &Vec<(S, S)> {
+        &self.attributes
+    }
 }
 
 // TODO implment From trait to convert from MentionNode to DomNode to allow MentionNode.into() usage
@@ -225,8 +230,10 @@ impl<S> ToPlainText<S> for MentionNode<S>
 where
     S: UnicodeString,
 {
-    fn to_plain_text(&self) -> S {
-        self.display_text()
+    fn fmt_plain_text(&self, buffer: &mut S, track_offsets: bool) {
+        write_leaf(buffer, &self.handle, track_offsets, |buffer| {
+            buffer.push(self.display_text())
+        });
     }
 }
 
@@ -266,10 +273,13 @@ where
     fn fmt_markdown(
         &self,
         buffer: &mut S,
-        _: &MarkdownOptions,
+        options: &MarkdownOptions,
         as_message: bool,
     ) -> Result<(), MarkdownError<S>> {
-        fmt_mention(self, buffer, as_message)?;
+        let track_offsets = options.contains(MarkdownOptions::TRACK_OFFSETS);
+        write_leaf(buffer, &self.handle, track_offsets, |buffer| {
+            fmt_mention(self, buffer, as_message)
+        })?;
         return Ok(());
 
         #[inline(always)]
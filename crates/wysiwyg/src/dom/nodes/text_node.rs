@@ -15,18 +15,23 @@
 use crate::composer_model::delete_text::Direction;
 use crate::composer_model::example_format::SelectionWriter;
 use crate::dom::dom_handle::DomHandle;
+use crate::dom::offset_markers::write_leaf;
 use crate::dom::to_html::{ToHtml, ToHtmlState};
-use crate::dom::to_markdown::{MarkdownError, MarkdownOptions, ToMarkdown};
+use crate::dom::to_markdown::{
+    MarkdownError, MarkdownOptions, MarkdownSyntax, ToMarkdown,
+};
 use crate::dom::to_plain_text::ToPlainText;
 use crate::dom::to_raw_text::ToRawText;
 use crate::dom::to_tree::ToTree;
 use crate::dom::unicode_string::{UnicodeStr, UnicodeStrExt, UnicodeStringExt};
 use crate::dom::UnicodeString;
-use html_escape;
 use std::ops::Range;
 
+/// The characters escaped in edited text that was imported from markdown.
+const MARKDOWN_PUNCTUATION: &str = "\\`*_[]<>~&#";
+
 // categories of character for backspace/delete word
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum CharType {
     Whitespace,
     Punctuation,
@@ -40,6 +45,7 @@ where
 {
     data: S,
     handle: DomHandle,
+    markdown_syntax: Option<MarkdownSyntax>,
 }
 
 impl<S> TextNode<S>
@@ -54,6 +60,7 @@ where
         Self {
             data,
             handle: DomHandle::new_unset(),
+            markdown_syntax: None,
         }
     }
 
@@ -77,15 +84,43 @@ where
         (&self.data[new_start..new_end], offset_before, offset_after)
     }
 
+    pub(crate) fn set_markdown_syntax(&mut self, syntax: MarkdownSyntax) {
+        self.markdown_syntax = Some(syntax);
+    }
+
     pub fn set_data(&mut self, data: S) {
         self.data = data;
+        self.mark_edited();
+    }
+
+    /// Stop writing the text as it was imported, as it no longer matches
+    /// the markdown it came from.
+    fn mark_edited(&mut self) {
+        if matches!(self.markdown_syntax, Some(MarkdownSyntax::Text { .. })) {
+            self.markdown_syntax = Some(MarkdownSyntax::EditedText);
+        }
+    }
+
+    /// A new text node holding part of this one's text.
+    fn with_part(&self, data: S) -> TextNode<S> {
+        let mut node = TextNode::from(data);
+        if self.markdown_syntax.is_some() {
+            node.markdown_syntax = Some(MarkdownSyntax::EditedText);
+        }
+        node
     }
 
     pub fn replace_range(&mut self, data: S, start: usize, end: usize) {
+        if start == end {
+            // Typing doesn't need to copy the rest of the text
+            self.data.insert(start, &data);
+            self.mark_edited();
+            return;
+        }
         let mut new_data = self.data[..start].to_owned();
         new_data.push(data);
         new_data.push(self.data[end..].to_owned());
-        self.data = new_data;
+        self.set_data(new_data);
     }
 
     pub fn handle(&self) -> DomHandle {
@@ -110,6 +145,7 @@ where
     pub fn remove_trailing_line_break(&mut self) -> bool {
         if self.data.chars().last() == Some('\n') {
             self.data.pop_last();
+            self.mark_edited();
             true
         } else {
             false
@@ -117,19 +153,43 @@ where
     }
 
     pub fn clone_with_range(&self, range: Range<usize>) -> TextNode<S> {
-        TextNode::from(self.data[range].to_owned())
+        self.with_part(self.data[range].to_owned())
     }
 
-    /// This gets the character at the cursor offset, considering the
+    /// This gets the grapheme cluster at the cursor offset, considering the
     /// direction of travel
+    fn grapheme_at_offset(
+        &self,
+        offset: usize,
+        direction: &Direction,
+    ) -> Option<<S::Str as UnicodeStr>::StringType> {
+        let (prev, next) = self.data().find_graphemes_at(offset);
+        match direction {
+            Direction::Forwards => next,
+            Direction::Backwards => prev,
+        }
+    }
+
+    /// This gets the length in code units of the grapheme cluster at the
+    /// cursor offset, considering the direction of travel
+    pub fn grapheme_len_at_offset(
+        &self,
+        offset: usize,
+        direction: &Direction,
+    ) -> Option<usize> {
+        self.grapheme_at_offset(offset, direction)
+            .map(|grapheme| grapheme.len())
+    }
+
+    /// This gets the first character of the grapheme cluster at the cursor
+    /// offset, considering the direction of travel
     fn char_at_offset(
         &self,
         offset: usize,
         direction: &Direction,
     ) -> Option<char> {
-        self.data()
-            .chars()
-            .nth(direction.get_index_from_cursor(offset))
+        self.grapheme_at_offset(offset, direction)
+            .and_then(|grapheme| grapheme.chars().next())
     }
 
     /// This gets the character type at the cursor offset, considering the
@@ -179,7 +239,7 @@ where
         assert!(position <= self.data.len());
         let data_after = self.data[position..].to_owned();
         self.set_data(self.data[..position].to_owned());
-        TextNode::from(data_after)
+        self.with_part(data_after)
     }
 
     /// Slice this text node before given position.
@@ -189,7 +249,7 @@ where
         assert!(position <= self.data.len());
         let data_before = self.data[..position].to_owned();
         self.set_data(self.data[position..].to_owned());
-        TextNode::from(data_before)
+        self.with_part(data_before)
     }
 }
 
@@ -219,32 +279,11 @@ where
         _as_message: bool,
     ) {
         let cur_pos = buf.len();
-        let string = self.data.to_string();
-
-        let mut escaped = html_escape::encode_text(&string).to_string();
-        // Replace all pairs of spaces with non-breaking ones. Transforms
-        // `a     b` to `a\u{A0}\u{A0}\u{A0}\u{A0} b`, which will render
-        // exactly as five spaces like in the input.
-        if !state.is_inside_code_block {
-            escaped = escaped.replace("  ", "\u{A0}\u{A0}");
-
-            if state.next_sibling.is_none()
-                && escaped.chars().next_back().map_or(false, |c| c == ' ')
-            {
-                // If this is the last node and it ends in a space, replace that
-                // space with a non-breaking one.
-                escaped.replace_range(escaped.len() - 1.., "\u{A0}");
-            }
-
-            if state.prev_sibling.is_none()
-                && escaped.chars().next().map_or(false, |c| c == ' ')
-            {
-                // If this is the first node and it starts with a space, replace that
-                // space with a non-breaking one.
-                escaped.replace_range(..1, "\u{A0}");
-            }
+        if !self.needs_escaping(state) {
+            buf.push(&*self.data);
+        } else {
+            self.fmt_escaped_html(buf, state);
         }
-        buf.push(escaped.as_str());
 
         if let Some(selection_writer) = selection_writer {
             selection_writer.write_selection_text_node(buf, cur_pos, self);
@@ -252,6 +291,72 @@ where
     }
 }
 
+impl<S> TextNode<S>
+where
+    S: UnicodeString,
+{
+    /// Whether any of the text is changed by [Self::fmt_escaped_html].
+    fn needs_escaping(&self, state: &ToHtmlState) -> bool {
+        let mut prev = None;
+        let mut is_first = true;
+        for c in self.data.chars() {
+            let is_special = match c {
+                '&' | '<' | '>' => true,
+                ' ' => {
+                    !state.is_inside_code_block
+                        && (prev == Some(' ')
+                            || (is_first && state.prev_sibling.is_none()))
+                }
+                _ => false,
+            };
+            if is_special {
+                return true;
+            }
+            prev = Some(c);
+            is_first = false;
+        }
+        prev == Some(' ')
+            && !state.is_inside_code_block
+            && state.next_sibling.is_none()
+    }
+
+    fn fmt_escaped_html(&self, buf: &mut S, state: &ToHtmlState) {
+        // Escaped as we go rather than through intermediate strings, as this
+        // runs over the whole document on every update
+        let mut chars = self.data.chars().peekable();
+        let mut is_first = true;
+        while let Some(c) = chars.next() {
+            match c {
+                ' ' if !state.is_inside_code_block => {
+                    if chars.peek() == Some(&' ') {
+                        // Replace all pairs of spaces with non-breaking ones.
+                        // Transforms `a     b` to
+                        // `a\u{A0}\u{A0}\u{A0}\u{A0} b`, which will render
+                        // exactly as five spaces like in the input.
+                        chars.next();
+                        buf.push("\u{A0}\u{A0}");
+                    } else if (is_first && state.prev_sibling.is_none())
+                        || (chars.peek().is_none()
+                            && state.next_sibling.is_none())
+                    {
+                        // If this is the first or last node and it starts or
+                        // ends with a space, replace that space with a
+                        // non-breaking one.
+                        buf.push('\u{A0}');
+                    } else {
+                        buf.push(' ');
+                    }
+                }
+                '&' => buf.push("&amp;"),
+                '<' => buf.push("&lt;"),
+                '>' => buf.push("&gt;"),
+                c => buf.push(c),
+            }
+            is_first = false;
+        }
+    }
+}
+
 impl<S> ToRawText<S> for TextNode<S>
 where
     S: UnicodeString,
@@ -265,8 +370,10 @@ impl<S> ToPlainText<S> for TextNode<S>
 where
     S: UnicodeString,
 {
-    fn to_plain_text(&self) -> S {
-        self.data.clone()
+    fn fmt_plain_text(&self, buffer: &mut S, track_offsets: bool) {
+        write_leaf(buffer, &self.handle, track_offsets, |buffer| {
+            buffer.push(self.data.clone())
+        });
     }
 }
 
@@ -293,10 +400,29 @@ where
     fn fmt_markdown(
         &self,
         buffer: &mut S,
-        _options: &MarkdownOptions,
+        options: &MarkdownOptions,
         _as_message: bool,
     ) -> Result<(), MarkdownError<S>> {
-        buffer.push(self.data.to_owned());
+        let track_offsets = options.contains(MarkdownOptions::TRACK_OFFSETS);
+        write_leaf(buffer, &self.handle, track_offsets, |buffer| {
+            // Keep the escapes and entities the text was imported with.
+            // Once it has been edited, they are lost, so escape it all
+            // instead.
+            match &self.markdown_syntax {
+                Some(MarkdownSyntax::Text { source }) => {
+                    buffer.push(source.as_str())
+                }
+                Some(MarkdownSyntax::EditedText) => {
+                    for c in self.data.chars() {
+                        if MARKDOWN_PUNCTUATION.contains(c) {
+                            buffer.push('\\');
+                        }
+                        buffer.push(c);
+                    }
+                }
+                _ => buffer.push(self.data.to_owned()),
+            }
+        });
 
         Ok(())
     }
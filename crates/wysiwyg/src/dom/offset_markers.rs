@@ -0,0 +1,206 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Markers written into markdown or plain text to find where each leaf of
+//! the DOM ended up, after containers have added their own syntax and
+//! indentation around it.
+//!
+//! A marker is made of Unicode noncharacters, which are reserved for
+//! internal use: a start char, the indices of the leaf's handle written as
+//! digits, and an end char. As nothing stops them from being typed or
+//! pasted, any of these chars in the text of a leaf are escaped, and
+//! anything that doesn't form a valid marker is kept as text.
+
+use crate::dom::unicode_string::{UnicodeStr, UnicodeStrExt, UnicodeStringExt};
+use crate::{DomHandle, UnicodeString};
+
+const MARKER_START: char = '\u{FDD0}';
+const MARKER_END: char = '\u{FDD1}';
+const INDEX_SEPARATOR: char = '\u{FDD2}';
+const ESCAPE: char = '\u{FDD3}';
+const FIRST_DIGIT: char = '\u{FDE0}';
+const LAST_DIGIT: char = '\u{FDE9}';
+
+/// Whether `c` is part of a marker.
+pub(crate) fn is_marker_char(c: char) -> bool {
+    ('\u{FDD0}'..='\u{FDEF}').contains(&c)
+}
+
+/// Whether `text` is made of markers only, without any escaped text.
+pub(crate) fn is_only_markers(text: &str) -> bool {
+    text.chars().all(|c| is_marker_char(c) && c != ESCAPE)
+}
+
+/// Write a marker for the leaf at `handle` into `buffer`.
+pub(crate) fn push_marker<S>(buffer: &mut S, handle: &DomHandle)
+where
+    S: UnicodeString,
+{
+    buffer.push(MARKER_START);
+    for index in handle.raw() {
+        for digit in index.to_string().chars() {
+            let digit = digit.to_digit(10).unwrap();
+            buffer.push(char::from_u32(FIRST_DIGIT as u32 + digit).unwrap());
+        }
+        buffer.push(INDEX_SEPARATOR);
+    }
+    buffer.push(MARKER_END);
+}
+
+/// Write a leaf with `write`. If `track_offsets` is set, it's preceded by a
+/// marker for `handle`, and any marker chars it writes are escaped.
+pub(crate) fn write_leaf<S, T>(
+    buffer: &mut S,
+    handle: &DomHandle,
+    track_offsets: bool,
+    write: impl FnOnce(&mut S) -> T,
+) -> T
+where
+    S: UnicodeString,
+{
+    if !track_offsets {
+        return write(buffer);
+    }
+    push_marker(buffer, handle);
+    let mut text = S::default();
+    let result = write(&mut text);
+    if text.chars().any(is_marker_char) {
+        for c in text.chars() {
+            if is_marker_char(c) {
+                buffer.push(ESCAPE);
+            }
+            buffer.push(c);
+        }
+    } else {
+        buffer.push(text);
+    }
+    result
+}
+
+/// Remove the markers from `text`, returning the text without them and the
+/// offset, in code units, at which each marked leaf starts.
+pub(crate) fn take_markers<S>(text: &S) -> (S, Vec<(DomHandle, usize)>)
+where
+    S: UnicodeString,
+{
+    let chars: Vec<char> = text.chars().collect();
+    let mut clean = S::default();
+    let mut markers = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        match c {
+            ESCAPE => {
+                if let Some(&c) = chars.get(i) {
+                    clean.push(c);
+                    i += 1;
+                }
+            }
+            MARKER_START => match read_marker(&chars[i..]) {
+                Some((path, len)) => {
+                    markers.push((DomHandle::from_raw(path), clean.len()));
+                    i += len;
+                }
+                None => clean.push(c),
+            },
+            c => clean.push(c),
+        }
+    }
+    (clean, markers)
+}
+
+/// Read the rest of a marker after its start char, returning the indices
+/// of its handle and the number of chars read. Returns None if the chars
+/// don't make a valid marker.
+fn read_marker(chars: &[char]) -> Option<(Vec<usize>, usize)> {
+    let mut path = Vec::new();
+    let mut index: Option<usize> = None;
+    for (i, c) in chars.iter().enumerate() {
+        match *c {
+            MARKER_END if index.is_none() => return Some((path, i + 1)),
+            INDEX_SEPARATOR => path.push(index.take()?),
+            c @ FIRST_DIGIT..=LAST_DIGIT => {
+                let digit = c as usize - FIRST_DIGIT as usize;
+                index = Some(
+                    index
+                        .unwrap_or_default()
+                        .checked_mul(10)?
+                        .checked_add(digit)?,
+                );
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use widestring::Utf16String;
+
+    use super::*;
+
+    #[test]
+    fn markers_are_taken_out_with_their_offsets() {
+        let mut text = Utf16String::from("a😀");
+        push_marker(&mut text, &DomHandle::from_raw(vec![0, 12]));
+        text.push('b');
+        push_marker(&mut text, &DomHandle::from_raw(vec![3]));
+        let (clean, markers) = take_markers(&text);
+        assert_eq!(clean, "a😀b");
+        assert_eq!(
+            markers,
+            vec![
+                (DomHandle::from_raw(vec![0, 12]), 3),
+                (DomHandle::from_raw(vec![3]), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn marker_chars_are_noncharacters() {
+        let mut text = String::new();
+        push_marker(&mut text, &DomHandle::from_raw(vec![9, 10]));
+        assert!(text.chars().all(is_marker_char));
+        assert!(!is_marker_char('\u{FFFC}'));
+    }
+
+    #[test]
+    fn marker_chars_written_by_a_leaf_are_kept_as_text() {
+        let mut text = String::new();
+        let handle = DomHandle::from_raw(vec![1]);
+        write_leaf(&mut text, &handle, true, |s: &mut String| {
+            s.push_str("a\u{FDD0}\u{FDE1}\u{FDD1}b")
+        });
+        let (clean, markers) = take_markers(&text);
+        assert_eq!(clean, "a\u{FDD0}\u{FDE1}\u{FDD1}b");
+        assert_eq!(markers, vec![(handle, 0)]);
+    }
+
+    #[test]
+    fn malformed_markers_are_kept_as_text() {
+        let overflowing: String = std::iter::once(MARKER_START)
+            .chain(std::iter::repeat(LAST_DIGIT).take(40))
+            .chain([INDEX_SEPARATOR, MARKER_END])
+            .collect();
+        for text in ["a\u{FDD0}b", "\u{FDD0}\u{FDEF}\u{FDD1}", "\u{FDD0}"] {
+            assert_eq!(
+                take_markers(&String::from(text)),
+                (text.into(), vec![])
+            );
+        }
+        assert_eq!(take_markers(&overflowing), (overflowing.clone(), vec![]));
+    }
+}
@@ -1,4 +1,5 @@
-pub mod markdown_html_parser;
+pub mod markdown_dom_parser;
 
-#[allow(unused_imports)]
-pub use markdown_html_parser::MarkdownHTMLParser;
+pub use markdown_dom_parser::{
+    parse_markdown, parse_markdown_with_dialect, ParsedMarkdown, SourceSpan,
+};
@@ -0,0 +1,1107 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Build a [Dom] from markdown by consuming the events of the
+//! `pulldown_cmark` parser directly.
+//!
+//! Each markdown line becomes a paragraph, and a blank line between two
+//! paragraphs becomes an empty paragraph, matching how [crate::ToMarkdown]
+//! writes them. Raw HTML, e.g. mention links, is parsed with the HTML
+//! parser and inserted as is.
+//!
+//! The [MarkdownDialect] decides which syntax extensions are recognised and
+//! what soft line breaks become.
+//!
+//! The syntax each node was written with, e.g. `_` or `*` for emphasis, is
+//! recorded on the node so that [crate::ToMarkdown] can write it back the
+//! same way.
+
+use std::ops::Range;
+
+use matrix_mentions::Mention;
+use once_cell::sync::Lazy;
+use pulldown_cmark::{
+    html::push_html, CodeBlockKind, Event, LinkType, Options, Parser, Tag,
+    TagEnd,
+};
+use regex::Regex;
+
+use crate::dom::dom_creation_error::MarkdownParseError;
+use crate::dom::nodes::dom_node::DomNodeKind;
+use crate::dom::nodes::{
+    ContainerNode, ContainerNodeKind, DomNode, LineBreakNode,
+};
+use crate::dom::parser::parse::{
+    convert_text, parse_fragment, post_process_blocks,
+};
+use crate::dom::to_markdown::MarkdownSyntax;
+use crate::dom::unicode_string::UnicodeStr;
+use crate::dom::Dom;
+use crate::{
+    DomHandle, InlineFormatType, ListType, MarkdownDialect, SoftBreakPolicy,
+    UnicodeString,
+};
+
+/// A [Dom] built from markdown, with the origin of its content in the
+/// markdown source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedMarkdown<S>
+where
+    S: UnicodeString,
+{
+    pub dom: Dom<S>,
+    /// One span for each text and mention node, in document order.
+    pub source_map: Vec<SourceSpan>,
+}
+
+/// The text of a node in the [Dom] and the markdown it was built from. Both
+/// ranges are measured in code units.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub location: Range<usize>,
+    pub source: Range<usize>,
+}
+
+impl<S> ParsedMarkdown<S>
+where
+    S: UnicodeString,
+{
+    /// The location in the [Dom] matching an offset in the markdown source.
+    /// Offsets inside markdown syntax map to the start of the following
+    /// node, or the end of the text after the last node.
+    pub fn location_for_source_offset(&self, offset: usize) -> usize {
+        for span in &self.source_map {
+            if offset < span.source.start {
+                return span.location.start;
+            }
+            if offset <= span.source.end {
+                let len = span.location.end - span.location.start;
+                return span.location.start
+                    + (offset - span.source.start).min(len);
+            }
+        }
+        self.dom.text_len()
+    }
+
+    /// The offset in the markdown source matching a location in the [Dom].
+    pub fn source_offset_for_location(&self, location: usize) -> usize {
+        let mut offset = 0;
+        for span in &self.source_map {
+            if location < span.location.start {
+                return span.source.start;
+            }
+            let len = span.source.end - span.source.start;
+            offset =
+                span.source.start + (location - span.location.start).min(len);
+            if location <= span.location.end {
+                return offset;
+            }
+        }
+        offset
+    }
+}
+
+/// GFM extended autolinks, without the trailing punctuation.
+static AUTOLINK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:https?://|www\.)[^\s<]*[^\s<?!.,:*_~)'\x22]").unwrap()
+});
+
+/// Parse markdown into a [Dom], using the default [MarkdownDialect].
+pub fn parse_markdown<S>(
+    markdown: &S,
+) -> Result<ParsedMarkdown<S>, MarkdownParseError>
+where
+    S: UnicodeString,
+{
+    parse_markdown_with_dialect(markdown, &MarkdownDialect::default())
+}
+
+/// Parse markdown written in the given dialect into a [Dom].
+pub fn parse_markdown_with_dialect<S>(
+    markdown: &S,
+    dialect: &MarkdownDialect,
+) -> Result<ParsedMarkdown<S>, MarkdownParseError>
+where
+    S: UnicodeString,
+{
+    let source = markdown.to_string();
+    let mut options = Options::empty();
+    if dialect.is_gfm() {
+        options.insert(Options::ENABLE_STRIKETHROUGH);
+    }
+    if dialect.has_gfm_extensions() {
+        options.insert(Options::ENABLE_TABLES);
+        options.insert(Options::ENABLE_TASKLISTS);
+    }
+    let events: Vec<_> = Parser::new_ext(&source, options)
+        .into_offset_iter()
+        .collect();
+
+    let mut builder = MarkdownDomBuilder::new(&source, dialect);
+    for (i, (event, range)) in events.iter().enumerate() {
+        let is_last_in_parent =
+            matches!(events.get(i + 1), None | Some((Event::End(_), _)));
+        builder.handle(event.clone(), range.clone(), is_last_in_parent);
+    }
+    let (dom, leaf_sources) = builder.finish();
+
+    let code_units = code_unit_offsets(markdown, &source);
+    let source_map = dom
+        .find_range(0, usize::MAX)
+        .leaves()
+        .zip(leaf_sources)
+        .map(|(leaf, source)| SourceSpan {
+            location: leaf.position..leaf.position + leaf.length,
+            source: code_units[source.start]..code_units[source.end],
+        })
+        .collect();
+    Ok(ParsedMarkdown { dom, source_map })
+}
+
+/// The offset in code units of each byte of `source`, the UTF-8 version of
+/// `markdown`.
+fn code_unit_offsets<S: UnicodeString>(
+    markdown: &S,
+    source: &str,
+) -> Vec<usize> {
+    let mut offsets = Vec::with_capacity(source.len() + 1);
+    let mut offset = 0;
+    for c in source.chars() {
+        for _ in 0..c.len_utf8() {
+            offsets.push(offset);
+        }
+        offset += markdown.char_len(&c);
+    }
+    offsets.push(offset);
+    offsets
+}
+
+enum Frame<S>
+where
+    S: UnicodeString,
+{
+    Container(ContainerNode<S>),
+    /// Content we can't represent, e.g. an image. Anything inside it is
+    /// dropped.
+    Ignored,
+    /// Structure we don't keep, e.g. a table. Its content goes to the
+    /// enclosing container.
+    Transparent,
+}
+
+/// Raw HTML collected until its tags are balanced.
+struct HtmlFragment {
+    html: String,
+    depth: usize,
+    source: Range<usize>,
+    is_block: bool,
+}
+
+impl HtmlFragment {
+    fn push_tag(&mut self, html: &str) {
+        self.html.push_str(html);
+        if self.is_block {
+            return;
+        }
+        let tag = html.trim();
+        let name: String = tag
+            .trim_start_matches(['<', '/'])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        if tag.starts_with("</") {
+            self.depth = self.depth.saturating_sub(1);
+        } else if !(tag.starts_with("<!")
+            || tag.ends_with("/>")
+            || is_void_element(&name))
+        {
+            self.depth += 1;
+        }
+    }
+}
+
+fn is_void_element(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "br" | "hr" | "img" | "input" | "wbr"
+    )
+}
+
+fn is_inline_tag(tag: &TagEnd) -> bool {
+    matches!(
+        tag,
+        TagEnd::Emphasis
+            | TagEnd::Strong
+            | TagEnd::Strikethrough
+            | TagEnd::Link
+    )
+}
+
+struct MarkdownDomBuilder<'a, S>
+where
+    S: UnicodeString,
+{
+    markdown: &'a str,
+    /// Where the source of the previous event ended, ignoring the start of
+    /// containers.
+    last_end: usize,
+    /// Open containers, innermost last. The document is always first.
+    stack: Vec<Frame<S>>,
+    /// Where each text and mention node came from, in document order.
+    leaf_sources: Vec<Range<usize>>,
+    html: Option<HtmlFragment>,
+    soft_break: SoftBreakPolicy,
+    /// Matches bare URLs, if they should become links.
+    autolink: Option<&'static Regex>,
+    /// The number of cells seen in the current table row.
+    table_cells: usize,
+}
+
+impl<'a, S> MarkdownDomBuilder<'a, S>
+where
+    S: UnicodeString,
+{
+    fn new(markdown: &'a str, dialect: &MarkdownDialect) -> Self {
+        let autolink = dialect.has_gfm_extensions().then(|| &*AUTOLINK);
+        let mut builder = Self {
+            markdown,
+            last_end: 0,
+            stack: Vec::new(),
+            leaf_sources: Vec::new(),
+            html: None,
+            soft_break: dialect.soft_break,
+            autolink,
+            table_cells: 0,
+        };
+        builder.open(ContainerNode::default());
+        builder
+    }
+
+    fn handle(
+        &mut self,
+        event: Event,
+        source: Range<usize>,
+        is_last_in_parent: bool,
+    ) {
+        let end = match &event {
+            Event::Start(Tag::Link { .. }) => Some(source.start + 1),
+            Event::Start(_) => None,
+            _ => Some(source.end),
+        };
+        self.handle_event(event, source, is_last_in_parent);
+        if let Some(end) = end {
+            self.last_end = end;
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        event: Event,
+        source: Range<usize>,
+        is_last_in_parent: bool,
+    ) {
+        if self.collect_html(&event, &source) {
+            return;
+        }
+        match event {
+            Event::Start(tag) => self.start(tag, source),
+            Event::End(tag) => self.end(tag, source),
+            Event::Text(text) => {
+                // Include the backslash of a leading escape.
+                let mut source = source;
+                if source.start > 0
+                    && self.markdown[..source.start].ends_with('\\')
+                    && text.starts_with(|c: char| c.is_ascii_punctuation())
+                {
+                    source.start -= 1;
+                }
+                self.push_text(&text, source, is_last_in_parent)
+            }
+            Event::Code(code) => {
+                let mut container = ContainerNode::new_formatting(
+                    InlineFormatType::InlineCode,
+                    Vec::new(),
+                );
+                if let Some(syntax) =
+                    code_span_delimiters(&self.markdown[source.clone()], &code)
+                {
+                    container.set_markdown_syntax(syntax);
+                }
+                self.open(container);
+                self.push_text(&code, source, true);
+                self.close();
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let mut fragment = HtmlFragment {
+                    html: String::new(),
+                    depth: 0,
+                    source,
+                    is_block: false,
+                };
+                fragment.push_tag(&html);
+                if fragment.depth == 0 {
+                    self.push_html(fragment);
+                } else {
+                    self.html = Some(fragment);
+                }
+            }
+            Event::SoftBreak if self.soft_break == SoftBreakPolicy::Space => {
+                self.push_node(DomNode::new_text(" ".into()), Some(source))
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                let mut line_break = LineBreakNode::default();
+                line_break.set_markdown_syntax(MarkdownSyntax::LineBreak {
+                    source: self.markdown[source].to_owned(),
+                });
+                self.push_node(DomNode::LineBreak(line_break), None)
+            }
+            Event::TaskListMarker(checked) => {
+                let marker = if checked { "[x] " } else { "[ ] " };
+                self.push_node(DomNode::new_text(marker.into()), Some(source))
+            }
+            // Rules, footnotes and maths aren't supported.
+            _ => {}
+        }
+    }
+
+    /// Add the event to the HTML being collected, if any and if the event
+    /// can be part of it. Returns false if the event must be handled
+    /// separately.
+    fn collect_html(&mut self, event: &Event, source: &Range<usize>) -> bool {
+        let Some(fragment) = self.html.as_mut() else {
+            return false;
+        };
+        let is_inline = match event {
+            Event::Html(html) | Event::InlineHtml(html) => {
+                if fragment.source.is_empty() {
+                    fragment.source.start = source.start;
+                }
+                fragment.push_tag(html);
+                fragment.source.end = source.end;
+                if fragment.depth == 0 && !fragment.is_block {
+                    let fragment = self.html.take().unwrap();
+                    self.push_html(fragment);
+                }
+                return true;
+            }
+            Event::End(TagEnd::HtmlBlock) => {
+                let fragment = self.html.take().unwrap();
+                self.push_html(fragment);
+                return true;
+            }
+            Event::Text(_)
+            | Event::Code(_)
+            | Event::SoftBreak
+            | Event::HardBreak => !fragment.is_block,
+            Event::Start(tag) => {
+                !fragment.is_block && is_inline_tag(&tag.to_end())
+            }
+            Event::End(tag) => !fragment.is_block && is_inline_tag(tag),
+            _ => false,
+        };
+        if !is_inline {
+            // The HTML isn't closed within its block, use what we have.
+            let fragment = self.html.take().unwrap();
+            self.push_html(fragment);
+            return false;
+        }
+        let event = match event {
+            Event::SoftBreak => Event::HardBreak,
+            event => event.clone(),
+        };
+        push_html(&mut fragment.html, std::iter::once(event));
+        fragment.source.end = source.end;
+        true
+    }
+
+    fn start(&mut self, tag: Tag, source: Range<usize>) {
+        if matches!(self.stack.last(), Some(Frame::Ignored)) {
+            self.stack.push(Frame::Ignored);
+            return;
+        }
+        let syntax = &self.markdown[source.clone()];
+        let container = match tag {
+            Tag::Paragraph | Tag::Heading { .. } => {
+                // A blank line between two paragraphs is an empty paragraph.
+                let follows_paragraph = self
+                    .current_container()
+                    .and_then(|c| c.children().last())
+                    .is_some_and(|n| n.kind() == DomNodeKind::Paragraph);
+                if follows_paragraph && self.follows_blank_line(source.start) {
+                    self.push_node(DomNode::new_paragraph(Vec::new()), None);
+                }
+                let mut paragraph = ContainerNode::new_paragraph(Vec::new());
+                if let Tag::Heading { .. } = tag {
+                    let prefix: String = syntax
+                        .chars()
+                        .take_while(|c| *c == '#')
+                        .chain(
+                            syntax
+                                .trim_start_matches('#')
+                                .chars()
+                                .take_while(|c| *c == ' ' || *c == '\t'),
+                        )
+                        .collect();
+                    if !prefix.is_empty() {
+                        paragraph.set_markdown_syntax(
+                            MarkdownSyntax::Heading { prefix },
+                        );
+                    }
+                }
+                paragraph
+            }
+            Tag::BlockQuote(_) => ContainerNode::new_quote(Vec::new()),
+            Tag::CodeBlock(kind) => {
+                let mut code_block = ContainerNode::new_code_block(Vec::new());
+                code_block.set_markdown_syntax(match kind {
+                    CodeBlockKind::Fenced(_) => {
+                        let line = syntax.trim_start_matches(' ');
+                        let line =
+                            &line[..line.find('\n').unwrap_or(line.len())];
+                        let fence_char = line.chars().next().unwrap_or('`');
+                        let info = line.trim_start_matches(fence_char);
+                        let fence = &line[..line.len() - info.len()];
+                        MarkdownSyntax::FencedCode {
+                            fence: fence.to_owned(),
+                            info: info.to_owned(),
+                            closing_fence: fence.to_owned(),
+                        }
+                    }
+                    CodeBlockKind::Indented => MarkdownSyntax::IndentedCode,
+                });
+                code_block
+            }
+            Tag::List(first_number) => {
+                let list_type = if first_number.is_some() {
+                    ListType::Ordered
+                } else {
+                    ListType::Unordered
+                };
+                let mut list = ContainerNode::new_list(list_type, Vec::new());
+                let marker =
+                    syntax.trim_start_matches(|c: char| c.is_ascii_digit());
+                let marker = marker.chars().next();
+                match (first_number, marker) {
+                    (Some(start), Some(delimiter @ ('.' | ')'))) => list
+                        .set_markdown_syntax(MarkdownSyntax::OrderedList {
+                            start,
+                            delimiter,
+                        }),
+                    (None, Some(marker @ ('*' | '-' | '+'))) => list
+                        .set_markdown_syntax(MarkdownSyntax::BulletList {
+                            marker,
+                        }),
+                    _ => {}
+                }
+                list
+            }
+            Tag::Item => ContainerNode::new_list_item(Vec::new()),
+            Tag::Emphasis => delimited(
+                InlineFormatType::Italic,
+                &syntax[..1.min(syntax.len())],
+            ),
+            Tag::Strong => delimited(
+                InlineFormatType::Bold,
+                &syntax[..2.min(syntax.len())],
+            ),
+            Tag::Strikethrough => {
+                let tildes =
+                    syntax.len() - syntax.trim_start_matches('~').len();
+                delimited(InlineFormatType::StrikeThrough, &syntax[..tildes])
+            }
+            Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            } => {
+                let mut link = ContainerNode::new_link(
+                    dest_url.as_ref().into(),
+                    Vec::new(),
+                    Vec::new(),
+                );
+                match link_type {
+                    // The suffix is known once the text has been read.
+                    LinkType::Inline => {
+                        link.set_markdown_syntax(MarkdownSyntax::InlineLink {
+                            url: dest_url.to_string(),
+                            suffix: String::new(),
+                        })
+                    }
+                    LinkType::Autolink | LinkType::Email => {
+                        link.set_markdown_syntax(MarkdownSyntax::Autolink)
+                    }
+                    _ => {}
+                }
+                link
+            }
+            // Each table row becomes a paragraph, with its cells separated
+            // by a pipe.
+            Tag::Table(_) => {
+                self.stack.push(Frame::Transparent);
+                return;
+            }
+            Tag::TableHead | Tag::TableRow => {
+                self.table_cells = 0;
+                ContainerNode::new_paragraph(Vec::new())
+            }
+            Tag::TableCell => {
+                if self.table_cells > 0 {
+                    let separator = DomNode::new_text(" | ".into());
+                    self.push_node(separator, Some(source.start..source.start));
+                }
+                self.table_cells += 1;
+                self.stack.push(Frame::Transparent);
+                return;
+            }
+            Tag::HtmlBlock => {
+                self.html = Some(HtmlFragment {
+                    html: String::new(),
+                    depth: 0,
+                    source: 0..0,
+                    is_block: true,
+                });
+                return;
+            }
+            _ => {
+                self.stack.push(Frame::Ignored);
+                return;
+            }
+        };
+        self.open(container);
+    }
+
+    fn end(&mut self, tag: TagEnd, source: Range<usize>) {
+        if matches!(
+            self.stack.last(),
+            Some(Frame::Ignored | Frame::Transparent)
+        ) {
+            self.stack.pop();
+            return;
+        }
+        match tag {
+            TagEnd::CodeBlock => {
+                let markdown = self.markdown;
+                if let Some(container) = self.current_container_mut() {
+                    // Drop the line break after the last line.
+                    let len = container.children().len();
+                    if len > 0 && container.children()[len - 1].is_line_break()
+                    {
+                        container.remove_child(len - 1);
+                    }
+                    // The closing fence may be longer than the opening one,
+                    // or missing at the end of the document.
+                    if let Some(MarkdownSyntax::FencedCode {
+                        fence,
+                        info,
+                        ..
+                    }) = container.markdown_syntax()
+                    {
+                        let source = markdown[source].trim_end_matches('\n');
+                        let last_line =
+                            &source[source.rfind('\n').map_or(0, |i| i + 1)..];
+                        let fence_char = fence.chars().next().unwrap_or('`');
+                        let closing = last_line.trim_start_matches(' ');
+                        let is_closed = source.contains('\n')
+                            && closing.len() >= fence.len()
+                            && closing.chars().all(|c| c == fence_char);
+                        let syntax = MarkdownSyntax::FencedCode {
+                            fence: fence.clone(),
+                            info: info.clone(),
+                            closing_fence: if is_closed {
+                                closing.to_owned()
+                            } else {
+                                String::new()
+                            },
+                        };
+                        container.set_markdown_syntax(syntax);
+                    }
+                }
+                self.close();
+            }
+            TagEnd::Link => {
+                let Some(Frame::Container(mut link)) = self.stack.pop() else {
+                    return;
+                };
+                if let Some(MarkdownSyntax::InlineLink { url, .. }) =
+                    link.markdown_syntax()
+                {
+                    let suffix = &self.markdown[self.last_end..source.end];
+                    if suffix.starts_with("](") {
+                        link.set_markdown_syntax(MarkdownSyntax::InlineLink {
+                            url: url.clone(),
+                            suffix: suffix.to_owned(),
+                        });
+                    }
+                }
+                let url = link.get_link_url().unwrap_or_default();
+                let node = match link.children().as_slice() {
+                    [DomNode::Text(text)]
+                        if Mention::is_valid_uri(&url.to_string()) =>
+                    {
+                        DomNode::new_mention(
+                            url,
+                            text.data().to_owned(),
+                            Vec::new(),
+                        )
+                        .map(DomNode::Mention)
+                        .unwrap_or(DomNode::Container(link))
+                    }
+                    _ => DomNode::Container(link),
+                };
+                if let Some(container) = self.current_container_mut() {
+                    container.append_child(node);
+                }
+            }
+            _ => self.close(),
+        }
+    }
+
+    fn open(&mut self, mut container: ContainerNode<S>) {
+        // Children can only be added to containers with a handle. It's
+        // updated when the container is added to its parent.
+        container.set_handle(DomHandle::root());
+        self.stack.push(Frame::Container(container));
+    }
+
+    fn close(&mut self) {
+        if let Some(Frame::Container(container)) = self.stack.pop() {
+            self.push_node(DomNode::Container(container), None);
+        }
+    }
+
+    fn current_container(&self) -> Option<&ContainerNode<S>> {
+        let frame = self
+            .stack
+            .iter()
+            .rev()
+            .find(|frame| !matches!(frame, Frame::Transparent));
+        match frame {
+            Some(Frame::Container(container)) => Some(container),
+            _ => None,
+        }
+    }
+
+    fn current_container_mut(&mut self) -> Option<&mut ContainerNode<S>> {
+        container_mut(&mut self.stack)
+    }
+
+    /// Whether the current text is inside a link or code, where URLs
+    /// aren't turned into links.
+    fn is_in_link_or_code(&self) -> bool {
+        self.is_in_code() || self.is_in_link()
+    }
+
+    fn is_in_link(&self) -> bool {
+        self.stack.iter().any(|frame| {
+            matches!(frame, Frame::Container(container)
+                if matches!(container.kind(), ContainerNodeKind::Link(_)))
+        })
+    }
+
+    /// Whether there's a blank line between the previous block and a block
+    /// starting at `start`.
+    fn follows_blank_line(&self, start: usize) -> bool {
+        let previous_line_ended =
+            self.markdown[..self.last_end].ends_with('\n');
+        let line_breaks =
+            self.markdown[self.last_end..start].matches('\n').count();
+        line_breaks + usize::from(previous_line_ended) >= 2
+    }
+
+    /// Whether the current text is code, which is written as is.
+    fn is_in_code(&self) -> bool {
+        self.stack.iter().any(|frame| match frame {
+            Frame::Container(container) => matches!(
+                container.kind(),
+                ContainerNodeKind::CodeBlock
+                    | ContainerNodeKind::Formatting(
+                        InlineFormatType::InlineCode
+                    )
+            ),
+            _ => false,
+        })
+    }
+
+    fn push_text(
+        &mut self,
+        text: &str,
+        source: Range<usize>,
+        is_last_in_parent: bool,
+    ) {
+        let urls: Vec<Range<usize>> = match &self.autolink {
+            Some(regex) if !self.is_in_link_or_code() => {
+                regex.find_iter(text).map(|m| m.range()).collect()
+            }
+            _ => Vec::new(),
+        };
+        if urls.is_empty() {
+            self.push_plain_text(text, source, is_last_in_parent);
+            return;
+        }
+        // Escapes and entities make the text shorter than its source, in
+        // which case the parts can only be mapped to the whole source.
+        let is_verbatim = text.len() == source.len();
+        let part_source = |part: &Range<usize>| {
+            if is_verbatim {
+                source.start + part.start..source.start + part.end
+            } else {
+                source.clone()
+            }
+        };
+        let mut end = 0;
+        for url in urls {
+            if url.start > end {
+                let part = end..url.start;
+                self.push_plain_text(
+                    &text[part.clone()],
+                    part_source(&part),
+                    false,
+                );
+            }
+            let url_text = &text[url.clone()];
+            let href = if url_text.starts_with("www.") {
+                format!("http://{url_text}")
+            } else {
+                url_text.to_owned()
+            };
+            let mut link = ContainerNode::new_link(
+                href.as_str().into(),
+                Vec::new(),
+                Vec::new(),
+            );
+            link.set_markdown_syntax(MarkdownSyntax::BareUrl);
+            self.open(link);
+            self.push_plain_text(url_text, part_source(&url), true);
+            self.close();
+            end = url.end;
+        }
+        if end < text.len() {
+            let part = end..text.len();
+            self.push_plain_text(
+                &text[part.clone()],
+                part_source(&part),
+                false,
+            );
+        }
+    }
+
+    fn push_plain_text(
+        &mut self,
+        text: &str,
+        source: Range<usize>,
+        is_last_in_parent: bool,
+    ) {
+        let Some(container) = self.current_container() else {
+            return;
+        };
+        let is_in_code_block =
+            matches!(container.kind(), ContainerNodeKind::CodeBlock);
+        let is_only_child =
+            container.children().is_empty() && is_last_in_parent;
+        let mut converted = ContainerNode::default();
+        converted.set_handle(DomHandle::root());
+        convert_text(
+            text,
+            &mut converted,
+            is_in_code_block,
+            self.is_in_link(),
+            is_only_child,
+        );
+        let children = converted.take_children();
+        let is_single_text = matches!(children.as_slice(), [DomNode::Text(_)]);
+        for node in children {
+            self.push_node(node, Some(source.clone()));
+        }
+        if is_single_text && !self.is_in_code() {
+            self.record_text_source();
+        }
+    }
+
+    /// Remember the source of the last text node if it isn't the text
+    /// itself, e.g. because of escapes or entities.
+    fn record_text_source(&mut self) {
+        let Some(range) = self.leaf_sources.last().cloned() else {
+            return;
+        };
+        let source = &self.markdown[range];
+        let Some(container) = container_mut(&mut self.stack) else {
+            return;
+        };
+        if let Some(DomNode::Text(text)) = container.last_child_mut() {
+            if *text.data() != *source {
+                text.set_markdown_syntax(MarkdownSyntax::Text {
+                    source: source.to_owned(),
+                });
+            }
+        }
+    }
+
+    /// Append a node to the current container. Text is merged into any text
+    /// node right before it.
+    fn push_node(&mut self, node: DomNode<S>, source: Option<Range<usize>>) {
+        let Some(container) = container_mut(&mut self.stack) else {
+            return;
+        };
+        if let (DomNode::Text(text), Some(DomNode::Text(previous))) =
+            (&node, container.last_child_mut())
+        {
+            let mut data = previous.data().to_owned();
+            data.extend(std::iter::once(text.data()));
+            previous.set_data(data);
+            if let (Some(last), Some(source)) =
+                (self.leaf_sources.last_mut(), source)
+            {
+                last.end = source.end;
+            }
+            return;
+        }
+        if let Some(source) = source {
+            if node.is_text_node() || node.is_mention_node() {
+                self.leaf_sources.push(source);
+            }
+        }
+        container.append_child(node);
+    }
+
+    fn push_html(&mut self, fragment: HtmlFragment) {
+        let Ok(mut dom) = parse_fragment::<S>(&fragment.html) else {
+            // Keep HTML we can't parse as text.
+            let text = DomNode::new_text(fragment.html.as_str().into());
+            self.push_node(text, Some(fragment.source));
+            return;
+        };
+        let is_in_link = self.is_in_link();
+        for child in dom.document_mut().remove_children() {
+            // Links can't hold links, so keep only the text of any in it
+            let children = if is_in_link {
+                without_links(child)
+            } else {
+                vec![child]
+            };
+            for child in children {
+                self.push_html_node(child, &fragment.source);
+            }
+        }
+    }
+
+    fn push_html_node(&mut self, node: DomNode<S>, source: &Range<usize>) {
+        if node.is_text_node() {
+            self.push_node(node, Some(source.clone()));
+            return;
+        }
+        let leaves = node
+            .iter_subtree()
+            .filter(|n| n.is_text_node() || n.is_mention_node())
+            .count();
+        self.leaf_sources
+            .extend(std::iter::repeat(source.clone()).take(leaves));
+        if let Some(container) = self.current_container_mut() {
+            container.append_child(node);
+        }
+    }
+
+    fn finish(mut self) -> (Dom<S>, Vec<Range<usize>>) {
+        if let Some(fragment) = self.html.take() {
+            self.push_html(fragment);
+        }
+        while self.stack.len() > 1 {
+            match self.stack.last() {
+                Some(Frame::Container(_)) => self.close(),
+                _ => {
+                    self.stack.pop();
+                }
+            }
+        }
+        let Some(Frame::Container(document)) = self.stack.pop() else {
+            unreachable!("The document is always a container");
+        };
+        let mut children = document.take_children();
+        // A single paragraph is just inline content, unless it's a heading.
+        if let [DomNode::Container(paragraph)] = children.as_slice() {
+            if matches!(paragraph.kind(), ContainerNodeKind::Paragraph)
+                && paragraph.markdown_syntax().is_none()
+            {
+                if let Some(DomNode::Container(paragraph)) = children.pop() {
+                    children = paragraph.take_children();
+                }
+            }
+        }
+        let mut dom = post_process_blocks(Dom::new(children));
+        dom.wrap_inline_nodes_into_paragraphs_if_needed(&DomHandle::root());
+        (dom, self.leaf_sources)
+    }
+}
+
+/// `node` with every link replaced by its children and every mention by
+/// its display text. Text that ends up next to other text is joined.
+fn without_links<S: UnicodeString>(node: DomNode<S>) -> Vec<DomNode<S>> {
+    match node {
+        DomNode::Mention(mention) => {
+            vec![DomNode::new_text(mention.display_text())]
+        }
+        DomNode::Container(container) => {
+            let is_link = container.is_link();
+            let mut children: Vec<DomNode<S>> = Vec::new();
+            for child in
+                container.children().iter().cloned().flat_map(without_links)
+            {
+                if let (DomNode::Text(text), Some(DomNode::Text(previous))) =
+                    (&child, children.last_mut())
+                {
+                    let mut data = previous.data().to_owned();
+                    data.extend(std::iter::once(text.data()));
+                    previous.set_data(data);
+                } else {
+                    children.push(child);
+                }
+            }
+            if is_link {
+                children
+            } else {
+                vec![DomNode::Container(
+                    container.clone_with_new_children(children),
+                )]
+            }
+        }
+        node => vec![node],
+    }
+}
+
+/// A formatting container written with the given delimiter on both sides.
+fn delimited<S: UnicodeString>(
+    format: InlineFormatType,
+    delimiter: &str,
+) -> ContainerNode<S> {
+    let mut container = ContainerNode::new_formatting(format, Vec::new());
+    container.set_markdown_syntax(MarkdownSyntax::Delimiters {
+        open: delimiter.to_owned(),
+        close: delimiter.to_owned(),
+    });
+    container
+}
+
+/// The delimiters of a code span, including the spaces stripped from its
+/// content. Returns None if they can't be told apart from the content.
+fn code_span_delimiters(source: &str, code: &str) -> Option<MarkdownSyntax> {
+    let backticks = source.len() - source.trim_start_matches('`').len();
+    let inner = source.get(backticks..source.len().checked_sub(backticks)?)?;
+    let padding = if inner == code {
+        ""
+    } else if inner.len() >= 2
+        && inner.starts_with(' ')
+        && inner.ends_with(' ')
+        && &inner[1..inner.len() - 1] == code
+    {
+        " "
+    } else {
+        return None;
+    };
+    let ticks = &source[..backticks];
+    Some(MarkdownSyntax::Delimiters {
+        open: format!("{ticks}{padding}"),
+        close: format!("{padding}{ticks}"),
+    })
+}
+
+/// The innermost container that isn't transparent, unless content is
+/// being ignored.
+fn container_mut<S: UnicodeString>(
+    stack: &mut [Frame<S>],
+) -> Option<&mut ContainerNode<S>> {
+    let frame = stack
+        .iter_mut()
+        .rev()
+        .find(|frame| !matches!(frame, Frame::Transparent));
+    match frame {
+        Some(Frame::Container(container)) => Some(container),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use widestring::Utf16String;
+
+    use super::{parse_markdown, parse_markdown_with_dialect, SourceSpan};
+    use crate::MarkdownDialect;
+
+    fn source_map(markdown: &str) -> Vec<SourceSpan> {
+        parse_markdown(&Utf16String::from_str(markdown))
+            .unwrap()
+            .source_map
+    }
+
+    fn span(
+        location: std::ops::Range<usize>,
+        source: std::ops::Range<usize>,
+    ) -> SourceSpan {
+        SourceSpan { location, source }
+    }
+
+    #[test]
+    fn source_map_skips_markdown_syntax() {
+        assert_eq!(
+            source_map("a **b** c"),
+            vec![span(0..2, 0..2), span(2..3, 4..5), span(3..5, 7..9)]
+        );
+    }
+
+    #[test]
+    fn source_map_covers_every_line() {
+        assert_eq!(
+            source_map("* ab\n* cd"),
+            vec![span(0..2, 2..4), span(3..5, 7..9)]
+        );
+    }
+
+    #[test]
+    fn source_map_is_in_code_units() {
+        assert_eq!(
+            source_map("😀 *a*"),
+            vec![span(0..3, 0..3), span(3..4, 4..5)]
+        );
+    }
+
+    #[test]
+    fn source_map_splits_autolinks() {
+        let source_map = parse_markdown_with_dialect(
+            &Utf16String::from_str("a www.x.org b"),
+            &MarkdownDialect::GFM,
+        )
+        .unwrap()
+        .source_map;
+        assert_eq!(
+            source_map,
+            vec![span(0..2, 0..2), span(2..11, 2..11), span(11..13, 11..13)]
+        );
+    }
+
+    #[test]
+    fn source_map_merges_escaped_text() {
+        assert_eq!(source_map("a\\*b"), vec![span(0..3, 0..4)]);
+    }
+
+    #[test]
+    fn locations_and_source_offsets_are_mapped_both_ways() {
+        let parsed =
+            parse_markdown(&Utf16String::from_str("a **bc** d")).unwrap();
+        assert_eq!(parsed.location_for_source_offset(0), 0);
+        // Inside the `**` before "bc"
+        assert_eq!(parsed.location_for_source_offset(3), 2);
+        assert_eq!(parsed.location_for_source_offset(5), 3);
+        assert_eq!(parsed.location_for_source_offset(10), 6);
+        assert_eq!(parsed.source_offset_for_location(3), 5);
+        assert_eq!(parsed.source_offset_for_location(6), 10);
+    }
+}
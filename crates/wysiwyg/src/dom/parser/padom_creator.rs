@@ -40,6 +40,28 @@ impl PaDomCreator {
     }
 }
 
+impl PaDomCreator {
+    /// Record HTML that can't be represented. Parsing carries on so that
+    /// html5ever stays happy, but it finishes with an error.
+    fn unsupported(&mut self, what: &str) {
+        self.state
+            .parse_errors
+            .push(format!("{what} not yet supported"));
+    }
+
+    /// A detached node standing in for HTML that can't be represented.
+    /// It never reaches the output because the parse fails anyway.
+    fn placeholder(&mut self) -> PaDomHandle {
+        self.state
+            .dom
+            .add_node(PaDomNode::Container(PaNodeContainer {
+                name: paqual_name("template"),
+                attrs: Vec::new(),
+                children: Vec::new(),
+            }))
+    }
+}
+
 impl Default for PaDomCreator {
     fn default() -> Self {
         Self {
@@ -82,7 +104,8 @@ impl TreeSink for PaDomCreator {
     }
 
     fn create_comment(&mut self, _text: StrTendril) -> Self::Handle {
-        todo!("Comments not yet supported")
+        self.unsupported("Comments");
+        self.placeholder()
     }
 
     fn create_pi(
@@ -90,7 +113,8 @@ impl TreeSink for PaDomCreator {
         _target: StrTendril,
         _data: StrTendril,
     ) -> Self::Handle {
-        todo!("create_pi not yet supported")
+        self.unsupported("create_pi");
+        self.placeholder()
     }
 
     fn append(
@@ -103,9 +127,7 @@ impl TreeSink for PaDomCreator {
                 match self.state.dom.get_mut_node(parent) {
                     PaDomNode::Container(p) => p.children.push(child),
                     PaDomNode::Document(p) => p.children.push(child),
-                    PaDomNode::Text(_) => {
-                        panic!("Appending node to text! {:?}", parent)
-                    }
+                    PaDomNode::Text(_) => self.unsupported("Appending to text"),
                 }
             }
             NodeOrText::AppendText(tendril) => {
@@ -145,7 +167,7 @@ impl TreeSink for PaDomCreator {
                         PaDomNode::Container(p) => p.children.push(new_handle),
                         PaDomNode::Document(p) => p.children.push(new_handle),
                         PaDomNode::Text(_) => {
-                            panic!("parent changed from container to text!")
+                            self.unsupported("Appending to text")
                         }
                     }
                 }
@@ -159,7 +181,7 @@ impl TreeSink for PaDomCreator {
         _prev_element: &Self::Handle,
         _child: NodeOrText<Self::Handle>,
     ) {
-        todo!("append_based_on_parent_node not yet supported")
+        self.unsupported("append_based_on_parent_node");
     }
 
     fn append_doctype_to_document(
@@ -168,14 +190,15 @@ impl TreeSink for PaDomCreator {
         _public_id: StrTendril,
         _system_id: StrTendril,
     ) {
-        todo!("append_doctype_to_document not yet supported")
+        self.unsupported("append_doctype_to_document");
     }
 
     fn get_template_contents(
         &mut self,
         _target: &Self::Handle,
     ) -> Self::Handle {
-        todo!("get_template_contents not yet supported")
+        self.unsupported("get_template_contents");
+        self.placeholder()
     }
 
     fn same_node(&self, x: &Self::Handle, y: &Self::Handle) -> bool {
@@ -191,7 +214,7 @@ impl TreeSink for PaDomCreator {
         _sibling: &Self::Handle,
         _new_node: NodeOrText<Self::Handle>,
     ) {
-        todo!("append_before_sibling not yet supported")
+        self.unsupported("append_before_sibling");
     }
 
     fn add_attrs_if_missing(
@@ -217,12 +240,12 @@ impl TreeSink for PaDomCreator {
                 .collect();
             node.attrs.extend(to_add);
         } else {
-            panic!("Non-element passed to add_attrs_if_missing!");
+            self.unsupported("Adding attributes to text");
         }
     }
 
     fn remove_from_parent(&mut self, _target: &Self::Handle) {
-        todo!("remove_from_parent not yet supported")
+        self.unsupported("remove_from_parent");
     }
 
     fn reparent_children(
@@ -230,7 +253,7 @@ impl TreeSink for PaDomCreator {
         _node: &Self::Handle,
         _new_parent: &Self::Handle,
     ) {
-        todo!("reparent_children not yet supported")
+        self.unsupported("reparent_children");
     }
 }
 
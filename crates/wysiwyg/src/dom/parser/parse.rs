@@ -12,13 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use once_cell::sync::Lazy;
 use regex::Regex;
 
 use crate::dom::dom_creation_error::HtmlParseError;
 use crate::dom::nodes::dom_node::DomNodeKind::{self};
+use crate::dom::nodes::mention_node::AT_ROOM;
 use crate::dom::nodes::{ContainerNode, ContainerNodeKind};
 use crate::dom::Dom;
-use crate::{DomHandle, DomNode, UnicodeString};
+use crate::{ComposerError, DomHandle, DomNode, TextDirection, UnicodeString};
 
 pub fn parse<S>(html: &str) -> Result<Dom<S>, HtmlParseError>
 where
@@ -35,6 +37,24 @@ where
     }
 }
 
+/// Parse a piece of HTML without converting its line breaks into paragraphs,
+/// so it can be inserted into a larger [Dom] that is post-processed as a
+/// whole.
+pub(crate) fn parse_fragment<S>(html: &str) -> Result<Dom<S>, HtmlParseError>
+where
+    S: UnicodeString,
+{
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "sys")] {
+            sys::HtmlParser::default().parse_fragment(html)
+        } else if #[cfg(all(feature = "js", target_arch = "wasm32"))] {
+            js::HtmlParser::default().parse_fragment(html)
+        } else {
+            unreachable!("The `sys` or `js` are mutually exclusive, and one of them must be enabled.")
+        }
+    }
+}
+
 #[cfg(feature = "sys")]
 mod sys {
     use matrix_mentions::Mention;
@@ -47,7 +67,7 @@ mod sys {
     use crate::dom::nodes::dom_node::DomNodeKind::CodeBlock;
     use crate::dom::nodes::{ContainerNode, DomNode};
     use crate::dom::parser::sys::PaNodeText;
-    use crate::ListType;
+    use crate::{ListType, TextDirection};
 
     pub(super) struct HtmlParser {
         current_path: Vec<DomNodeKind>,
@@ -63,14 +83,21 @@ mod sys {
             &mut self,
             html: &str,
         ) -> Result<Dom<S>, HtmlParseError>
+        where
+            S: UnicodeString,
+        {
+            self.parse_fragment(html).map(post_process)
+        }
+
+        pub(super) fn parse_fragment<S>(
+            &mut self,
+            html: &str,
+        ) -> Result<Dom<S>, HtmlParseError>
         where
             S: UnicodeString,
         {
             PaDomCreator::parse(html)
-                .map(|pa_dom| {
-                    let dom = self.padom_to_dom(pa_dom);
-                    post_process_blocks(dom)
-                })
+                .map(|pa_dom| self.padom_to_dom(pa_dom))
                 .map_err(|err| {
                     self.padom_creation_error_to_html_parse_error(err)
                 })
@@ -125,12 +152,15 @@ mod sys {
                         // Special case for code block, translate '\n' into <br /> nodes
                         let is_inside_code_block =
                             self.current_path.contains(&CodeBlock);
+                        let is_inside_link =
+                            self.current_path.contains(&DomNodeKind::Link);
                         let is_only_child_in_parent =
                             panode.children.len() == 1;
                         convert_text(
                             &text.content,
                             node,
                             is_inside_code_block,
+                            is_inside_link,
                             is_only_child_in_parent,
                         );
                     }
@@ -152,6 +182,10 @@ mod sys {
             match tag {
                 "b" | "code" | "del" | "em" | "i" | "strong" | "u" => {
                     let formatting_node = Self::new_formatting(tag);
+                    let Ok(formatting_node) = formatting_node else {
+                        self.convert_children(padom, child, Some(node));
+                        return;
+                    };
                     if tag == "code" && self.current_path.contains(&CodeBlock) {
                         self.convert_children(padom, child, Some(node));
                     } else {
@@ -181,6 +215,10 @@ mod sys {
                 "li" => {
                     self.current_path.push(DomNodeKind::ListItem);
                     node.append_child(Self::new_list_item());
+                    Self::read_text_direction(
+                        child,
+                        last_container_mut_in(node),
+                    );
                     self.convert_children(
                         padom,
                         child,
@@ -200,7 +238,18 @@ mod sys {
                         _ => None,
                     };
 
-                    if is_mention && text.is_some() {
+                    // Older at-room mentions are only marked by their text
+                    let is_at_room = child.get_attr("data-mention-type")
+                        == Some("at-room")
+                        || (child.get_attr("href") == Some("#")
+                            && child.children.len() == 1
+                            && text.is_some_and(|t| t.content == AT_ROOM));
+                    if is_at_room {
+                        self.current_path.push(DomNodeKind::Mention);
+                        node.append_child(DomNode::Mention(
+                            DomNode::new_at_room_mention(Vec::new()),
+                        ));
+                    } else if is_mention && text.is_some() {
                         self.current_path.push(DomNodeKind::Mention);
                         let mention = Self::new_mention(child, text.unwrap());
                         node.append_child(mention);
@@ -220,6 +269,10 @@ mod sys {
                 "pre" => {
                     self.current_path.push(DomNodeKind::CodeBlock);
                     node.append_child(Self::new_code_block());
+                    Self::read_text_direction(
+                        child,
+                        last_container_mut_in(node),
+                    );
                     self.convert_children(
                         padom,
                         child,
@@ -230,6 +283,10 @@ mod sys {
                 "blockquote" => {
                     self.current_path.push(DomNodeKind::Quote);
                     node.append_child(Self::new_quote());
+                    Self::read_text_direction(
+                        child,
+                        last_container_mut_in(node),
+                    );
                     self.convert_children(
                         padom,
                         child,
@@ -246,6 +303,30 @@ mod sys {
                 "p" => {
                     self.current_path.push(DomNodeKind::Paragraph);
                     node.append_child(Self::new_paragraph());
+                    Self::read_text_direction(
+                        child,
+                        last_container_mut_in(node),
+                    );
+                    self.convert_children(
+                        padom,
+                        child,
+                        last_container_mut_in(node),
+                    );
+                    self.current_path.remove(cur_path_idx);
+                }
+                // Message HTML sends paragraphs as lines, with any direction
+                // on a span around the line
+                "span" if child.get_attr("dir").is_some() => {
+                    if self.current_path.iter().any(is_inline_or_paragraph) {
+                        self.convert_children(padom, child, Some(node));
+                        return;
+                    }
+                    self.current_path.push(DomNodeKind::Paragraph);
+                    node.append_child(Self::new_paragraph());
+                    Self::read_text_direction(
+                        child,
+                        last_container_mut_in(node),
+                    );
                     self.convert_children(
                         padom,
                         child,
@@ -277,14 +358,11 @@ mod sys {
         }
 
         /// Create a formatting node
-        fn new_formatting<S>(tag: &str) -> DomNode<S>
+        fn new_formatting<S>(tag: &str) -> Result<DomNode<S>, ComposerError>
         where
             S: UnicodeString,
         {
-            DomNode::Container(ContainerNode::new_formatting_from_tag(
-                tag.into(),
-                Vec::new(),
-            ))
+            DomNode::new_formatting_from_tag(tag.into(), Vec::new())
         }
 
         /// Create a br node
@@ -379,6 +457,21 @@ mod sys {
             DomNode::Container(ContainerNode::new_paragraph(Vec::new()))
         }
 
+        /// Copy a valid `dir` attribute from the parsed block to its node
+        fn read_text_direction<S>(
+            child: &PaNodeContainer,
+            node: Option<&mut ContainerNode<S>>,
+        ) where
+            S: UnicodeString,
+        {
+            let direction = child
+                .get_attr("dir")
+                .and_then(|dir| dir.parse::<TextDirection>().ok());
+            if let (Some(node), Some(direction)) = (node, direction) {
+                node.set_text_direction(Some(direction));
+            }
+        }
+
         fn padom_creation_error_to_html_parse_error(
             &mut self,
             e: PaDomCreationError,
@@ -772,6 +865,33 @@ mod sys {
             .roundtrips();
         }
 
+        #[test]
+        fn parse_code_block_with_empty_formatting_on_a_line_roundtrips() {
+            assert_that!(
+                "<pre><code><a href=\"https://matrix.org\"><strong>link</strong></a>\n<a href=\"https://matrix.org\"><strong></strong></a></code></pre>"
+            )
+            .roundtrips();
+        }
+
+        #[test]
+        fn parse_code_block_followed_by_an_empty_paragraph() {
+            let html = "<pre><code>Some code</code></pre><p></p>";
+            let dom: Dom<Utf16String> =
+                HtmlParser::default().parse(html).unwrap();
+            let tree = dom.to_tree().to_string();
+            assert_eq!(
+                tree,
+                indoc! {
+                r#"
+
+                ├>codeblock
+                │ └>p
+                │   └>"Some code"
+                └>p
+                "#}
+            );
+        }
+
         #[test]
         fn parse_code_block_post_processes_it() {
             let mut parser = HtmlParser::default();
@@ -888,6 +1008,37 @@ mod sys {
             );
         }
 
+        #[test]
+        fn parse_at_room_mention_links() {
+            assert_that!(
+                r##"<a data-mention-type="at-room" href="#" contenteditable="false">@room</a> hello!"##
+            )
+            .roundtrips();
+        }
+
+        #[test]
+        fn parse_at_room_text_in_links() {
+            assert_that!(r#"<a href="https://matrix.org">@room hello!</a>"#)
+                .roundtrips();
+        }
+
+        #[test]
+        fn parse_at_room_links_without_a_mention_type() {
+            let html = r##"<a href="#">@room</a> hello!"##;
+            let dom: Dom<Utf16String> =
+                HtmlParser::default().parse(html).unwrap();
+            let tree = dom.to_tree().to_string();
+            assert_eq!(
+                tree,
+                indoc! {
+                r#"
+
+                ├>mention "@room"
+                └>" hello!"
+                "#}
+            );
+        }
+
         #[test]
         fn parse_mentions() {
             let html = r#"<p><a href="https://matrix.to/#/@test:example.org">test</a> hello!</p>"#;
@@ -925,7 +1076,48 @@ mod sys {
     }
 }
 
-fn post_process_blocks<S: UnicodeString>(mut dom: Dom<S>) -> Dom<S> {
+/// Whether a node of `kind` can only hold inline content, so a paragraph
+/// can't be added inside it.
+fn is_inline_or_paragraph(kind: &DomNodeKind) -> bool {
+    matches!(
+        kind,
+        DomNodeKind::Paragraph
+            | DomNodeKind::Formatting(_)
+            | DomNodeKind::Link
+            | DomNodeKind::Mention
+    )
+}
+
+/// Convert the line breaks of a parsed Dom into paragraphs, then repair
+/// whatever the HTML nested in ways we can't edit.
+fn post_process<S: UnicodeString>(dom: Dom<S>) -> Dom<S> {
+    let mut dom = post_process_blocks(dom);
+    dom.repair();
+    remove_detected_directions(&mut dom);
+    dom
+}
+
+/// Blocks starting with right-to-left text are output with `dir="auto"`
+/// when they have no direction, so the attribute isn't kept as an explicit
+/// direction on those blocks. Otherwise parsing our own HTML would turn
+/// every detected direction into an explicit one.
+fn remove_detected_directions<S: UnicodeString>(dom: &mut Dom<S>) {
+    let handles: Vec<DomHandle> = dom
+        .iter_containers()
+        .filter(|c| {
+            c.text_direction() == Some(TextDirection::Auto)
+                && c.detects_auto_direction()
+        })
+        .map(|c| c.handle())
+        .collect();
+    for handle in handles {
+        if let DomNode::Container(container) = dom.lookup_node_mut(&handle) {
+            container.set_text_direction(None);
+        }
+    }
+}
+
+pub(crate) fn post_process_blocks<S: UnicodeString>(mut dom: Dom<S>) -> Dom<S> {
     let block_handles = find_blocks(&dom);
     for handle in block_handles.iter().rev() {
         dom = post_process_block_lines(dom, handle);
@@ -954,11 +1146,16 @@ fn post_process_block_lines<S: UnicodeString>(
         let mut line_breaks: Vec<Option<DomHandle>> = Vec::new();
         let mut next_lines: Vec<DomHandle> = Vec::new();
 
-        let nodes = dom
-            .iter_from_handle(&last_handle)
-            .filter(|n| n.is_leaf() && handle.is_ancestor_of(&n.handle()))
-            .rev()
+        // Empty containers end their branch too, so a line can start at them
+        let mut nodes = dom
+            .lookup_node(handle)
+            .iter_subtree()
+            .filter(|n| {
+                (n.is_leaf() || is_empty_container(n))
+                    && handle.is_ancestor_of(&n.handle())
+            })
             .collect::<Vec<_>>();
+        nodes.reverse();
         let mut next_handle = if nodes.is_empty() {
             last_handle.clone()
         } else {
@@ -987,6 +1184,9 @@ fn post_process_block_lines<S: UnicodeString>(
         return dom;
     }
 
+    let siblings_before =
+        (!handle.is_root()).then(|| sibling_count(&dom, handle));
+
     // Create a new node to hold the processed contents if necessary
     let new_node = match container_node.kind() {
         ContainerNodeKind::Paragraph => None,
@@ -999,16 +1199,42 @@ fn post_process_block_lines<S: UnicodeString>(
         for (i, line_handle) in lines.iter().enumerate() {
             let mut sub_tree =
                 dom.split_sub_tree_from(line_handle, 0, handle.depth());
+            // Splitting copies an empty container the line starts at
+            // instead of moving it, along with its ancestors
+            let mut left_over = line_handle.clone();
+            while left_over.depth() > handle.depth()
+                && dom.contains(&left_over)
+                && is_empty_container(dom.lookup_node(&left_over))
+            {
+                dom.remove(&left_over);
+                left_over = left_over.parent_handle();
+            }
 
+            let mut markdown_syntax = None;
             if let Some(line_break_handle) = &line_breaks[i] {
+                if let DomNode::LineBreak(line_break) =
+                    dom.lookup_node(line_break_handle)
+                {
+                    markdown_syntax = line_break.markdown_syntax().cloned();
+                }
                 dom.remove(line_break_handle);
             }
 
             // If the nodes following the line break start with inline nodes,
             // ensure they are wrapped in a paragraph in order to add an
             // implicit line break here.
-            group_inline_nodes(sub_tree.document_mut().remove_children())
-                .iter()
+            let mut line =
+                group_inline_nodes(sub_tree.document_mut().remove_children());
+            // The paragraph remembers the markdown of the line break, if
+            // any.
+            if let (Some(syntax), Some(DomNode::Container(paragraph))) =
+                (markdown_syntax, line.first_mut())
+            {
+                if paragraph.kind() == &ContainerNodeKind::Paragraph {
+                    paragraph.set_markdown_syntax(syntax);
+                }
+            }
+            line.iter()
                 .rev()
                 .for_each(|n| contents.insert(0, n.clone()));
         }
@@ -1019,12 +1245,10 @@ fn post_process_block_lines<S: UnicodeString>(
         return Dom::new(contents);
     }
 
-    let needs_removal = if dom.contains(handle) {
-        let block = dom.lookup_node(handle);
-        block.is_empty()
-    } else {
-        false
-    };
+    // Splitting off the lines may have removed the block already, in which
+    // case the node now at its handle is the next block
+    let needs_removal = Some(sibling_count(&dom, handle)) == siblings_before
+        && dom.lookup_node(handle).is_empty();
 
     if needs_removal {
         dom.remove(handle);
@@ -1044,6 +1268,15 @@ fn post_process_block_lines<S: UnicodeString>(
     dom
 }
 
+fn sibling_count<S: UnicodeString>(dom: &Dom<S>, handle: &DomHandle) -> usize {
+    dom.parent(handle).children().len()
+}
+
+fn is_empty_container<S: UnicodeString>(node: &DomNode<S>) -> bool {
+    node.as_container()
+        .is_some_and(|container| container.children().is_empty())
+}
+
 // Group consecutive inline nodes into paragraphs.
 //
 // This function accepts a list of nodes of any type, inline or block.
@@ -1088,10 +1321,17 @@ fn last_container_mut_in<S: UnicodeString>(
     node.last_child_mut().and_then(|n| n.as_container_mut())
 }
 
-fn convert_text<S: UnicodeString>(
+// Compiled once, as there is a text node for every line of a document
+static SURROUNDING_INDENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\s*\n\s*)+|(\s*\n\s*)+$").unwrap());
+static INTERNAL_INDENT: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"s*\n\s*").unwrap());
+
+pub(crate) fn convert_text<S: UnicodeString>(
     text: &str,
     node: &mut ContainerNode<S>,
     is_inside_code_block: bool,
+    is_inside_link: bool,
     is_only_child_in_parent: bool,
 ) {
     if is_inside_code_block {
@@ -1110,20 +1350,26 @@ fn convert_text<S: UnicodeString>(
     } else {
         let contents = text;
         let is_nbsp = contents == "\u{A0}" || contents == "&nbsp;";
-        if is_nbsp && is_only_child_in_parent {
+        // A lone nbsp only holds an empty block open
+        if is_nbsp && is_only_child_in_parent && node.is_block_node() {
             return;
         }
 
         // Trim any surrounding indentation
-        let surrounding_indent =
-            Regex::new(r"^(\s*\n\s*)+|(\s*\n\s*)+$").unwrap();
-        let contents = &surrounding_indent.replace_all(contents, "");
+        let contents = &SURROUNDING_INDENT.replace_all(contents, "");
 
         // Replace any internal indentation with a single space
-        let internal_indent = Regex::new(r"s*\n\s*").unwrap();
-        let contents = &internal_indent.replace_all(contents, " ");
+        let contents = &INTERNAL_INDENT.replace_all(contents, " ");
+
+        // Mentions are links themselves, so they can't go inside one
+        if is_inside_link {
+            if !contents.is_empty() {
+                node.append_child(DomNode::new_text(contents.as_ref().into()));
+            }
+            return;
+        }
 
-        for (i, part) in contents.split("@room").enumerate() {
+        for (i, part) in contents.split(AT_ROOM).enumerate() {
             if i > 0 {
                 node.append_child(DomNode::Mention(
                     DomNode::new_at_room_mention(vec![]),
@@ -1143,7 +1389,7 @@ mod js {
     use crate::dom::nodes::dom_node::DomNodeKind::CodeBlock;
     use crate::{
         dom::nodes::{ContainerNode, DomNode},
-        InlineFormatType, ListType,
+        InlineFormatType, ListType, TextDirection,
     };
     use matrix_mentions::Mention;
     use std::fmt;
@@ -1164,6 +1410,16 @@ mod js {
             &mut self,
             html: &str,
         ) -> Result<Dom<S>, HtmlParseError>
+        where
+            S: UnicodeString,
+        {
+            self.parse_fragment(html).map(post_process)
+        }
+
+        pub(super) fn parse_fragment<S>(
+            &mut self,
+            html: &str,
+        ) -> Result<Dom<S>, HtmlParseError>
         where
             S: UnicodeString,
         {
@@ -1181,9 +1437,7 @@ mod js {
                     )
                 })?;
 
-            self.webdom_to_dom(document)
-                .map_err(to_dom_creation_error)
-                .map(post_process_blocks)
+            self.webdom_to_dom(document).map_err(to_dom_creation_error)
         }
 
         fn webdom_to_dom<S>(
@@ -1232,11 +1486,14 @@ mod js {
                         Some(value) => {
                             let is_inside_code_block =
                                 self.current_path.contains(&CodeBlock);
+                            let is_inside_link =
+                                self.current_path.contains(&DomNodeKind::Link);
                             let is_only_child_in_parent = number_of_nodes == 1;
                             convert_text(
                                 value.as_str(),
                                 dom,
                                 is_inside_code_block,
+                                is_inside_link,
                                 is_only_child_in_parent,
                             );
                         }
@@ -1279,7 +1536,22 @@ mod js {
                             }
                             None => false,
                         };
-                        if has_text && is_mention {
+                        // Older at-room mentions are only marked by their
+                        // text
+                        let is_at_room = node
+                            .unchecked_ref::<Element>()
+                            .get_attribute("data-mention-type")
+                            .is_some_and(|kind| kind == "at-room")
+                            || (url == "#"
+                                && has_text
+                                && node.child_nodes().length() == 1
+                                && node.text_content().as_deref()
+                                    == Some(AT_ROOM));
+                        if is_at_room {
+                            dom.append_child(DomNode::Mention(
+                                DomNode::new_at_room_mention(attributes),
+                            ));
+                        } else if has_text && is_mention {
                             dom.append_child(
                                 DomNode::Mention(
                                     DomNode::new_mention(
@@ -1334,9 +1606,12 @@ mod js {
                     "LI" => {
                         self.current_path.push(DomNodeKind::ListItem);
                         dom.append_child(DomNode::Container(
-                            ContainerNode::new_list_item(
-                                self.convert(node.child_nodes())?
-                                    .take_children(),
+                            with_text_direction(
+                                ContainerNode::new_list_item(
+                                    self.convert(node.child_nodes())?
+                                        .take_children(),
+                                ),
+                                &node,
                             ),
                         ));
                         self.current_path.pop();
@@ -1355,8 +1630,11 @@ mod js {
                             children
                         };
                         dom.append_child(DomNode::Container(
-                            ContainerNode::new_code_block(
-                                self.convert(children)?.take_children(),
+                            with_text_direction(
+                                ContainerNode::new_code_block(
+                                    self.convert(children)?.take_children(),
+                                ),
+                                &node,
                             ),
                         ));
                         self.current_path.pop();
@@ -1365,9 +1643,12 @@ mod js {
                     "BLOCKQUOTE" => {
                         self.current_path.push(DomNodeKind::Quote);
                         dom.append_child(DomNode::Container(
-                            ContainerNode::new_quote(
-                                self.convert(node.child_nodes())?
-                                    .take_children(),
+                            with_text_direction(
+                                ContainerNode::new_quote(
+                                    self.convert(node.child_nodes())?
+                                        .take_children(),
+                                ),
+                                &node,
                             ),
                         ));
                         self.current_path.pop();
@@ -1376,14 +1657,45 @@ mod js {
                     "P" => {
                         self.current_path.push(DomNodeKind::Paragraph);
                         dom.append_child(DomNode::Container(
-                            ContainerNode::new_paragraph(
-                                self.convert(node.child_nodes())?
-                                    .take_children(),
+                            with_text_direction(
+                                ContainerNode::new_paragraph(
+                                    self.convert(node.child_nodes())?
+                                        .take_children(),
+                                ),
+                                &node,
                             ),
                         ));
                         self.current_path.pop();
                     }
 
+                    // Message HTML sends paragraphs as lines, with any
+                    // direction on a span around the line
+                    "SPAN"
+                        if node
+                            .unchecked_ref::<Element>()
+                            .has_attribute("dir") =>
+                    {
+                        if self.current_path.iter().any(is_inline_or_paragraph)
+                        {
+                            dom.append_children(
+                                self.convert(node.child_nodes())?
+                                    .take_children(),
+                            );
+                        } else {
+                            self.current_path.push(DomNodeKind::Paragraph);
+                            dom.append_child(DomNode::Container(
+                                with_text_direction(
+                                    ContainerNode::new_paragraph(
+                                        self.convert(node.child_nodes())?
+                                            .take_children(),
+                                    ),
+                                    &node,
+                                ),
+                            ));
+                            self.current_path.pop();
+                        }
+                    }
+
                     node_name => {
                         let children_nodes =
                             self.convert(node.child_nodes())?.take_children();
@@ -1420,6 +1732,24 @@ mod js {
         }
     }
 
+    /// Copy a valid `dir` attribute from the web node to the container
+    fn with_text_direction<S>(
+        mut container: ContainerNode<S>,
+        node: &web_sys::Node,
+    ) -> ContainerNode<S>
+    where
+        S: UnicodeString,
+    {
+        let direction = node
+            .unchecked_ref::<Element>()
+            .get_attribute("dir")
+            .and_then(|dir| dir.parse::<TextDirection>().ok());
+        if direction.is_some() {
+            container.set_text_direction(direction);
+        }
+        container
+    }
+
     fn to_dom_creation_error<E>(error: E) -> HtmlParseError
     where
         E: ToString,
@@ -1506,6 +1836,13 @@ mod js {
             );
         }
 
+        #[wasm_bindgen_test]
+        fn at_room_mention() {
+            roundtrip(
+                r##"<a data-mention-type="at-room" href="#" contenteditable="false">@room</a> hello!"##,
+            );
+        }
+
         #[wasm_bindgen_test]
         fn mention_with_bad_attribute() {
             let html = r#"<a invalidattribute="true" href="https://matrix.to/#/@test:example.org">test</a>"#;
@@ -102,6 +102,17 @@ impl DomLocation {
         self.kind.is_leaf_kind()
     }
 
+    /// Whether the node at this location is part of the range. A collapsed
+    /// range touches the nodes containing the cursor, but not the block it's
+    /// right after.
+    pub(crate) fn touches_range(&self, collapsed: bool) -> bool {
+        if collapsed {
+            !self.kind.is_block_kind() || self.start_offset < self.length
+        } else {
+            self.start_offset < self.end_offset
+        }
+    }
+
     /// Returns the relative position of this DomLocation towards the range.
     #[allow(clippy::collapsible_else_if)] // It's a lot easier to differentiate these 2 cases
     pub fn relative_position(&self) -> DomLocationPosition {
@@ -380,8 +391,14 @@ impl Range {
     ///     
     fn find_shared_parent(&self, allow_covered_nodes: bool) -> DomHandle {
         let mut shared_path = vec![];
-        let min_leaf_path = self.leaves().min().unwrap().node_handle.raw();
-        let max_leaf_path = self.leaves().max().unwrap().node_handle.raw();
+        let (Some(min_leaf), Some(max_leaf)) =
+            (self.leaves().min(), self.leaves().max())
+        else {
+            // Nothing is selected inside an empty document
+            return DomHandle::root();
+        };
+        let min_leaf_path = min_leaf.node_handle.raw();
+        let max_leaf_path = max_leaf.node_handle.raw();
 
         for i in 0..min(min_leaf_path.len() - 1, max_leaf_path.len() - 1) {
             if min_leaf_path[i] != max_leaf_path[i] {
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use super::UnicodeString;
+use crate::MarkdownDialect;
 use std::{error::Error, fmt};
 
 #[derive(Debug)]
@@ -63,16 +64,91 @@ where
     }
 }
 
+/// How a node was written in the markdown it was imported from, so that
+/// it's written back the same way. Each variant only applies to some kinds
+/// of nodes, and is ignored once it no longer matches the node's content.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum MarkdownSyntax {
+    /// The delimiters of inline formatting, e.g. `_` or `` `` ``.
+    Delimiters {
+        open: String,
+        close: String,
+    },
+    /// An inline link to `url`, written as `[`, its text, then `suffix`,
+    /// e.g. `](<url> "title")`.
+    InlineLink {
+        url: String,
+        suffix: String,
+    },
+    /// A link written as `<url>`.
+    Autolink,
+    /// A bare URL turned into a link.
+    BareUrl,
+    BulletList {
+        marker: char,
+    },
+    OrderedList {
+        start: u64,
+        delimiter: char,
+    },
+    /// A fenced code block. The closing fence is empty if the block was
+    /// never closed.
+    FencedCode {
+        fence: String,
+        info: String,
+        closing_fence: String,
+    },
+    IndentedCode,
+    /// The prefix of a heading, e.g. `## `.
+    Heading {
+        prefix: String,
+    },
+    /// A hard or soft line break, e.g. `\\\n` or `\n`. Paragraphs created
+    /// from a line break keep it to write the break before them.
+    LineBreak {
+        source: String,
+    },
+    /// Text with escapes or entities, as long as it hasn't been edited.
+    Text {
+        source: String,
+    },
+    /// Text that had escapes or entities before it was edited.
+    EditedText,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct MarkdownOptions {
     bits: u8,
+    dialect: MarkdownDialect,
 }
 
 impl MarkdownOptions {
-    pub const IGNORE_LINE_BREAK: Self = Self { bits: 0b0001 };
+    pub const IGNORE_LINE_BREAK: Self = Self {
+        bits: 0b0001,
+        dialect: MarkdownDialect::GFM,
+    };
+
+    /// Precede the output of every leaf with a marker from
+    /// [crate::dom::offset_markers], to find where it ended up.
+    pub(crate) const TRACK_OFFSETS: Self = Self {
+        bits: 0b0010,
+        dialect: MarkdownDialect::GFM,
+    };
 
     pub const fn empty() -> Self {
-        Self { bits: 0 }
+        Self {
+            bits: 0,
+            dialect: MarkdownDialect::GFM,
+        }
+    }
+
+    /// No flags, writing markdown in the given dialect.
+    pub const fn with_dialect(dialect: MarkdownDialect) -> Self {
+        Self { bits: 0, dialect }
+    }
+
+    pub const fn dialect(&self) -> &MarkdownDialect {
+        &self.dialect
     }
 
     /// Returns `true` if all of the flags in `other` are contained within `self`.
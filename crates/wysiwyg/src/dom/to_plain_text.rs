@@ -18,5 +18,14 @@ pub trait ToPlainText<S>
 where
     S: UnicodeString,
 {
-    fn to_plain_text(&self) -> S;
+    fn to_plain_text(&self) -> S {
+        let mut buffer = S::default();
+        self.fmt_plain_text(&mut buffer, false);
+        buffer
+    }
+
+    /// Write the plain text into `buffer`. If `track_offsets` is set, the
+    /// text of every leaf is preceded by a marker from
+    /// [crate::dom::offset_markers], to find where it ended up.
+    fn fmt_plain_text(&self, buffer: &mut S, track_offsets: bool);
 }
@@ -0,0 +1,547 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Built-in emoji shortcode table, shared by all platforms so that
+//! `:shortcode` completion behaves identically everywhere, offline.
+
+use crate::EmojiCandidate;
+
+/// The prefix character starting an emoji shortcode.
+pub(crate) const SHORTCODE_PREFIX: char = ':';
+
+/// Queries shorter than this don't produce suggestions, so that typing
+/// e.g. `:D` or a time like `:30` doesn't open the menu.
+const MIN_QUERY_LENGTH: usize = 2;
+
+/// Maximum number of candidates returned for a query.
+const MAX_CANDIDATES: usize = 10;
+
+/// Extract the shortcode query from a word such as `:smi` or `:smile:`.
+/// Returns None if the word is not a valid (partial) shortcode.
+pub(crate) fn shortcode_query(text: &str) -> Option<String> {
+    let query = text.strip_prefix(SHORTCODE_PREFIX)?;
+    let query = query.strip_suffix(SHORTCODE_PREFIX).unwrap_or(query);
+    if query.chars().count() < MIN_QUERY_LENGTH
+        || !query.chars().all(is_shortcode_char)
+    {
+        return None;
+    }
+    Some(query.to_lowercase())
+}
+
+/// Find the emoji whose shortcode matches the given query. Exact matches
+/// come first, then shortcodes starting with the query, then shortcodes
+/// containing a word starting with the query, e.g. `cat` for `smile_cat`.
+pub(crate) fn candidates_for_query(query: &str) -> Vec<EmojiCandidate> {
+    let exact = EMOJI.iter().filter(|(code, _)| *code == query);
+    let prefix = EMOJI
+        .iter()
+        .filter(|(code, _)| *code != query && code.starts_with(query));
+    let word = EMOJI.iter().filter(|(code, _)| {
+        !code.starts_with(query)
+            && code
+                .split(['_', '-'])
+                .skip(1)
+                .any(|part| part.starts_with(query))
+    });
+    exact
+        .chain(prefix)
+        .chain(word)
+        .take(MAX_CANDIDATES)
+        .map(|(shortcode, emoji)| EmojiCandidate {
+            shortcode: (*shortcode).to_owned(),
+            emoji: (*emoji).to_owned(),
+        })
+        .collect()
+}
+
+fn is_shortcode_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '+'
+}
+
+/// Shortcode to emoji table, ordered by how commonly the emoji is used so
+/// that prefix matches surface popular emoji first.
+const EMOJI: &[(&str, &str)] = &[
+    ("joy", "😂"),
+    ("heart", "❤️"),
+    ("heart_eyes", "😍"),
+    ("rofl", "🤣"),
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("slightly_smiling_face", "🙂"),
+    ("blush", "😊"),
+    ("grinning", "😀"),
+    ("grin", "😁"),
+    ("laughing", "😆"),
+    ("sweat_smile", "😅"),
+    ("wink", "😉"),
+    ("innocent", "😇"),
+    ("upside_down_face", "🙃"),
+    ("relaxed", "☺️"),
+    ("yum", "😋"),
+    ("kissing_heart", "😘"),
+    ("kissing", "😗"),
+    ("stuck_out_tongue", "😛"),
+    ("stuck_out_tongue_winking_eye", "😜"),
+    ("zany_face", "🤪"),
+    ("sunglasses", "😎"),
+    ("nerd_face", "🤓"),
+    ("star_struck", "🤩"),
+    ("partying_face", "🥳"),
+    ("smirk", "😏"),
+    ("unamused", "😒"),
+    ("disappointed", "😞"),
+    ("pensive", "😔"),
+    ("worried", "😟"),
+    ("confused", "😕"),
+    ("slightly_frowning_face", "🙁"),
+    ("persevere", "😣"),
+    ("confounded", "😖"),
+    ("tired_face", "😫"),
+    ("weary", "😩"),
+    ("pleading_face", "🥺"),
+    ("cry", "😢"),
+    ("sob", "😭"),
+    ("triumph", "😤"),
+    ("angry", "😠"),
+    ("rage", "😡"),
+    ("exploding_head", "🤯"),
+    ("flushed", "😳"),
+    ("hot_face", "🥵"),
+    ("cold_face", "🥶"),
+    ("scream", "😱"),
+    ("fearful", "😨"),
+    ("cold_sweat", "😰"),
+    ("hugs", "🤗"),
+    ("thinking", "🤔"),
+    ("shushing_face", "🤫"),
+    ("lying_face", "🤥"),
+    ("no_mouth", "😶"),
+    ("neutral_face", "😐"),
+    ("expressionless", "😑"),
+    ("grimacing", "😬"),
+    ("roll_eyes", "🙄"),
+    ("hushed", "😯"),
+    ("open_mouth", "😮"),
+    ("astonished", "😲"),
+    ("yawning_face", "🥱"),
+    ("sleeping", "😴"),
+    ("drooling_face", "🤤"),
+    ("sleepy", "😪"),
+    ("dizzy_face", "😵"),
+    ("zipper_mouth_face", "🤐"),
+    ("woozy_face", "🥴"),
+    ("nauseated_face", "🤢"),
+    ("vomiting_face", "🤮"),
+    ("sneezing_face", "🤧"),
+    ("mask", "😷"),
+    ("face_with_thermometer", "🤒"),
+    ("money_mouth_face", "🤑"),
+    ("cowboy_hat_face", "🤠"),
+    ("smiling_imp", "😈"),
+    ("imp", "👿"),
+    ("clown_face", "🤡"),
+    ("poop", "💩"),
+    ("ghost", "👻"),
+    ("skull", "💀"),
+    ("alien", "👽"),
+    ("robot", "🤖"),
+    ("jack_o_lantern", "🎃"),
+    ("smiley_cat", "😺"),
+    ("smile_cat", "😸"),
+    ("joy_cat", "😹"),
+    ("heart_eyes_cat", "😻"),
+    ("scream_cat", "🙀"),
+    ("see_no_evil", "🙈"),
+    ("hear_no_evil", "🙉"),
+    ("speak_no_evil", "🙊"),
+    ("+1", "👍"),
+    ("thumbsup", "👍"),
+    ("-1", "👎"),
+    ("thumbsdown", "👎"),
+    ("ok_hand", "👌"),
+    ("pinched_fingers", "🤌"),
+    ("v", "✌️"),
+    ("crossed_fingers", "🤞"),
+    ("metal", "🤘"),
+    ("call_me_hand", "🤙"),
+    ("point_left", "👈"),
+    ("point_right", "👉"),
+    ("point_up", "☝️"),
+    ("point_down", "👇"),
+    ("wave", "👋"),
+    ("raised_hand", "✋"),
+    ("vulcan_salute", "🖖"),
+    ("fist", "✊"),
+    ("facepunch", "👊"),
+    ("clap", "👏"),
+    ("raised_hands", "🙌"),
+    ("open_hands", "👐"),
+    ("handshake", "🤝"),
+    ("pray", "🙏"),
+    ("writing_hand", "✍️"),
+    ("muscle", "💪"),
+    ("eyes", "👀"),
+    ("eye", "👁️"),
+    ("brain", "🧠"),
+    ("facepalm", "🤦"),
+    ("shrug", "🤷"),
+    ("bow", "🙇"),
+    ("raising_hand", "🙋"),
+    ("ok_woman", "🙆"),
+    ("no_good", "🙅"),
+    ("dancer", "💃"),
+    ("man_dancing", "🕺"),
+    ("running", "🏃"),
+    ("walking", "🚶"),
+    ("baby", "👶"),
+    ("child", "🧒"),
+    ("person", "🧑"),
+    ("man", "👨"),
+    ("woman", "👩"),
+    ("older_adult", "🧓"),
+    ("family", "👪"),
+    ("couple", "👫"),
+    ("orange_heart", "🧡"),
+    ("yellow_heart", "💛"),
+    ("green_heart", "💚"),
+    ("blue_heart", "💙"),
+    ("purple_heart", "💜"),
+    ("black_heart", "🖤"),
+    ("white_heart", "🤍"),
+    ("brown_heart", "🤎"),
+    ("broken_heart", "💔"),
+    ("two_hearts", "💕"),
+    ("sparkling_heart", "💖"),
+    ("heartpulse", "💗"),
+    ("heartbeat", "💓"),
+    ("revolving_hearts", "💞"),
+    ("cupid", "💘"),
+    ("gift_heart", "💝"),
+    ("kiss", "💋"),
+    ("100", "💯"),
+    ("anger", "💢"),
+    ("boom", "💥"),
+    ("dizzy", "💫"),
+    ("sweat_drops", "💦"),
+    ("dash", "💨"),
+    ("speech_balloon", "💬"),
+    ("thought_balloon", "💭"),
+    ("zzz", "💤"),
+    ("fire", "🔥"),
+    ("sparkles", "✨"),
+    ("star", "⭐"),
+    ("star2", "🌟"),
+    ("tada", "🎉"),
+    ("confetti_ball", "🎊"),
+    ("balloon", "🎈"),
+    ("gift", "🎁"),
+    ("birthday", "🎂"),
+    ("trophy", "🏆"),
+    ("medal_sports", "🏅"),
+    ("1st_place_medal", "🥇"),
+    ("2nd_place_medal", "🥈"),
+    ("3rd_place_medal", "🥉"),
+    ("soccer", "⚽"),
+    ("basketball", "🏀"),
+    ("football", "🏈"),
+    ("tennis", "🎾"),
+    ("video_game", "🎮"),
+    ("dart", "🎯"),
+    ("game_die", "🎲"),
+    ("musical_note", "🎵"),
+    ("notes", "🎶"),
+    ("microphone", "🎤"),
+    ("headphones", "🎧"),
+    ("guitar", "🎸"),
+    ("art", "🎨"),
+    ("movie_camera", "🎥"),
+    ("camera", "📷"),
+    ("tv", "📺"),
+    ("computer", "💻"),
+    ("keyboard", "⌨️"),
+    ("iphone", "📱"),
+    ("telephone_receiver", "📞"),
+    ("battery", "🔋"),
+    ("electric_plug", "🔌"),
+    ("bulb", "💡"),
+    ("flashlight", "🔦"),
+    ("books", "📚"),
+    ("book", "📖"),
+    ("memo", "📝"),
+    ("pencil2", "✏️"),
+    ("paperclip", "📎"),
+    ("pushpin", "📌"),
+    ("scissors", "✂️"),
+    ("calendar", "📆"),
+    ("chart_with_upwards_trend", "📈"),
+    ("chart_with_downwards_trend", "📉"),
+    ("clipboard", "📋"),
+    ("file_folder", "📁"),
+    ("package", "📦"),
+    ("email", "📧"),
+    ("envelope", "✉️"),
+    ("mailbox", "📫"),
+    ("lock", "🔒"),
+    ("unlock", "🔓"),
+    ("key", "🔑"),
+    ("hammer", "🔨"),
+    ("wrench", "🔧"),
+    ("gear", "⚙️"),
+    ("link", "🔗"),
+    ("bell", "🔔"),
+    ("no_bell", "🔕"),
+    ("mag", "🔍"),
+    ("hourglass", "⌛"),
+    ("watch", "⌚"),
+    ("alarm_clock", "⏰"),
+    ("moneybag", "💰"),
+    ("dollar", "💵"),
+    ("credit_card", "💳"),
+    ("gem", "💎"),
+    ("rocket", "🚀"),
+    ("airplane", "✈️"),
+    ("car", "🚗"),
+    ("bus", "🚌"),
+    ("train", "🚋"),
+    ("bike", "🚲"),
+    ("ship", "🚢"),
+    ("house", "🏠"),
+    ("office", "🏢"),
+    ("hospital", "🏥"),
+    ("school", "🏫"),
+    ("earth_africa", "🌍"),
+    ("earth_americas", "🌎"),
+    ("earth_asia", "🌏"),
+    ("globe_with_meridians", "🌐"),
+    ("sunny", "☀️"),
+    ("partly_sunny", "⛅"),
+    ("cloud", "☁️"),
+    ("umbrella", "☔"),
+    ("snowflake", "❄️"),
+    ("snowman", "⛄"),
+    ("zap", "⚡"),
+    ("rainbow", "🌈"),
+    ("ocean", "🌊"),
+    ("crescent_moon", "🌙"),
+    ("full_moon", "🌕"),
+    ("new_moon", "🌑"),
+    ("christmas_tree", "🎄"),
+    ("evergreen_tree", "🌲"),
+    ("deciduous_tree", "🌳"),
+    ("palm_tree", "🌴"),
+    ("cactus", "🌵"),
+    ("seedling", "🌱"),
+    ("herb", "🌿"),
+    ("four_leaf_clover", "🍀"),
+    ("fallen_leaf", "🍂"),
+    ("mushroom", "🍄"),
+    ("rose", "🌹"),
+    ("sunflower", "🌻"),
+    ("tulip", "🌷"),
+    ("cherry_blossom", "🌸"),
+    ("bouquet", "💐"),
+    ("dog", "🐶"),
+    ("cat", "🐱"),
+    ("mouse", "🐭"),
+    ("hamster", "🐹"),
+    ("rabbit", "🐰"),
+    ("fox_face", "🦊"),
+    ("bear", "🐻"),
+    ("panda_face", "🐼"),
+    ("koala", "🐨"),
+    ("tiger", "🐯"),
+    ("lion", "🦁"),
+    ("cow", "🐮"),
+    ("pig", "🐷"),
+    ("frog", "🐸"),
+    ("monkey_face", "🐵"),
+    ("chicken", "🐔"),
+    ("penguin", "🐧"),
+    ("bird", "🐦"),
+    ("eagle", "🦅"),
+    ("owl", "🦉"),
+    ("unicorn", "🦄"),
+    ("bee", "🐝"),
+    ("bug", "🐛"),
+    ("butterfly", "🦋"),
+    ("snail", "🐌"),
+    ("turtle", "🐢"),
+    ("snake", "🐍"),
+    ("dragon", "🐉"),
+    ("t-rex", "🦖"),
+    ("crab", "🦀"),
+    ("octopus", "🐙"),
+    ("fish", "🐟"),
+    ("dolphin", "🐬"),
+    ("whale", "🐳"),
+    ("shark", "🦈"),
+    ("horse", "🐴"),
+    ("sloth", "🦥"),
+    ("apple", "🍎"),
+    ("green_apple", "🍏"),
+    ("pear", "🍐"),
+    ("tangerine", "🍊"),
+    ("lemon", "🍋"),
+    ("banana", "🍌"),
+    ("watermelon", "🍉"),
+    ("grapes", "🍇"),
+    ("strawberry", "🍓"),
+    ("cherries", "🍒"),
+    ("peach", "🍑"),
+    ("pineapple", "🍍"),
+    ("avocado", "🥑"),
+    ("eggplant", "🍆"),
+    ("carrot", "🥕"),
+    ("corn", "🌽"),
+    ("hot_pepper", "🌶️"),
+    ("bread", "🍞"),
+    ("cheese", "🧀"),
+    ("egg", "🥚"),
+    ("bacon", "🥓"),
+    ("hamburger", "🍔"),
+    ("fries", "🍟"),
+    ("pizza", "🍕"),
+    ("hotdog", "🌭"),
+    ("taco", "🌮"),
+    ("burrito", "🌯"),
+    ("spaghetti", "🍝"),
+    ("ramen", "🍜"),
+    ("sushi", "🍣"),
+    ("rice", "🍚"),
+    ("popcorn", "🍿"),
+    ("doughnut", "🍩"),
+    ("cookie", "🍪"),
+    ("cake", "🍰"),
+    ("chocolate_bar", "🍫"),
+    ("candy", "🍬"),
+    ("icecream", "🍦"),
+    ("coffee", "☕"),
+    ("tea", "🍵"),
+    ("beer", "🍺"),
+    ("beers", "🍻"),
+    ("wine_glass", "🍷"),
+    ("cocktail", "🍸"),
+    ("champagne", "🍾"),
+    ("clinking_glasses", "🥂"),
+    ("white_check_mark", "✅"),
+    ("heavy_check_mark", "✔️"),
+    ("x", "❌"),
+    ("negative_squared_cross_mark", "❎"),
+    ("warning", "⚠️"),
+    ("no_entry", "⛔"),
+    ("no_entry_sign", "🚫"),
+    ("question", "❓"),
+    ("grey_question", "❔"),
+    ("exclamation", "❗"),
+    ("bangbang", "‼️"),
+    ("interrobang", "⁉️"),
+    ("heavy_plus_sign", "➕"),
+    ("heavy_minus_sign", "➖"),
+    ("arrow_right", "➡️"),
+    ("arrow_left", "⬅️"),
+    ("arrow_up", "⬆️"),
+    ("arrow_down", "⬇️"),
+    ("arrows_counterclockwise", "🔄"),
+    ("recycle", "♻️"),
+    ("infinity", "♾️"),
+    ("copyright", "©️"),
+    ("registered", "®️"),
+    ("tm", "™️"),
+    ("information_source", "ℹ️"),
+    ("new", "🆕"),
+    ("free", "🆓"),
+    ("up", "🆙"),
+    ("cool", "🆒"),
+    ("ok", "🆗"),
+    ("sos", "🆘"),
+    ("red_circle", "🔴"),
+    ("orange_circle", "🟠"),
+    ("yellow_circle", "🟡"),
+    ("green_circle", "🟢"),
+    ("large_blue_circle", "🔵"),
+    ("purple_circle", "🟣"),
+    ("black_circle", "⚫"),
+    ("white_circle", "⚪"),
+    ("checkered_flag", "🏁"),
+    ("triangular_flag_on_post", "🚩"),
+    ("rainbow_flag", "🏳️‍🌈"),
+    ("transgender_flag", "🏳️‍⚧️"),
+    ("pirate_flag", "🏴‍☠️"),
+    ("white_flag", "🏳️"),
+    ("black_flag", "🏴"),
+];
+
+#[cfg(test)]
+mod test {
+    use super::{candidates_for_query, shortcode_query};
+
+    #[test]
+    fn shortcode_query_strips_colons() {
+        assert_eq!(shortcode_query(":smi"), Some("smi".into()));
+        assert_eq!(shortcode_query(":smile:"), Some("smile".into()));
+        assert_eq!(shortcode_query(":+1"), Some("+1".into()));
+    }
+
+    #[test]
+    fn shortcode_query_is_lowercased() {
+        assert_eq!(shortcode_query(":Tada"), Some("tada".into()));
+    }
+
+    #[test]
+    fn short_or_invalid_shortcodes_are_ignored() {
+        assert_eq!(shortcode_query(":"), None);
+        assert_eq!(shortcode_query(":s"), None);
+        assert_eq!(shortcode_query(":)"), None);
+        assert_eq!(shortcode_query(":sm!le"), None);
+        assert_eq!(shortcode_query("smile"), None);
+    }
+
+    #[test]
+    fn exact_match_comes_first() {
+        let candidates = candidates_for_query("smile");
+        assert_eq!(candidates[0].shortcode, "smile");
+        assert_eq!(candidates[0].emoji, "😄");
+        assert_eq!(candidates[1].shortcode, "smiley");
+    }
+
+    #[test]
+    fn word_matches_come_after_prefix_matches() {
+        let candidates: Vec<String> = candidates_for_query("cat")
+            .into_iter()
+            .map(|c| c.shortcode)
+            .collect();
+        assert_eq!(
+            candidates,
+            vec![
+                "cat",
+                "smiley_cat",
+                "smile_cat",
+                "joy_cat",
+                "heart_eyes_cat",
+                "scream_cat"
+            ]
+        );
+    }
+
+    #[test]
+    fn candidates_are_limited() {
+        assert_eq!(candidates_for_query("he").len(), 10);
+    }
+
+    #[test]
+    fn unknown_shortcode_has_no_candidates() {
+        assert!(candidates_for_query("notanemoji").is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// How [crate::ComposerModel::find] matches its query against the text.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FindOptions {
+    /// Only match text with the same case as the query.
+    pub case_sensitive: bool,
+    /// Only match whole words, i.e. matches must not be directly preceded
+    /// or followed by a letter, digit or underscore.
+    pub whole_word: bool,
+    /// Treat the query as a regular expression rather than literal text.
+    pub regex: bool,
+}
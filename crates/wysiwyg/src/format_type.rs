@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{ComposerAction, UnicodeString};
+use std::str::FromStr;
+
+use crate::{ComposerAction, ComposerError};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum InlineFormatType {
@@ -45,17 +47,18 @@ impl InlineFormatType {
     }
 }
 
-impl<S: UnicodeString> From<S> for InlineFormatType {
-    fn from(value: S) -> Self {
-        match value.to_string().as_str() {
-            "b" | "strong" => InlineFormatType::Bold,
-            "i" | "em" => InlineFormatType::Italic,
-            "del" => InlineFormatType::StrikeThrough,
-            "u" => InlineFormatType::Underline,
-            "code" => InlineFormatType::InlineCode,
-            _ => {
-                panic!("Unknown format type {}", value.to_string().as_str());
-            }
+impl FromStr for InlineFormatType {
+    type Err = ComposerError;
+
+    /// Find the format of an HTML formatting tag, e.g. `strong` or `b`.
+    fn from_str(tag: &str) -> Result<Self, Self::Err> {
+        match tag {
+            "b" | "strong" => Ok(InlineFormatType::Bold),
+            "i" | "em" => Ok(InlineFormatType::Italic),
+            "del" => Ok(InlineFormatType::StrikeThrough),
+            "u" => Ok(InlineFormatType::Underline),
+            "code" => Ok(InlineFormatType::InlineCode),
+            _ => Err(ComposerError::UnknownFormatType(tag.to_owned())),
         }
     }
 }
@@ -0,0 +1,472 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for fuzzing sequences of [ComposerModel] operations.
+//!
+//! The fuzz target in `crates/wysiwyg/fuzz` decodes its input with
+//! [decode_operations] and passes the result to [run_operations], which
+//! panics as soon as the model gets into a bad state. Crashes it finds can
+//! be printed with `{:?}` and pasted into a regression test in
+//! `tests/test_fuzz_regressions.rs`.
+//!
+//! Only built with the "fuzzing" feature, which also turns on
+//! "assert-invariants".
+
+use widestring::Utf16String;
+
+use crate::dom::nodes::mention_node::AT_ROOM;
+use crate::{
+    BulletMarker, ComposerError, ComposerModel, Direction, EmphasisMarker,
+    FenceStyle, Granularity, HardBreakStyle, Location, MarkdownDialect,
+    SoftBreakPolicy, TextDirection, ToTree,
+};
+
+/// Locations go a little past the end of most generated documents, so that
+/// out of range selections get tested too.
+const MAX_LOCATION: u8 = 64;
+
+const TEXTS: &[&str] = &[
+    "a",
+    "foo bar",
+    " ",
+    "\n",
+    "😄",
+    "e\u{301}",
+    "1. ",
+    "* ",
+    "> ",
+    "```",
+    "line\nbreak",
+    "1. item",
+    "* item",
+    "> quote",
+    "**",
+    "",
+];
+
+/// Content to set, including malformed HTML that can't be parsed.
+const HTML: &[&str] = &[
+    "",
+    "<b>bold</b> plain",
+    "<//strong>",
+    "<ol><li>",
+    "<!-- comment -->",
+    "<b>a<i>b</b>c</i>",
+    "<table><tr><td>cell</td></tr></table>",
+    "<a href=\"https://x.org\"><a href=\"https://y.org\">n</a></a>",
+    "<code><b>x</b></code>",
+    "<pre><code>x\ny</code></pre>",
+    "<blockquote><p>q</p><ul><li>l</li></ul></blockquote>",
+    "<p>a</p><p></p><p>b</p>",
+    "<br><br>text<br>",
+    "<a href=\"https://matrix.to/#/@alice:matrix.org\">Alice</a> hi",
+    "<em><strong>bo</strong>th</em><del>x</del><u>y</u>",
+    "<ul><li><p>a</p><ol><li>b</li></ol></li></ul>",
+];
+
+const MARKDOWN: &[&str] = &[
+    "",
+    "**bold** _it_",
+    "* a\n* b\n\n1. c",
+    "> q\n>\n> r",
+    "```\ncode\n```",
+    "`",
+    "[link](https://x.org)",
+    "- [ ] x\n  - y",
+    "a  \nb\\\nc",
+    "***",
+    "<b>html</b>",
+    "* ```\n  x",
+];
+
+const AT_ROOM_MENTION_HTML: &str = r##"<a data-mention-type="at-room" href="#" contenteditable="false">@room</a>"##;
+const LINK_URL: &str = "https://matrix.org";
+const MENTION_URL: &str = "https://matrix.to/#/@alice:matrix.org";
+
+/// One call to a public [ComposerModel] method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Select(usize, usize),
+    ReplaceText(&'static str),
+    Enter,
+    Backspace,
+    Delete,
+    Bold,
+    Italic,
+    Underline,
+    StrikeThrough,
+    InlineCode,
+    OrderedList,
+    UnorderedList,
+    Indent,
+    Unindent,
+    Quote,
+    CodeBlock,
+    Undo,
+    Redo,
+    SetLink,
+    SetLinkWithText,
+    RemoveLinks,
+    InsertMention,
+    InsertAtRoomMention,
+    ReplaceTextIn(&'static str, usize, usize),
+    BackspaceWord,
+    DeleteWord,
+    SetContentFromHtml(&'static str),
+    SetContentFromMarkdown(&'static str),
+    SwitchToPlain,
+    SwitchToRich,
+    ClearFormatting(bool),
+    MoveBlockUp,
+    MoveBlockDown,
+    DuplicateBlock,
+    /// Select the word, then the sentence, then the paragraph at a location.
+    SelectTextAt(usize),
+    MoveCursor(Direction, Granularity),
+    ExtendSelection(Direction, Granularity),
+    SetTextDirection,
+    SetMarkdownDialect,
+    /// Convert a location and an offset between the model and its markdown
+    /// and plain text.
+    Offsets(usize, usize),
+    SelectAll,
+    Clear,
+}
+
+/// Turn arbitrary bytes into operations. Every input decodes to something,
+/// and inputs that share a prefix decode to operations sharing a prefix,
+/// which helps the fuzzer to build on inputs it has already found.
+pub fn decode_operations(data: &[u8]) -> Vec<Operation> {
+    let mut bytes = data.iter().copied();
+    let mut operations = Vec::new();
+    while let Some(operation) = Operation::decode(&mut bytes) {
+        operations.push(operation);
+    }
+    operations
+}
+
+/// Apply `operations` to an empty model, checking it after each one.
+///
+/// Panics if an operation fails, other than setting content that can't be
+/// parsed, if the model breaks one of its invariants, or if the HTML of the
+/// model changes when parsed and written again.
+pub fn run_operations(operations: &[Operation]) {
+    let mut model = ComposerModel::new();
+    for operation in operations {
+        match operation.apply(&mut model) {
+            Err(error @ ComposerError::Internal(_)) => {
+                panic!("{operation:?} failed: {error}")
+            }
+            Err(error) if !operation.sets_content() => {
+                panic!("{operation:?} failed: {error}")
+            }
+            _ => {}
+        }
+        check_model(&model);
+    }
+}
+
+impl Operation {
+    fn decode(bytes: &mut impl Iterator<Item = u8>) -> Option<Self> {
+        let kind = bytes.next()?;
+        let mut location =
+            || bytes.next().map(|b| usize::from(b % MAX_LOCATION));
+        let operation = match kind % 42 {
+            0 => Self::Select(location()?, location()?),
+            1 => Self::ReplaceText(TEXTS[location()? % TEXTS.len()]),
+            2 => Self::Enter,
+            3 => Self::Backspace,
+            4 => Self::Delete,
+            5 => Self::Bold,
+            6 => Self::Italic,
+            7 => Self::Underline,
+            8 => Self::StrikeThrough,
+            9 => Self::InlineCode,
+            10 => Self::OrderedList,
+            11 => Self::UnorderedList,
+            12 => Self::Indent,
+            13 => Self::Unindent,
+            14 => Self::Quote,
+            15 => Self::CodeBlock,
+            16 => Self::Undo,
+            17 => Self::Redo,
+            18 => Self::SetLink,
+            19 => Self::SetLinkWithText,
+            20 => Self::RemoveLinks,
+            21 => Self::InsertMention,
+            22 => Self::InsertAtRoomMention,
+            23 => Self::ReplaceTextIn(
+                TEXTS[location()? % TEXTS.len()],
+                location()?,
+                location()?,
+            ),
+            24 => Self::BackspaceWord,
+            25 => Self::DeleteWord,
+            26 => Self::SetContentFromHtml(HTML[location()? % HTML.len()]),
+            27 => Self::SetContentFromMarkdown(
+                MARKDOWN[location()? % MARKDOWN.len()],
+            ),
+            28 => Self::SwitchToPlain,
+            29 => Self::SwitchToRich,
+            30 => Self::ClearFormatting(location()? % 2 == 0),
+            31 => Self::MoveBlockUp,
+            32 => Self::MoveBlockDown,
+            33 => Self::DuplicateBlock,
+            34 => Self::SelectTextAt(location()?),
+            35 => Self::MoveCursor(
+                direction(location()?),
+                granularity(location()?),
+            ),
+            36 => Self::ExtendSelection(
+                direction(location()?),
+                granularity(location()?),
+            ),
+            37 => Self::SetTextDirection,
+            38 => Self::SetMarkdownDialect,
+            39 => Self::Offsets(location()?, location()?),
+            40 => Self::SelectAll,
+            _ => Self::Clear,
+        };
+        Some(operation)
+    }
+
+    fn apply(
+        self,
+        model: &mut ComposerModel<Utf16String>,
+    ) -> Result<(), ComposerError> {
+        let text = Utf16String::from_str;
+        match self {
+            Self::Select(start, end) => {
+                model.select(Location::from(start), Location::from(end))?;
+            }
+            Self::ReplaceText(new_text) => {
+                model.replace_text(text(new_text))?;
+            }
+            Self::Enter => {
+                model.enter()?;
+            }
+            Self::Backspace => {
+                model.backspace()?;
+            }
+            Self::Delete => {
+                model.delete()?;
+            }
+            Self::Bold => {
+                model.bold()?;
+            }
+            Self::Italic => {
+                model.italic()?;
+            }
+            Self::Underline => {
+                model.underline()?;
+            }
+            Self::StrikeThrough => {
+                model.strike_through()?;
+            }
+            Self::InlineCode => {
+                model.inline_code()?;
+            }
+            Self::OrderedList => {
+                model.ordered_list()?;
+            }
+            Self::UnorderedList => {
+                model.unordered_list()?;
+            }
+            Self::Indent => {
+                model.indent()?;
+            }
+            Self::Unindent => {
+                model.unindent()?;
+            }
+            Self::Quote => {
+                model.quote()?;
+            }
+            Self::CodeBlock => {
+                model.code_block()?;
+            }
+            Self::Undo => {
+                model.undo()?;
+            }
+            Self::Redo => {
+                model.redo()?;
+            }
+            Self::SetLink => {
+                model.set_link(text(LINK_URL), Vec::new())?;
+            }
+            Self::SetLinkWithText => {
+                model.set_link_with_text(
+                    text(LINK_URL),
+                    text("link"),
+                    Vec::new(),
+                )?;
+            }
+            Self::RemoveLinks => {
+                model.remove_links()?;
+            }
+            Self::InsertMention => {
+                model.insert_mention(
+                    text(MENTION_URL),
+                    text("Alice"),
+                    Vec::new(),
+                )?;
+            }
+            Self::InsertAtRoomMention => {
+                model.insert_at_room_mention(Vec::new())?;
+            }
+            Self::ReplaceTextIn(new_text, start, end) => {
+                model.replace_text_in(text(new_text), start, end)?;
+            }
+            Self::BackspaceWord => {
+                model.backspace_word()?;
+            }
+            Self::DeleteWord => {
+                model.delete_word()?;
+            }
+            Self::SetContentFromHtml(html) => {
+                model.set_content_from_html(&text(html))?;
+            }
+            Self::SetContentFromMarkdown(markdown) => {
+                model.set_content_from_markdown(&text(markdown))?;
+            }
+            Self::SwitchToPlain => {
+                model.switch_to_plain()?;
+            }
+            Self::SwitchToRich => {
+                model.switch_to_rich()?;
+            }
+            Self::ClearFormatting(include_blocks) => {
+                model.clear_formatting(include_blocks)?;
+            }
+            Self::MoveBlockUp => {
+                model.move_block_up()?;
+            }
+            Self::MoveBlockDown => {
+                model.move_block_down()?;
+            }
+            Self::DuplicateBlock => {
+                model.duplicate_block()?;
+            }
+            Self::SelectTextAt(location) => {
+                let location = Location::from(location);
+                model.select_word_at(location)?;
+                model.select_sentence_at(location)?;
+                model.select_paragraph_at(location)?;
+            }
+            Self::MoveCursor(direction, granularity) => {
+                model.move_cursor(direction, granularity)?;
+            }
+            Self::ExtendSelection(direction, granularity) => {
+                model.extend_selection_by(granularity, direction)?;
+            }
+            Self::SetTextDirection => {
+                model.set_text_direction(TextDirection::Rtl)?;
+            }
+            Self::SetMarkdownDialect => {
+                model.set_markdown_dialect(MarkdownDialect {
+                    emphasis: EmphasisMarker::Underscore,
+                    bullet: BulletMarker::Dash,
+                    fence: FenceStyle::Tildes,
+                    hard_break: HardBreakStyle::TrailingSpaces,
+                    soft_break: SoftBreakPolicy::Space,
+                    ..MarkdownDialect::COMMONMARK
+                });
+            }
+            Self::Offsets(location, offset) => {
+                let location = Location::from(location);
+                let _ = model.location_to_markdown_offset(location);
+                let _ = model.markdown_offset_to_location(offset);
+                model.location_to_plain_text_offset(location);
+                model.plain_text_offset_to_location(offset);
+            }
+            Self::SelectAll => {
+                model.select_all()?;
+            }
+            Self::Clear => {
+                model.clear()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the operation sets new content, which fails if the content
+    /// can't be parsed.
+    fn sets_content(self) -> bool {
+        matches!(
+            self,
+            Self::SetContentFromHtml(_) | Self::SetContentFromMarkdown(_)
+        )
+    }
+}
+
+fn direction(value: usize) -> Direction {
+    match value % 2 {
+        0 => Direction::Forwards,
+        _ => Direction::Backwards,
+    }
+}
+
+fn granularity(value: usize) -> Granularity {
+    match value % 5 {
+        0 => Granularity::Character,
+        1 => Granularity::Word,
+        2 => Granularity::Sentence,
+        3 => Granularity::Paragraph,
+        _ => Granularity::Document,
+    }
+}
+
+fn check_model(model: &ComposerModel<Utf16String>) {
+    model.explicitly_assert_invariants();
+    read_everything(model);
+
+    let html = model.get_content_as_html().to_string();
+    let reparsed = match ComposerModel::<Utf16String>::from_html(&html, 0, 0) {
+        Ok(reparsed) => reparsed,
+        Err(error) => panic!("Could not parse our own HTML {html}: {error}"),
+    };
+    reparsed.state.dom.explicitly_assert_invariants();
+    assert_eq!(
+        normalize_html(&reparsed.get_content_as_html().to_string()),
+        normalize_html(&html),
+        "HTML changed when parsed again. Tree was:\n{}",
+        model.state.dom.to_tree()
+    );
+}
+
+/// Call every getter, as any of them could panic on a bad model.
+fn read_everything(model: &ComposerModel<Utf16String>) {
+    model.get_content_as_message_html();
+    let _ = model.get_content_as_markdown();
+    let _ = model.get_content_as_message_markdown();
+    model.get_content_as_plain_text();
+    model.get_link_action();
+    model.get_mentions_state();
+    model.action_states();
+    model.stats();
+    model.to_tree();
+}
+
+/// Remove the differences parsing can't avoid: both an empty block and a
+/// block holding a single space are written as a lone nbsp, and "@room" text
+/// is parsed as an at-room mention.
+fn normalize_html(html: &str) -> String {
+    let html = html
+        .replace(">\u{A0}<", "><")
+        .replace(AT_ROOM_MENTION_HTML, AT_ROOM);
+    if html == "\u{A0}" {
+        String::new()
+    } else {
+        html
+    }
+}
@@ -0,0 +1,273 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use strum_macros::{AsRefStr, EnumString};
+
+/// A platform-neutral description of a key press.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyEvent {
+    pub fn new(key: Key, modifiers: KeyModifiers) -> Self {
+        Self { key, modifiers }
+    }
+
+    /// Character keys are matched case-insensitively, because platforms
+    /// disagree on whether Shift changes the reported character.
+    fn normalized(&self) -> Self {
+        let key = match self.key {
+            Key::Character(c) => {
+                Key::Character(c.to_lowercase().next().unwrap_or(c))
+            }
+            ref key => key.clone(),
+        };
+        Self {
+            key,
+            modifiers: self.modifiers.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyModifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// The Command key on Apple platforms, or the Windows/Super key.
+    pub meta: bool,
+}
+
+impl KeyModifiers {
+    pub const NONE: Self = Self {
+        ctrl: false,
+        alt: false,
+        shift: false,
+        meta: false,
+    };
+    pub const CTRL: Self = Self {
+        ctrl: true,
+        ..Self::NONE
+    };
+    pub const ALT: Self = Self {
+        alt: true,
+        ..Self::NONE
+    };
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ..Self::NONE
+    };
+    pub const META: Self = Self {
+        meta: true,
+        ..Self::NONE
+    };
+
+    pub fn with_shift(self) -> Self {
+        Self {
+            shift: true,
+            ..self
+        }
+    }
+}
+
+/// The keys the composer may react to. Names follow the web's
+/// `KeyboardEvent.key` values, so `Key::from_str("Backspace")` works.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Character(char),
+    Backspace,
+    Delete,
+    Enter,
+    Tab,
+    Escape,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+    Home,
+    End,
+}
+
+impl FromStr for Key {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Self::Character(c));
+        }
+        match s {
+            "Backspace" => Ok(Self::Backspace),
+            "Delete" => Ok(Self::Delete),
+            "Enter" => Ok(Self::Enter),
+            "Tab" => Ok(Self::Tab),
+            "Escape" => Ok(Self::Escape),
+            "ArrowLeft" => Ok(Self::ArrowLeft),
+            "ArrowRight" => Ok(Self::ArrowRight),
+            "ArrowUp" => Ok(Self::ArrowUp),
+            "ArrowDown" => Ok(Self::ArrowDown),
+            "Home" => Ok(Self::Home),
+            "End" => Ok(Self::End),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The model operations a key can be bound to.
+#[derive(AsRefStr, Clone, Copy, Debug, EnumString, PartialEq, Eq, Hash)]
+pub enum KeyCommand {
+    Bold,
+    Italic,
+    StrikeThrough,
+    Underline,
+    InlineCode,
+    Undo,
+    Redo,
+    OrderedList,
+    UnorderedList,
+    Indent,
+    Unindent,
+    CodeBlock,
+    Quote,
+    Backspace,
+    Delete,
+    BackspaceWord,
+    DeleteWord,
+    Enter,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeymapPreset {
+    /// Ctrl-based shortcuts, as on Windows, Linux and Android.
+    Standard,
+    /// Command-based shortcuts, as on macOS and iOS.
+    Apple,
+}
+
+/// Maps key presses to [KeyCommand]s for [crate::ComposerModel::handle_key].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyEvent, KeyCommand>,
+}
+
+impl Keymap {
+    /// A keymap with no bindings.
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    pub fn preset(preset: KeymapPreset) -> Self {
+        let (primary, word) = match preset {
+            KeymapPreset::Standard => (KeyModifiers::CTRL, KeyModifiers::CTRL),
+            KeymapPreset::Apple => (KeyModifiers::META, KeyModifiers::ALT),
+        };
+        let mut keymap = Self::empty();
+        let mut bind = |key, modifiers: &KeyModifiers, command| {
+            keymap.bind(KeyEvent::new(key, modifiers.clone()), command)
+        };
+        bind(Key::Character('b'), &primary, KeyCommand::Bold);
+        bind(Key::Character('i'), &primary, KeyCommand::Italic);
+        bind(Key::Character('u'), &primary, KeyCommand::Underline);
+        bind(Key::Character('z'), &primary, KeyCommand::Undo);
+        bind(
+            Key::Character('z'),
+            &primary.clone().with_shift(),
+            KeyCommand::Redo,
+        );
+        if preset == KeymapPreset::Standard {
+            bind(Key::Character('y'), &primary, KeyCommand::Redo);
+        }
+        bind(Key::Tab, &KeyModifiers::NONE, KeyCommand::Indent);
+        bind(Key::Tab, &KeyModifiers::SHIFT, KeyCommand::Unindent);
+        bind(Key::Backspace, &KeyModifiers::NONE, KeyCommand::Backspace);
+        bind(Key::Delete, &KeyModifiers::NONE, KeyCommand::Delete);
+        bind(Key::Backspace, &word, KeyCommand::BackspaceWord);
+        bind(Key::Delete, &word, KeyCommand::DeleteWord);
+        keymap
+    }
+
+    /// Bind `event` to `command`, replacing any existing binding.
+    pub fn bind(&mut self, event: KeyEvent, command: KeyCommand) {
+        self.bindings.insert(event.normalized(), command);
+    }
+
+    pub fn unbind(&mut self, event: &KeyEvent) {
+        self.bindings.remove(&event.normalized());
+    }
+
+    pub fn command_for(&self, event: &KeyEvent) -> Option<KeyCommand> {
+        self.bindings.get(&event.normalized()).copied()
+    }
+
+    pub fn bindings(&self) -> impl Iterator<Item = (&KeyEvent, &KeyCommand)> {
+        self.bindings.iter()
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(KeymapPreset::Standard)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn character_keys_match_case_insensitively() {
+        let keymap = Keymap::default();
+        let event =
+            KeyEvent::new(Key::Character('Z'), KeyModifiers::CTRL.with_shift());
+        assert_eq!(keymap.command_for(&event), Some(KeyCommand::Redo));
+    }
+
+    #[test]
+    fn apple_preset_uses_command_key() {
+        let keymap = Keymap::preset(KeymapPreset::Apple);
+        let ctrl_b = KeyEvent::new(Key::Character('b'), KeyModifiers::CTRL);
+        let cmd_b = KeyEvent::new(Key::Character('b'), KeyModifiers::META);
+        assert_eq!(keymap.command_for(&ctrl_b), None);
+        assert_eq!(keymap.command_for(&cmd_b), Some(KeyCommand::Bold));
+    }
+
+    #[test]
+    fn bindings_can_be_replaced_and_removed() {
+        let mut keymap = Keymap::default();
+        let ctrl_b = KeyEvent::new(Key::Character('b'), KeyModifiers::CTRL);
+        keymap.bind(ctrl_b.clone(), KeyCommand::CodeBlock);
+        assert_eq!(keymap.command_for(&ctrl_b), Some(KeyCommand::CodeBlock));
+        keymap.unbind(&ctrl_b);
+        assert_eq!(keymap.command_for(&ctrl_b), None);
+    }
+
+    #[test]
+    fn keys_are_parsed_from_web_names() {
+        assert_eq!(Key::from_str("a"), Ok(Key::Character('a')));
+        assert_eq!(Key::from_str("Backspace"), Ok(Key::Backspace));
+        assert_eq!(Key::from_str("Shift"), Err(()));
+        assert_eq!(
+            KeyCommand::from_str("BackspaceWord"),
+            Ok(KeyCommand::BackspaceWord)
+        );
+    }
+}
@@ -14,29 +14,62 @@
 
 mod action_state;
 mod char;
+mod command;
 mod composer_action;
+mod composer_error;
+mod composer_mode;
 mod composer_model;
 mod composer_state;
+mod composer_stats;
 mod composer_update;
 mod dom;
+#[cfg(feature = "emoji")]
+mod emoji;
+mod find_options;
 mod format_type;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzzing;
+mod granularity;
+mod keymap;
 mod link_action;
 mod list_type;
 mod location;
+mod markdown_dialect;
+mod max_length;
 mod mentions_state;
 mod menu_action;
 mod menu_state;
+mod node_info;
 mod pattern_key;
 mod suggestion_pattern;
+mod suggestion_trigger;
 mod tests;
+mod text_direction;
+mod text_transform;
 mod text_update;
+mod typography;
 
 pub use crate::action_state::ActionState;
+pub use crate::command::CommandArgumentDefinition;
+pub use crate::command::CommandArgumentKind;
+pub use crate::command::CommandArgumentValue;
+pub use crate::command::CommandDefinition;
+pub use crate::command::CommandParseError;
+pub use crate::command::ParsedCommand;
+pub use crate::command::ParsedCommandArgument;
 pub use crate::composer_action::ComposerAction;
+pub use crate::composer_error::ComposerError;
+pub use crate::composer_mode::ComposerMode;
+pub use crate::composer_model::delete_text::Direction;
 pub use crate::composer_model::ComposerModel;
 pub use crate::composer_state::ComposerState;
+pub use crate::composer_stats::ComposerStats;
 pub use crate::composer_update::ComposerUpdate;
 pub use crate::dom::nodes::DomNode;
+pub use crate::dom::parser::markdown::parse_markdown;
+pub use crate::dom::parser::markdown::parse_markdown_with_dialect;
+pub use crate::dom::parser::markdown::ParsedMarkdown;
+pub use crate::dom::parser::markdown::SourceSpan;
 pub use crate::dom::parser::parse;
 pub use crate::dom::DomCreationError;
 pub use crate::dom::DomHandle;
@@ -47,18 +80,44 @@ pub use crate::dom::ToRawText;
 pub use crate::dom::ToTree;
 pub use crate::dom::UnicodeString;
 pub use crate::dom::{MarkdownError, ToMarkdown};
+pub use crate::find_options::FindOptions;
 pub use crate::format_type::InlineFormatType;
+pub use crate::granularity::Granularity;
+pub use crate::keymap::Key;
+pub use crate::keymap::KeyCommand;
+pub use crate::keymap::KeyEvent;
+pub use crate::keymap::KeyModifiers;
+pub use crate::keymap::Keymap;
+pub use crate::keymap::KeymapPreset;
 pub use crate::link_action::LinkAction;
 pub use crate::link_action::LinkActionUpdate;
 pub use crate::list_type::ListType;
 pub use crate::location::Location;
+pub use crate::markdown_dialect::BulletMarker;
+pub use crate::markdown_dialect::EmphasisMarker;
+pub use crate::markdown_dialect::FenceStyle;
+pub use crate::markdown_dialect::HardBreakStyle;
+pub use crate::markdown_dialect::MarkdownDialect;
+pub use crate::markdown_dialect::MarkdownFlavor;
+pub use crate::markdown_dialect::SoftBreakPolicy;
+pub use crate::max_length::MaxLength;
+pub use crate::max_length::MaxLengthBehaviour;
+pub use crate::max_length::MaxLengthExceeded;
 pub use crate::mentions_state::MentionsState;
+pub use crate::menu_action::EmojiCandidate;
+pub use crate::menu_action::EmojiSuggestion;
 pub use crate::menu_action::MenuAction;
 pub use crate::menu_action::MenuActionSuggestion;
 pub use crate::menu_state::MenuState;
 pub use crate::menu_state::MenuStateUpdate;
+pub use crate::node_info::NodeInfo;
+pub use crate::node_info::NodeKind;
 pub use crate::pattern_key::PatternKey;
 pub use crate::suggestion_pattern::SuggestionPattern;
+pub use crate::suggestion_trigger::SuggestionTrigger;
+pub use crate::text_direction::TextDirection;
+pub use crate::text_transform::TextTransform;
 pub use crate::text_update::ReplaceAll;
 pub use crate::text_update::Selection;
 pub use crate::text_update::TextUpdate;
+pub use crate::typography::TypographyRule;
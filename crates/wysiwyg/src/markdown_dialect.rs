@@ -0,0 +1,140 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// The markdown syntax used when importing and exporting markdown.
+///
+/// The default is GitHub Flavored Markdown, with `*` for simple emphasis,
+/// `*` bullets, backtick fences and backslash hard breaks. Of the GFM
+/// extensions, only strikethrough is recognised when importing by default,
+/// see [MarkdownDialect::gfm_extensions].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarkdownDialect {
+    pub flavor: MarkdownFlavor,
+    /// The character used for simple emphasis. Strong emphasis uses the
+    /// other one, so that `<em><strong>…</strong></em>` is never written
+    /// as the ambiguous `***…***`.
+    pub emphasis: EmphasisMarker,
+    pub bullet: BulletMarker,
+    pub fence: FenceStyle,
+    /// How line breaks are written.
+    pub hard_break: HardBreakStyle,
+    /// What a single newline inside a paragraph becomes when importing.
+    pub soft_break: SoftBreakPolicy,
+    /// Whether importing GFM also recognises tables, task lists and bare
+    /// URLs as links, rather than keeping them as text.
+    pub gfm_extensions: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MarkdownFlavor {
+    /// Strict CommonMark. Strikethrough is written as raw HTML.
+    CommonMark,
+    /// GitHub Flavored Markdown: strikethrough, tables, task lists and
+    /// bare URLs as links.
+    Gfm,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmphasisMarker {
+    /// `*italic*` and `__bold__`.
+    Asterisk,
+    /// `_italic_` and `**bold**`.
+    Underscore,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BulletMarker {
+    Asterisk,
+    Dash,
+    Plus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FenceStyle {
+    Backticks,
+    Tildes,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HardBreakStyle {
+    /// A backslash at the end of the line.
+    Backslash,
+    /// Two spaces at the end of the line.
+    TrailingSpaces,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoftBreakPolicy {
+    /// Keep the newline as a line break.
+    LineBreak,
+    /// Join the lines with a space, as CommonMark renderers do.
+    Space,
+}
+
+impl MarkdownDialect {
+    pub const GFM: Self = Self {
+        flavor: MarkdownFlavor::Gfm,
+        emphasis: EmphasisMarker::Asterisk,
+        bullet: BulletMarker::Asterisk,
+        fence: FenceStyle::Backticks,
+        hard_break: HardBreakStyle::Backslash,
+        soft_break: SoftBreakPolicy::LineBreak,
+        gfm_extensions: true,
+    };
+
+    pub const COMMONMARK: Self = Self {
+        flavor: MarkdownFlavor::CommonMark,
+        ..Self::GFM
+    };
+
+    pub(crate) fn is_gfm(&self) -> bool {
+        self.flavor == MarkdownFlavor::Gfm
+    }
+
+    /// Whether tables, task lists and bare URLs are recognised on import.
+    pub(crate) fn has_gfm_extensions(&self) -> bool {
+        self.is_gfm() && self.gfm_extensions
+    }
+
+    /// The delimiters of simple and strong emphasis.
+    pub(crate) fn emphasis_delimiters(&self) -> (&'static str, &'static str) {
+        match self.emphasis {
+            EmphasisMarker::Asterisk => ("*", "__"),
+            EmphasisMarker::Underscore => ("_", "**"),
+        }
+    }
+
+    pub(crate) fn bullet_marker(&self) -> &'static str {
+        match self.bullet {
+            BulletMarker::Asterisk => "* ",
+            BulletMarker::Dash => "- ",
+            BulletMarker::Plus => "+ ",
+        }
+    }
+
+    pub(crate) fn fence(&self) -> &'static str {
+        match self.fence {
+            FenceStyle::Backticks => "```",
+            FenceStyle::Tildes => "~~~",
+        }
+    }
+}
+
+impl Default for MarkdownDialect {
+    fn default() -> Self {
+        let mut dialect = Self::GFM;
+        dialect.gfm_extensions = false;
+        dialect
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// A limit on the length of the message, counted in characters of its plain
+/// text (see [crate::ComposerStats::characters]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxLength {
+    pub characters: usize,
+    pub behaviour: MaxLengthBehaviour,
+}
+
+/// What happens to input that would make the message exceed its
+/// [MaxLength].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaxLengthBehaviour {
+    /// Insert as much of the input as fits, without splitting graphemes.
+    Truncate,
+    /// Ignore the input entirely.
+    Reject,
+}
+
+/// Reported in a [crate::ComposerUpdate] when input was changed or ignored
+/// because of the [MaxLength].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaxLengthExceeded {
+    Truncated,
+    Rejected,
+}
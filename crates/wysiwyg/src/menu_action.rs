@@ -19,9 +19,25 @@ pub enum MenuAction {
     Keep,
     None,
     Suggestion(SuggestionPattern),
+    EmojiSuggestion(EmojiSuggestion),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MenuActionSuggestion {
     pub suggestion_pattern: SuggestionPattern,
 }
+
+/// A `:shortcode` suggestion computed from the built-in emoji table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmojiSuggestion {
+    /// The pattern to pass to `replace_text_suggestion` alongside the
+    /// chosen emoji.
+    pub suggestion_pattern: SuggestionPattern,
+    pub candidates: Vec<EmojiCandidate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmojiCandidate {
+    pub shortcode: String,
+    pub emoji: String,
+}
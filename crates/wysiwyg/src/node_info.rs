@@ -0,0 +1,148 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::dom::nodes::{ContainerNodeKind, MentionNodeKind};
+use crate::{
+    DomHandle, DomNode, InlineFormatType, ListType, Location, UnicodeString,
+};
+
+/// A read-only snapshot of a node in the composer's DOM, for platforms that
+/// need to inspect the document structure, e.g. for custom rendering or
+/// accessibility.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeInfo {
+    /// The path of child indices leading to this node from the root.
+    pub handle: DomHandle,
+    pub kind: NodeKind,
+    /// HTML attributes of the node, e.g. the `href` of links and mentions.
+    pub attributes: Vec<(String, String)>,
+    /// The text of text nodes and the display text of mentions.
+    pub text: Option<String>,
+    /// The start of the node's text in the composer, in code units.
+    pub start: Location,
+    /// The end of the node's text, excluding any separator after a block.
+    pub end: Location,
+    pub children: Vec<NodeInfo>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NodeKind {
+    /// The root node of the document.
+    Generic,
+    Text,
+    LineBreak,
+    Mention,
+    Formatting(InlineFormatType),
+    Link,
+    List(ListType),
+    ListItem,
+    CodeBlock,
+    Quote,
+    Paragraph,
+}
+
+impl NodeInfo {
+    /// Build the snapshot of `node` and its descendants. `positions` maps
+    /// node handles to the start of their text.
+    pub(crate) fn from_node<S: UnicodeString>(
+        node: &DomNode<S>,
+        positions: &HashMap<DomHandle, usize>,
+    ) -> Self {
+        let handle = node.handle();
+        let start = positions.get(&handle).copied().unwrap_or_default();
+        let (kind, attributes, text, children) = match node {
+            DomNode::Container(container) => {
+                let kind = match container.kind() {
+                    ContainerNodeKind::Generic => NodeKind::Generic,
+                    ContainerNodeKind::Formatting(format) => {
+                        NodeKind::Formatting(format.clone())
+                    }
+                    ContainerNodeKind::Link(_) => NodeKind::Link,
+                    ContainerNodeKind::List(list_type) => {
+                        NodeKind::List(list_type.clone())
+                    }
+                    ContainerNodeKind::ListItem => NodeKind::ListItem,
+                    ContainerNodeKind::CodeBlock => NodeKind::CodeBlock,
+                    ContainerNodeKind::Quote => NodeKind::Quote,
+                    ContainerNodeKind::Paragraph => NodeKind::Paragraph,
+                };
+                let attributes = container
+                    .attributes()
+                    .map(|attrs| to_string_pairs(attrs))
+                    .unwrap_or_default();
+                let children = container
+                    .children()
+                    .iter()
+                    .map(|child| Self::from_node(child, positions))
+                    .collect();
+                (kind, attributes, None, children)
+            }
+            DomNode::Text(text) => (
+                NodeKind::Text,
+                Vec::new(),
+                Some(text.data().to_string()),
+                Vec::new(),
+            ),
+            DomNode::LineBreak(_) => {
+                (NodeKind::LineBreak, Vec::new(), None, Vec::new())
+            }
+            DomNode::Mention(mention) => {
+                let mut attributes = to_string_pairs(mention.attributes());
+                if let MentionNodeKind::MatrixUri { mention } = mention.kind() {
+                    attributes
+                        .push(("href".to_owned(), mention.uri().to_owned()));
+                }
+                (
+                    NodeKind::Mention,
+                    attributes,
+                    Some(mention.display_text().to_string()),
+                    Vec::new(),
+                )
+            }
+        };
+        Self {
+            handle,
+            kind,
+            attributes,
+            text,
+            start: Location::from(start),
+            end: Location::from(start + node.text_len()),
+            children,
+        }
+    }
+
+    /// Whether this is a block node, e.g. a paragraph or a list item.
+    pub fn is_block(&self) -> bool {
+        matches!(
+            self.kind,
+            NodeKind::Generic
+                | NodeKind::List(_)
+                | NodeKind::ListItem
+                | NodeKind::CodeBlock
+                | NodeKind::Quote
+                | NodeKind::Paragraph
+        )
+    }
+}
+
+fn to_string_pairs<S: UnicodeString>(
+    attrs: &[(S, S)],
+) -> Vec<(String, String)> {
+    attrs
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
@@ -0,0 +1,158 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use regex::Regex;
+
+use crate::ComposerError;
+
+/// A custom suggestion trigger provided by the client at runtime, e.g. `:`
+/// for emoji shortcodes or `!` for bot commands.
+///
+/// A trigger matches any word starting with its prefix character when the
+/// text typed after the prefix (the query) satisfies the trigger's rule. A
+/// match produces a [crate::PatternKey::Custom] suggestion keyed by the
+/// prefix, with the captured query as its text.
+#[derive(Clone, Debug)]
+pub struct SuggestionTrigger {
+    prefix: char,
+    rule: QueryRule,
+}
+
+#[derive(Clone, Debug)]
+enum QueryRule {
+    /// The whole query must match the regex. If the regex contains a
+    /// capture group, the first group is used as the suggestion text.
+    Regex(Regex),
+    /// The query must contain between `min` and `max` characters.
+    Length { min: usize, max: Option<usize> },
+}
+
+impl SuggestionTrigger {
+    /// Read a trigger prefix given as a string, which must be a single
+    /// character.
+    pub fn parse_prefix(prefix: &str) -> Result<char, ComposerError> {
+        let mut chars = prefix.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(ComposerError::InvalidSuggestionPrefix(prefix.to_owned())),
+        }
+    }
+
+    /// Create a trigger whose query must entirely match `pattern`.
+    pub fn with_regex(
+        prefix: char,
+        pattern: &str,
+    ) -> Result<Self, ComposerError> {
+        let regex = Regex::new(&format!("^(?:{pattern})$"))?;
+        Ok(Self {
+            prefix,
+            rule: QueryRule::Regex(regex),
+        })
+    }
+
+    /// Create a trigger whose query must contain at least `min` and, if
+    /// given, at most `max` characters.
+    pub fn with_length(prefix: char, min: usize, max: Option<usize>) -> Self {
+        Self {
+            prefix,
+            rule: QueryRule::Length { min, max },
+        }
+    }
+
+    pub fn prefix(&self) -> char {
+        self.prefix
+    }
+
+    /// Return the suggestion text for the given word if it starts with our
+    /// prefix and the rest of it satisfies our rule.
+    pub(crate) fn captured_query(&self, text: &str) -> Option<String> {
+        let query = text.strip_prefix(self.prefix)?;
+        match &self.rule {
+            QueryRule::Regex(regex) => {
+                let captures = regex.captures(query)?;
+                let captured = captures.get(1).or_else(|| captures.get(0))?;
+                Some(captured.as_str().to_owned())
+            }
+            QueryRule::Length { min, max } => {
+                let len = query.chars().count();
+                if len >= *min && max.map_or(true, |max| len <= max) {
+                    Some(query.to_owned())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SuggestionTrigger;
+    use crate::ComposerError;
+
+    #[test]
+    fn regex_trigger_matches_whole_query() {
+        let trigger = SuggestionTrigger::with_regex('!', "[a-z]+").unwrap();
+        assert_eq!(trigger.captured_query("!ban"), Some("ban".into()));
+        assert_eq!(trigger.captured_query("!ban2"), None);
+        assert_eq!(trigger.captured_query("ban"), None);
+    }
+
+    #[test]
+    fn regex_trigger_uses_first_capture_group() {
+        let trigger =
+            SuggestionTrigger::with_regex(':', "([a-z_]+):?").unwrap();
+        assert_eq!(trigger.captured_query(":smile:"), Some("smile".into()));
+        assert_eq!(trigger.captured_query(":smi"), Some("smi".into()));
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        assert!(matches!(
+            SuggestionTrigger::with_regex(':', "("),
+            Err(ComposerError::InvalidRegex(_))
+        ));
+    }
+
+    #[test]
+    fn prefix_must_be_a_single_character() {
+        assert_eq!(SuggestionTrigger::parse_prefix(":"), Ok(':'));
+        assert_eq!(SuggestionTrigger::parse_prefix("😄"), Ok('😄'));
+        for prefix in ["", "::"] {
+            assert_eq!(
+                SuggestionTrigger::parse_prefix(prefix),
+                Err(ComposerError::InvalidSuggestionPrefix(prefix.into()))
+            );
+        }
+    }
+
+    #[test]
+    fn length_trigger_respects_bounds() {
+        let trigger = SuggestionTrigger::with_length(':', 2, Some(4));
+        assert_eq!(trigger.captured_query(":s"), None);
+        assert_eq!(trigger.captured_query(":sm"), Some("sm".into()));
+        assert_eq!(trigger.captured_query(":smil"), Some("smil".into()));
+        assert_eq!(trigger.captured_query(":smile"), None);
+    }
+
+    #[test]
+    fn length_trigger_without_max_is_unbounded() {
+        let trigger = SuggestionTrigger::with_length('!', 0, None);
+        assert_eq!(trigger.captured_query("!"), Some("".into()));
+        assert_eq!(
+            trigger.captured_query("!averyverylongcommand"),
+            Some("averyverylongcommand".into())
+        );
+    }
+}
@@ -14,27 +14,45 @@
 
 #![cfg(test)]
 
+pub mod test_block_moves;
 pub mod test_characters;
+pub mod test_clear_formatting;
+pub mod test_commands;
+pub mod test_composer_mode;
+pub mod test_content_offsets;
 pub mod test_deleting;
+pub mod test_dom_inspection;
 pub mod test_emoji_replacement;
+pub mod test_find_replace;
 pub mod test_formatting;
+pub mod test_fuzz_regressions;
 pub mod test_get_link_action;
+pub mod test_graphemes;
+pub mod test_keymap;
 pub mod test_links;
 pub mod test_lists;
 pub mod test_lists_with_blocks;
+pub mod test_markdown_dialect;
+pub mod test_markdown_round_trip;
 pub mod test_mentions;
 pub mod test_menu_action;
 pub mod test_menu_state;
+pub mod test_nested_blocks;
+pub mod test_no_panics;
 pub mod test_paragraphs;
 pub mod test_remove_links;
 pub mod test_selection;
 pub mod test_set_content;
+pub mod test_stats;
 pub mod test_suggestions;
+pub mod test_text_direction;
+pub mod test_text_transform;
 pub mod test_to_markdown;
 pub mod test_to_message_html;
 pub mod test_to_plain_text;
 pub mod test_to_raw_text;
 pub mod test_to_tree;
+pub mod test_typography;
 pub mod test_undo_redo;
 pub mod testutils_composer_model;
 pub mod testutils_conversion;
@@ -0,0 +1,153 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::tests::testutils_composer_model::{cm, tx};
+
+#[test]
+fn move_paragraph_up() {
+    let mut model = cm("<p>abc</p><p>de|f</p><p>ghi</p>");
+    model.move_block_up().unwrap();
+    assert_eq!(tx(&model), "<p>de|f</p><p>abc</p><p>ghi</p>");
+}
+
+#[test]
+fn move_paragraph_down() {
+    let mut model = cm("<p>abc</p><p>de|f</p><p>ghi</p>");
+    model.move_block_down().unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>ghi</p><p>de|f</p>");
+}
+
+#[test]
+fn move_first_paragraph_up_does_nothing() {
+    let mut model = cm("<p>a|bc</p><p>def</p>");
+    model.move_block_up().unwrap();
+    assert_eq!(tx(&model), "<p>a|bc</p><p>def</p>");
+}
+
+#[test]
+fn move_last_paragraph_down_does_nothing() {
+    let mut model = cm("<p>abc</p><p>d|ef</p>");
+    model.move_block_down().unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>d|ef</p>");
+}
+
+#[test]
+fn move_several_paragraphs_down() {
+    let mut model = cm("<p>a{bc</p><p>de}|f</p><p>ghi</p>");
+    model.move_block_down().unwrap();
+    assert_eq!(tx(&model), "<p>ghi</p><p>a{bc</p><p>de}|f</p>");
+}
+
+#[test]
+fn move_paragraphs_ending_in_an_empty_one_down() {
+    let mut model = cm("<p>a{bc</p><p>}|</p><p>def</p>");
+    model.move_block_down().unwrap();
+    assert_eq!(tx(&model), "<p>def</p><p>a{bc</p><p>&nbsp;}|</p>");
+}
+
+#[test]
+fn move_paragraphs_ending_in_an_empty_last_one_down_does_nothing() {
+    let mut model = cm("<p>a{bc</p><p>}|</p>");
+    model.move_block_down().unwrap();
+    assert_eq!(tx(&model), "<p>a{bc</p><p>&nbsp;}|</p>");
+}
+
+#[test]
+fn move_list_item_up_within_its_list() {
+    let mut model = cm("<ol><li>abc</li><li>de|f</li></ol>");
+    model.move_block_up().unwrap();
+    assert_eq!(tx(&model), "<ol><li>de|f</li><li>abc</li></ol>");
+}
+
+#[test]
+fn move_first_list_item_up_does_nothing() {
+    let mut model = cm("<p>abc</p><ul><li>de|f</li></ul>");
+    model.move_block_up().unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><ul><li>de|f</li></ul>");
+}
+
+#[test]
+fn move_paragraph_down_past_a_list() {
+    let mut model = cm("<p>a|bc</p><ul><li>def</li><li>ghi</li></ul>");
+    model.move_block_down().unwrap();
+    assert_eq!(tx(&model), "<ul><li>def</li><li>ghi</li></ul><p>a|bc</p>");
+}
+
+#[test]
+fn move_paragraph_within_quote() {
+    let mut model =
+        cm("<blockquote><p>abc</p><p>d|ef</p></blockquote><p>ghi</p>");
+    model.move_block_up().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<blockquote><p>d|ef</p><p>abc</p></blockquote><p>ghi</p>"
+    );
+}
+
+#[test]
+fn move_code_block_down() {
+    let mut model = cm("<pre><code>a|bc\ndef</code></pre><p>ghi</p>");
+    model.move_block_down().unwrap();
+    assert_eq!(tx(&model), "<p>ghi</p><pre><code>a|bc\ndef</code></pre>");
+}
+
+#[test]
+fn move_selection_across_quote_and_paragraph() {
+    let mut model =
+        cm("<p>abc</p><blockquote><p>d{ef</p></blockquote><p>gh}|i</p>");
+    model.move_block_up().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<blockquote><p>d{ef</p></blockquote><p>gh}|i</p><p>abc</p>"
+    );
+}
+
+#[test]
+fn move_block_is_undoable() {
+    let mut model = cm("<p>abc</p><p>de|f</p>");
+    model.move_block_up().unwrap();
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>de|f</p>");
+}
+
+#[test]
+fn duplicate_paragraph() {
+    let mut model = cm("<p>a|bc</p><p>def</p>");
+    model.duplicate_block().unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>a|bc</p><p>def</p>");
+}
+
+#[test]
+fn duplicate_list_items() {
+    let mut model = cm("<ul><li>{abc</li><li>de}|f</li><li>ghi</li></ul>");
+    model.duplicate_block().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ul><li>abc</li><li>def</li><li>{abc</li><li>de}|f</li><li>ghi</li></ul>"
+    );
+}
+
+#[test]
+fn duplicate_empty_paragraph() {
+    let mut model = cm("<p>abc</p><p>|</p>");
+    model.duplicate_block().unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>&nbsp;</p><p>&nbsp;|</p>");
+}
+
+#[test]
+fn duplicate_without_blocks_does_nothing() {
+    let mut model = cm("ab|c");
+    model.duplicate_block().unwrap();
+    assert_eq!(tx(&model), "ab|c");
+}
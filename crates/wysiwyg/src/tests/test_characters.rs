@@ -49,11 +49,26 @@ fn typing_a_character_in_the_middle_inserts_it() {
 #[test]
 fn replacing_a_selection_past_the_end_is_harmless() {
     let mut model = cm("|");
-    model.select(Location::from(7), Location::from(7));
+    model.select(Location::from(7), Location::from(7)).unwrap();
     replace_text(&mut model, "Z");
     assert_eq!(tx(&model), "Z|");
 }
 
+#[test]
+fn typing_in_an_empty_quote_adds_a_paragraph() {
+    let mut model =
+        ComposerModel::from_html("<blockquote></blockquote>", 0, 0).unwrap();
+    replace_text(&mut model, "Z");
+    assert_eq!(tx(&model), "<blockquote><p>Z|</p></blockquote>");
+}
+
+#[test]
+fn typing_in_an_empty_list_adds_a_list_item() {
+    let mut model = ComposerModel::from_html("<ol></ol>", 0, 0).unwrap();
+    replace_text(&mut model, "Z");
+    assert_eq!(tx(&model), "<ol><li>Z|</li></ol>");
+}
+
 #[test]
 fn replacing_a_selection_with_a_character() {
     let mut model = cm("abc{def}|ghi");
@@ -81,14 +96,14 @@ fn typing_a_character_after_a_multi_codepoint_character() {
 fn replacing_an_explicit_text_range_works() {
     let mut model = cm("0123456789|");
     let new_text = utf16("654");
-    model.replace_text_in(new_text, 4, 7);
+    model.replace_text_in(new_text, 4, 7).unwrap();
     assert_eq!(tx(&model), "0123654|789");
 }
 
 #[test]
 fn can_replace_text_in_an_empty_composer_model() {
     let mut cm = ComposerModel::new();
-    cm.replace_text(utf16("foo"));
+    cm.replace_text(utf16("foo")).unwrap();
     assert_eq!(tx(&cm), "foo|");
 }
 
@@ -102,7 +117,7 @@ fn typing_a_character_when_spanning_two_tags_extends_the_first_tag() {
 #[test]
 fn replacing_an_explicit_range_when_spanning_two_tags_extends_the_first_tag() {
     let mut model = cm("|before<b>bold</b>after");
-    model.replace_text_in(utf16("XYZ"), 8, 13);
+    model.replace_text_in(utf16("XYZ"), 8, 13).unwrap();
     assert_eq!(tx(&model), "before<b>boXYZ|</b>er");
 }
 
@@ -145,7 +160,7 @@ fn typing_a_character_can_join_the_parents_and_grandparents() {
 fn typing_when_spanning_multiple_close_tags_extends_the_first_tag() {
     let mut model = cm("00<code><i>2<b>33{33</b></i>55</code>6}|6");
     replace_text(&mut model, "Z");
-    assert_eq!(tx(&model), "00<code><i>2<b>33Z|</b></i></code>6");
+    assert_eq!(tx(&model), "00<i><code>2<b>33Z|</b></code></i>6");
 }
 
 #[test]
@@ -266,6 +281,27 @@ fn replacing_text_with_empty_paragraphs_removes_nbsps_from_them() {
     );
 }
 
+#[test]
+fn typing_over_whole_paragraphs_keeps_the_new_text() {
+    let mut model = cm("<p>{a</p><p>b</p><p>}|c</p>");
+    replace_text(&mut model, "x");
+    assert_eq!(tx(&model), "<p>x|c</p>");
+}
+
+#[test]
+fn typing_over_a_quote_and_the_start_of_a_paragraph_keeps_the_new_text() {
+    let mut model = cm("<blockquote><p>{a</p></blockquote><p>}|c</p>");
+    replace_text(&mut model, "x");
+    assert_eq!(tx(&model), "<p>x|c</p>");
+}
+
+#[test]
+fn typing_over_a_paragraph_and_the_end_of_the_next_keeps_the_new_text() {
+    let mut model = cm("<p>{a</p><p>b}|</p>");
+    replace_text(&mut model, "x");
+    assert_eq!(tx(&model), "<p>x|</p>");
+}
+
 #[test]
 fn typing_html_does_not_break_anything() {
     let mut model = cm("|");
@@ -296,31 +332,62 @@ fn leading_and_trailing_newline_characters_insert_br_tags() {
     assert_eq!(tx(&model), "<p>&nbsp;</p><p>abc</p><p>&nbsp;|</p>");
 }
 
+#[test]
+fn pasting_lines_is_the_same_as_typing_them() {
+    let text = "abc\ndef\n\nghi\njkl";
+    for html in [
+        "|",
+        "<p>ab|cd</p>",
+        "<p>|</p><p>after</p>",
+        "<p><strong>ab|</strong></p>",
+        r#"<p><a href="https://matrix.org">ab|cd</a></p>"#,
+        "<blockquote><p>ab|</p><p>cd</p></blockquote>",
+        "<pre><code>ab|\ncd</code></pre>",
+        "<ol><li><p>ab|</p></li></ol>",
+        "<ol><li>ab|</li></ol>",
+    ] {
+        let mut pasted = cm(html);
+        replace_text(&mut pasted, text);
+
+        let mut typed = cm(html);
+        let mut lines = text.split('\n').peekable();
+        while let Some(line) = lines.next() {
+            if !line.is_empty() {
+                replace_text(&mut typed, line);
+            }
+            if lines.peek().is_some() {
+                typed.enter().unwrap();
+            }
+        }
+        assert_eq!(tx(&pasted), tx(&typed), "Pasting into {html}");
+    }
+}
+
 #[test]
 #[allow(deprecated)]
 fn inserting_a_line_break_and_text_before_a_line_break_works() {
     let mut model = cm("|{AAA}");
-    model.add_line_break();
-    model.select(Location::from(0), Location::from(0));
+    model.add_line_break().unwrap();
+    model.select(Location::from(0), Location::from(0)).unwrap();
     // Inserting a line break at index 0 (no text node before it) can cause issues
-    model.add_line_break();
-    model.select(Location::from(0), Location::from(0));
+    model.add_line_break().unwrap();
+    model.select(Location::from(0), Location::from(0)).unwrap();
     // Inserting text before a line break with no text node before it is a special case too
-    model.replace_text("Test".into());
+    model.replace_text("Test".into()).unwrap();
     assert_eq!(tx(&model), "Test|<br /><br />");
 }
 
 #[test]
 fn insert_text_between_line_breaks() {
     let mut model = cm("A<br />|<br />B");
-    model.replace_text(utf16("C"));
+    model.replace_text(utf16("C")).unwrap();
     assert_eq!(tx(&model), "<p>A</p><p>C|</p><p>B</p>");
 }
 
 #[test]
 fn insert_text_between_line_breaks_in_format_node() {
     let mut model = cm("A<br /><b>|<br />B</b>");
-    model.replace_text(utf16("C"));
+    model.replace_text(utf16("C")).unwrap();
     assert_eq!(tx(&model), "<p>A</p><p><b>C|</b></p><p><b>B</b></p>");
 }
 
@@ -361,5 +428,5 @@ fn multiple_leading_and_trailing_whitespace_are_all_replaced_with_nbsp() {
 }
 
 fn replace_text(model: &mut ComposerModel<Utf16String>, new_text: &str) {
-    model.replace_text(utf16(new_text));
+    model.replace_text(utf16(new_text)).unwrap();
 }
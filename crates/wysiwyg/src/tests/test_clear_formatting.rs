@@ -0,0 +1,176 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::{ActionState, ComposerAction};
+
+#[test]
+fn clearing_without_formatting_does_nothing() {
+    let mut model = cm("{abc}|");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(tx(&model), "{abc}|");
+    assert!(model.previous_states.is_empty());
+}
+
+#[test]
+fn clear_removes_all_inline_formats() {
+    let mut model =
+        cm("{<strong><em>a</em></strong><del>b</del><u>c</u><code>d</code>}|");
+    model.clear_formatting(false).unwrap();
+    assert_eq!(tx(&model), "{abcd}|");
+}
+
+#[test]
+fn clear_splits_formatting_at_selection_edges() {
+    let mut model = cm("<strong>ab{cd</strong><em>ef}|gh</em>");
+    model.clear_formatting(false).unwrap();
+    assert_eq!(tx(&model), "<strong>ab</strong>{cdef}|<em>gh</em>");
+}
+
+#[test]
+fn clear_removes_links() {
+    let mut model = cm("{<a href=\"https://matrix.org\">matrix</a>}|");
+    model.clear_formatting(false).unwrap();
+    assert_eq!(tx(&model), "{matrix}|");
+}
+
+#[test]
+fn clear_splits_links_at_selection_edges() {
+    let mut model = cm("<a href=\"https://matrix.org\">ma{tr}|ix</a>");
+    model.clear_formatting(false).unwrap();
+    assert_eq!(
+        tx(&model),
+        "<a href=\"https://matrix.org\">ma</a>{tr}|<a href=\"https://matrix.org\">ix</a>"
+    );
+}
+
+#[test]
+fn clear_without_blocks_keeps_lists() {
+    let mut model = cm("<ul><li>{<strong>abc</strong>}|</li></ul>");
+    model.clear_formatting(false).unwrap();
+    assert_eq!(tx(&model), "<ul><li>{abc}|</li></ul>");
+}
+
+#[test]
+fn clear_with_blocks_removes_list() {
+    let mut model = cm("<ul><li>{abc</li><li>def}|</li></ul>");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(tx(&model), "<p>{abc</p><p>def}|</p>");
+}
+
+#[test]
+fn clear_with_blocks_splits_list() {
+    let mut model = cm("<ol><li>abc</li><li>d{e}|f</li><li>ghi</li></ol>");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ol><li>abc</li></ol><p>d{e}|f</p><ol><li>ghi</li></ol>"
+    );
+}
+
+#[test]
+fn clear_with_blocks_splits_quote() {
+    let mut model =
+        cm("<blockquote><p>abc</p><p>d{e}|f</p><p>ghi</p></blockquote>");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(
+        tx(&model),
+        "<blockquote><p>abc</p></blockquote><p>d{e}|f</p><blockquote><p>ghi</p></blockquote>"
+    );
+}
+
+#[test]
+fn clear_with_blocks_removes_code_block() {
+    let mut model = cm("<pre><code>a{bc}|</code></pre>");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(tx(&model), "<p>a{bc}|</p>");
+}
+
+#[test]
+fn clear_with_blocks_at_cursor_removes_quote() {
+    let mut model = cm("<blockquote><p>ab|c</p></blockquote>");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(tx(&model), "<p>ab|c</p>");
+}
+
+#[test]
+fn clear_with_blocks_removes_nested_blocks() {
+    let mut model = cm("<blockquote><ul><li>{abc}|</li></ul></blockquote>");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(tx(&model), "<p>{abc}|</p>");
+}
+
+#[test]
+fn clear_at_cursor_turns_off_active_formats() {
+    let mut model = cm("<strong><em>abc|</em></strong>");
+    model.clear_formatting(false).unwrap();
+    assert_eq!(
+        model.action_states().get(&ComposerAction::Bold),
+        Some(&ActionState::Enabled)
+    );
+    model.replace_text("d".into()).unwrap();
+    assert_eq!(tx(&model), "<strong><em>abc</em></strong>d|");
+}
+
+#[test]
+fn clear_at_cursor_discards_pending_formats() {
+    let mut model = cm("abc|");
+    model.bold().unwrap();
+    model.clear_formatting(false).unwrap();
+    model.replace_text("d".into()).unwrap();
+    assert_eq!(tx(&model), "abcd|");
+}
+
+#[test]
+fn clear_is_a_single_undo_step() {
+    let mut model =
+        cm("<blockquote><p><strong>{abc}|</strong></p></blockquote>");
+    model.clear_formatting(true).unwrap();
+    assert_eq!(tx(&model), "<p>{abc}|</p>");
+    model.undo().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<blockquote><p><strong>{abc}|</strong></p></blockquote>"
+    );
+}
+
+#[test]
+fn clear_formatting_action_is_disabled_without_formatting() {
+    let mut model = cm("abc|");
+    model.select(0.into(), 3.into()).unwrap();
+    assert_eq!(
+        model.action_states().get(&ComposerAction::ClearFormatting),
+        Some(&ActionState::Disabled)
+    );
+}
+
+#[test]
+fn clear_formatting_action_is_enabled_with_formatting() {
+    let mut model = cm("a<em>b</em>c|");
+    model.select(0.into(), 3.into()).unwrap();
+    assert_eq!(
+        model.action_states().get(&ComposerAction::ClearFormatting),
+        Some(&ActionState::Enabled)
+    );
+}
+
+#[test]
+fn clear_formatting_action_is_enabled_in_a_quote() {
+    let mut model = cm("<blockquote><p>abc|</p></blockquote>");
+    model.select(1.into(), 1.into()).unwrap();
+    assert_eq!(
+        model.action_states().get(&ComposerAction::ClearFormatting),
+        Some(&ActionState::Enabled)
+    );
+}
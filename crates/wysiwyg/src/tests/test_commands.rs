@@ -0,0 +1,227 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::{
+    tests::testutils_composer_model::{cm, tx},
+    CommandArgumentDefinition, CommandArgumentKind, CommandArgumentValue,
+    CommandDefinition, CommandParseError, ComposerAction, ComposerError,
+    ComposerModel, ParsedCommand, ParsedCommandArgument,
+};
+
+#[test]
+fn plain_message_is_not_a_command() {
+    let model = model_with_commands("hello world|");
+    assert!(!model.is_command());
+    assert_eq!(model.parse_command(), Ok(None));
+}
+
+#[test]
+fn unknown_command_is_not_a_command() {
+    let model = model_with_commands("/unknown arg|");
+    assert!(!model.is_command());
+    assert_eq!(model.parse_command(), Ok(None));
+}
+
+#[test]
+fn command_is_found_from_its_name() {
+    assert!(model_with_commands("/me|").is_command());
+    assert!(model_with_commands("/me waves|").is_command());
+    let long_message = format!("/me {}|", "waves at everyone ".repeat(500));
+    assert!(model_with_commands(&long_message).is_command());
+}
+
+#[test]
+fn nothing_is_a_command_without_registered_commands() {
+    let model = cm("/me waves|");
+    assert!(!model.is_command());
+}
+
+#[test]
+fn command_with_text_argument_is_parsed() {
+    let model = model_with_commands("/me waves at everyone|");
+    assert_eq!(
+        model.parse_command(),
+        Ok(Some(ParsedCommand {
+            name: "me".into(),
+            arguments: vec![text_arg("message", "waves at everyone")],
+        }))
+    );
+}
+
+#[test]
+fn command_with_user_mention_is_parsed() {
+    let mut model = model_with_commands("/invite |");
+    model
+        .insert_mention(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "Alice".into(),
+            vec![],
+        )
+        .unwrap();
+    model.replace_text("be nice".into()).unwrap();
+    assert_eq!(
+        model.parse_command(),
+        Ok(Some(ParsedCommand {
+            name: "invite".into(),
+            arguments: vec![
+                ParsedCommandArgument {
+                    name: "user".into(),
+                    value: CommandArgumentValue::User {
+                        user_id: "@alice:matrix.org".into(),
+                        display_name: "Alice".into(),
+                    },
+                },
+                text_arg("reason", "be nice"),
+            ],
+        }))
+    );
+}
+
+#[test]
+fn command_with_typed_user_id_is_parsed() {
+    let model = model_with_commands("/invite @alice:matrix.org|");
+    assert_eq!(
+        model.parse_command(),
+        Ok(Some(ParsedCommand {
+            name: "invite".into(),
+            arguments: vec![ParsedCommandArgument {
+                name: "user".into(),
+                value: CommandArgumentValue::User {
+                    user_id: "@alice:matrix.org".into(),
+                    display_name: "@alice:matrix.org".into(),
+                },
+            }],
+        }))
+    );
+}
+
+#[test]
+fn command_with_typed_room_alias_is_parsed() {
+    let model = model_with_commands("/join #room:matrix.org|");
+    assert_eq!(
+        model.parse_command(),
+        Ok(Some(ParsedCommand {
+            name: "join".into(),
+            arguments: vec![ParsedCommandArgument {
+                name: "room".into(),
+                value: CommandArgumentValue::Room {
+                    room_id_or_alias: "#room:matrix.org".into(),
+                },
+            }],
+        }))
+    );
+}
+
+#[test]
+fn command_with_missing_argument_reports_it() {
+    let model = model_with_commands("/invite|");
+    assert_eq!(
+        model.parse_command(),
+        Err(ComposerError::CommandParseError(
+            CommandParseError::MissingArgument("user".into())
+        ))
+    );
+}
+
+#[test]
+fn command_with_invalid_argument_reports_it() {
+    let model = model_with_commands("/invite alice|");
+    assert_eq!(
+        model.parse_command(),
+        Err(ComposerError::CommandParseError(
+            CommandParseError::InvalidArgument("user".into())
+        ))
+    );
+}
+
+#[test]
+fn command_with_too_many_arguments_reports_it() {
+    let model = model_with_commands("/join #room:matrix.org now|");
+    assert_eq!(
+        model.parse_command(),
+        Err(ComposerError::CommandParseError(
+            CommandParseError::UnexpectedArgument("now".into())
+        ))
+    );
+}
+
+#[test]
+fn formatting_is_refused_for_commands() {
+    let mut model = model_with_commands("{/me waves}|");
+    model.bold().unwrap();
+    assert_eq!(tx(&model), "{/me waves}|");
+    model.code_block().unwrap();
+    assert_eq!(tx(&model), "{/me waves}|");
+}
+
+#[test]
+fn formatting_actions_are_disabled_for_commands() {
+    let mut model = model_with_commands("|");
+    model.replace_text("/me waves".into()).unwrap();
+    assert!(!model.action_is_enabled(ComposerAction::Bold));
+    assert!(!model.action_is_enabled(ComposerAction::Quote));
+    assert!(model.action_is_enabled(ComposerAction::Undo));
+}
+
+#[test]
+fn formatting_is_allowed_for_non_commands() {
+    let mut model = model_with_commands("{/shrug}|");
+    model.bold().unwrap();
+    assert_eq!(tx(&model), "<strong>{/shrug}|</strong>");
+}
+
+fn model_with_commands(text: &str) -> ComposerModel<Utf16String> {
+    let mut model = cm(text);
+    model.set_commands(vec![
+        CommandDefinition {
+            name: "me".into(),
+            arguments: vec![argument("message", CommandArgumentKind::Text)],
+        },
+        CommandDefinition {
+            name: "invite".into(),
+            arguments: vec![
+                argument("user", CommandArgumentKind::User),
+                CommandArgumentDefinition {
+                    optional: true,
+                    ..argument("reason", CommandArgumentKind::Text)
+                },
+            ],
+        },
+        CommandDefinition {
+            name: "join".into(),
+            arguments: vec![argument("room", CommandArgumentKind::Room)],
+        },
+    ]);
+    model
+}
+
+fn argument(
+    name: &str,
+    kind: CommandArgumentKind,
+) -> CommandArgumentDefinition {
+    CommandArgumentDefinition {
+        name: name.into(),
+        kind,
+        optional: false,
+    }
+}
+
+fn text_arg(name: &str, value: &str) -> ParsedCommandArgument {
+    ParsedCommandArgument {
+        name: name.into(),
+        value: CommandArgumentValue::Text(value.into()),
+    }
+}
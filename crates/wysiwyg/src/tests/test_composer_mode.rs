@@ -0,0 +1,166 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::tests::testutils_conversion::utf16;
+use crate::{ComposerAction, ComposerMode, ComposerModel};
+
+fn md(model: &ComposerModel<Utf16String>) -> String {
+    model.get_content_as_markdown().unwrap().to_string()
+}
+
+#[test]
+fn models_start_in_rich_mode() {
+    assert_eq!(cm("|").mode(), ComposerMode::Rich);
+}
+
+#[test]
+fn switching_to_plain_shows_the_markdown_source() {
+    let mut model = cm("<b>foo</b> bar|");
+    model.switch_to_plain().unwrap();
+    assert_eq!(model.mode(), ComposerMode::Plain);
+    assert_eq!(tx(&model), "<p>__foo__ bar|</p>");
+    assert_eq!(md(&model), "__foo__ bar");
+}
+
+#[test]
+fn switching_to_plain_keeps_the_cursor_in_the_same_text() {
+    let mut model = cm("<b>fo|o</b> bar");
+    model.switch_to_plain().unwrap();
+    assert_eq!(tx(&model), "<p>__fo|o__ bar</p>");
+}
+
+#[test]
+fn switching_to_plain_keeps_the_selected_text() {
+    let mut model = cm("<em>a {b</em> c}|");
+    model.switch_to_plain().unwrap();
+    assert_eq!(tx(&model), "<p>*a {b* c}|</p>");
+}
+
+#[test]
+fn each_line_of_the_source_is_a_paragraph() {
+    let mut model = cm("<ul><li>a</li><li>b|</li></ul>");
+    model.switch_to_plain().unwrap();
+    assert_eq!(tx(&model), "<p>* a</p><p>* b|</p>");
+}
+
+#[test]
+fn switching_to_rich_parses_the_source() {
+    let mut model = cm("|");
+    model.switch_to_plain().unwrap();
+    model.replace_text(utf16("**foo** bar")).unwrap();
+    model.enter().unwrap();
+    model.replace_text(utf16("> baz")).unwrap();
+    model.switch_to_rich().unwrap();
+    assert_eq!(model.mode(), ComposerMode::Rich);
+    assert_eq!(
+        tx(&model),
+        "<p><strong>foo</strong> bar</p><blockquote><p>baz|</p></blockquote>"
+    );
+}
+
+#[test]
+fn switching_to_rich_keeps_the_cursor_in_the_same_text() {
+    let mut model = cm("|");
+    model.switch_to_plain().unwrap();
+    model.replace_text(utf16("a **bold** word")).unwrap();
+    model.select(5.into(), 5.into()).unwrap();
+    model.switch_to_rich().unwrap();
+    assert_eq!(tx(&model), "a <strong>b|old</strong> word");
+}
+
+#[test]
+fn switching_back_and_forth_keeps_content_and_selection() {
+    let mut model = cm("<p><em>a</em></p><ol><li>b{c</li><li>d}|</li></ol>");
+    let html = model.get_content_as_html();
+    let selection = model.get_selection();
+    model.switch_to_plain().unwrap();
+    model.switch_to_rich().unwrap();
+    assert_eq!(model.get_content_as_html(), html);
+    assert_eq!(model.get_selection(), selection);
+}
+
+#[test]
+fn switching_to_the_current_mode_does_nothing() {
+    let mut model = cm("a|");
+    model.switch_to_rich().unwrap();
+    assert_eq!(tx(&model), "a|");
+    model.switch_to_plain().unwrap();
+    model.switch_to_plain().unwrap();
+    assert_eq!(tx(&model), "<p>a|</p>");
+}
+
+#[test]
+fn mentions_stay_pills_in_plain_mode() {
+    let mut model = cm("|");
+    model
+        .insert_mention(
+            utf16("https://matrix.to/#/@alice:example.org"),
+            utf16("Alice"),
+            vec![],
+        )
+        .unwrap();
+    model.switch_to_plain().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<p><a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:example.org\" contenteditable=\"false\">Alice</a>&nbsp;|</p>"
+    );
+    assert_eq!(
+        md(&model),
+        "<a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:example.org\" contenteditable=\"false\">Alice</a> "
+    );
+    assert_eq!(
+        model.get_content_as_message_markdown().unwrap().to_string(),
+        "Alice "
+    );
+    // Markdown drops the trailing space
+    model.switch_to_rich().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:example.org\" contenteditable=\"false\">Alice</a>|"
+    );
+}
+
+#[test]
+fn formatting_is_refused_in_plain_mode() {
+    let mut model = cm("{foo}|");
+    model.switch_to_plain().unwrap();
+    model.bold().unwrap();
+    model.ordered_list().unwrap();
+    assert_eq!(tx(&model), "<p>{foo}|</p>");
+    assert!(model.action_is_disabled(ComposerAction::Bold));
+    assert!(model.action_is_disabled(ComposerAction::Quote));
+    model.switch_to_rich().unwrap();
+    assert!(model.action_is_enabled(ComposerAction::Bold));
+}
+
+#[test]
+fn setting_content_in_plain_mode_shows_its_source() {
+    let mut model = cm("|");
+    model.switch_to_plain().unwrap();
+    model.set_content_from_markdown(&utf16("* a\n* b")).unwrap();
+    assert_eq!(tx(&model), "<p>* a</p><p>* b|</p>");
+    model.set_content_from_html(&utf16("<i>c</i>")).unwrap();
+    assert_eq!(tx(&model), "<p>*c*|</p>");
+}
+
+#[test]
+fn message_html_is_rendered_from_the_source_in_plain_mode() {
+    let mut model = cm("|");
+    model.switch_to_plain().unwrap();
+    model.replace_text(utf16("*a*")).unwrap();
+    assert_eq!(model.get_content_as_message_html(), "<em>a</em>");
+}
@@ -0,0 +1,190 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::dom::offset_markers::take_markers;
+use crate::dom::to_markdown::MarkdownOptions;
+use crate::dom::to_plain_text::ToPlainText;
+use crate::tests::testutils_composer_model::cm;
+use crate::tests::testutils_conversion::utf16;
+use crate::{ComposerModel, Location, ToMarkdown};
+
+fn markdown_offsets(model: &ComposerModel<Utf16String>) -> Vec<usize> {
+    (0..=model.state.dom.text_len())
+        .map(|l| {
+            model
+                .location_to_markdown_offset(Location::from(l))
+                .unwrap()
+        })
+        .collect()
+}
+
+fn plain_text_offsets(model: &ComposerModel<Utf16String>) -> Vec<usize> {
+    (0..=model.state.dom.text_len())
+        .map(|l| model.location_to_plain_text_offset(Location::from(l)))
+        .collect()
+}
+
+fn markdown_locations(model: &ComposerModel<Utf16String>) -> Vec<usize> {
+    (0..=model.get_content_as_markdown().unwrap().len())
+        .map(|o| model.markdown_offset_to_location(o).unwrap().into())
+        .collect()
+}
+
+#[test]
+fn text_is_found_inside_formatting() {
+    let model = cm("a <b>bc</b>|");
+    assert_eq!(model.get_content_as_markdown().unwrap(), "a __bc__");
+    assert_eq!(markdown_offsets(&model), [0, 1, 4, 5, 6]);
+    assert_eq!(markdown_locations(&model), [0, 1, 2, 2, 2, 3, 4, 4, 4]);
+}
+
+#[test]
+fn list_markers_are_skipped() {
+    let model = cm("<ul><li>a</li><li>b|</li></ul>");
+    assert_eq!(model.get_content_as_markdown().unwrap(), "* a\n* b");
+    assert_eq!(markdown_offsets(&model), [2, 3, 6, 7]);
+    assert_eq!(markdown_locations(&model), [0, 0, 0, 1, 2, 2, 2, 3]);
+}
+
+#[test]
+fn indentation_inside_code_blocks_is_skipped() {
+    let model = cm("<blockquote><pre><code>a\nb|</code></pre></blockquote>");
+    assert_eq!(
+        model.get_content_as_markdown().unwrap(),
+        "> ```\n> a\n> b\n> ```\n"
+    );
+    let b = model
+        .location_to_markdown_offset(Location::from(2))
+        .unwrap();
+    assert_eq!(b, 12);
+    assert_eq!(
+        model.markdown_offset_to_location(b).unwrap(),
+        Location::from(2)
+    );
+}
+
+#[test]
+fn escapes_kept_from_import_are_skipped() {
+    let mut model = cm("|");
+    model.set_content_from_markdown(&utf16("a \\*b")).unwrap();
+    assert_eq!(model.get_content_as_markdown().unwrap(), "a \\*b");
+    assert_eq!(markdown_offsets(&model), [0, 1, 3, 4, 5]);
+}
+
+#[test]
+fn mentions_are_a_single_location() {
+    let mut model = cm("|");
+    model
+        .insert_mention(
+            utf16("https://matrix.to/#/@alice:example.org"),
+            utf16("Alice"),
+            vec![],
+        )
+        .unwrap();
+    let markdown = model.get_content_as_markdown().unwrap();
+    let mention_end = markdown.len() - 1;
+    assert_eq!(markdown_offsets(&model), [0, mention_end, mention_end + 1]);
+    assert_eq!(
+        model.markdown_offset_to_location(mention_end).unwrap(),
+        Location::from(1)
+    );
+    assert_eq!(model.get_content_as_plain_text(), "Alice ");
+    assert_eq!(plain_text_offsets(&model), [0, 5, 6]);
+    assert_eq!(model.plain_text_offset_to_location(5), Location::from(1));
+}
+
+#[test]
+fn paragraphs_are_separated_by_line_breaks_in_plain_text() {
+    let model = cm("<p>a</p><p>bc|</p>");
+    assert_eq!(model.get_content_as_plain_text(), "a\nbc\n");
+    assert_eq!(plain_text_offsets(&model), [0, 1, 2, 3, 4]);
+    assert_eq!(model.plain_text_offset_to_location(3), Location::from(3));
+}
+
+#[test]
+fn offsets_are_in_code_units() {
+    let model = cm("<em>😀a|</em>");
+    assert_eq!(model.get_content_as_markdown().unwrap(), "*😀a*");
+    assert_eq!(markdown_offsets(&model), [1, 2, 3, 4]);
+    assert_eq!(
+        model.markdown_offset_to_location(3).unwrap(),
+        Location::from(2)
+    );
+}
+
+#[test]
+fn markdown_source_maps_to_itself_in_plain_mode() {
+    let mut model = cm("<b>a</b> b|");
+    model.switch_to_plain().unwrap();
+    assert_eq!(model.get_content_as_markdown().unwrap(), "__a__ b");
+    assert_eq!(markdown_offsets(&model), [0, 1, 2, 3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn tracking_offsets_does_not_change_the_output() {
+    for html in [
+        "a <b>b<i>c</i></b> <a href=\"https://x.org\">d</a>",
+        "<ol><li><p>a</p><ul><li>b</li></ul></li><li>c<br />d</li></ol>",
+        "<blockquote><p>a</p><p></p><pre><code>b\n\nc</code></pre></blockquote>",
+        "<p>a<br /><br />b</p><p><code>c<br />d</code></p>",
+        "<ul><li><blockquote><p>a</p></blockquote></li><li></li></ul>",
+    ] {
+        let model = cm(&format!("{html}|"));
+        let mut options = MarkdownOptions::empty();
+        options.insert(MarkdownOptions::TRACK_OFFSETS);
+        let mut marked = Utf16String::new();
+        model
+            .state
+            .dom
+            .fmt_markdown(&mut marked, &options, false)
+            .unwrap();
+        let (markdown, _) = take_markers(&marked);
+        assert_eq!(markdown, model.state.dom.to_markdown().unwrap(), "{html}");
+
+        let mut marked = Utf16String::new();
+        model.state.dom.fmt_plain_text(&mut marked, true);
+        let (text, _) = take_markers(&marked);
+        assert_eq!(text, model.get_content_as_plain_text(), "{html}");
+    }
+}
+
+#[test]
+fn noncharacters_in_the_text_are_not_taken_for_markers() {
+    let mut model = cm("|");
+    model.replace_text(utf16("a\u{FDD0}b c")).unwrap();
+    assert_eq!(plain_text_offsets(&model), [0, 1, 2, 3, 4, 5]);
+    assert_eq!(markdown_offsets(&model), [0, 1, 2, 3, 4, 5]);
+    model.select(Location::from(3), Location::from(3)).unwrap();
+    model.switch_to_plain().unwrap();
+    assert_eq!(model.get_content_as_markdown().unwrap(), "a\u{FDD0}b c");
+    assert_eq!(
+        model.get_selection(),
+        (Location::from(3), Location::from(3))
+    );
+}
+
+#[test]
+fn malformed_markers_in_links_are_kept_as_text() {
+    let mut model = cm("|");
+    model
+        .set_link_with_text(
+            utf16("https://x.org/\u{FDD0}\u{FDEF}\u{FDD1}"),
+            utf16("a"),
+            vec![],
+        )
+        .unwrap();
+    assert_eq!(markdown_offsets(&model).len(), 2);
+}
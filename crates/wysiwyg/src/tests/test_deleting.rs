@@ -20,7 +20,7 @@ use crate::{
 #[test]
 fn backspacing_a_character_at_the_end_deletes_it() {
     let mut model = cm("abc|");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "ab|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -28,7 +28,7 @@ fn backspacing_a_character_at_the_end_deletes_it() {
 #[test]
 fn backspacing_a_character_at_the_beginning_does_nothing() {
     let mut model = cm("|abc");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|abc");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -36,7 +36,7 @@ fn backspacing_a_character_at_the_beginning_does_nothing() {
 #[test]
 fn backspacing_a_character_in_the_middle_deletes_it() {
     let mut model = cm("ab|c");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "a|c");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -44,7 +44,7 @@ fn backspacing_a_character_in_the_middle_deletes_it() {
 #[test]
 fn backspacing_a_selection_deletes_it() {
     let mut model = cm("a{bc}|");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "a|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -52,7 +52,7 @@ fn backspacing_a_selection_deletes_it() {
 #[test]
 fn backspacing_a_backwards_selection_deletes_it() {
     let mut model = cm("a|{bc}");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "a|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -61,8 +61,8 @@ fn backspacing_a_backwards_selection_deletes_it() {
 #[allow(deprecated)]
 fn backspacing_a_lone_line_break_deletes_it() {
     let mut model = ComposerModel::new();
-    model.add_line_break();
-    model.backspace();
+    model.add_line_break().unwrap();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -71,7 +71,7 @@ fn backspacing_a_lone_line_break_deletes_it() {
 #[allow(deprecated)]
 fn backspacing_a_line_break_deletes_it() {
     let mut model = cm("abc|");
-    let update = model.add_line_break();
+    let update = model.add_line_break().unwrap();
 
     let replace_all = match update.text_update {
         TextUpdate::Keep => panic!("expected ReplaceAll"),
@@ -82,8 +82,8 @@ fn backspacing_a_line_break_deletes_it() {
     assert_eq!(replace_all.start, 4);
     assert_eq!(replace_all.end, 4);
 
-    model.backspace();
-    model.backspace();
+    model.backspace().unwrap();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "ab|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -91,7 +91,7 @@ fn backspacing_a_line_break_deletes_it() {
 #[test]
 fn deleting_a_character_at_the_end_does_nothing() {
     let mut model = cm("abc|");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "abc|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -99,7 +99,7 @@ fn deleting_a_character_at_the_end_does_nothing() {
 #[test]
 fn deleting_a_character_at_the_beginning_deletes_it() {
     let mut model = cm("|abc");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "|bc");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -107,7 +107,7 @@ fn deleting_a_character_at_the_beginning_deletes_it() {
 #[test]
 fn deleting_a_character_in_the_middle_deletes_it() {
     let mut model = cm("a|bc");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "a|c");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -115,7 +115,7 @@ fn deleting_a_character_in_the_middle_deletes_it() {
 #[test]
 fn deleting_a_selection_deletes_it() {
     let mut model = cm("a{bc}|");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "a|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -123,7 +123,7 @@ fn deleting_a_selection_deletes_it() {
 #[test]
 fn deleting_a_backwards_selection_deletes_it() {
     let mut model = cm("a|{bc}");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "a|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -131,7 +131,7 @@ fn deleting_a_backwards_selection_deletes_it() {
 #[test]
 fn deleting_a_range_removes_it() {
     let mut model = cm("abcd|");
-    model.delete_in(1, 3);
+    model.delete_in(1, 3).unwrap();
     assert_eq!(tx(&model), "a|d");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -139,7 +139,7 @@ fn deleting_a_range_removes_it() {
 #[test]
 fn deleting_when_spanning_two_separate_identical_tags_joins_them() {
     let mut model = cm("<b>bo{ld</b> plain <b>BO}|LD</b>");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<b>bo|LD</b>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -152,7 +152,7 @@ fn deleting_across_list_items_joins_them() {
             <li>33</li>\
             <li>4}|4</li>\
         </ol>");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<ol>\
@@ -172,7 +172,7 @@ fn deleting_across_lists_joins_them() {
             <li>33</li>\
             <li>4}|4</li>\
         </ol>");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>1|4</li></ol>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -190,7 +190,7 @@ fn deleting_across_lists_joins_them_nested() {
             <li>33</li>\
             <li>4}|4</li>\
         </ol>");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>1|4</li></ol>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -198,7 +198,7 @@ fn deleting_across_lists_joins_them_nested() {
 #[test]
 fn deleting_across_formatting_different_types() {
     let mut model = cm("<b><i>some {italic</i></b> and}| <b>bold</b> text");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<b><i>some&nbsp;|</i></b> <b>bold</b> text");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -206,7 +206,7 @@ fn deleting_across_formatting_different_types() {
 #[test]
 fn deleting_across_formatting_different_types_on_node_boundary() {
     let mut model = cm("<b><i>some {italic</i></b> and }|<b>bold</b> text");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<b><i>some&nbsp;|</i>bold</b> text");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -214,7 +214,7 @@ fn deleting_across_formatting_different_types_on_node_boundary() {
 #[test]
 fn deleting_in_nested_structure_and_format_nodes_works() {
     let mut model = cm("<ul><li>A</li><li><b>B{B</b><b>C}|C</b></li></ul>");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<ul><li>A</li><li><b>B|C</b></li></ul>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -222,7 +222,7 @@ fn deleting_in_nested_structure_and_format_nodes_works() {
 #[test]
 fn deleting_empty_list_item() {
     let mut model = cm("<ul><li>A{</li><li>}|</li></ul>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<ul><li>A|</li></ul>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -230,8 +230,8 @@ fn deleting_empty_list_item() {
 #[test]
 fn deleting_a_newline_deletes_it() {
     let mut model = cm("abc|<br />def");
-    model.delete();
-    model.delete();
+    model.delete().unwrap();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<p>abc|ef</p>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -239,7 +239,7 @@ fn deleting_a_newline_deletes_it() {
 #[test]
 fn test_backspace_emoji() {
     let mut model = cm("😄|😅");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|😅");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -247,10 +247,10 @@ fn test_backspace_emoji() {
 #[test]
 fn test_backspace_complex_emoji() {
     let mut model = cm("Test😮‍💨|😅");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "Test|😅");
-    model.select(6.into(), 6.into());
-    model.backspace();
+    model.select(6.into(), 6.into()).unwrap();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "Test|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -258,7 +258,7 @@ fn test_backspace_complex_emoji() {
 #[test]
 fn test_delete_emoji() {
     let mut model = cm("😄|😅");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "😄|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -266,10 +266,10 @@ fn test_delete_emoji() {
 #[test]
 fn test_delete_complex_emoji() {
     let mut model = cm("Test😮‍💨|😅");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "Test😮‍💨|");
-    model.select(4.into(), 4.into());
-    model.delete();
+    model.select(4.into(), 4.into()).unwrap();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "Test|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -277,7 +277,7 @@ fn test_delete_complex_emoji() {
 #[test]
 fn test_delete_complex_grapheme() {
     let mut model = cm("Test|О́");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "Test|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -285,7 +285,7 @@ fn test_delete_complex_grapheme() {
 #[test]
 fn test_backspace_complex_grapheme() {
     let mut model = cm("TestО́|");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "Test|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -293,7 +293,7 @@ fn test_backspace_complex_grapheme() {
 #[test]
 fn deleting_initial_text_node_removes_it_completely_without_crashing() {
     let mut model = cm("abc<br />def<br />gh|");
-    model.delete_in(4, 10);
+    model.delete_in(4, 10).unwrap();
     assert_eq!(tx(&model), "<p>abc</p><p>&nbsp;|</p>",);
     model.state.dom.explicitly_assert_invariants();
 }
@@ -301,7 +301,7 @@ fn deleting_initial_text_node_removes_it_completely_without_crashing() {
 #[test]
 fn deleting_initial_text_node_via_selection_removes_it_completely() {
     let mut model = cm("abc<br />{def<br />gh}|");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<p>abc</p><p>&nbsp;|</p>",);
     model.state.dom.explicitly_assert_invariants();
 }
@@ -309,7 +309,7 @@ fn deleting_initial_text_node_via_selection_removes_it_completely() {
 #[test]
 fn deleting_all_initial_text_and_merging_later_text_produces_one_text_node() {
     let mut model = cm("abc<br />{def<br />gh}|ijk");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<p>abc</p><p>|ijk</p>",);
     model.state.dom.explicitly_assert_invariants();
 }
@@ -317,7 +317,7 @@ fn deleting_all_initial_text_and_merging_later_text_produces_one_text_node() {
 #[test]
 fn deleting_all_initial_text_within_a_tag_preserves_the_tag() {
     let mut model = cm("abc<br /><strong>{def<br />gh}|ijk</strong>");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<p>abc</p><p><strong>|ijk</strong></p>",);
     model.state.dom.explicitly_assert_invariants();
 }
@@ -325,7 +325,7 @@ fn deleting_all_initial_text_within_a_tag_preserves_the_tag() {
 #[test]
 fn deleting_all_text_within_a_tag_deletes_the_tag() {
     let mut model = cm("abc<br /><strong>{def<br />gh}|</strong>ijk");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<p>abc</p><p>|ijk</p>",);
     model.state.dom.explicitly_assert_invariants();
 }
@@ -333,7 +333,7 @@ fn deleting_all_text_within_a_tag_deletes_the_tag() {
 #[test]
 fn deleting_last_character_in_a_container() {
     let mut model = cm("<b>t|</b>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -341,7 +341,7 @@ fn deleting_last_character_in_a_container() {
 #[test]
 fn deleting_selection_in_a_container() {
     let mut model = cm("<b>{test}|</b>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -349,7 +349,7 @@ fn deleting_selection_in_a_container() {
 #[test]
 fn deleting_selection_in_multiple_containers() {
     let mut model = cm("<i><b>{test}|</b></i>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -357,7 +357,7 @@ fn deleting_selection_in_multiple_containers() {
 #[test]
 fn deleting_selection_of_a_container_in_multiple_containers() {
     let mut model = cm("<i><b>{test}|</b> test</i>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<i>|&nbsp;test</i>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -365,7 +365,7 @@ fn deleting_selection_of_a_container_in_multiple_containers() {
 #[test]
 fn deleting_selection_of_a_container_with_text_node_neighbors() {
     let mut model = cm("<em>abc<del>{def}|</del>ghi</em>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<em>abc|ghi</em>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -375,8 +375,8 @@ fn deleting_selection_of_a_container_with_matching_neighbors() {
     let mut model = cm(
         "<em><strong>abc</strong><del>{def}|</del><strong>ghi</strong></em>",
     );
-    model.backspace();
-    assert_eq!(tx(&model), "<em><strong>abc|ghi</strong></em>");
+    model.backspace().unwrap();
+    assert_eq!(tx(&model), "<strong><em>abc|ghi</em></strong>");
     model.state.dom.explicitly_assert_invariants();
 }
 
@@ -385,104 +385,104 @@ fn deleting_selection_of_a_container_with_matching_neighbors() {
 #[test]
 fn plain_backspace_word_at_beginning_does_nothing() {
     let mut model = cm("|abc");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "|abc")
 }
 #[test]
 fn plain_delete_word_at_end_does_nothing() {
     let mut model = cm("abc|");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "abc|")
 }
 
 #[test]
 fn plain_backspace_word_with_selection_only_removes_selection() {
     let mut model = cm("ab{c def}|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "ab|")
 }
 #[test]
 fn plain_delete_word_with_selection_only_removes_selection() {
     let mut model = cm("ab{c def}|");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "ab|")
 }
 
 #[test]
 fn plain_backspace_word_at_end_of_single_word_removes_word() {
     let mut model = cm("abc|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "|")
 }
 #[test]
 fn plain_delete_word_at_start_of_single_word_removes_word() {
     let mut model = cm("|abc");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "|")
 }
 
 #[test]
 fn plain_backspace_word_in_word_removes_start_of_word() {
     let mut model = cm("ab|c");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "|c")
 }
 #[test]
 fn plain_delete_word_in_word_removes_end_of_word() {
     let mut model = cm("a|bc");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "a|")
 }
 
 #[test]
 fn plain_backspace_word_with_multiple_words_removes_single_word() {
     let mut model = cm("abc def| ghi");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "abc | ghi")
 }
 #[test]
 fn plain_delete_word_with_multiple_words_removes_single_word() {
     let mut model = cm("abc |def ghi");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "abc | ghi")
 }
 
 #[test]
 fn plain_backspace_word_removes_whitespace_then_word() {
     let mut model = cm("abc def          |");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "abc |")
 }
 #[test]
 fn plain_delete_word_removes_whitespace_then_word() {
     let mut model = cm("|          abc def");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "| def")
 }
 
 #[test]
 fn plain_backspace_word_removes_runs_of_non_word_characters() {
     let mut model = cm("abc,.()!@$^*|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "abc|")
 }
 #[test]
 fn plain_delete_word_removes_runs_of_non_word_characters() {
     let mut model = cm("|,.()!@$^*abc");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "|abc")
 }
 
 #[test]
 fn plain_backspace_word_removes_runs_of_non_word_characters_and_whitespace() {
     let mut model = cm("abc  ,.!@$%       |");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "abc  |")
 }
 #[test]
 fn plain_delete_word_removes_runs_of_non_word_characters_and_whitespace() {
     let mut model = cm("|  ,.!@$%  abc");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|  abc")
 }
 
@@ -490,147 +490,147 @@ fn plain_delete_word_removes_runs_of_non_word_characters_and_whitespace() {
 #[test]
 fn html_backspace_word_at_beginning_does_nothing() {
     let mut model = cm("<em>|abc</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "<em>|abc</em>")
 }
 #[test]
 fn html_delete_word_at_end_does_nothing() {
     let mut model = cm("<em>abc|</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "<em>abc|</em>")
 }
 
 #[test]
 fn html_backspace_word_with_selection_only_removes_selection() {
     let mut model = cm("<em>ab{c def}|</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "<em>ab|</em>")
 }
 #[test]
 fn html_delete_word_with_selection_only_removes_selection() {
     let mut model = cm("<em>ab{c def}|</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "<em>ab|</em>")
 }
 
 #[test]
 fn html_backspace_word_at_end_of_single_word_removes_word() {
     let mut model = cm("<em>abc|</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "|")
 }
 #[test]
 fn html_delete_word_at_start_of_single_word_removes_word() {
     let mut model = cm("<em>|abc</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "|")
 }
 
 #[test]
 fn html_backspace_word_in_word_removes_start_of_word() {
     let mut model = cm("<em>ab|c</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "<em>|c</em>")
 }
 #[test]
 fn html_delete_word_in_word_removes_end_of_word() {
     let mut model = cm("<em>a|bc</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "<em>a|</em>")
 }
 
 #[test]
 fn html_backspace_word_with_multiple_words_removes_single_word() {
     let mut model = cm("<em>abc def| ghi</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>abc | ghi</em>")
 }
 #[test]
 fn html_delete_word_with_multiple_words_removes_single_word() {
     let mut model = cm("<em>abc |def ghi</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>abc | ghi</em>")
 }
 
 #[test]
 fn html_backspace_word_removes_whitespace_then_word() {
     let mut model = cm("<em>abc def          |</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>abc |</em>")
 }
 #[test]
 fn html_delete_word_removes_whitespace_then_word() {
     let mut model = cm("<em>|          abc def</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>| def</em>")
 }
 
 #[test]
 fn html_backspace_word_removes_runs_of_non_word_characters() {
     let mut model = cm("<em>abc,.()!@$^*|</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "<em>abc|</em>")
 }
 #[test]
 fn html_delete_word_removes_runs_of_non_word_characters() {
     let mut model = cm("<em>|,.()!@$^*abc</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(tx(&model), "<em>|abc</em>")
 }
 
 #[test]
 fn html_backspace_word_removes_runs_of_non_word_characters_and_whitespace() {
     let mut model = cm("<em>abc  ,.!@$%       |</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>abc  |</em>")
 }
 #[test]
 fn html_delete_word_removes_runs_of_non_word_characters_and_whitespace() {
     let mut model = cm("<em>|  ,.!@$%  abc</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>|  abc</em>")
 }
 
 #[test]
 fn html_backspace_word_removes_single_linebreak() {
     let mut model = cm("<br />|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p> |</p>")
 }
 #[test]
 fn html_delete_word_removes_single_linebreak() {
     let mut model = cm("|<br />");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p> |</p>")
 }
 
 #[test]
 fn html_backspace_word_removes_only_one_linebreak_of_many() {
     let mut model = cm("<br /><br />|<br />");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;</p><p>&nbsp;|</p><p>&nbsp;</p>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p><p>&nbsp;</p>");
 }
 #[test]
 fn html_delete_word_removes_only_one_linebreak_of_many() {
     let mut model = cm("<br />|<br /><br />");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p> </p><p> |</p><p> </p>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p> </p><p> |</p>");
 }
 
 #[test]
 fn html_backspace_word_does_not_remove_past_linebreak_in_word() {
     let mut model = cm("a<br />defg|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(tx(&model), "<p>a</p><p>&nbsp;|</p>")
 }
 #[test]
 fn html_delete_word_does_not_remove_past_linebreak_in_word() {
     let mut model = cm("|abcd<br />f ");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p> |</p><p>f </p>")
 }
 
@@ -638,13 +638,13 @@ fn html_delete_word_does_not_remove_past_linebreak_in_word() {
 #[test]
 fn html_backspace_word_at_linebreak_removes_linebreak() {
     let mut model = cm("abc <br/>|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p>abc </p><p> |</p>");
 }
 #[test]
 fn html_delete_word_at_linebreak_removes_linebreak() {
     let mut model = cm("|<br/> abc");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p>| abc</p>");
 }
 
@@ -652,76 +652,76 @@ fn html_delete_word_at_linebreak_removes_linebreak() {
 #[test]
 fn html_backspace_word_removes_past_linebreak_in_whitespace() {
     let mut model = cm("abc <br/> |");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p>abc |</p>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p> |</p>");
 }
 #[test]
 fn html_delete_word_removes_past_linebreak_in_whitespace() {
     let mut model = cm("| <br/> abc");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p>| abc</p>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<p> |</p>");
 }
 
 #[test]
 fn html_backspace_word_removes_whole_word() {
     let mut model = cm("<em>italic|</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 #[test]
 fn html_delete_word_removes_whole_word() {
     let mut model = cm("<em>|italic</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
 #[test]
 fn html_backspace_word_removes_into_a_tag() {
     let mut model = cm("<em>some em</em>phasis|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>some |</em>");
 }
 #[test]
 fn html_delete_word_removes_into_a_tag() {
     let mut model = cm("|so<em>me emphasis</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>| emphasis</em>");
 }
 
 #[test]
 fn html_backspace_word_removes_through_a_tag() {
     let mut model = cm("si<em>ng</em>le|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 #[test]
 fn html_delete_word_removes_through_a_tag() {
     let mut model = cm("|si<em>ng</em>le");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
 #[test]
 fn html_backspace_word_removes_between_tags() {
     let mut model = cm("<em>start spl</em><strong>it</strong>| end");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>start |</em> end");
 }
 #[test]
 fn html_delete_word_removes_between_tags() {
     let mut model = cm("<em>start |spl</em><strong>it</strong> end");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>start |</em> end");
 }
 
 #[test]
 fn html_backspace_word_removes_between_nested_tags() {
     let mut model = cm("<em><em>start spl</em></em><strong>it</strong>| end");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<em><em>start |</em></em> end"
@@ -730,7 +730,7 @@ fn html_backspace_word_removes_between_nested_tags() {
 #[test]
 fn html_delete_word_removes_between_nested_tags() {
     let mut model = cm("<em><em>start |spl</em></em><strong>it</strong> end");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<em><em>start |</em></em> end"
@@ -740,14 +740,14 @@ fn html_delete_word_removes_between_nested_tags() {
 #[test]
 fn html_backspace_word_into_deep_nesting() {
     let mut model = cm("<em>remains <em>all<em>of<em>the<em>rest</em>goes</em>away</em>x</em>y|</em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>remains |</em>");
     model.state.dom.explicitly_assert_invariants();
 }
 #[test]
 fn html_delete_word_into_deep_nesting() {
     let mut model = cm("<em>remains |<em>all<em>of<em>the<em>rest</em>goes</em>away</em>x</em>y</em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em>remains |</em>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -756,7 +756,7 @@ fn html_delete_word_into_deep_nesting() {
 fn html_backspace_word_out_of_deep_nesting() {
     let mut model =
         cm("<em><em>stop <em><em><em>removethis|</em></em></em></em></em>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em><em>stop |</em></em>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -764,7 +764,7 @@ fn html_backspace_word_out_of_deep_nesting() {
 fn html_delete_word_out_of_deep_nesting() {
     let mut model =
         cm("<em><em><em><em><em>|removethis</em></em></em> stop</em></em>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<em><em>| stop</em></em>");
     model.state.dom.explicitly_assert_invariants();
 }
@@ -773,20 +773,20 @@ fn html_delete_word_out_of_deep_nesting() {
 fn html_backspace_word_inside_single_list_item() {
     let mut model =
         cm("<ol><li>remove\u{00A0}\u{00A0}\u{00A0}\u{00A0}\u{00A0}|</li></ol>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>|</li></ol>");
 }
 #[test]
 fn html_delete_word_inside_single_list_item() {
     let mut model = cm("<ol><li>|    remove</li></ol>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>|</li></ol>");
 }
 
 #[test]
 fn html_backspace_word_does_not_move_outside_list_item() {
     let mut model = cm("<ol><li>1</li><li>12|</li><li>123</li></ol>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<ol><li>1</li><li>|</li><li>123</li></ol>"
@@ -795,7 +795,7 @@ fn html_backspace_word_does_not_move_outside_list_item() {
 #[test]
 fn html_delete_word_does_not_move_outside_list_item() {
     let mut model = cm("<ol><li>1</li><li>|12</li><li>123</li></ol>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<ol><li>1</li><li>|</li><li>123</li></ol>"
@@ -805,14 +805,14 @@ fn html_delete_word_does_not_move_outside_list_item() {
 #[test]
 fn backspace_between_block_nodes() {
     let mut model = cm("<p>First</p><p>|Second</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>First|Second</p>");
 }
 
 #[test]
 fn backspace_between_nested_block_nodes() {
     let mut model = cm("<p>First</p><blockquote><p>|Second</p></blockquote>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>First|Second</p>");
 }
 
@@ -820,34 +820,34 @@ fn backspace_between_nested_block_nodes() {
 // TODO: remove these tests when implementing list behaviour
 fn html_backspace_word_does_not_change_model() {
     let mut model = cm("<ol><li>|</li></ol>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>|</li></ol>");
 }
 #[test]
 // TODO: remove these tests when implementing list behaviour
 fn html_delete_word_does_not_change_model() {
     let mut model = cm("<ol><li>|</li></ol>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>|</li></ol>");
 }
 
 #[test]
 fn html_backspace_word_for_single_empty_list_item() {
     let mut model = cm("<ol><li>|</li></ol>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>|</li></ol>");
 }
 #[test]
 fn html_delete_word_for_single_empty_list_item() {
     let mut model = cm("<ol><li>|</li></ol>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "<ol><li>|</li></ol>");
 }
 
 #[test]
 fn html_backspace_word_for_empty_list_item() {
     let mut model = cm("<ol><li>1</li><li>|</li><li>123</li></ol>");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<ol><li>1</li><li>|</li><li>123</li></ol>"
@@ -856,7 +856,7 @@ fn html_backspace_word_for_empty_list_item() {
 #[test]
 fn html_delete_word_for_empty_list_item() {
     let mut model = cm("<ol><li>1</li><li>|</li><li>123</li></ol>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<ol><li>1</li><li>|123</li></ol>"
@@ -868,7 +868,7 @@ fn backspace_immutable_link_from_edge_of_link() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">test|</a>",
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -877,7 +877,7 @@ fn backspace_immutable_link_from_inside_link() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">tes|t</a>",
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -886,12 +886,12 @@ fn backspace_immutable_link_multiple() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">first</a><a contenteditable=\"false\" href=\"https://matrix.org\">second|</a>",
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<a contenteditable=\"false\" href=\"https://matrix.org\">first|</a>"
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -900,12 +900,12 @@ fn backspace_mention_multiple() {
     let mut model = cm(
         "<a href=\"https://matrix.to/#/@test:example.org\">first</a><a href=\"https://matrix.to/#/@test:example.org\">second</a>|",
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<a data-mention-type=\"user\" href=\"https://matrix.to/#/@test:example.org\" contenteditable=\"false\">first</a>|"
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -914,7 +914,7 @@ fn backspace_word_from_edge_of_immutable_link() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">two words|</a>",
     );
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -922,14 +922,14 @@ fn backspace_word_from_edge_of_immutable_link() {
 fn backspace_mention_from_end() {
     let mut model =
         cm("<a href=\"https://matrix.to/#/@test:example.org\">mention</a>|");
-    model.backspace_word();
+    model.backspace_word().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
 #[test]
 fn backspace_word_returns_replace_all_update() {
     let mut model = cm("Some text with multiple words|");
-    let update = model.backspace_word();
+    let update = model.backspace_word().unwrap();
     assert!(matches!(update.text_update, TextUpdate::ReplaceAll(_)))
 }
 
@@ -938,7 +938,7 @@ fn delete_immutable_link_from_edge_of_link() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">|test</a>",
     );
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -947,7 +947,7 @@ fn delete_immutable_link_from_inside_link() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">te|st</a>",
     );
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -955,7 +955,7 @@ fn delete_immutable_link_from_inside_link() {
 fn delete_mention_from_start() {
     let mut model =
         cm("|<a href=\"https://matrix.to/#/@test:example.org\">test</a>");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -964,12 +964,12 @@ fn delete_first_immutable_link_of_multiple() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">|first</a><a contenteditable=\"false\" href=\"https://matrix.org\">second</a>",
     );
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<a contenteditable=\"false\" href=\"https://matrix.org\">|second</a>"
     );
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -978,12 +978,12 @@ fn delete_first_mention_of_multiple() {
     let mut model = cm(
         "|<a href=\"https://matrix.to/#/@test:example.org\">first</a><a href=\"https://matrix.to/#/@test:example.org\">second</a>",
     );
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "|<a data-mention-type=\"user\" href=\"https://matrix.to/#/@test:example.org\" contenteditable=\"false\">second</a>"
     );
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -992,12 +992,12 @@ fn delete_second_immutable_link_of_multiple() {
     let mut model = cm(
         "<a contenteditable=\"false\" href=\"https://matrix.org\">first</a><a contenteditable=\"false\" href=\"https://matrix.org\">second|</a>",
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<a contenteditable=\"false\" href=\"https://matrix.org\">first|</a>"
     );
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(restore_whitespace(&tx(&model)), "|");
 }
 
@@ -1006,7 +1006,7 @@ fn delete_second_mention_of_multiple() {
     let mut model = cm(
         "<a href=\"https://matrix.to/#/@test:example.org\">first</a> |<a href=\"https://matrix.to/#/@test:example.org\">second</a>",
     );
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<a data-mention-type=\"user\" href=\"https://matrix.to/#/@test:example.org\" contenteditable=\"false\">first</a> |"
@@ -1016,7 +1016,7 @@ fn delete_second_mention_of_multiple() {
 #[test]
 fn delete_word_from_edge_of_link() {
     let mut model = cm("<a href=\"https://matrix.org\">|two words</a>");
-    model.delete_word();
+    model.delete_word().unwrap();
     assert_eq!(
         restore_whitespace(&tx(&model)),
         "<a href=\"https://matrix.org\">| words</a>",
@@ -1027,13 +1027,13 @@ fn delete_word_from_edge_of_link() {
 fn backspacing_several_paragraphs_with_only_nbsps() {
     let mut model =
         cm("<p>{ </p><p>second</p><p>third</p><p> </p><p>fifth}|</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>")
 }
 
 #[test]
 fn backspacing_paragraphs_with_nbsp_at_start() {
     let mut model = cm("<p> |test</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>|test</p>")
 }
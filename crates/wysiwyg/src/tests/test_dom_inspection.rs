@@ -0,0 +1,152 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::tests::testutils_composer_model::cm;
+use crate::{
+    ComposerModel, DomHandle, InlineFormatType, ListType, Location, NodeInfo,
+    NodeKind,
+};
+
+#[test]
+fn dom_tree_describes_every_node() {
+    let model = cm("<p>a<strong>bc</strong></p><p>d|</p>");
+    let tree = model.dom_tree();
+    assert_eq!(tree.kind, NodeKind::Generic);
+    assert_eq!(tree.handle, DomHandle::root());
+    assert_eq!(tree.start, 0);
+    assert_eq!(tree.end, 5);
+    let kinds: Vec<_> = tree.children.iter().map(|c| c.kind.clone()).collect();
+    assert_eq!(kinds, vec![NodeKind::Paragraph, NodeKind::Paragraph]);
+
+    let first = &tree.children[0];
+    assert_eq!(
+        (first.start, first.end),
+        (Location::from(0), Location::from(3))
+    );
+    let strong = &first.children[1];
+    assert_eq!(strong.kind, NodeKind::Formatting(InlineFormatType::Bold));
+    assert_eq!(strong.handle, DomHandle::from_raw(vec![0, 1]));
+    assert_eq!(strong.children[0].text.as_deref(), Some("bc"));
+    assert_eq!(
+        (strong.start, strong.end),
+        (Location::from(1), Location::from(3))
+    );
+
+    let second = &tree.children[1];
+    assert_eq!(
+        (second.start, second.end),
+        (Location::from(4), Location::from(5))
+    );
+}
+
+#[test]
+fn dom_tree_includes_attributes() {
+    let model = cm("<a href=\"https://matrix.org\">link|</a>");
+    let link = &model.dom_tree().children[0];
+    assert_eq!(link.kind, NodeKind::Link);
+    assert_eq!(
+        link.attributes,
+        vec![("href".to_owned(), "https://matrix.org".to_owned())]
+    );
+}
+
+#[test]
+fn dom_tree_includes_mentions() {
+    let model =
+        cm("<a href=\"https://matrix.to/#/@alice:matrix.org\">Alice</a>|");
+    let mention = &model.dom_tree().children[0];
+    assert_eq!(mention.kind, NodeKind::Mention);
+    assert_eq!(mention.text.as_deref(), Some("Alice"));
+    assert!(mention.attributes.contains(&(
+        "href".to_owned(),
+        "https://matrix.to/#/@alice:matrix.org".to_owned()
+    )));
+    assert_eq!(
+        (mention.start, mention.end),
+        (Location::from(0), Location::from(1))
+    );
+}
+
+#[test]
+fn dom_tree_of_empty_model_is_an_empty_root() {
+    let model: ComposerModel<Utf16String> = ComposerModel::new();
+    let tree = model.dom_tree();
+    assert_eq!(tree.kind, NodeKind::Generic);
+    assert!(tree.children.is_empty());
+}
+
+#[test]
+fn node_at_location_finds_the_text_node() {
+    let model = cm("<p>ab</p><ol><li><p>cd|</p></li></ol>");
+    let node = model.node_at_location(Location::from(4)).unwrap();
+    assert_eq!(node.kind, NodeKind::Text);
+    assert_eq!(node.text.as_deref(), Some("cd"));
+    assert_eq!(node.handle, DomHandle::from_raw(vec![1, 0, 0, 0]));
+}
+
+#[test]
+fn node_at_location_prefers_the_following_node() {
+    let model = cm("a<em>b|</em>");
+    let node = model.node_at_location(Location::from(1)).unwrap();
+    assert_eq!(node.text.as_deref(), Some("b"));
+}
+
+#[test]
+fn node_at_location_at_end_of_text_finds_the_last_node() {
+    let model = cm("a<em>b|</em>");
+    let node = model.node_at_location(Location::from(2)).unwrap();
+    assert_eq!(node.text.as_deref(), Some("b"));
+}
+
+#[test]
+fn node_at_location_in_empty_list_item_finds_the_list_item() {
+    let model = cm("<ul><li>a</li><li>|</li></ul>");
+    let node = model.node_at_location(Location::from(2)).unwrap();
+    assert_eq!(node.kind, NodeKind::ListItem);
+    assert!(node.is_block());
+}
+
+#[test]
+fn node_at_location_handle_leads_to_enclosing_blocks() {
+    let model = cm("<blockquote><ul><li>a|</li></ul></blockquote>");
+    let node = model.node_at_location(Location::from(0)).unwrap();
+    let tree = model.dom_tree();
+    let ancestors: Vec<&NodeInfo> = node
+        .handle
+        .raw()
+        .iter()
+        .scan(&tree, |node, &i| {
+            *node = &node.children[i];
+            Some(*node)
+        })
+        .collect();
+    let kinds: Vec<_> = ancestors.iter().map(|n| n.kind.clone()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            NodeKind::Quote,
+            NodeKind::List(ListType::Unordered),
+            NodeKind::ListItem,
+            NodeKind::Text
+        ]
+    );
+}
+
+#[test]
+fn node_at_location_beyond_the_text_is_none() {
+    let model = cm("abc|");
+    assert!(model.node_at_location(Location::from(4)).is_none());
+}
@@ -14,6 +14,8 @@
 
 use widestring::Utf16String;
 
+#[cfg(feature = "emoji")]
+use crate::tests::testutils_composer_model::cm;
 use crate::{
     tests::testutils_composer_model::tx, ComposerModel, MenuAction, PatternKey,
 };
@@ -22,12 +24,119 @@ use crate::{
 fn can_do_plain_text_to_empji_replacement() {
     let mut model: ComposerModel<Utf16String> = ComposerModel::new();
     model.set_custom_suggestion_patterns(vec![":)".into()]);
-    let update = model.replace_text("Hey That's great! :)".into());
+    let update = model.replace_text("Hey That's great! :)".into()).unwrap();
     let MenuAction::Suggestion(suggestion) = update.menu_action else {
         panic!("No suggestion pattern found")
     };
     assert_eq!(suggestion.key, PatternKey::Custom(":)".into()),);
-    model.replace_text_suggestion("🙂".into(), suggestion, false);
+    model
+        .replace_text_suggestion("🙂".into(), suggestion, false)
+        .unwrap();
 
     assert_eq!(tx(&model), "Hey That's great! 🙂|");
 }
+
+#[cfg(feature = "emoji")]
+#[test]
+fn emoji_shortcode_suggests_candidates() {
+    let mut model = emoji_cm("|");
+    let update = model.replace_text("Congrats :tad".into()).unwrap();
+    let MenuAction::EmojiSuggestion(suggestion) = update.menu_action else {
+        panic!("No emoji suggestion found")
+    };
+    assert_eq!(
+        suggestion.suggestion_pattern.key,
+        PatternKey::Custom(":".into())
+    );
+    assert_eq!(suggestion.suggestion_pattern.text, "tad");
+    assert_eq!(suggestion.suggestion_pattern.start, 9);
+    assert_eq!(suggestion.suggestion_pattern.end, 13);
+    assert_eq!(suggestion.candidates[0].shortcode, "tada");
+    assert_eq!(suggestion.candidates[0].emoji, "🎉");
+}
+
+#[cfg(feature = "emoji")]
+#[test]
+fn emoji_shortcode_can_be_replaced_with_candidate() {
+    let mut model = emoji_cm("|");
+    let update = model.replace_text("Congrats :tada:".into()).unwrap();
+    let MenuAction::EmojiSuggestion(suggestion) = update.menu_action else {
+        panic!("No emoji suggestion found")
+    };
+    let emoji = suggestion.candidates[0].emoji.clone();
+    let update = model
+        .replace_text_suggestion(
+            emoji.as_str().into(),
+            suggestion.suggestion_pattern,
+            false,
+        )
+        .unwrap();
+    assert_eq!(tx(&model), "Congrats 🎉|");
+    assert_eq!(update.menu_action, MenuAction::None);
+}
+
+#[cfg(feature = "emoji")]
+#[test]
+fn emoji_shortcode_replacement_is_undoable() {
+    let mut model = emoji_cm("|");
+    let update = model.replace_text(":fire".into()).unwrap();
+    let MenuAction::EmojiSuggestion(suggestion) = update.menu_action else {
+        panic!("No emoji suggestion found")
+    };
+    model
+        .replace_text_suggestion(
+            "🔥".into(),
+            suggestion.suggestion_pattern,
+            true,
+        )
+        .unwrap();
+    assert_eq!(tx(&model), "🔥&nbsp;|");
+    model.undo().unwrap();
+    assert_eq!(tx(&model), ":fire|");
+}
+
+#[cfg(feature = "emoji")]
+#[test]
+fn emoji_shortcode_needs_two_characters() {
+    let model = emoji_cm(":f|");
+    assert_eq!(model.compute_menu_action(), MenuAction::None);
+}
+
+#[cfg(feature = "emoji")]
+#[test]
+fn emoji_shortcode_without_candidates_is_ignored() {
+    let model = emoji_cm(":notanemoji|");
+    assert_eq!(model.compute_menu_action(), MenuAction::None);
+}
+
+#[cfg(feature = "emoji")]
+#[test]
+fn emoji_shortcode_is_not_detected_in_code() {
+    let model = emoji_cm("<code>:smile|</code>");
+    assert_eq!(model.compute_menu_action(), MenuAction::None);
+}
+
+#[cfg(feature = "emoji")]
+#[test]
+fn custom_patterns_take_precedence_over_emoji_shortcodes() {
+    let mut model = emoji_cm(":)|");
+    model.set_custom_suggestion_patterns(vec![":)".into()]);
+    let MenuAction::Suggestion(suggestion) = model.compute_menu_action() else {
+        panic!("No suggestion pattern found")
+    };
+    assert_eq!(suggestion.key, PatternKey::Custom(":)".into()));
+}
+
+#[cfg(feature = "emoji")]
+#[test]
+fn emoji_shortcodes_are_not_suggested_by_default() {
+    let model = cm(":tada|");
+    assert_eq!(model.compute_menu_action(), MenuAction::None);
+}
+
+#[cfg(feature = "emoji")]
+fn emoji_cm(text: &str) -> ComposerModel<Utf16String> {
+    let mut model = cm(text);
+    model.set_emoji_suggestions_enabled(true);
+    model
+}
@@ -0,0 +1,240 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::{ComposerModel, FindOptions, Location};
+
+fn ranges(
+    model: &ComposerModel<Utf16String>,
+    query: &str,
+    options: &FindOptions,
+) -> Vec<(usize, usize)> {
+    model
+        .find(query, options)
+        .unwrap()
+        .into_iter()
+        .map(|(s, e)| (s.into(), e.into()))
+        .collect()
+}
+
+fn case_sensitive() -> FindOptions {
+    FindOptions {
+        case_sensitive: true,
+        ..Default::default()
+    }
+}
+
+fn whole_word() -> FindOptions {
+    FindOptions {
+        whole_word: true,
+        ..Default::default()
+    }
+}
+
+fn regex() -> FindOptions {
+    FindOptions {
+        regex: true,
+        ..Default::default()
+    }
+}
+
+fn insert_alice_mention(model: &mut ComposerModel<Utf16String>) {
+    model
+        .insert_mention(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "Alice".into(),
+            vec![],
+        )
+        .unwrap();
+}
+
+#[test]
+fn find_is_case_insensitive_by_default() {
+    let model = cm("Foo foo FOO|");
+    assert_eq!(
+        ranges(&model, "foo", &FindOptions::default()),
+        vec![(0, 3), (4, 7), (8, 11)]
+    );
+}
+
+#[test]
+fn find_can_be_case_sensitive() {
+    let model = cm("Foo foo FOO|");
+    assert_eq!(ranges(&model, "foo", &case_sensitive()), vec![(4, 7)]);
+}
+
+#[test]
+fn find_with_empty_query_finds_nothing() {
+    let model = cm("foo|");
+    assert!(ranges(&model, "", &FindOptions::default()).is_empty());
+}
+
+#[test]
+fn find_escapes_the_query_unless_regex_is_set() {
+    let model = cm("a.c abc|");
+    assert_eq!(ranges(&model, "a.c", &FindOptions::default()), vec![(0, 3)]);
+    assert_eq!(ranges(&model, "a.c", &regex()), vec![(0, 3), (4, 7)]);
+}
+
+#[test]
+fn find_whole_words_only() {
+    let model = cm("cat concat cat_s cat.|");
+    assert_eq!(ranges(&model, "cat", &whole_word()), vec![(0, 3), (17, 20)]);
+}
+
+#[test]
+fn find_spans_formatting_boundaries() {
+    let model = cm("a<b>bc</b><i>de</i>f|");
+    assert_eq!(
+        ranges(&model, "bcdef", &FindOptions::default()),
+        vec![(1, 6)]
+    );
+}
+
+#[test]
+fn find_does_not_cross_paragraphs() {
+    let model = cm("<p>ab</p><p>cd|</p>");
+    assert!(ranges(&model, "bc", &FindOptions::default()).is_empty());
+    assert_eq!(ranges(&model, "c", &FindOptions::default()), vec![(3, 4)]);
+}
+
+#[test]
+fn find_ignores_mentions() {
+    let mut model = cm("Alice |");
+    insert_alice_mention(&mut model);
+    assert_eq!(
+        ranges(&model, "alice", &FindOptions::default()),
+        vec![(0, 5)]
+    );
+    assert_eq!(ranges(&model, " .", &regex()), Vec::new());
+}
+
+#[test]
+fn find_with_invalid_regex_fails() {
+    let model = cm("abc|");
+    assert!(model.find("(", &regex()).is_err());
+    assert!(model.find("(", &FindOptions::default()).is_ok());
+}
+
+#[test]
+fn find_skips_empty_regex_matches() {
+    let model = cm("baab|");
+    assert_eq!(ranges(&model, "a*", &regex()), vec![(1, 3)]);
+}
+
+#[test]
+fn find_uses_utf16_positions() {
+    let model = cm("😀 foo|");
+    assert_eq!(ranges(&model, "foo", &FindOptions::default()), vec![(3, 6)]);
+}
+
+#[test]
+fn replace_match_keeps_surrounding_formatting() {
+    let mut model = cm("<b>foo bar baz|</b>");
+    model
+        .replace_match(Location::from(4), Location::from(7), "qux".into())
+        .unwrap();
+    assert_eq!(tx(&model), "<b>foo qux| baz</b>");
+}
+
+#[test]
+fn replace_match_across_formatting_uses_the_start_formatting() {
+    let mut model = cm("a<b>bc</b><i>de</i>f|");
+    model
+        .replace_match(Location::from(1), Location::from(6), "X".into())
+        .unwrap();
+    assert_eq!(tx(&model), "a<b>X|</b>");
+}
+
+#[test]
+fn replace_match_ignores_ranges_with_mentions() {
+    let mut model = cm("hi |");
+    insert_alice_mention(&mut model);
+    let before = tx(&model);
+    model
+        .replace_match(Location::from(2), Location::from(4), "X".into())
+        .unwrap();
+    assert_eq!(tx(&model), before);
+}
+
+#[test]
+fn replace_match_ignores_invalid_ranges() {
+    let mut model = cm("abc|");
+    model
+        .replace_match(Location::from(2), Location::from(9), "X".into())
+        .unwrap();
+    assert_eq!(tx(&model), "abc|");
+}
+
+#[test]
+fn replace_all_replaces_every_match() {
+    let mut model = cm("foo <b>Foo</b> bar foo|");
+    model
+        .replace_all("foo", &FindOptions::default(), "baz".into())
+        .unwrap();
+    assert_eq!(tx(&model), "baz| <b>baz</b> bar baz");
+}
+
+#[test]
+fn replace_all_is_a_single_undo_step() {
+    let mut model = cm("a a a|");
+    model
+        .replace_all("a", &FindOptions::default(), "bb".into())
+        .unwrap();
+    assert_eq!(tx(&model), "bb| bb bb");
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "a a a|");
+}
+
+#[test]
+fn replace_all_with_no_matches_keeps_the_model() {
+    let mut model = cm("abc|");
+    model
+        .replace_all("x", &FindOptions::default(), "y".into())
+        .unwrap();
+    assert_eq!(tx(&model), "abc|");
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "abc|");
+}
+
+#[test]
+fn replace_all_leaves_mentions_intact() {
+    let mut model = cm("Alice |");
+    insert_alice_mention(&mut model);
+    model
+        .replace_all("alice", &FindOptions::default(), "Bob".into())
+        .unwrap();
+    assert_eq!(
+        tx(&model),
+        "Bob| <a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:matrix.org\" contenteditable=\"false\">Alice</a>&nbsp;"
+    );
+}
+
+#[test]
+fn replace_all_in_several_paragraphs() {
+    let mut model = cm("<p>one cat</p><p>two cats|</p>");
+    model
+        .replace_all("cat", &whole_word(), "dog".into())
+        .unwrap();
+    assert_eq!(tx(&model), "<p>one dog|</p><p>two cats</p>");
+}
+
+#[test]
+fn replace_all_with_regex() {
+    let mut model = cm("a1 b22 c333|");
+    model.replace_all(r"\d+", &regex(), "#".into()).unwrap();
+    assert_eq!(tx(&model), "a#| b# c#");
+}
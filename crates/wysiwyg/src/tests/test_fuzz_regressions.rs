@@ -0,0 +1,209 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Minimised crashes found by the `composer_operations` fuzz target.
+
+use crate::fuzzing::{decode_operations, run_operations, Operation};
+
+use Operation::*;
+
+#[test]
+fn decoding_bytes_into_operations() {
+    assert_eq!(
+        decode_operations(&[0, 3, 70, 1, 2, 2, 42, 0, 64]),
+        [Select(3, 6), ReplaceText(" "), Enter, Select(0, 0)]
+    );
+}
+
+#[test]
+fn decoding_stops_at_an_incomplete_operation() {
+    assert_eq!(decode_operations(&[5, 0, 1]), [Bold]);
+}
+
+#[test]
+fn enter_in_a_quote_inside_a_list_item() {
+    run_operations(&[
+        Underline,
+        ReplaceText("* "),
+        OrderedList,
+        Quote,
+        Enter,
+        Quote,
+        Enter,
+    ]);
+}
+
+#[test]
+fn enter_with_a_selection_across_quotes_in_a_list() {
+    run_operations(&[
+        Underline,
+        ReplaceText("* "),
+        OrderedList,
+        Quote,
+        InsertAtRoomMention,
+        Enter,
+        Quote,
+        InsertAtRoomMention,
+        Quote,
+        Select(5, 61),
+        Enter,
+    ]);
+}
+
+#[test]
+fn typing_a_quote_marker_in_inline_code_inside_a_code_block() {
+    run_operations(&[CodeBlock, InlineCode, ReplaceText("> ")]);
+}
+
+#[test]
+fn typing_a_code_fence_in_inline_code_inside_a_code_block() {
+    run_operations(&[InlineCode, CodeBlock, ReplaceText("```")]);
+}
+
+#[test]
+fn code_block_around_links_in_inline_code() {
+    run_operations(&[InlineCode, SetLinkWithText, SetLinkWithText, CodeBlock]);
+}
+
+#[test]
+fn typing_in_a_code_block_made_from_a_list_with_mentions() {
+    run_operations(&[
+        InlineCode,
+        UnorderedList,
+        Undo,
+        Enter,
+        InsertAtRoomMention,
+        CodeBlock,
+        InlineCode,
+        ReplaceText("😄"),
+    ]);
+}
+
+#[test]
+fn set_link_over_a_selection_ending_in_an_empty_container() {
+    run_operations(&[
+        SetLinkWithText,
+        Underline,
+        SetLinkWithText,
+        Bold,
+        Select(7, 6),
+        InlineCode,
+        UnorderedList,
+        SetLinkWithText,
+        UnorderedList,
+        Enter,
+        Enter,
+        CodeBlock,
+        Backspace,
+        InsertAtRoomMention,
+        Select(19, 11),
+        SetLink,
+    ]);
+}
+
+#[test]
+fn enter_at_the_end_of_a_link_in_a_code_block() {
+    run_operations(&[Bold, SetLinkWithText, CodeBlock, Enter]);
+}
+
+#[test]
+fn enter_after_a_new_line_in_a_code_block() {
+    run_operations(&[
+        ReplaceText("e\u{301}"),
+        CodeBlock,
+        ReplaceText("\n"),
+        Enter,
+    ]);
+}
+
+#[test]
+fn code_block_after_a_code_fence_in_inline_code() {
+    run_operations(&[InlineCode, ReplaceText("```"), Enter, CodeBlock]);
+}
+
+#[test]
+fn replacing_a_selection_ending_at_the_start_of_a_link() {
+    run_operations(&[
+        InsertAtRoomMention,
+        Enter,
+        SetLinkWithText,
+        Select(3, 0),
+        ReplaceText(" "),
+    ]);
+}
+
+#[test]
+fn set_link_then_remove_links_over_at_room_mentions() {
+    run_operations(&[
+        InsertMention,
+        InsertAtRoomMention,
+        InlineCode,
+        Italic,
+        InsertAtRoomMention,
+        Select(1, 54),
+        SetLink,
+        RemoveLinks,
+    ]);
+}
+
+#[test]
+fn enter_after_undoing_a_code_fence_in_an_indented_quote() {
+    run_operations(&[
+        Quote,
+        Italic,
+        SetLinkWithText,
+        CodeBlock,
+        Backspace,
+        StrikeThrough,
+        Underline,
+        StrikeThrough,
+        Underline,
+        SetLinkWithText,
+        RemoveLinks,
+        UnorderedList,
+        ReplaceText("\n"),
+        SetLinkWithText,
+        InlineCode,
+        Quote,
+        Enter,
+        Indent,
+        Select(24, 4),
+        ReplaceText("```"),
+        Undo,
+        Enter,
+    ]);
+}
+
+#[test]
+fn formatting_after_deleting_into_inline_code() {
+    run_operations(&[
+        Italic,
+        Underline,
+        Italic,
+        Undo,
+        InsertAtRoomMention,
+        SetLinkWithText,
+        Undo,
+        StrikeThrough,
+        ReplaceText("```"),
+        Italic,
+        InsertMention,
+        Enter,
+        InlineCode,
+        Underline,
+        StrikeThrough,
+        Delete,
+        InsertMention,
+    ]);
+}
@@ -221,7 +221,9 @@ fn get_link_action_on_multiple_link_with_first_immutable() {
     "#});
     assert_eq!(model.get_link_action(), LinkAction::Disabled);
     // Selecting the mutable link afterwards works
-    model.select(Location::from(20), Location::from(20));
+    model
+        .select(Location::from(20), Location::from(20))
+        .unwrap();
     assert_eq!(
         model.get_link_action(),
         LinkAction::Edit("https://rust-lang.org".into()),
@@ -237,7 +239,7 @@ fn get_link_action_on_multiple_link_with_last_immutable() {
     "#});
     assert_eq!(model.get_link_action(), LinkAction::Disabled);
     // Selecting the mutable link afterwards works
-    model.select(Location::from(0), Location::from(0));
+    model.select(Location::from(0), Location::from(0)).unwrap();
     assert_eq!(
         model.get_link_action(),
         LinkAction::Edit("https://rust-lang.org".into()),
@@ -284,7 +286,9 @@ fn get_link_action_on_multiple_link_with_first_is_mention() {
         LinkAction::Edit("https://rust-lang.org".into()),
     );
     // Selecting the link afterwards works
-    model.select(Location::from(10), Location::from(10));
+    model
+        .select(Location::from(10), Location::from(10))
+        .unwrap();
     assert_eq!(
         model.get_link_action(),
         LinkAction::Edit("https://rust-lang.org".into()),
@@ -303,7 +307,7 @@ fn get_link_action_on_multiple_link_with_last_is_mention() {
         LinkAction::Edit("https://rust-lang.org".into()),
     );
     // Selecting the mutable link afterwards works
-    model.select(Location::from(0), Location::from(0));
+    model.select(Location::from(0), Location::from(0)).unwrap();
     assert_eq!(
         model.get_link_action(),
         LinkAction::Edit("https://rust-lang.org".into()),
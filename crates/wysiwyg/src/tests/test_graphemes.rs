@@ -0,0 +1,231 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use unicode_segmentation::UnicodeSegmentation;
+use widestring::Utf16String;
+
+use crate::dom::unicode_string::UnicodeStrExt;
+use crate::tests::testutils_composer_model::{cm, restore_whitespace, tx};
+use crate::{ComposerModel, Direction, Granularity, Location, UnicodeString};
+
+/// Text whose grapheme clusters span several chars, and often several UTF-16
+/// code units per char.
+const TRICKY_TEXT: &[&str] = &[
+    // ZWJ family sequence
+    "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{200D}\u{1F466}",
+    // Flags made of regional indicators
+    "\u{1F1EC}\u{1F1E7}\u{1F1EB}\u{1F1F7}",
+    // Skin tone modifiers
+    "\u{1F44D}\u{1F3FD}\u{1F44B}\u{1F3FF}",
+    // Keycap sequence
+    "1\u{FE0F}\u{20E3}",
+    // Latin with combining marks
+    "e\u{0301}a\u{0308}\u{0323}o",
+    // Devanagari with virama and vowel signs
+    "\u{0928}\u{092E}\u{0938}\u{094D}\u{0924}\u{0947}",
+    // Tamil
+    "\u{0BA8}\u{0BBF}\u{0BB2}\u{0BBE}",
+    // Thai with sara am
+    "\u{0E01}\u{0E33}\u{0E19}",
+    // Precomposed Hangul syllables
+    "\u{D55C}\u{AD6D}\u{C5B4}",
+    // Conjoining Hangul jamo
+    "\u{1100}\u{1161}\u{11A8}\u{1112}\u{1161}\u{11AB}",
+    // A mixture
+    "a\u{1F469}\u{1F3FD}\u{200D}\u{1F4BB}b\u{0301}\u{1F1EF}\u{1F1F5}",
+];
+
+fn model_with_text<S: UnicodeString>(text: &str) -> ComposerModel<S> {
+    let mut model = ComposerModel::<S>::new();
+    model.replace_text(S::from(text)).unwrap();
+    model
+}
+
+fn code_units<S: UnicodeString>(text: &str) -> usize {
+    S::from(text).len()
+}
+
+/// The plain text content, and the cursor position in code units.
+fn text_and_cursor<S: UnicodeString>(
+    model: &ComposerModel<S>,
+) -> (String, Location) {
+    assert_eq!(model.state.start, model.state.end);
+    let text = model.get_content_as_plain_text().to_string();
+    (restore_whitespace(&text), model.state.end)
+}
+
+fn backspace_removes_whole_graphemes<S: UnicodeString>() {
+    for text in TRICKY_TEXT {
+        let mut model = model_with_text::<S>(text);
+        let mut graphemes: Vec<&str> = text.graphemes(true).collect();
+        while graphemes.pop().is_some() {
+            model.backspace().unwrap();
+            let expected = graphemes.concat();
+            assert_eq!(
+                text_and_cursor(&model),
+                (expected.clone(), Location::from(code_units::<S>(&expected))),
+                "backspacing {text:?}"
+            );
+        }
+    }
+}
+
+fn delete_removes_whole_graphemes<S: UnicodeString>() {
+    for text in TRICKY_TEXT {
+        let mut model = model_with_text::<S>(text);
+        model.select(Location::from(0), Location::from(0)).unwrap();
+        let mut graphemes: Vec<&str> = text.graphemes(true).collect();
+        while !graphemes.is_empty() {
+            graphemes.remove(0);
+            model.delete().unwrap();
+            assert_eq!(
+                text_and_cursor(&model),
+                (graphemes.concat(), Location::from(0)),
+                "deleting {text:?}"
+            );
+        }
+    }
+}
+
+fn cursor_moves_over_whole_graphemes<S: UnicodeString>() {
+    for text in TRICKY_TEXT {
+        let mut model = model_with_text::<S>(text);
+        model.select(Location::from(0), Location::from(0)).unwrap();
+        let mut position = 0;
+        for grapheme in text.graphemes(true) {
+            position += code_units::<S>(grapheme);
+            model
+                .move_cursor(Direction::Forwards, Granularity::Character)
+                .unwrap();
+            assert_eq!(model.state.start, Location::from(position));
+            assert_eq!(model.state.end, Location::from(position));
+        }
+        for grapheme in text.graphemes(true).rev() {
+            position -= code_units::<S>(grapheme);
+            model
+                .move_cursor(Direction::Backwards, Granularity::Character)
+                .unwrap();
+            assert_eq!(model.state.end, Location::from(position));
+        }
+    }
+}
+
+#[test]
+fn backspace_removes_whole_graphemes_utf16() {
+    backspace_removes_whole_graphemes::<Utf16String>();
+}
+
+#[test]
+fn backspace_removes_whole_graphemes_utf8() {
+    backspace_removes_whole_graphemes::<String>();
+}
+
+#[test]
+fn delete_removes_whole_graphemes_utf16() {
+    delete_removes_whole_graphemes::<Utf16String>();
+}
+
+#[test]
+fn delete_removes_whole_graphemes_utf8() {
+    delete_removes_whole_graphemes::<String>();
+}
+
+#[test]
+fn cursor_moves_over_whole_graphemes_utf16() {
+    cursor_moves_over_whole_graphemes::<Utf16String>();
+}
+
+#[test]
+fn cursor_moves_over_whole_graphemes_utf8() {
+    cursor_moves_over_whole_graphemes::<String>();
+}
+
+#[test]
+fn backspace_removes_grapheme_split_across_formatting() {
+    let mut model = cm("ae<b>\u{0301}|</b>");
+    model.backspace().unwrap();
+    assert_eq!(tx(&model), "a|");
+}
+
+#[test]
+fn backspace_at_the_start_of_a_text_node_removes_whole_grapheme() {
+    let mut model = cm("\u{1F44D}\u{1F3FD}<b>|bold</b>");
+    model.backspace().unwrap();
+    assert_eq!(tx(&model), "<b>|bold</b>");
+}
+
+#[test]
+fn backspace_word_removes_whole_emoji_sequences() {
+    let mut model =
+        cm("hi \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}\u{1F1EC}\u{1F1E7}|");
+    model.backspace_word().unwrap();
+    assert_eq!(tx(&model), "hi&nbsp;|");
+}
+
+#[test]
+fn backspace_word_handles_multi_byte_chars_in_utf8() {
+    let mut model = model_with_text::<String>("naïve café");
+    model.backspace_word().unwrap();
+    assert_eq!(
+        text_and_cursor(&model),
+        (
+            "naïve ".to_owned(),
+            Location::from(code_units::<String>("naïve "))
+        )
+    );
+}
+
+#[test]
+fn move_cursor_collapses_selection() {
+    let mut model = cm("a{bc}|d");
+    model
+        .move_cursor(Direction::Backwards, Granularity::Character)
+        .unwrap();
+    assert_eq!(tx(&model), "a|bcd");
+    let mut model = cm("a{bc}|d");
+    model
+        .move_cursor(Direction::Forwards, Granularity::Character)
+        .unwrap();
+    assert_eq!(tx(&model), "abc|d");
+}
+
+#[test]
+fn move_cursor_by_word() {
+    let mut model = cm("|one two three");
+    model
+        .move_cursor(Direction::Forwards, Granularity::Word)
+        .unwrap();
+    assert_eq!(tx(&model), "one| two three");
+    model
+        .move_cursor(Direction::Forwards, Granularity::Word)
+        .unwrap();
+    assert_eq!(tx(&model), "one two| three");
+    model
+        .move_cursor(Direction::Backwards, Granularity::Word)
+        .unwrap();
+    assert_eq!(tx(&model), "one |two three");
+}
+
+#[test]
+fn move_cursor_crosses_paragraphs() {
+    let mut model = cm("<p>ab|</p><p>cd</p>");
+    model
+        .move_cursor(Direction::Forwards, Granularity::Character)
+        .unwrap();
+    assert_eq!(tx(&model), "<p>ab</p><p>|cd</p>");
+    model
+        .move_cursor(Direction::Backwards, Granularity::Character)
+        .unwrap();
+    assert_eq!(tx(&model), "<p>ab|</p><p>cd</p>");
+}
@@ -0,0 +1,91 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    tests::testutils_composer_model::{cm, tx},
+    Key, KeyCommand, KeyEvent, KeyModifiers, Keymap, KeymapPreset,
+};
+
+#[test]
+fn ctrl_b_toggles_bold() {
+    let mut model = cm("{abc}|");
+    assert!(model.handle_key(&ctrl('b')).unwrap().is_some());
+    assert_eq!(tx(&model), "<strong>{abc}|</strong>");
+}
+
+#[test]
+fn ctrl_z_undoes_and_ctrl_shift_z_redoes() {
+    let mut model = cm("|");
+    model.replace_text("abc".into()).unwrap();
+    assert!(model.handle_key(&ctrl('z')).unwrap().is_some());
+    assert_eq!(tx(&model), "|");
+    let ctrl_shift_z =
+        KeyEvent::new(Key::Character('Z'), KeyModifiers::CTRL.with_shift());
+    assert!(model.handle_key(&ctrl_shift_z).unwrap().is_some());
+    assert_eq!(tx(&model), "abc|");
+}
+
+#[test]
+fn ctrl_backspace_deletes_a_word() {
+    let mut model = cm("abc def|");
+    let event = KeyEvent::new(Key::Backspace, KeyModifiers::CTRL);
+    assert!(model.handle_key(&event).unwrap().is_some());
+    assert_eq!(tx(&model), "abc&nbsp;|");
+}
+
+#[test]
+fn tab_indents_list_items() {
+    let mut model = cm("<ol><li>abc</li><li>def|</li></ol>");
+    let event = KeyEvent::new(Key::Tab, KeyModifiers::NONE);
+    assert!(model.handle_key(&event).unwrap().is_some());
+    assert_eq!(
+        tx(&model),
+        "<ol><li><p>abc</p><ol><li>def|</li></ol></li></ol>"
+    );
+    let event = KeyEvent::new(Key::Tab, KeyModifiers::SHIFT);
+    assert!(model.handle_key(&event).unwrap().is_some());
+    assert_eq!(tx(&model), "<ol><li>abc</li><li>def|</li></ol>");
+}
+
+#[test]
+fn tab_is_not_consumed_outside_lists() {
+    let mut model = cm("abc|");
+    let event = KeyEvent::new(Key::Tab, KeyModifiers::NONE);
+    assert!(model.handle_key(&event).unwrap().is_none());
+    assert_eq!(tx(&model), "abc|");
+}
+
+#[test]
+fn unbound_keys_are_not_consumed() {
+    let mut model = cm("abc|");
+    assert!(model.handle_key(&ctrl('q')).unwrap().is_none());
+    let event = KeyEvent::new(Key::Character('b'), KeyModifiers::NONE);
+    assert!(model.handle_key(&event).unwrap().is_none());
+    assert_eq!(tx(&model), "abc|");
+}
+
+#[test]
+fn custom_keymap_is_used() {
+    let mut model = cm("{abc}|");
+    let mut keymap = Keymap::preset(KeymapPreset::Apple);
+    keymap.bind(ctrl('k'), KeyCommand::InlineCode);
+    model.set_keymap(keymap);
+    assert!(model.handle_key(&ctrl('b')).unwrap().is_none());
+    assert!(model.handle_key(&ctrl('k')).unwrap().is_some());
+    assert_eq!(tx(&model), "<code>{abc}|</code>");
+}
+
+fn ctrl(c: char) -> KeyEvent {
+    KeyEvent::new(Key::Character(c), KeyModifiers::CTRL)
+}
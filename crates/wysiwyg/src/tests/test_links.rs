@@ -18,21 +18,21 @@ use crate::tests::testutils_conversion::utf16;
 #[test]
 fn set_link_to_empty_selection_at_end_of_alink() {
     let mut model = cm("<a href=\"https://matrix.org\">test_link</a>|");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(tx(&model), "<a href=\"https://element.io\">test_link|</a>");
 }
 
 #[test]
 fn set_link_to_empty_selection_within_a_link() {
     let mut model = cm("<a href=\"https://matrix.org\">test_|link</a>");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(tx(&model), "<a href=\"https://element.io\">test_|link</a>");
 }
 
 #[test]
 fn set_link_to_empty_selection_at_start_of_a_link() {
     let mut model = cm("<a href=\"https://matrix.org\">|test_link</a>");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(tx(&model), "<a href=\"https://element.io\">|test_link</a>");
 }
 
@@ -40,14 +40,14 @@ fn set_link_to_empty_selection_at_start_of_a_link() {
 fn set_link_to_empty_selection() {
     // This use case should never happen but in case it would...
     let mut model = cm("test|");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(tx(&model), "test|");
 }
 
 #[test]
 fn set_link_wraps_selection_in_link_tag() {
     let mut model = cm("{hello}| world");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<a href=\"https://element.io\">hello</a> world"
@@ -57,7 +57,7 @@ fn set_link_wraps_selection_in_link_tag() {
 #[test]
 fn set_link_in_multiple_leaves_of_formatted_text() {
     let mut model = cm("{<i>test_italic<b>test_italic_bold</b></i>}|");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<a href=\"https://element.io\"><i>test_italic<b>test_italic_bold</b></i></a>"
@@ -67,7 +67,7 @@ fn set_link_in_multiple_leaves_of_formatted_text() {
 #[test]
 fn set_link_in_multiple_leaves_of_formatted_text_partially_covered() {
     let mut model = cm("<i>test_it{alic<b>test_ital}|ic_bold</b></i>");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<i>test_it<a href=\"https://element.io\">alic<b>test_ital</b></a><b>ic_bold</b></i>"
@@ -77,7 +77,7 @@ fn set_link_in_multiple_leaves_of_formatted_text_partially_covered() {
 #[test]
 fn set_link_in_multiple_leaves_of_formatted_text_partially_covered_2() {
     let mut model = cm("<i><u>test_it{alic_underline</u>test_italic<b>test_ital}|ic_bold</b></i>");
-    model.set_link(utf16("https://element.io"), vec![]);
+    model.set_link(utf16("https://element.io"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<i><u>test_it</u><a href=\"https://element.io\"><u>alic_underline</u>test_italic<b>test_ital</b></a><b>ic_bold</b></i>"
@@ -87,7 +87,7 @@ fn set_link_in_multiple_leaves_of_formatted_text_partially_covered_2() {
 #[test]
 fn set_link_in_already_linked_text() {
     let mut model = cm("{<a href=\"https://element.io\">link_text</a>}|");
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<a href=\"https://matrix.org\">link_text</a>"
@@ -97,7 +97,7 @@ fn set_link_in_already_linked_text() {
 #[test]
 fn set_link_in_already_linked_text_with_partial_selection() {
     let mut model = cm("<a href=\"https://element.io\">link_{text}|</a>");
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<a href=\"https://matrix.org\">link_text</a>"
@@ -108,7 +108,7 @@ fn set_link_in_already_linked_text_with_partial_selection() {
 fn set_link_in_text_and_already_linked_text() {
     let mut model =
         cm("{non_link_text<a href=\"https://element.io\">link_text</a>}|");
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<a href=\"https://matrix.org\">non_link_textlink_text</a>"
@@ -118,7 +118,7 @@ fn set_link_in_text_and_already_linked_text() {
 #[test]
 fn set_link_in_multiple_leaves_of_formatted_text_with_link() {
     let mut model = cm("{<i><a href=\"https://element.io\">test_italic</a><b><a href=\"https://element.io\">test_italic_bold</a></b></i>}|");
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         model.state.dom.to_string(),
         "<a href=\"https://matrix.org\"><i>test_italic<b>test_italic_bold</b></i></a>"
@@ -128,7 +128,7 @@ fn set_link_in_multiple_leaves_of_formatted_text_with_link() {
 #[test]
 fn set_link_partially_highlighted_inside_a_link_and_starting_inside() {
     let mut model = cm("<a href=\"https://element.io\">test_{link</a> test}|");
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://matrix.org\">test_{link test}|</a>"
@@ -138,7 +138,7 @@ fn set_link_partially_highlighted_inside_a_link_and_starting_inside() {
 #[test]
 fn set_link_partially_highlighted_inside_a_link_and_starting_before() {
     let mut model = cm("{test <a href=\"https://element.io\">test}|_link</a>");
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://matrix.org\">{test test}|_link</a>"
@@ -148,7 +148,7 @@ fn set_link_partially_highlighted_inside_a_link_and_starting_before() {
 #[test]
 fn set_link_highlighted_inside_a_link() {
     let mut model = cm("<a href=\"https://element.io\">test {test}| test</a>");
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         r#"<a href="https://matrix.org">test {test}| test</a>"#
@@ -158,7 +158,7 @@ fn set_link_highlighted_inside_a_link() {
 #[test]
 fn set_link_around_links() {
     let mut model = cm(r#"{X <a href="linkA">A</a> <a href="linkB">B</a> Y}|"#);
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(tx(&model), r#"<a href="https://matrix.org">{X A B Y}|</a>"#);
 }
 
@@ -167,17 +167,28 @@ fn set_link_around_mentions() {
     let mut model = cm(
         r#"{X <a href="https://matrix.to/#/@test:example.org">test</a> <a href="https://matrix.to/#/@test:example.org">test</a> Y}|"#,
     );
-    model.set_link(utf16("https://matrix.org"), vec![]);
+    model.set_link(utf16("https://matrix.org"), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         r#"<a href="https://matrix.org">{X test test Y}|</a>"#
     );
 }
 
+#[test]
+fn replace_text_across_paragraphs_ending_at_start_of_link() {
+    let mut model =
+        cm(r#"<p>{ab</p><p>}|<a href="https://matrix.org">link</a></p>"#);
+    model.replace_text(utf16("c")).unwrap();
+    assert_eq!(
+        tx(&model),
+        r#"<p>c|<a href="https://matrix.org">link</a></p>"#
+    );
+}
+
 #[test]
 fn add_text_at_end_of_link() {
     let mut model = cm("<a href=\"https://element.io\">link|</a>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://element.io\">link</a>added_text|"
@@ -188,14 +199,14 @@ fn add_text_at_end_of_link() {
 fn add_text_at_end_of_link_inside_a_container() {
     let mut model =
         cm("<b>test <a href=\"https://element.io\">test_link|</a> test</b>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "<b>test <a href=\"https://element.io\">test_link</a>added_text| test</b>");
 }
 
 #[test]
 fn replace_text_partially_highlighted_inside_a_link_and_starting_inside() {
     let mut model = cm("<a href=\"https://element.io\">test_{link</a> test}|");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://element.io\">test_</a>added_text|"
@@ -207,7 +218,7 @@ fn replace_text_partially_highlighted_inside_a_link_and_starting_inside_in_a_con
 ) {
     let mut model =
         cm("<b>test <a href=\"https://element.io\">test_{link</a> test}|</b>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "<b>test <a href=\"https://element.io\">test_</a>added_text|</b>"
@@ -217,7 +228,7 @@ fn replace_text_partially_highlighted_inside_a_link_and_starting_inside_in_a_con
 #[test]
 fn replace_text_partially_highlighted_inside_a_link_and_starting_before() {
     let mut model = cm("{test <a href=\"https://element.io\">test}|_link</a>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "added_text|<a href=\"https://element.io\">_link</a>"
@@ -229,7 +240,7 @@ fn replace_text_partially_highlighted_inside_a_link_and_starting_before_in_a_con
 ) {
     let mut model =
         cm("<b>{test <a href=\"https://element.io\">test}|_link</a> test</b>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "<b>added_text|<a href=\"https://element.io\">_link</a> test</b>"
@@ -242,7 +253,7 @@ fn replace_text_partially_highlighted_inside_a_link_and_starting_before_in_a_con
 fn replace_text_with_selection_starting_in_one_link_and_ending_in_another() {
     let mut model =
         cm("test {<a href=\"https://element.io\">test_link_1</a> <a href=\"https://matrix.org\">test_link_2}|</a> test");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "test added_text| test");
 }
 
@@ -251,7 +262,7 @@ fn replace_text_with_selection_starting_partially_in_one_link_and_ending_in_anot
 ) {
     let mut model =
         cm("test <a href=\"https://element.io\">test_{link_1</a> <a href=\"https://matrix.org\">test}|_link_2</a> test");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "test <a href=\"https://element.io\">test_</a>added_text|<a href=\"https://matrix.org\">_link_2</a> test");
 }
 
@@ -262,7 +273,7 @@ fn replace_text_with_selection_starting_in_one_link_and_ending_in_another_partia
 ) {
     let mut model =
         cm("test <a href=\"https://element.io\">{test_link_1</a> <a href=\"https://matrix.org\">test}|_link_2</a> test");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "test added_text|<a href=\"https://matrix.org\">_link_2</a> test"
@@ -274,7 +285,7 @@ fn replace_text_with_selection_starting_partially_in_one_link_and_ending_in_anot
 ) {
     let mut model =
         cm("test <a href=\"https://element.io\">test_{link_1</a> <a href=\"https://matrix.org\">test_link_2}|</a> test");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "test <a href=\"https://element.io\">test_</a>added_text| test"
@@ -287,7 +298,7 @@ fn replace_text_with_selection_starting_partially_in_one_link_and_ending_in_anot
 fn replace_text_over_a_link() {
     let mut model =
         cm("test {<a href=\"https://element.io\">test_link</a>}| test");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "test added_text| test");
 }
 
@@ -295,7 +306,7 @@ fn replace_text_over_a_link() {
 fn replace_text_over_a_link_starting_before() {
     let mut model =
         cm("{test <a href=\"https://element.io\">test_link</a>}| test");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "added_text| test");
 }
 
@@ -305,7 +316,7 @@ fn replace_text_over_a_link_starting_before() {
 fn replace_text_over_a_link_ending_after() {
     let mut model =
         cm("test {<a href=\"https://element.io\">test_link</a> test}|");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "test added_text|");
 }
 
@@ -313,7 +324,7 @@ fn replace_text_over_a_link_ending_after() {
 fn replace_text_over_a_link_starting_before_and_ending_afterwards() {
     let mut model =
         cm("{test <a href=\"https://element.io\">test_link</a> test}|");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "added_text|");
 }
 
@@ -322,8 +333,8 @@ fn replace_text_in_a_partially_highlighted_container_inside_a_link_at_the_start(
 ) {
     let mut model =
         cm("<a href=\"https://element.io\"><i><b>{test_bold}|_italic_link</b></i></a>");
-    model.replace_text(utf16("added_text"));
-    assert_eq!(tx(&model), "<a href=\"https://element.io\"><i><b>added_text|_italic_link</b></i></a>");
+    model.replace_text(utf16("added_text")).unwrap();
+    assert_eq!(tx(&model), "<a href=\"https://element.io\"><b><i>added_text|_italic_link</i></b></a>");
 }
 
 #[test]
@@ -331,8 +342,8 @@ fn replace_text_in_a_partially_highlighted_container_inside_a_link_starting_and_
 ) {
     let mut model =
         cm("<a href=\"https://element.io\"><i><b>test_bold_{italic}|_link</b></i></a>");
-    model.replace_text(utf16("added_text"));
-    assert_eq!(tx(&model), "<a href=\"https://element.io\"><i><b>test_bold_added_text|_link</b></i></a>");
+    model.replace_text(utf16("added_text")).unwrap();
+    assert_eq!(tx(&model), "<a href=\"https://element.io\"><b><i>test_bold_added_text|_link</i></b></a>");
 }
 
 #[test]
@@ -340,11 +351,11 @@ fn replace_text_in_a_partially_highlighted_container_inside_a_link_starting_insi
 ) {
     let mut model =
         cm("<a href=\"https://element.io\"><i><b>test_bold_{italic_link}|</b></i></a>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     // It looses the bold and italic property, but this is actually google doc's behaviour
     // However we have task to actually support the extension of the contained containers in the future
     // This also only happens when the link is the outermost container
-    assert_eq!(tx(&model), "<a href=\"https://element.io\"><i><b>test_bold_</b></i></a>added_text|");
+    assert_eq!(tx(&model), "<a href=\"https://element.io\"><b><i>test_bold_</i></b></a>added_text|");
 }
 
 #[test]
@@ -353,7 +364,7 @@ fn replace_text_in_a_partially_highlighted_container_inside_a_link_starting_insi
 fn replace_text_in_a_completely_highlighted_container_inside_a_link() {
     let mut model =
         cm("<a href=\"https://element.io\"><i><b>{test_bold_italic_link}|</b></i></a>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "added_text|");
 }
 
@@ -362,7 +373,7 @@ fn replace_text_in_a_completely_highlighted_container_inside_a_link() {
 // TODO: Fix replacing link text when selection starts at the start of the link bug
 fn replace_text_in_a_link_inside_a_list() {
     let mut model = cm("<ul><li>list_element</li><li><a href=\"https://element.io\">{link_in_list}|</a></li></ul>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li>list_element</li><li>added_text|</li></ul>"
@@ -372,7 +383,7 @@ fn replace_text_in_a_link_inside_a_list() {
 #[test]
 fn replace_text_in_a_link_inside_a_list_partially_selected_starting_inside() {
     let mut model = cm("<ul><li>list_element</li><li><a href=\"https://element.io\">link_in_{list}|</a></li></ul>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "<ul><li>list_element</li><li><a href=\"https://element.io\">link_in_</a>added_text|</li></ul>");
 }
 
@@ -381,7 +392,7 @@ fn replace_text_in_a_link_inside_a_list_partially_selected_starting_inside() {
 // TODO: Fix replacing link text when selection starts at the start of the link bug
 fn replace_text_in_a_link_inside_a_list_partially_selected_ending_inside() {
     let mut model = cm("<ul><li>list_element</li><li><a href=\"https://element.io\">{link}|_in_list</a></li></ul>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "<ul><li>list_element</li><li>added_text|<a href=\"https://element.io\">_in_list</a></li></ul>");
 }
 
@@ -389,18 +400,20 @@ fn replace_text_in_a_link_inside_a_list_partially_selected_ending_inside() {
 fn replace_text_in_a_link_inside_a_list_partially_selected_starting_inside_ending_inside(
 ) {
     let mut model = cm("<ul><li>list_element</li><li><a href=\"https://element.io\">link{_in_}|list</a></li></ul>");
-    model.replace_text(utf16("added_text"));
+    model.replace_text(utf16("added_text")).unwrap();
     assert_eq!(tx(&model), "<ul><li>list_element</li><li><a href=\"https://element.io\">linkadded_text|list</a></li></ul>");
 }
 
 #[test]
 fn set_link_with_text() {
     let mut model = cm("test|");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "test<a href=\"https://element.io\">added_link|</a>"
@@ -410,27 +423,31 @@ fn set_link_with_text() {
 #[test]
 fn set_link_with_text_and_undo() {
     let mut model = cm("test|");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "test<a href=\"https://element.io\">added_link|</a>"
     );
-    model.undo();
+    model.undo().unwrap();
     assert_eq!(tx(&model), "test|");
 }
 
 #[test]
 fn set_link_with_text_in_container() {
     let mut model = cm("<b>test_bold|</b> test");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<b>test_bold<a href=\"https://element.io\">added_link|</a></b> test"
@@ -440,22 +457,26 @@ fn set_link_with_text_in_container() {
 #[test]
 fn set_link_with_text_on_blank_selection() {
     let mut model = cm("{   }|");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a href=\"https://element.io\">added_link|</a>");
 }
 
 #[test]
 fn set_link_with_text_on_blank_selection_after_text() {
     let mut model = cm("test{   }|");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "test<a href=\"https://element.io\">added_link|</a>"
@@ -465,11 +486,13 @@ fn set_link_with_text_on_blank_selection_after_text() {
 #[test]
 fn set_link_with_text_on_blank_selection_before_text() {
     let mut model = cm("{   }|test");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://element.io\">added_link|</a>test"
@@ -479,11 +502,13 @@ fn set_link_with_text_on_blank_selection_before_text() {
 #[test]
 fn set_link_with_text_on_blank_selection_between_texts() {
     let mut model = cm("test{   }|test");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "test<a href=\"https://element.io\">added_link|</a>test"
@@ -493,11 +518,13 @@ fn set_link_with_text_on_blank_selection_between_texts() {
 #[test]
 fn set_link_with_text_on_blank_selection_in_container() {
     let mut model = cm("<b>test{   }| test</b>");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<b>test<a href=\"https://element.io\">added_link|</a> test</b>"
@@ -507,11 +534,13 @@ fn set_link_with_text_on_blank_selection_in_container() {
 #[test]
 fn set_link_with_text_on_blank_selection_with_line_break() {
     let mut model = cm("test{  <br> }|test");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<p>test<a href=\"https://element.io\">added_link|</a>test</p>"
@@ -521,11 +550,13 @@ fn set_link_with_text_on_blank_selection_with_line_break() {
 #[test]
 fn set_link_with_text_on_blank_selection_with_different_containers() {
     let mut model = cm("<b>test_bold{ </b><br>  ~ <i> }|test_italic</i>");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<p><b>test_bold<a href=\"https://element.io\">added_link|</a></b><i>test_italic</i></p>");
 }
 
@@ -536,11 +567,13 @@ fn set_link_with_text_at_end_of_a_link() {
     // This fails returning <a href=\"https://element.io\">test_linkadded_link|</a>
     // Since it considers the added_link part as part of the first link itself
     let mut model = cm("<a href=\"https://matrix.org\">test_link|</a>");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a href=\"https://matrix.org\">test_link</a><a href=\"https://element.io\">added_link|</a>");
 }
 
@@ -548,11 +581,13 @@ fn set_link_with_text_at_end_of_a_link() {
 fn set_link_with_text_within_a_link() {
     // This use case should never happen, but just in case it would...
     let mut model = cm("<a href=\"https://matrix.org\">test|_link</a>");
-    model.set_link_with_text(
-        utf16("https://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://element.io\">testadded_link|_link</a>"
@@ -562,18 +597,22 @@ fn set_link_with_text_within_a_link() {
 #[test]
 fn set_link_without_http_scheme_and_www() {
     let mut model = cm("|");
-    model.set_link_with_text(utf16("element.io"), utf16("added_link"), vec![]);
+    model
+        .set_link_with_text(utf16("element.io"), utf16("added_link"), vec![])
+        .unwrap();
     assert_eq!(tx(&model), "<a href=\"https://element.io\">added_link|</a>");
 }
 
 #[test]
 fn set_link_without_http_scheme() {
     let mut model = cm("|");
-    model.set_link_with_text(
-        utf16("www.element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("www.element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://www.element.io\">added_link|</a>"
@@ -583,11 +622,13 @@ fn set_link_without_http_scheme() {
 #[test]
 fn set_link_do_not_change_scheme_for_http() {
     let mut model = cm("|");
-    model.set_link_with_text(
-        utf16("https://www.element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("https://www.element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://www.element.io\">added_link|</a>"
@@ -597,22 +638,26 @@ fn set_link_do_not_change_scheme_for_http() {
 #[test]
 fn set_link_do_not_change_scheme_for_udp() {
     let mut model = cm("|");
-    model.set_link_with_text(
-        utf16("udp://element.io"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("udp://element.io"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a href=\"udp://element.io\">added_link|</a>");
 }
 
 #[test]
 fn set_link_do_not_change_scheme_for_mail() {
     let mut model = cm("|");
-    model.set_link_with_text(
-        utf16("mailto:mymail@mail.com"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("mailto:mymail@mail.com"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"mailto:mymail@mail.com\">added_link|</a>"
@@ -622,11 +667,13 @@ fn set_link_do_not_change_scheme_for_mail() {
 #[test]
 fn set_link_add_mail_scheme() {
     let mut model = cm("|");
-    model.set_link_with_text(
-        utf16("mymail@mail.com"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("mymail@mail.com"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"mailto:mymail@mail.com\">added_link|</a>"
@@ -636,11 +683,13 @@ fn set_link_add_mail_scheme() {
 #[test]
 fn set_link_add_mail_scheme_with_plus() {
     let mut model = cm("|");
-    model.set_link_with_text(
-        utf16("mymail+01@mail.com"),
-        utf16("added_link"),
-        vec![],
-    );
+    model
+        .set_link_with_text(
+            utf16("mymail+01@mail.com"),
+            utf16("added_link"),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"mailto:mymail+01@mail.com\">added_link|</a>"
@@ -650,14 +699,14 @@ fn set_link_add_mail_scheme_with_plus() {
 #[test]
 fn set_link_with_selection_add_http_scheme() {
     let mut model = cm("<a href=\"https://matrix.org\">test_link</a>|");
-    model.set_link(utf16("element.io"), vec![]);
+    model.set_link(utf16("element.io"), vec![]).unwrap();
     assert_eq!(tx(&model), "<a href=\"https://element.io\">test_link|</a>");
 }
 
 #[test]
 fn set_link_accross_list_items() {
     let mut model = cm("<ul><li>Te{st</li><li>Bo}|ld</li></ul>");
-    model.set_link("https://element.io".into(), vec![]);
+    model.set_link("https://element.io".into(), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<ul>\
@@ -670,7 +719,7 @@ fn set_link_accross_list_items() {
 #[test]
 fn set_link_accross_list_items_with_container() {
     let mut model = cm("<ul><li><b>Te{st</b></li><li><b>Bo}|ld</b></li></ul>");
-    model.set_link("https://element.io".into(), vec![]);
+    model.set_link("https://element.io".into(), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<ul>\
@@ -689,7 +738,7 @@ fn set_link_across_list_items_with_multiple_inline_formattings_selected() {
     let mut model = cm(
         "<ul><li>tes{t<b>test_bold</b></li><li><i>test_}|italic</i></li></ul>",
     );
-    model.set_link("https://element.io".into(), vec![]);
+    model.set_link("https://element.io".into(), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<ul>\
@@ -708,7 +757,7 @@ fn set_link_across_list_items_including_an_entire_item() {
     // panicked at 'All child nodes of handle DomHandle { path: Some([0]) } must be either inline nodes or block nodes
     let mut model =
         cm("<ul><li>te{st1</li><li>test2</li><li>te}|st3</li></ul>");
-    model.set_link("https://element.io".into(), vec![]);
+    model.set_link("https://element.io".into(), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<ul>\
@@ -729,7 +778,7 @@ fn set_link_across_list_items_including_an_entire_item() {
 fn set_link_accross_quote() {
     let mut model =
         cm("<blockquote>test_{block_quote</blockquote><p> test}|</p>");
-    model.set_link("https://element.io".into(), vec![]);
+    model.set_link("https://element.io".into(), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote>\
@@ -744,7 +793,7 @@ fn set_link_accross_quote() {
 #[test]
 fn set_link_across_multiple_paragraphs() {
     let mut model = cm("<p>te{st1</p><p>te}|st2</p>");
-    model.set_link("https://element.io".into(), vec![]);
+    model.set_link("https://element.io".into(), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<p>te<a href=\"https://element.io\">{st1</a></p><p><a href=\"https://element.io\">te}|</a>st2</p>"
@@ -755,7 +804,7 @@ fn set_link_across_multiple_paragraphs() {
 fn set_link_across_multiple_paragraphs_containing_an_entire_pagraph() {
     // This panics saying 'All child nodes of handle DomHandle { path: Some([0]) } must be either inline nodes or block nodes'
     let mut model = cm("<p>te{st1</p><p>test2</p><p>tes}|t3</p>");
-    model.set_link("https://element.io".into(), vec![]);
+    model.set_link("https://element.io".into(), vec![]).unwrap();
     assert_eq!(
         tx(&model),
         "<p>\
@@ -773,15 +822,13 @@ fn set_link_across_multiple_paragraphs_containing_an_entire_pagraph() {
 #[test]
 fn create_link_after_enter_with_formatting_applied() {
     let mut model = cm("|");
-    model.replace_text("test ".into());
-    model.bold();
-    model.replace_text("test".into());
-    model.enter();
-    model.set_link_with_text(
-        "https://matrix.org".into(),
-        "test".into(),
-        vec![],
-    );
+    model.replace_text("test ".into()).unwrap();
+    model.bold().unwrap();
+    model.replace_text("test".into()).unwrap();
+    model.enter().unwrap();
+    model
+        .set_link_with_text("https://matrix.org".into(), "test".into(), vec![])
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<p>test <strong>test</strong></p><p><a href=\"https://matrix.org\"><strong>test|</strong></a></p>",
@@ -791,12 +838,10 @@ fn create_link_after_enter_with_formatting_applied() {
 #[test]
 fn create_link_after_enter_with_no_formatting_applied() {
     let mut model = cm("|");
-    model.enter();
-    model.set_link_with_text(
-        "https://matrix.org".into(),
-        "test".into(),
-        vec![],
-    );
+    model.enter().unwrap();
+    model
+        .set_link_with_text("https://matrix.org".into(), "test".into(), vec![])
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<p>&nbsp;</p><p><a href=\"https://matrix.org\">test|</a></p>"
@@ -806,14 +851,14 @@ fn create_link_after_enter_with_no_formatting_applied() {
 #[test]
 fn replace_text_right_before_link() {
     let mut model = cm("<a href=\"https://matrix.org\">|Matrix</a>");
-    model.replace_text("text".into());
+    model.replace_text("text".into()).unwrap();
     assert_eq!(tx(&model), "text|<a href=\"https://matrix.org\">Matrix</a>",)
 }
 
 #[test]
 fn replace_text_right_before_link_with_prev_text() {
     let mut model = cm("text|<a href=\"https://matrix.org\">Matrix</a>");
-    model.replace_text("text".into());
+    model.replace_text("text".into()).unwrap();
     assert_eq!(
         tx(&model),
         "texttext|<a href=\"https://matrix.org\">Matrix</a>",
@@ -824,7 +869,7 @@ fn replace_text_right_before_link_with_prev_text() {
 fn replace_text_right_before_link_with_formatted_prev_text() {
     let mut model =
         cm("<strong>text|</strong><a href=\"https://matrix.org\">Matrix</a>");
-    model.replace_text("text".into());
+    model.replace_text("text".into()).unwrap();
     assert_eq!(
         tx(&model),
         "<strong>texttext|</strong><a href=\"https://matrix.org\">Matrix</a>",
@@ -834,14 +879,14 @@ fn replace_text_right_before_link_with_formatted_prev_text() {
 #[test]
 fn replace_text_right_after_link() {
     let mut model = cm("<a href=\"https://matrix.org\">Matrix|</a>");
-    model.replace_text("text".into());
+    model.replace_text("text".into()).unwrap();
     assert_eq!(tx(&model), "<a href=\"https://matrix.org\">Matrix</a>text|",)
 }
 
 #[test]
 fn replace_text_right_after_link_with_next_text() {
     let mut model = cm("<a href=\"https://matrix.org\">Matrix|</a>text");
-    model.replace_text("text".into());
+    model.replace_text("text".into()).unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://matrix.org\">Matrix</a>text|text",
@@ -852,7 +897,7 @@ fn replace_text_right_after_link_with_next_text() {
 fn replace_text_right_after_link_with_next_formatted_text() {
     let mut model =
         cm("<a href=\"https://matrix.org\">Matrix|</a><strong>text</strong>");
-    model.replace_text("text".into());
+    model.replace_text("text".into()).unwrap();
     assert_eq!(
         tx(&model),
         "<a href=\"https://matrix.org\">Matrix</a><strong>text|text</strong>",
@@ -862,10 +907,12 @@ fn replace_text_right_after_link_with_next_formatted_text() {
 #[test]
 fn set_link_with_custom_attributes() {
     let mut model = cm("{hello}| world");
-    model.set_link(
-        "https://matrix.org".into(),
-        vec![("customattribute".into(), "customvalue".into())],
-    );
+    model
+        .set_link(
+            "https://matrix.org".into(),
+            vec![("customattribute".into(), "customvalue".into())],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a customattribute=\"customvalue\" href=\"https://matrix.org\">{hello}|</a> world"
@@ -875,11 +922,13 @@ fn set_link_with_custom_attributes() {
 #[test]
 fn set_link_with_text_and_custom_attributes() {
     let mut model = cm("|");
-    model.set_link_with_text(
-        "https://matrix.org".into(),
-        "link".into(),
-        vec![("customattribute".into(), "customvalue".into())],
-    );
+    model
+        .set_link_with_text(
+            "https://matrix.org".into(),
+            "link".into(),
+            vec![("customattribute".into(), "customvalue".into())],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a customattribute=\"customvalue\" href=\"https://matrix.org\">link|</a>"
@@ -892,12 +941,10 @@ fn set_link_in_list_then_exit_list() {
     let mut model = cm("|");
 
     // start a list, add a link
-    model.unordered_list();
-    model.set_link_with_text(
-        "https://matrix.org".into(),
-        "test".into(),
-        vec![],
-    );
+    model.unordered_list().unwrap();
+    model
+        .set_link_with_text("https://matrix.org".into(), "test".into(), vec![])
+        .unwrap();
 
     assert_eq!(
         tx(&model),
@@ -905,8 +952,8 @@ fn set_link_in_list_then_exit_list() {
     );
 
     // exit the list by pressing enter twice
-    model.enter();
-    model.enter();
+    model.enter().unwrap();
+    model.enter().unwrap();
 
     // check that the list has ended, a new paragraph has been started, and the link has not been split
     // into two parts
@@ -922,12 +969,10 @@ fn set_links_in_list_then_add_list_item() {
     let mut model = cm("|");
 
     // start a list, add a link
-    model.unordered_list();
-    model.set_link_with_text(
-        "https://matrix.org".into(),
-        "test".into(),
-        vec![],
-    );
+    model.unordered_list().unwrap();
+    model
+        .set_link_with_text("https://matrix.org".into(), "test".into(), vec![])
+        .unwrap();
 
     assert_eq!(
         tx(&model),
@@ -935,7 +980,7 @@ fn set_links_in_list_then_add_list_item() {
     );
 
     // press enter once to add a new list item
-    model.enter();
+    model.enter().unwrap();
 
     // check that the link has not been split into two, with one part empty
     assert_eq!(
@@ -22,11 +22,11 @@ use crate::ComposerModel;
 #[test]
 fn creating_ordered_list_and_writing() {
     let mut model = cm("|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>|</li></ol>");
     replace_text(&mut model, "abcd");
     assert_eq!(tx(&model), "<ol><li>abcd|</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>abcd</li><li>|</li></ol>");
     replace_text(&mut model, "efgh");
     assert_eq!(tx(&model), "<ol><li>abcd</li><li>efgh|</li></ol>");
@@ -35,54 +35,54 @@ fn creating_ordered_list_and_writing() {
 #[test]
 fn creating_unordered_list() {
     let mut model = cm("|");
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>|</li></ul>");
 }
 
 #[test]
 fn can_create_list_in_empty_model() {
     let mut model = ComposerModel::new();
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>|</li></ul>");
 }
 
 #[test]
 fn removing_list_item() {
     let mut model = cm("<ol><li>abcd</li><li>|</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>abcd</li></ol><p>&nbsp;|</p>");
 
     let mut model = cm("<ol><li>abcd</li><li>|</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<ol><li>abcd|</li></ol>");
 
     let mut model = cm("<ol><li>|</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
 
     let mut model = cm("<ol><li>|</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|");
 }
 
 #[test]
 fn backspacing_in_list_leaving_some_text_keeps_items_unchanged() {
     let mut model = cm("<ol><li>~abcd</li><li>~ef{gh}|</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<ol><li>~abcd</li><li>~ef|</li></ol>");
 }
 
 #[test]
 fn backspacing_whole_of_second_item_list_into_part_of_first_leaves_one_item() {
     let mut model = cm("<ol><li>~ab{cd</li><li>~efgh}|</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<ol><li>~ab|</li></ol>");
 }
 
 #[test]
 fn backspacing_empty_second_list_item_into_whole_of_first_leaves_empty_item() {
     let mut model = cm("<ol><li>~{abcd</li><li>~}|</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<ol><li>~|</li></ol>");
 }
 
@@ -90,7 +90,7 @@ fn backspacing_empty_second_list_item_into_whole_of_first_leaves_empty_item() {
 fn backspacing_trailing_part_of_a_list_item() {
     let mut model =
         cm("<ol><li>~abc{def}|</li><li><strong>~abcd</strong>ef</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li>~abc|</li><li><strong>~abcd</strong>ef</li></ol>"
@@ -106,14 +106,14 @@ fn backspacing_the_whole_list() {
             <li>Second}|</li>\
         </ul>\
         <p>More text</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>Text|</p><p>More text</p>");
 }
 
 #[test]
 fn backspacing_through_several_list_items() {
     let mut model = cm("<p>Text</p><ul><li>Fi{rst</li><li>Seco}|nd</li></ul>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>Text</p><ul><li>Fi|nd</li></ul>");
 }
 
@@ -121,7 +121,7 @@ fn backspacing_through_several_list_items() {
 fn backspacing_at_start_of_first_list_item_adds_it_to_previous_block_node_if_exists(
 ) {
     let mut model = cm("<p>Text</p><ul><li>|First</li><li>Second</li></ul>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>Text|First</p><ul><li>Second</li></ul>");
 }
 
@@ -129,7 +129,7 @@ fn backspacing_at_start_of_first_list_item_adds_it_to_previous_block_node_if_exi
 fn backspacing_at_start_of_first_list_item_extracts_it_if_no_previous_block_node_exists(
 ) {
     let mut model = cm("<ul><li>|First</li><li>Second</li></ul>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>|First</p><ul><li>Second</li></ul>");
 }
 
@@ -137,56 +137,56 @@ fn backspacing_at_start_of_first_list_item_extracts_it_if_no_previous_block_node
 // #[ignore] // TODO: fix replace_text for cases where a list item contains formatting
 fn backspacing_trailing_part_of_a_list_item_with_formatting() {
     let mut model = cm("<ol><li><strong>abc{d</strong>ef}|</li><li><strong>abcd</strong>ef</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<ol><li><strong>abc|</strong></li><li><strong>abcd</strong>ef</li></ol>")
 }
 
 #[test]
 fn entering_with_entire_selection_in_one_node_deletes_list() {
     let mut model = cm("<ol><li>{abcd}|</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
 }
 
 #[test]
 fn entering_with_entire_selection_across_multiple_nodes_deletes_list() {
     let mut model = cm("<ol><li>{abcd</li><li>}|</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
 }
 
 #[test]
 fn entering_with_entire_selection_with_formatting() {
     let mut model = cm("<ol><li><b>{abcd}|</b></li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
 }
 
 #[test]
 fn entering_with_subsequent_items() {
     let mut model = cm("<ol><li>abcd|</li><li>ef</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>abcd</li><li>|</li><li>ef</li></ol>")
 }
 
 #[test]
 fn entering_mid_text_node() {
     let mut model = cm("<ol><li>ab|gh</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>ab</li><li>|gh</li></ol>");
 }
 
 #[test]
 fn entering_mid_text_node_with_subsequent_items() {
     let mut model = cm("<ol><li>ab|cd</li><li>ef</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>ab</li><li>|cd</li><li>ef</li></ol>")
 }
 
 #[test]
 fn entering_mid_text_node_with_formatting() {
     let mut model = cm("<ol><li><strong>abc|def</strong></li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li><strong>abc</strong></li><li><strong>|def</strong></li></ol>"
@@ -196,14 +196,14 @@ fn entering_mid_text_node_with_formatting() {
 #[test]
 fn entering_mid_text_node_with_multiple_formatting() {
     let mut model = cm("<ol><li><em><strong>abc|def</strong></em></li></ol>");
-    model.enter();
-    assert_eq!(tx(&model), "<ol><li><em><strong>abc</strong></em></li><li><em><strong>|def</strong></em></li></ol>")
+    model.enter().unwrap();
+    assert_eq!(tx(&model), "<ol><li><em><strong>abc</strong></em></li><li><strong><em>|def</em></strong></li></ol>")
 }
 
 #[test]
 fn entering_mid_text_node_with_leading_formatting() {
     let mut model = cm("<ol><li><strong>abc|d</strong>ef</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li><strong>abc</strong></li><li><strong>|d</strong>ef</li></ol>"
@@ -213,7 +213,7 @@ fn entering_mid_text_node_with_leading_formatting() {
 #[test]
 fn entering_mid_text_node_with_trailing_formatting() {
     let mut model = cm("<ol><li>ab<strong>c|def</strong></li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li>ab<strong>c</strong></li><li><strong>|def</strong></li></ol>"
@@ -223,29 +223,50 @@ fn entering_mid_text_node_with_trailing_formatting() {
 #[test]
 fn entering_mid_text_node_with_selection() {
     let mut model = cm("<ol><li>ab{cdef}|gh</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>ab</li><li>|gh</li></ol>");
 }
 
 #[test]
 fn removing_list() {
     let mut model = cm("|");
-    model.ordered_list();
-    model.enter();
+    model.ordered_list().unwrap();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
 }
 
+#[test]
+fn removing_list_after_another_list() {
+    let mut model = cm("<ul><li>a</li><li>b</li></ul><ol><li>c|</li></ol>");
+    model.ordered_list().unwrap();
+    assert_eq!(tx(&model), "<ul><li>a</li><li>b</li></ul><p>c|</p>");
+}
+
+#[test]
+fn changing_list_type_with_selection_ending_after_the_list() {
+    let mut model = cm("<ul><li>a{b</li></ul><p>c}|</p>");
+    model.ordered_list().unwrap();
+    assert_eq!(tx(&model), "<ol><li>a{b</li></ol><p>c}|</p>");
+}
+
+#[test]
+fn removing_trailing_list_item_holding_a_paragraph_with_enter() {
+    let mut model = cm("<ol><li><p>abc</p></li><li><p>|</p></li></ol>");
+    model.enter().unwrap();
+    assert_eq!(tx(&model), "<ol><li><p>abc</p></li></ol><p>&nbsp;|</p>");
+}
+
 #[test]
 fn removing_trailing_list_item_with_enter() {
     let mut model = cm("<ol><li>abc</li><li>|</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>abc</li></ol><p>&nbsp;|</p>")
 }
 
 #[test]
 fn removing_trailing_list_item_with_list_toggle() {
     let mut model = cm("<ol><li>abc</li><li>|</li></ol>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>abc</li></ol><p>&nbsp;|</p>")
 }
 
@@ -253,7 +274,7 @@ fn removing_trailing_list_item_with_list_toggle() {
 fn removing_formatted_trailing_list_item_with_enter() {
     let mut model =
         cm("<ol><li><strong>abc</strong></li><li><strong>|</strong></li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li><strong>abc</strong></li></ol><p><strong>|</strong></p>"
@@ -264,7 +285,7 @@ fn removing_formatted_trailing_list_item_with_enter() {
 fn removing_formatted_trailing_list_item_with_list_toggle() {
     let mut model =
         cm("<ol><li><strong>abc</strong></li><li><strong>|</strong></li></ol>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li><strong>abc</strong></li></ol><p><strong>|</strong></p>"
@@ -274,7 +295,7 @@ fn removing_formatted_trailing_list_item_with_list_toggle() {
 #[test]
 fn enter_in_empty_indented_and_formatted_list_item() {
     let mut model = cm("<ol><li><p><strong>abc</strong></p><ol><li><strong>|</strong></li></ol></li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li><strong>abc</strong></li><li><strong>|</strong></li></ol>"
@@ -284,7 +305,7 @@ fn enter_in_empty_indented_and_formatted_list_item() {
 #[test]
 fn removing_trailing_list_item_then_replace_text() {
     let mut model = cm("<ol><li>abc</li><li>|</li></ol>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<ol><li>abc</li></ol><p>&nbsp;|</p>");
     replace_text(&mut model, "def");
     assert_eq!(tx(&model), "<ol><li>abc</li></ol><p>def|</p>");
@@ -293,41 +314,41 @@ fn removing_trailing_list_item_then_replace_text() {
 #[test]
 fn updating_list_type() {
     let mut model = cm("<ol><li>ab</li><li>cd|</li></ol>");
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>ab</li><li>cd|</li></ul>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>ab</li><li>cd|</li></ol>");
 }
 
 #[test]
 fn moving_list_item_content_out() {
     let mut model = cm("<ol><li>ab</li><li>cd|</li></ol>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>ab</li></ol><p>cd|</p>");
 }
 
 #[test]
 fn appending_new_list_to_previous() {
     let mut model = cm("<ol><li>ab</li></ol><p>cd|</p>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>ab</li><li>cd|</li></ol>");
 }
 
 #[test]
 fn creating_list_of_different_type_doesnt_merge() {
     let mut model = cm("<ul><li>foo</li></ul>bar|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>foo</li></ul><ol><li>bar|</li></ol>");
 }
 
 #[test]
 fn creating_a_new_list_immediately_after_an_old_one_joins_them() {
     let mut model = cm("abc|");
-    model.unordered_list();
-    model.enter();
-    model.enter();
-    model.replace_text(Utf16String::from_str("def"));
-    model.unordered_list();
+    model.unordered_list().unwrap();
+    model.enter().unwrap();
+    model.enter().unwrap();
+    model.replace_text(Utf16String::from_str("def")).unwrap();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>abc</li><li>def|</li></ul>");
 }
 
@@ -340,7 +361,7 @@ fn indent_single_list_item_works() {
     let range = model.state.dom.find_range(s, e);
     let can_indent = model.can_indent(&range.locations);
     assert!(can_indent);
-    model.indent();
+    model.indent().unwrap();
     assert_eq!(tx(&model), "<ul><li><p>First item</p><ul><li>Second item|</li></ul></li><li>Third item</li></ul>");
 }
 
@@ -352,7 +373,7 @@ fn indent_single_empty_list_item_works() {
     let range = model.state.dom.find_range(s, e);
     let can_indent = model.can_indent(&range.locations);
     assert!(can_indent);
-    model.indent();
+    model.indent().unwrap();
     assert_eq!(tx(&model), "<ul><li><p>First item</p><ul><li>|</li></ul></li><li>Third item</li></ul>");
 }
 
@@ -361,7 +382,7 @@ fn indent_several_list_items_simple_case_works() {
     let mut model = cm(
         "<ul><li>First item</li><li>{Second item</li><li>Third item}|</li></ul>",
     );
-    model.indent();
+    model.indent().unwrap();
     assert_eq!(tx(&model), "<ul><li><p>First item</p><ul><li>{Second item</li><li>Third item}|</li></ul></li></ul>");
 }
 
@@ -370,7 +391,7 @@ fn indent_several_list_items_complex_case_works() {
     let mut model = cm(
         "<ul><li>First item</li><li>{Second item</li><li>Third item</li><li>Fourth item}|</li></ul>",
     );
-    model.indent();
+    model.indent().unwrap();
     assert_eq!(tx(&model), "<ul><li><p>First item</p><ul><li>{Second item</li><li>Third item</li><li>Fourth item}|</li></ul></li></ul>");
 }
 
@@ -379,7 +400,7 @@ fn indent_several_list_items_with_sub_levels_works() {
     let mut model = cm(
         "<ul><li><p>First item</p><ul><li>Second item</li><li><p>{Third item</p><ul><li>Fourth item</li><li>Fifth item}|</li></ul></li></ul></li></ul>",
     );
-    model.indent();
+    model.indent().unwrap();
     assert_eq!(tx(&model), "<ul><li><p>First item</p><ul><li><p>Second item</p><ul><li><p>{Third item</p><ul><li>Fourth item</li><li>Fifth item}|</li></ul></li></ul></li></ul></li></ul>");
 }
 
@@ -387,7 +408,7 @@ fn indent_several_list_items_with_sub_levels_works() {
 fn unindent_several_items_works() {
     let mut model =
         cm("<ul><li><p>First item</p><ul><li>{Second item</li><li>Third item}|</li></ul></li></ul>");
-    model.unindent();
+    model.unindent().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li>First item</li><li>{Second item</li><li>Third item}|</li></ul>"
@@ -398,7 +419,7 @@ fn unindent_several_items_works() {
 fn unindent_nested_lists_works() {
     let mut model =
         cm("<ul><li><p>First item</p><ul><li><p>{Second item</p><ul><li>Third item}|</li></ul></li></ul></li></ul>");
-    model.unindent();
+    model.unindent().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li>First item</li><li><p>{Second item</p><ul><li>Third item}|</li></ul></li></ul>"
@@ -409,7 +430,7 @@ fn unindent_nested_lists_works() {
 fn unindent_middle_list_item_works() {
     let mut model =
         cm("<ul><li><p>First item</p><ul><li>Second item</li><li>{Third item}|</li><li>Fourth item</li></ul></li></ul>");
-    model.unindent();
+    model.unindent().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>First item</p><ul><li>Second item</li></ul></li><li><p>{Third item}|</p><ul><li>Fourth item</li></ul></li></ul>"
@@ -420,7 +441,7 @@ fn unindent_middle_list_item_works() {
 fn unindent_nested_lists_with_remnants_works() {
     let mut model =
         cm("<ul><li><p>First item</p><ul><li><p>Second item</p><ul><li>{Third item</li><li>Fourth item}|</li><li>Fifth item</li></ul></li></ul></li></ul>");
-    model.unindent();
+    model.unindent().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>First item</p><ul><li>Second item</li><li>{Third item</li><li><p>Fourth item}|</p><ul><li>Fifth item</li></ul></li></ul></li></ul>"
@@ -444,14 +465,14 @@ fn replacing_cross_list_item_selection_with_text_containing_newline_works() {
 #[test]
 fn creating_list_from_multiline_selection() {
     let mut model = cm("{abc<br />def}|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>{abc</li><li>def}|</li></ol>")
 }
 
 #[test]
 fn creating_list_from_multiline_selection_with_formatting() {
     let mut model = cm("{a<strong>b</strong>c<br />def}|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li>{a<strong>b</strong>c</li><li>def}|</li></ol>"
@@ -461,7 +482,7 @@ fn creating_list_from_multiline_selection_with_formatting() {
 #[test]
 fn creating_list_from_multiline_selection_with_leading_formatting() {
     let mut model = cm("{<strong>ab</strong>c<br />def}|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li><strong>{ab</strong>c</li><li>def}|</li></ol>"
@@ -471,7 +492,7 @@ fn creating_list_from_multiline_selection_with_leading_formatting() {
 #[test]
 fn creating_list_from_multiline_selection_with_trailing_formatting() {
     let mut model = cm("{abc<br />d<strong>ef</strong>}|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li>{abc</li><li>d<strong>ef}|</strong></li></ol>"
@@ -481,7 +502,7 @@ fn creating_list_from_multiline_selection_with_trailing_formatting() {
 #[test]
 fn creating_list_from_multiline_selection_with_cross_trailing_formatting() {
     let mut model = cm("{abc<br />d<strong>e}|f</strong>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li>{abc</li><li>d<strong>e}|f</strong></li></ol>"
@@ -491,48 +512,48 @@ fn creating_list_from_multiline_selection_with_cross_trailing_formatting() {
 #[test]
 fn creating_list_from_multiline_selection_with_cross_leading_formatting() {
     let mut model = cm("a<em>b{c</em><br />def}|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>a<em>b{c</em></li><li>def}|</li></ol>")
 }
 
 #[test]
 fn backspacing_in_empty_list_then_creating_a_new_list() {
     let mut model = cm("<ol><li>|</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|");
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>|</li></ul>");
 }
 
 #[test]
 fn toggle_list_then_type_text() {
     let mut model = cm("|");
-    model.unordered_list();
-    model.unordered_list();
+    model.unordered_list().unwrap();
+    model.unordered_list().unwrap();
     replace_text(&mut model, "A");
-    model.backspace();
-    model.unordered_list();
+    model.backspace().unwrap();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>|</li></ul>");
 }
 
 #[test]
 fn multiple_list_toggle() {
     let mut model = cm("|");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>|</li></ol>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<ol><li>|</li></ol>");
 }
 
 #[test]
 fn new_list_after_linebreak() {
     let mut model = cm("start|");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>start</p><p>&nbsp;|</p>");
 
-    model.unordered_list();
+    model.unordered_list().unwrap();
     // TODO: make 'start' be contained into a paragraph
     assert_eq!(tx(&model), "<p>start</p><ul><li>|</li></ul>");
 }
@@ -540,7 +561,7 @@ fn new_list_after_linebreak() {
 #[test]
 fn backspace_text_in_list_item() {
     let mut model = cm("<p>test</p><ol><li>looks good|</li></ol>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>test</p><ol><li>looks goo|</li></ol>")
 }
 
@@ -548,12 +569,12 @@ fn backspace_text_in_list_item() {
 fn backspacing_an_empty_indented_list_item_removes_parent_list_item_paragraph()
 {
     let mut model = cm("<ul><li><p>item</p><ul><li><p>it</p><ul><li>|</li></ul></li></ul></li></ul>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>item</p><ul><li>it|</li></ul></li></ul>"
     );
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>item</p><ul><li>it</li><li>|</li></ul></li></ul>"
@@ -564,12 +585,12 @@ fn backspacing_an_empty_indented_list_item_removes_parent_list_item_paragraph()
 fn backspacing_at_a_non_empty_indented_list_item_start_removes_parent_list_item_paragraph(
 ) {
     let mut model = cm("<ul><li><p>item</p><ul><li><p>it</p><ul><li>|em</li></ul></li></ul></li></ul>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>item</p><ul><li>it|em</li></ul></li></ul>"
     );
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>item</p><ul><li>it</li><li>|em</li></ul></li></ul>"
@@ -580,12 +601,12 @@ fn backspacing_at_a_non_empty_indented_list_item_start_removes_parent_list_item_
 fn backspacing_with_selection_across_an_indented_list_item_removes_parent_list_item_paragraph(
 ) {
     let mut model = cm("<ul><li><p>item</p><ul><li><p>i{t</p><ul><li>em</li></ul></li></ul></li></ul><p>ab}|c</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>item</p><ul><li>i|c</li></ul></li></ul>"
     );
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>item</p><ul><li>i</li><li>|c</li></ul></li></ul>"
@@ -596,7 +617,7 @@ fn backspacing_with_selection_across_an_indented_list_item_removes_parent_list_i
 fn backspacing_an_indented_list_item_with_siblings_doesnt_remove_parent_list_item_paragraph(
 ) {
     let mut model = cm("<ul><li><p>item</p><ul><li><p>i{t</p><ul><li>em}|</li><li>sibling</li></ul></li></ul></li></ul>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li><p>item</p><ul><li><p>i|</p><ul><li>sibling</li></ul></li></ul></li></ul>"
@@ -604,5 +625,5 @@ fn backspacing_an_indented_list_item_with_siblings_doesnt_remove_parent_list_ite
 }
 
 fn replace_text(model: &mut ComposerModel<Utf16String>, new_text: &str) {
-    model.replace_text(utf16(new_text));
+    model.replace_text(utf16(new_text)).unwrap();
 }
@@ -22,7 +22,7 @@ use crate::tests::testutils_composer_model::{cm, tx};
 #[test]
 fn create_list_inside_quote() {
     let mut model = cm("<blockquote><p>a|</p></blockquote>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(tx(&model), "<blockquote><ol><li>a|</li></ol></blockquote>")
 }
 
@@ -30,7 +30,7 @@ fn create_list_inside_quote() {
 fn create_list_inside_quote_with_multiple_paragraphs() {
     let mut model =
         cm("<blockquote><p>{a</p><p>b</p><p>c}|</p><p>d</p></blockquote>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote><ol><li>{a</li><li>b</li><li>c}|</li></ol><p>d</p></blockquote>"
@@ -40,7 +40,7 @@ fn create_list_inside_quote_with_multiple_paragraphs() {
 #[test]
 fn create_list_with_selected_paragraph_and_quote() {
     let mut model = cm("<p>{text</p><blockquote><p>quote}|</p></blockquote>");
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>{text</li><li>quote}|</li></ul>")
 }
 
@@ -50,7 +50,7 @@ fn create_list_with_selected_paragraph_and_quote_with_multiple_nested_paragraphs
     let mut model = cm(
         "<p>{text</p><blockquote><p>quote</p><p>more quote}|</p></blockquote>",
     );
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li>{text</li><li>quote</li><li>more quote}|</li></ul>"
@@ -60,14 +60,14 @@ fn create_list_with_selected_paragraph_and_quote_with_multiple_nested_paragraphs
 #[test]
 fn create_list_with_selected_paragraph_and_codeblock() {
     let mut model = cm("<p>{text</p><pre><code>some code}|</code></pre>");
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(tx(&model), "<ul><li>{text</li><li>some code}|</li></ul>")
 }
 
 #[test]
 fn create_list_with_selected_paragraph_and_codeblock_with_multiple_lines() {
     let mut model = cm("<p>{text</p><pre><code>code\nmore code}|</code></pre>");
-    model.unordered_list();
+    model.unordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ul><li>{text</li><li>code</li><li>more code}|</li></ul>"
@@ -77,7 +77,7 @@ fn create_list_with_selected_paragraph_and_codeblock_with_multiple_lines() {
 #[test]
 fn create_list_with_selected_paragraph_quote_and_code_block() {
     let mut model = cm("<blockquote><p>{quote</p><p>more quote</p></blockquote><p>text</p><pre><code>code\nmore code}|</code></pre>");
-    model.ordered_list();
+    model.ordered_list().unwrap();
     assert_eq!(
         tx(&model),
         "<ol><li>{quote</li><li>more quote</li><li>text</li><li>code</li><li>more code}|</li></ol>"
@@ -0,0 +1,181 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::dom::to_markdown::MarkdownOptions;
+use crate::dom::Dom;
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::tests::testutils_conversion::utf16;
+use crate::{
+    BulletMarker, ComposerModel, DomNode, EmphasisMarker, FenceStyle,
+    HardBreakStyle, MarkdownDialect, SoftBreakPolicy, ToMarkdown,
+};
+
+fn md(model: &ComposerModel<Utf16String>) -> String {
+    model.get_content_as_markdown().unwrap().to_string()
+}
+
+fn model_with(dialect: MarkdownDialect) -> ComposerModel<Utf16String> {
+    let mut model = cm("|");
+    model.set_markdown_dialect(dialect);
+    model
+}
+
+#[test]
+fn default_dialect_is_gfm_without_import_extensions() {
+    assert_eq!(
+        *cm("|").markdown_dialect(),
+        MarkdownDialect {
+            gfm_extensions: false,
+            ..MarkdownDialect::GFM
+        }
+    );
+}
+
+#[test]
+fn default_import_keeps_bare_urls_and_tables_as_text() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16(
+            "~~a~~ www.example.org\n\n| b |\n|---|",
+        ))
+        .unwrap();
+    assert_eq!(
+        tx(&model),
+        "<p><del>a</del> www.example.org</p><p>&nbsp;</p><p>| b |</p><p>|---||</p>"
+    );
+}
+
+#[test]
+fn default_dialect_keeps_existing_markdown() {
+    let model = cm("<em>a</em> <strong>b</strong> <del>c</del>|");
+    assert_eq!(md(&model), "*a* __b__ ~~c~~");
+}
+
+#[test]
+fn underscore_emphasis_swaps_delimiters() {
+    let mut model = cm("<em>a</em> <strong>b</strong>|");
+    model.set_markdown_dialect(MarkdownDialect {
+        emphasis: EmphasisMarker::Underscore,
+        ..MarkdownDialect::GFM
+    });
+    assert_eq!(md(&model), "_a_ **b**");
+}
+
+#[test]
+fn commonmark_writes_strikethrough_as_html() {
+    let mut model = cm("<del>a</del>|");
+    model.set_markdown_dialect(MarkdownDialect::COMMONMARK);
+    assert_eq!(md(&model), "<del>a</del>");
+}
+
+#[test]
+fn bullet_marker_is_configurable() {
+    let mut model = cm("<ul><li>a</li><li>b|</li></ul>");
+    model.set_markdown_dialect(MarkdownDialect {
+        bullet: BulletMarker::Dash,
+        ..MarkdownDialect::GFM
+    });
+    assert_eq!(md(&model), "- a\n- b");
+}
+
+#[test]
+fn fence_style_is_configurable() {
+    let mut model = cm("<pre><code>a|</code></pre>");
+    model.set_markdown_dialect(MarkdownDialect {
+        fence: FenceStyle::Tildes,
+        ..MarkdownDialect::GFM
+    });
+    assert_eq!(md(&model), "~~~\na\n~~~\n");
+}
+
+#[test]
+fn trailing_spaces_hard_breaks_keep_empty_lines() {
+    let dom = Dom::new(vec![
+        DomNode::new_text(utf16("a")),
+        DomNode::new_line_break(),
+        DomNode::new_text(utf16("b")),
+        DomNode::new_line_break(),
+        DomNode::new_line_break(),
+        DomNode::new_text(utf16("c")),
+    ]);
+    let options = MarkdownOptions::with_dialect(MarkdownDialect {
+        hard_break: HardBreakStyle::TrailingSpaces,
+        ..MarkdownDialect::GFM
+    });
+    let mut markdown = Utf16String::new();
+    dom.fmt_markdown(&mut markdown, &options, false).unwrap();
+    assert_eq!(markdown.to_string(), "a  \nb  \n\\\nc");
+}
+
+#[test]
+fn commonmark_import_ignores_gfm_extensions() {
+    let mut model = model_with(MarkdownDialect::COMMONMARK);
+    model
+        .set_content_from_markdown(&utf16("~~a~~ www.example.org"))
+        .unwrap();
+    assert_eq!(tx(&model), "~~a~~ www.example.org|");
+}
+
+#[test]
+fn gfm_import_links_bare_urls() {
+    let mut model = model_with(MarkdownDialect::GFM);
+    model
+        .set_content_from_markdown(&utf16("see www.example.org."))
+        .unwrap();
+    assert_eq!(
+        tx(&model),
+        "see <a href=\"http://www.example.org\">www.example.org</a>.|"
+    );
+}
+
+#[test]
+fn gfm_import_doesnt_link_urls_in_code() {
+    let mut model = model_with(MarkdownDialect::GFM);
+    model
+        .set_content_from_markdown(&utf16("`https://example.org`"))
+        .unwrap();
+    assert_eq!(tx(&model), "<code>https://example.org|</code>");
+}
+
+#[test]
+fn gfm_import_keeps_task_list_markers() {
+    let mut model = model_with(MarkdownDialect::GFM);
+    model
+        .set_content_from_markdown(&utf16("- [x] done\n- [ ] todo"))
+        .unwrap();
+    assert_eq!(tx(&model), "<ul><li>[x] done</li><li>[ ] todo|</li></ul>");
+}
+
+#[test]
+fn gfm_import_turns_table_rows_into_paragraphs() {
+    let mut model = model_with(MarkdownDialect::GFM);
+    model
+        .set_content_from_markdown(&utf16("| a | b |\n|---|---|\n| c | d |"))
+        .unwrap();
+    assert_eq!(tx(&model), "<p>a | b</p><p>c | d|</p>");
+}
+
+#[test]
+fn soft_breaks_can_become_spaces() {
+    let mut model = model_with(MarkdownDialect {
+        soft_break: SoftBreakPolicy::Space,
+        ..MarkdownDialect::GFM
+    });
+    model
+        .set_content_from_markdown(&utf16("a\nb\\\nc"))
+        .unwrap();
+    assert_eq!(tx(&model), "<p>a b</p><p>c|</p>");
+}
@@ -0,0 +1,177 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Markdown imported into the model must be written back exactly as it was
+//! typed, for every construct the model can represent.
+
+use widestring::Utf16String;
+
+use crate::tests::testutils_conversion::utf16;
+use crate::{ComposerModel, Location, MarkdownDialect, SoftBreakPolicy};
+
+/// Examples from the CommonMark and GFM specs, without the final line
+/// break when the model doesn't write one.
+const CORPUS: &[&str] = &[
+    // Emphasis and strong emphasis
+    "*foo bar*",
+    "_foo bar_",
+    "**foo bar**",
+    "__foo bar__",
+    "foo*bar*",
+    "*foo**bar**baz*",
+    "***strong emph***",
+    "*foo [bar](/url)*",
+    "**foo \"*bar*\" foo**",
+    // Code spans
+    "`foo`",
+    "`` foo ` bar ``",
+    "` `` `",
+    "`foo   bar`",
+    // Links
+    "[link](/uri \"title\")",
+    "[link](/uri)",
+    "[link](<my uri>)",
+    "[link](foo(and(bar)))",
+    "[link *foo **bar** `#`*](/uri)",
+    "<http://foo.bar.baz>",
+    "<foo@bar.example.com>",
+    // Backslash escapes and entities
+    "\\*not emphasized*",
+    "\\[not a link](/foo)",
+    "foo\\_bar\\_",
+    "&copy; &amp; &#35;",
+    "&nbsp;&amp;",
+    // Line breaks
+    "foo\\\nbar",
+    "foo  \nbar",
+    "foo\nbaz",
+    // Paragraphs
+    "aaa\n\nbbb",
+    // Headings
+    "# foo",
+    "### foo *bar*",
+    "# foo\n\nbar",
+    // Lists
+    "- foo\n- bar",
+    "+ foo\n+ bar",
+    "* foo\n* bar",
+    "1. foo\n2. bar",
+    "3) foo\n4) bar",
+    "- foo\n  - bar\n    - baz",
+    "- a\n  ```\n  b\n  ```",
+    "1. a\n   - b",
+    // Block quotes
+    "> foo\n> bar\n",
+    "> # Foo\n> bar\n",
+    "> - foo\n> - bar\n",
+    // Code blocks
+    "```\n<\n >\n```\n",
+    "~~~\naaa\n~~~\n",
+    "````\naaa\n```\n``````\n",
+    "```\naaa\n",
+    "```ruby\ndef foo(x)\n  return 3\nend\n```\n",
+    "    a simple\n      indented code block\n",
+    // GFM extensions
+    "~~Hi~~ Hello, world!",
+    "~Hi~ Hello",
+    "www.commonmark.org",
+    "Visit www.commonmark.org/help for more information.",
+    "- [x] foo\n- [ ] bar",
+];
+
+fn round_trip(markdown: &str, dialect: MarkdownDialect) -> String {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model.set_markdown_dialect(dialect);
+    model.set_content_from_markdown(&utf16(markdown)).unwrap();
+    model.get_content_as_markdown().unwrap().to_string()
+}
+
+fn assert_corpus_round_trips(dialect: MarkdownDialect) {
+    let failures: Vec<String> = CORPUS
+        .iter()
+        .filter_map(|markdown| {
+            let output = round_trip(markdown, dialect);
+            (output != *markdown)
+                .then(|| format!("{markdown:?} became {output:?}"))
+        })
+        .collect();
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn corpus_round_trips_with_the_default_dialect() {
+    assert_corpus_round_trips(MarkdownDialect::default());
+}
+
+#[test]
+fn corpus_round_trips_regardless_of_the_dialect_used_for_new_content() {
+    assert_corpus_round_trips(MarkdownDialect {
+        emphasis: crate::EmphasisMarker::Underscore,
+        bullet: crate::BulletMarker::Plus,
+        fence: crate::FenceStyle::Tildes,
+        ..MarkdownDialect::GFM
+    });
+}
+
+#[test]
+fn new_formatting_uses_the_dialect() {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model.set_content_from_markdown(&utf16("_a_ b")).unwrap();
+    model.select(2.into(), 3.into()).unwrap();
+    model.bold().unwrap();
+    assert_eq!(
+        model.get_content_as_markdown().unwrap().to_string(),
+        "_a_ __b__"
+    );
+}
+
+#[test]
+fn edited_text_escapes_markdown_characters() {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model.set_content_from_markdown(&utf16("a \\*b")).unwrap();
+    model.replace_text(utf16("c")).unwrap();
+    assert_eq!(
+        model.get_content_as_markdown().unwrap().to_string(),
+        "a \\*bc"
+    );
+}
+
+#[test]
+fn text_split_by_formatting_keeps_its_escapes() {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model
+        .set_content_from_markdown(&utf16("\\*a\\* b"))
+        .unwrap();
+    model.select(Location::from(4), Location::from(5)).unwrap();
+    model.bold().unwrap();
+    assert_eq!(
+        model.get_content_as_markdown().unwrap().to_string(),
+        "\\*a\\* __b__"
+    );
+}
+
+#[test]
+fn soft_breaks_joined_with_spaces_are_written_as_typed() {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model.set_markdown_dialect(MarkdownDialect {
+        soft_break: SoftBreakPolicy::Space,
+        ..MarkdownDialect::GFM
+    });
+    model.set_content_from_markdown(&utf16("foo\nbar")).unwrap();
+    assert_eq!(model.get_content_as_plain_text().to_string(), "foo bar");
+    assert_eq!(
+        model.get_content_as_markdown().unwrap().to_string(),
+        "foo\nbar"
+    );
+}
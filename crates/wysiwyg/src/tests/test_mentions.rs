@@ -24,36 +24,42 @@ use crate::{
 #[test]
 fn inserting_with_invalid_mention_url_does_nothing() {
     let mut model = cm("|");
-    model.insert_mention("invalid mention url".into(), "@Alice".into(), vec![]);
+    model
+        .insert_mention("invalid mention url".into(), "@Alice".into(), vec![])
+        .unwrap();
     assert_eq!(tx(&model), "|");
 }
 
 #[test]
 fn inserting_with_room_url_inserts_room_type() {
     let mut model = cm("|");
-    model.insert_mention(
-        "https://matrix.to/#/#test:example.org".into(),
-        "test room".into(),
-        vec![],
-    );
+    model
+        .insert_mention(
+            "https://matrix.to/#/#test:example.org".into(),
+            "test room".into(),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a data-mention-type=\"room\" href=\"https://matrix.to/#/#test:example.org\" contenteditable=\"false\">test room</a>&nbsp;|");
 }
 
 #[test]
 fn inserting_with_user_url_inserts_user_type() {
     let mut model = cm("|");
-    model.insert_mention(
-        "https://matrix.to/#/@test:example.org".into(),
-        "test user".into(),
-        vec![],
-    );
+    model
+        .insert_mention(
+            "https://matrix.to/#/@test:example.org".into(),
+            "test user".into(),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a data-mention-type=\"user\" href=\"https://matrix.to/#/@test:example.org\" contenteditable=\"false\">test user</a>&nbsp;|");
 }
 
 #[test]
 fn inserting_with_at_room_inner_text_inserts_at_room_type() {
     let mut model = cm("|");
-    model.insert_at_room_mention(vec![]);
+    model.insert_at_room_mention(vec![]).unwrap();
     assert_eq!(tx(&model), "<a data-mention-type=\"at-room\" href=\"#\" contenteditable=\"false\">@room</a>&nbsp;|");
 }
 
@@ -63,22 +69,28 @@ fn inserting_with_at_room_inner_text_inserts_at_room_type() {
 #[test]
 fn inserting_with_external_user_works() {
     let mut model = cm("|");
-    model.insert_mention(
-        "https://custom.custom.com/?secretstuff/#/@alice:example.org".into(),
-        "@Alice".into(),
-        vec![],
-    );
+    model
+        .insert_mention(
+            "https://custom.custom.com/?secretstuff/#/@alice:example.org"
+                .into(),
+            "@Alice".into(),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a data-mention-type=\"user\" href=\"https://custom.custom.com/?secretstuff/#/@alice:example.org\" contenteditable=\"false\">@Alice</a>&nbsp;|");
 }
 
 #[test]
 fn inserting_with_external_room_works() {
     let mut model = cm("|");
-    model.insert_mention(
-        "https://custom.custom.com/?secretstuff/#/!roomid:example.org".into(),
-        "some room".into(),
-        vec![],
-    );
+    model
+        .insert_mention(
+            "https://custom.custom.com/?secretstuff/#/!roomid:example.org"
+                .into(),
+            "some room".into(),
+            vec![],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a data-mention-type=\"room\" href=\"https://custom.custom.com/?secretstuff/#/!roomid:example.org\" contenteditable=\"false\">some room</a>&nbsp;|");
 }
 
@@ -99,16 +111,18 @@ fn mention_without_attributes() {
 #[test]
 fn mention_with_attributes() {
     let mut model = cm("|");
-    let update = model.replace_text("@alic".into());
+    let update = model.replace_text("@alic".into()).unwrap();
     let MenuAction::Suggestion(suggestion) = update.menu_action else {
         panic!("No suggestion pattern found")
     };
-    model.insert_mention_at_suggestion(
-        "https://matrix.to/#/@alice:matrix.org".into(),
-        "Alice".into(),
-        suggestion,
-        vec![("style".into(), "{some: CSS}".into())],
-    );
+    model
+        .insert_mention_at_suggestion(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "Alice".into(),
+            suggestion,
+            vec![("style".into(), "{some: CSS}".into())],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a style=\"{some: CSS}\" data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:matrix.org\" contenteditable=\"false\">Alice</a>&nbsp;|",
@@ -215,16 +229,18 @@ fn mention_insert_after() {
 #[test]
 fn formatting_node_replace_all() {
     let mut model = cm("<strong>|</strong>");
-    let update = model.replace_text("@alic".into());
+    let update = model.replace_text("@alic".into()).unwrap();
     let MenuAction::Suggestion(suggestion) = update.menu_action else {
         panic!("No suggestion pattern found")
     };
-    model.insert_mention_at_suggestion(
-        "https://matrix.to/#/@alice:matrix.org".into(),
-        "Alice".into(),
-        suggestion,
-        vec![],
-    );
+    model
+        .insert_mention_at_suggestion(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "Alice".into(),
+            suggestion,
+            vec![],
+        )
+        .unwrap();
     assert_eq!(
         tx(&model),
         "<a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:matrix.org\" contenteditable=\"false\">Alice</a>&nbsp;|",
@@ -590,7 +606,9 @@ fn selection_paragraph_spanning() {
 #[test]
 fn can_insert_at_room_mention() {
     let mut model = cm("|");
-    model.insert_at_room_mention(vec![("style".into(), "some css".into())]);
+    model
+        .insert_at_room_mention(vec![("style".into(), "some css".into())])
+        .unwrap();
     assert_eq!(tx(&model), "<a style=\"some css\" data-mention-type=\"at-room\" href=\"#\" contenteditable=\"false\">@room</a>&nbsp;|")
 }
 
@@ -715,22 +733,26 @@ fn get_mentions_state_for_multiple_mentions() {
  * HELPER FUNCTIONS
  */
 fn insert_mention_at_cursor(model: &mut ComposerModel<Utf16String>) {
-    let update = model.replace_text("@alic".into());
+    let update = model.replace_text("@alic".into()).unwrap();
     let MenuAction::Suggestion(suggestion) = update.menu_action else {
         panic!("No suggestion pattern found")
     };
-    model.insert_mention_at_suggestion(
-        "https://matrix.to/#/@alice:matrix.org".into(),
-        "Alice".into(),
-        suggestion,
-        vec![],
-    );
+    model
+        .insert_mention_at_suggestion(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "Alice".into(),
+            suggestion,
+            vec![],
+        )
+        .unwrap();
 }
 
 fn insert_mention_at_selection(model: &mut ComposerModel<Utf16String>) {
-    model.insert_mention(
-        "https://matrix.to/#/@alice:matrix.org".into(),
-        "Alice".into(),
-        vec![],
-    );
+    model
+        .insert_mention(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "Alice".into(),
+            vec![],
+        )
+        .unwrap();
 }
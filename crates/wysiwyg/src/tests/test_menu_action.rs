@@ -15,7 +15,7 @@
 use crate::PatternKey::{At, Hash, Slash};
 use crate::{
     tests::testutils_composer_model::cm, Location, MenuAction, PatternKey,
-    SuggestionPattern,
+    SuggestionPattern, SuggestionTrigger,
 };
 
 // MenuAction computation tests.
@@ -129,88 +129,176 @@ fn slash_pattern_is_not_detected_if_not_at_the_beginning_of_dom() {
     assert_eq!(model.compute_menu_action(), MenuAction::None);
 }
 
+#[test]
+fn regex_trigger_pattern_is_detected() {
+    let mut model = cm(":smi|");
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_regex(
+        ':', "[a-z_]+",
+    )
+    .unwrap()]);
+    assert_eq!(model.compute_menu_action(), sp(custom(":"), "smi", 0, 4));
+}
+
+#[test]
+fn regex_trigger_pattern_is_not_detected_if_query_does_not_match() {
+    let mut model = cm(":SMI|");
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_regex(
+        ':', "[a-z_]+",
+    )
+    .unwrap()]);
+    assert_eq!(model.compute_menu_action(), MenuAction::None);
+}
+
+#[test]
+fn regex_trigger_pattern_reports_captured_query() {
+    let mut model = cm("Nice :smile:|");
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_regex(
+        ':',
+        "([a-z_]+):",
+    )
+    .unwrap()]);
+    assert_eq!(model.compute_menu_action(), sp(custom(":"), "smile", 5, 12));
+}
+
+#[test]
+fn length_trigger_pattern_is_detected_within_bounds() {
+    let mut model = cm("!c|");
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_length(
+        '!',
+        2,
+        Some(8),
+    )]);
+    assert_eq!(model.compute_menu_action(), MenuAction::None);
+    model.replace_text("md".into()).unwrap();
+    assert_eq!(model.compute_menu_action(), sp(custom("!"), "cmd", 0, 4));
+}
+
+#[test]
+fn trigger_pattern_is_not_detected_in_code() {
+    let mut model = cm("<code>!cmd|</code>");
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_length(
+        '!', 0, None,
+    )]);
+    assert_eq!(model.compute_menu_action(), MenuAction::None);
+}
+
+#[test]
+fn first_matching_trigger_wins() {
+    let mut model = cm("!ban|");
+    model.set_custom_suggestion_triggers(vec![
+        SuggestionTrigger::with_regex('!', "[0-9]+").unwrap(),
+        SuggestionTrigger::with_regex('!', "(b)an").unwrap(),
+        SuggestionTrigger::with_length('!', 0, None),
+    ]);
+    assert_eq!(model.compute_menu_action(), sp(custom("!"), "b", 0, 4));
+}
+
+#[test]
+fn static_patterns_take_precedence_over_triggers() {
+    let mut model = cm("@alic|");
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_length(
+        '@', 0, None,
+    )]);
+    assert_eq!(model.compute_menu_action(), sp(At, "alic", 0, 5));
+}
+
+#[test]
+fn exact_custom_patterns_take_precedence_over_triggers() {
+    let mut model = cm(":)|");
+    model.set_custom_suggestion_patterns(vec![":)".into()]);
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_length(
+        ':', 0, None,
+    )]);
+    assert_eq!(model.compute_menu_action(), sp(custom(":)"), ":)", 0, 2));
+}
+
 // MenuAction update tests.
 #[test]
 fn at_pattern_is_updated_on_character_input() {
     let mut model = cm("|");
     assert_eq!(model.compute_menu_action(), MenuAction::None);
-    let update = model.replace_text("@ali".into());
+    let update = model.replace_text("@ali".into()).unwrap();
     assert_eq!(update.menu_action, sp(At, "ali", 0, 4));
-    let update = model.replace_text("c".into());
+    let update = model.replace_text("c".into()).unwrap();
     assert_eq!(update.menu_action, sp(At, "alic", 0, 5));
 }
 
 #[test]
 fn at_pattern_is_updated_on_whitespace_input() {
     let mut model = cm("@alic|");
-    let update = model.replace_text(" ".into());
+    let update = model.replace_text(" ".into()).unwrap();
     assert_eq!(update.menu_action, MenuAction::None);
 }
 
 #[test]
 fn at_pattern_is_updated_upon_selection() {
     let mut model = cm("@alic abc|");
-    let update = model.select(Location::from(5), Location::from(5));
+    let update = model.select(Location::from(5), Location::from(5)).unwrap();
     assert_eq!(update.menu_action, sp(At, "alic", 0, 5));
 }
 
 #[test]
 fn at_pattern_is_updated_on_backspace() {
     let mut model = cm("@alic|");
-    let update = model.backspace();
+    let update = model.backspace().unwrap();
     assert_eq!(update.menu_action, sp(At, "ali", 0, 4));
 
     let mut model = cm("@|alic");
-    let update = model.backspace();
+    let update = model.backspace().unwrap();
     assert_eq!(update.menu_action, MenuAction::None);
 }
 
 #[test]
 fn at_pattern_is_updated_on_delete() {
     let mut model = cm("@|alic");
-    let update = model.delete();
+    let update = model.delete().unwrap();
     assert_eq!(update.menu_action, sp(At, "lic", 0, 4));
 
     let mut model = cm("|@alic");
-    let update = model.delete();
+    let update = model.delete().unwrap();
     assert_eq!(update.menu_action, MenuAction::None);
 }
 
 #[test]
 fn at_pattern_is_still_detected_after_moving_inside_structure_node() {
     let mut model = cm("@alic|");
-    let update = model.ordered_list();
+    let update = model.ordered_list().unwrap();
     assert_eq!(update.menu_action, sp(At, "alic", 0, 5));
 
     let mut model = cm("@alic|");
-    let update = model.quote();
+    let update = model.quote().unwrap();
     assert_eq!(update.menu_action, sp(At, "alic", 0, 5));
 }
 
 #[test]
 fn at_pattern_is_still_detected_after_applying_formatting() {
     let mut model = cm("{@alic}|");
-    let update = model.bold();
+    let update = model.bold().unwrap();
     assert_eq!(update.menu_action, sp(At, "alic", 0, 5))
 }
 
 #[test]
 fn at_pattern_is_not_detected_after_moving_in_code_block() {
     let mut model = cm("@alic|");
-    let update = model.code_block();
+    let update = model.code_block().unwrap();
     assert_eq!(update.menu_action, MenuAction::None);
 }
 
 #[test]
 fn menu_action_retuns_keep_after_format_with_cursor() {
     let mut model = cm("@alic|");
-    let update = model.bold();
+    let update = model.bold().unwrap();
     assert_eq!(update.menu_action, MenuAction::Keep);
     // Unbold
-    let update = model.bold();
+    let update = model.bold().unwrap();
     assert_eq!(update.menu_action, MenuAction::Keep);
 }
 
+/// Short wrapper around [PatternKey::Custom].
+fn custom(key: &str) -> PatternKey {
+    PatternKey::Custom(key.into())
+}
+
 /// Short wrapper around [MenuAction::Suggestion(SuggestionPattern)].
 fn sp(k: PatternKey, t: &str, s: usize, e: usize) -> MenuAction {
     MenuAction::Suggestion(SuggestionPattern {
@@ -0,0 +1,132 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::tests::testutils_composer_model::{cm, tx};
+
+#[test]
+fn quote_inside_list_item_is_valid() {
+    let model = cm("<ul><li><blockquote><p>a|</p></blockquote></li></ul>");
+    model.state.dom.explicitly_assert_invariants();
+}
+
+#[test]
+fn quoting_a_code_block_in_a_list_item_wraps_the_whole_code_block() {
+    let mut model = cm("<ul><li><pre><code>a|</code></pre></li></ul>");
+    model.quote().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ul><li><blockquote><pre><code>a|</code></pre></blockquote></li></ul>"
+    );
+    model.state.dom.explicitly_assert_invariants();
+}
+
+#[test]
+fn quoting_a_code_block_wraps_all_of_its_lines() {
+    let mut model = cm("<pre><code>a|\nb</code></pre>");
+    model.quote().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<blockquote><pre><code>a|\nb</code></pre></blockquote>"
+    );
+}
+
+#[test]
+fn backspace_at_start_of_quote_in_list_item_only_removes_the_quote() {
+    let mut model = cm("<ul><li><blockquote><p>|a</p></blockquote></li></ul>");
+    model.backspace().unwrap();
+    assert_eq!(tx(&model), "<ul><li><p>|a</p></li></ul>");
+    model.state.dom.explicitly_assert_invariants();
+}
+
+#[test]
+fn backspace_at_start_of_code_block_in_list_item_only_removes_it() {
+    let mut model = cm("<ul><li><p>x</p><pre><code>|a</code></pre></li></ul>");
+    model.backspace().unwrap();
+    assert_eq!(tx(&model), "<ul><li><p>x</p><p>|a</p></li></ul>");
+}
+
+#[test]
+fn backspace_inside_quote_in_list_item_deletes_text() {
+    let mut model = cm("<ul><li><blockquote><p>ab|</p></blockquote></li></ul>");
+    model.backspace().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ul><li><blockquote><p>a|</p></blockquote></li></ul>"
+    );
+}
+
+#[test]
+fn backspace_at_start_of_list_inside_quote_removes_the_list_item() {
+    let mut model = cm("<blockquote><ul><li>|a</li></ul></blockquote>");
+    model.backspace().unwrap();
+    assert_eq!(tx(&model), "<blockquote><p>|a</p></blockquote>");
+}
+
+#[test]
+fn enter_in_quote_inside_list_item_adds_a_paragraph() {
+    let mut model = cm("<ul><li><blockquote><p>a|</p></blockquote></li></ul>");
+    model.enter().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ul><li><blockquote><p>a</p><p>&nbsp;|</p></blockquote></li></ul>"
+    );
+    model.state.dom.explicitly_assert_invariants();
+}
+
+#[test]
+fn create_list_inside_quote_inside_list_item_changes_outer_list_type() {
+    let mut model = cm("<ul><li><blockquote><p>a|</p></blockquote></li></ul>");
+    model.ordered_list().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ol><li><blockquote><p>a|</p></blockquote></li></ol>"
+    );
+}
+
+#[test]
+fn indent_list_item_containing_a_quote() {
+    let mut model = cm(
+        "<ul><li><p>a</p></li><li><blockquote><p>b|</p></blockquote></li></ul>",
+    );
+    model.indent().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ul><li><p>a</p><ul><li><blockquote><p>b|</p></blockquote></li></ul></li></ul>"
+    );
+    model.state.dom.explicitly_assert_invariants();
+}
+
+#[test]
+fn unindent_list_item_containing_a_quote() {
+    let mut model = cm(
+        "<ul><li><p>a</p><ul><li><blockquote><p>b|</p></blockquote></li></ul></li></ul>",
+    );
+    model.unindent().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ul><li>a</li><li><blockquote><p>b|</p></blockquote></li></ul>"
+    );
+    model.state.dom.explicitly_assert_invariants();
+}
+
+#[test]
+fn indent_list_item_containing_a_code_block() {
+    let mut model =
+        cm("<ul><li><p>a</p></li><li><pre><code>b|</code></pre></li></ul>");
+    model.indent().unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ul><li><p>a</p><ul><li><pre><code>b|</code></pre></li></ul></li></ul>"
+    );
+}
@@ -0,0 +1,53 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applies long pseudo-random sequences of the operations used by the
+//! fuzz target, including malformed content and out of range locations, and
+//! checks that none of them panic, whether or not the panic is caught and
+//! returned as an internal error.
+
+use std::panic::catch_unwind;
+
+use crate::fuzzing::{decode_operations, run_operations, Operation};
+
+const SEEDS: u64 = 200;
+const STEPS: usize = 50;
+/// Enough bytes for [STEPS] operations, as none takes more than four.
+const BYTES: usize = STEPS * 4;
+
+/// A small xorshift generator so every run is reproducible without
+/// pulling in extra dependencies.
+struct Rng(u64);
+
+impl Rng {
+    fn byte(&mut self) -> u8 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 24) as u8
+    }
+}
+
+#[test]
+fn random_operation_sequences_never_panic() {
+    for seed in 1..=SEEDS {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let bytes: Vec<u8> = (0..BYTES).map(|_| rng.byte()).collect();
+        let operations: Vec<Operation> =
+            decode_operations(&bytes).into_iter().take(STEPS).collect();
+        if catch_unwind(|| run_operations(&operations)).is_err() {
+            panic!("seed {seed} panicked running {operations:?}");
+        }
+    }
+}
@@ -25,7 +25,7 @@ use super::testutils_conversion::utf16;
 #[allow(deprecated)]
 fn pressing_enter_with_a_brand_new_model() {
     let mut model = ComposerModel::new();
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "<br />|");
 }
 
@@ -34,18 +34,18 @@ fn pressing_enter_with_a_brand_new_model() {
 fn adding_line_break_after_replacing_with_empty_html() {
     let mut model = ComposerModel::<Utf16String>::new();
     model.set_content_from_html(&utf16("")).unwrap();
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "<br />|");
 }
 
 #[test]
 fn pressing_enter_after_backspacing_a_paragraph() {
     let mut model = cm("|");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;</p><p>&nbsp;|</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;</p><p>&nbsp;|</p>");
 }
 
@@ -53,7 +53,7 @@ fn pressing_enter_after_backspacing_a_paragraph() {
 #[allow(deprecated)]
 fn pressing_enter_with_an_empty_model_inserts_a_line_break() {
     let mut model = cm("|");
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "<br />|");
 }
 
@@ -61,7 +61,7 @@ fn pressing_enter_with_an_empty_model_inserts_a_line_break() {
 #[allow(deprecated)]
 fn adding_line_break_at_the_beginning_of_a_line_makes_a_new_line_above() {
     let mut model = cm("|abc");
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "<br />|abc");
 }
 
@@ -69,7 +69,7 @@ fn adding_line_break_at_the_beginning_of_a_line_makes_a_new_line_above() {
 #[allow(deprecated)]
 fn adding_line_break_at_the_end_of_a_line_makes_a_new_line() {
     let mut model = cm("abc|");
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "abc<br />|");
 }
 
@@ -77,7 +77,7 @@ fn adding_line_break_at_the_end_of_a_line_makes_a_new_line() {
 #[allow(deprecated)]
 fn adding_line_break_in_the_middle_of_a_line_splits_it() {
     let mut model = cm("123|abc");
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "123<br />|abc");
 }
 
@@ -85,7 +85,7 @@ fn adding_line_break_in_the_middle_of_a_line_splits_it() {
 #[allow(deprecated)]
 fn adding_line_break_with_text_selected_splits_the_line() {
     let mut model = cm("123{XYZ}|abc");
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "123<br />|abc");
 }
 
@@ -93,23 +93,23 @@ fn adding_line_break_with_text_selected_splits_the_line() {
 #[allow(deprecated)]
 fn multiple_line_breaks_can_be_added() {
     let mut model = cm("123|abc");
-    model.add_line_break();
-    model.add_line_break();
-    model.add_line_break();
+    model.add_line_break().unwrap();
+    model.add_line_break().unwrap();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "123<br /><br /><br />|abc");
 }
 
 #[test]
 fn can_place_cursor_inside_brs_and_backspace() {
     let mut model = cm("123<br />|<br />abc");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>123|</p><p>abc</p>");
 }
 
 #[test]
 fn can_place_cursor_inside_brs_and_delete() {
     let mut model = cm("123<br />|<br />abc");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<p>123</p><p>|abc</p>");
 }
 
@@ -117,28 +117,28 @@ fn can_place_cursor_inside_brs_and_delete() {
 #[allow(deprecated)]
 fn can_add_line_break_on_later_lines() {
     let mut model = cm("asd<br />sad|");
-    model.add_line_break();
+    model.add_line_break().unwrap();
     // Doesn't crash
 }
 
 #[test]
 fn backspace_to_beginning_of_line() {
     let mut model = cm("123<br />a|bc");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>123</p><p>|bc</p>");
 }
 
 #[test]
 fn backspace_deletes_br() {
     let mut model = cm("123<br />|abc");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>123|abc</p>");
 }
 
 #[test]
 fn delete_deletes_br() {
     let mut model = cm("123|<br />abc");
-    model.delete();
+    model.delete().unwrap();
     assert_eq!(tx(&model), "<p>123|abc</p>");
 }
 
@@ -146,8 +146,8 @@ fn delete_deletes_br() {
 #[allow(deprecated)]
 fn type_after_adding_line_break() {
     let mut model = cm("a|");
-    model.add_line_break();
-    model.replace_text(Utf16String::from_str("b"));
+    model.add_line_break().unwrap();
+    model.replace_text(Utf16String::from_str("b")).unwrap();
     assert_eq!(tx(&model), "a<br />b|");
 }
 
@@ -155,9 +155,9 @@ fn type_after_adding_line_break() {
 #[allow(deprecated)]
 fn can_backspace_to_beginning_after_adding_a_line_break() {
     let mut model = cm("a|");
-    model.add_line_break();
-    model.backspace();
-    model.backspace();
+    model.add_line_break().unwrap();
+    model.backspace().unwrap();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "|");
 }
 
@@ -165,14 +165,14 @@ fn can_backspace_to_beginning_after_adding_a_line_break() {
 #[allow(deprecated)]
 fn test_replace_text_in_first_line_with_line_break() {
     let mut model = cm("{AAA}|<br />BBB");
-    model.add_line_break();
+    model.add_line_break().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;</p><p>|BBB</p><br />");
 }
 
 #[test]
 fn backspace_merges_text_nodes() {
     let mut model = cm("a<br />|b");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>a|b</p>");
     // The two text nodes were merged
     assert_eq!(model.state.dom.document().children().len(), 1);
@@ -181,21 +181,21 @@ fn backspace_merges_text_nodes() {
 #[test]
 fn backspace_merges_formatting_nodes() {
     let mut model = cm("<b>a</b><br />|<b>b</b>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p><b>a|b</b></p>");
 }
 
 #[test]
 fn enter_in_code_block_in_text_node_adds_line_break_as_text() {
     let mut model = cm("<pre><code>Test|</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<pre><code>Test\n&nbsp;|</code></pre>")
 }
 
 #[test]
 fn enter_in_code_block_at_start_adds_the_line_break() {
     let mut model = cm("<pre><code>|Test</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<pre><code>&nbsp;\n|Test</code></pre>")
 }
 
@@ -204,14 +204,14 @@ fn enter_in_code_block_at_start_with_previous_line_break_moves_it_outside_the_co
 ) {
     // The initial line break will be removed, so it's the same as having a single line break at the start
     let mut model = cm("|");
-    model.code_block();
-    model.replace_text("Test".into());
-    model.select(0.into(), 0.into());
+    model.code_block().unwrap();
+    model.replace_text("Test".into()).unwrap();
+    model.select(0.into(), 0.into()).unwrap();
     assert_eq!(tx(&model), "<pre><code>|Test</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<pre><code>&nbsp;\n|Test</code></pre>");
-    model.select(0.into(), 0.into());
-    model.enter();
+    model.select(0.into(), 0.into()).unwrap();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p><pre><code>Test</code></pre>");
 }
 
@@ -219,7 +219,7 @@ fn enter_in_code_block_at_start_with_previous_line_break_moves_it_outside_the_co
 fn enter_in_code_block_at_start_with_previous_line_break_moves_it_outside_the_code_block_with_text_around(
 ) {
     let mut model = cm("<p>ASDA</p><pre><code>|\nTest</code></pre><p>ASD</p>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<p>ASDA</p><p>&nbsp;|</p><pre><code>Test</code></pre><p>ASD</p>"
@@ -229,14 +229,14 @@ fn enter_in_code_block_at_start_with_previous_line_break_moves_it_outside_the_co
 #[test]
 fn enter_in_code_block_at_start_with_a_line_break_after_it_adds_another_one() {
     let mut model = cm("<pre><code>\n\n|Test</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<pre><code>&nbsp;\n\n\n|Test</code></pre>")
 }
 
 #[test]
 fn enter_in_code_block_after_line_break_in_middle_splits_code_block() {
     let mut model = cm("<pre><code>Test\n|\ncode blocks</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<pre><code>Test</code></pre><p>&nbsp;|</p><pre><code>code blocks</code></pre>"
@@ -247,14 +247,14 @@ fn enter_in_code_block_after_line_break_in_middle_splits_code_block() {
 fn enter_in_code_block_after_nested_line_break_in_middle_splits_code_block() {
     let mut model =
         cm("<pre><code><b><i>Test\n|\ncode blocks</i></b></code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<pre><code><b><i>Test</i></b></code></pre><p>&nbsp;|</p><pre><code><b><i>code blocks</i></b></code></pre>")
 }
 
 #[test]
 fn enter_in_code_block_after_line_break_at_end_exits_it() {
     let mut model = cm("<pre><code><b>Bold</b> plain\n|</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<pre><code><b>Bold</b> plain</code></pre><p>&nbsp;|</p>"
@@ -264,7 +264,7 @@ fn enter_in_code_block_after_line_break_at_end_exits_it() {
 #[test]
 fn simple_enter_in_quote_adds_new_paragraph() {
     let mut model = cm("<blockquote><p>Left|Right</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote><p>Left</p><p>|Right</p></blockquote>"
@@ -295,7 +295,7 @@ fn enter_after_setting_html_with_blockquote_with_a_single_line() {
     model
         .set_content_from_html(&utf16("<blockquote>A</blockquote>"))
         .unwrap();
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote><p>A</p><p>&nbsp;|</p></blockquote>"
@@ -309,7 +309,7 @@ fn enter_after_setting_html_with_blockquote_containing_formatting() {
         .set_content_from_html(&utf16("<blockquote>A<b>test</b></blockquote>"))
         .unwrap();
     assert_eq!(tx(&model), "<blockquote>A<b>test|</b></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote><p>A<b>test</b></p><p><b>|</b></p></blockquote>"
@@ -320,7 +320,7 @@ fn enter_after_setting_html_with_blockquote_containing_formatting() {
 fn double_enter_in_quote_exits_the_quote() {
     let mut model =
         cm("<blockquote><p>Left</p><p>|</p><p>Right</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote><p>Left</p></blockquote><p>&nbsp;|</p><blockquote><p>Right</p></blockquote>"
@@ -330,14 +330,14 @@ fn double_enter_in_quote_exits_the_quote() {
 #[test]
 fn double_enter_in_quote_at_start_when_empty() {
     let mut model = cm("<blockquote><p>|</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
 }
 
 #[test]
 fn double_enter_in_quote_at_start_when_not_empty() {
     let mut model = cm("<blockquote><p>|</p><p>Text</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<p>&nbsp;|</p><blockquote><p>Text</p></blockquote>"
@@ -347,7 +347,7 @@ fn double_enter_in_quote_at_start_when_not_empty() {
 #[test]
 fn double_enter_in_quote_at_end_when_not_empty_exits_it() {
     let mut model = cm("<blockquote><p>Text</p><p>|</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote><p>Text</p></blockquote><p>&nbsp;|</p>"
@@ -357,11 +357,11 @@ fn double_enter_in_quote_at_end_when_not_empty_exits_it() {
 #[test]
 fn double_enter_in_code_block_when_empty_removes_it_and_adds_new_line() {
     let mut model = cm("|");
-    model.code_block();
+    model.code_block().unwrap();
     assert_eq!(tx(&model), "<pre><code>&nbsp;|</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;|</p>");
-    model.replace_text("asd".into());
+    model.replace_text("asd".into()).unwrap();
     assert_eq!(tx(&model), "<p>asd|</p>");
 }
 
@@ -373,7 +373,7 @@ fn double_enter_in_quote_in_nested_nodes() {
             <p>|</p>\
             <p><b><i>Right</i></b></p>\
         </blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote>\
@@ -389,7 +389,7 @@ fn double_enter_in_quote_in_nested_nodes() {
 #[test]
 fn backspace_at_start_of_code_block_moves_contents_to_previous_block() {
     let mut model = cm("<p>Test</p><pre><code>|code</code></pre>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>Test|code</p>");
 }
 
@@ -398,7 +398,7 @@ fn backspace_at_end_of_code_block_adds_the_content_of_the_next_block_node_to_it(
 ) {
     let mut model =
         cm("<p>Test</p><pre><code>code</code></pre><p>|and more</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(
         tx(&model),
         "<p>Test</p><pre><code>code|and more</code></pre>"
@@ -408,30 +408,30 @@ fn backspace_at_end_of_code_block_adds_the_content_of_the_next_block_node_to_it(
 #[test]
 fn backspace_emptying_code_block_removes_it() {
     let mut model = cm("<p>Test</p><pre><code>|</code></pre>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>Test|</p>");
 }
 
 #[test]
 fn text_typed_after_line_break_goes_into_last_paragraph() {
     let mut model = cm("|");
-    model.enter();
-    model.select(1.into(), 1.into());
-    model.replace_text("Test".into());
+    model.enter().unwrap();
+    model.select(1.into(), 1.into()).unwrap();
+    model.replace_text("Test".into()).unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;</p><p>Test|</p>");
 }
 
 #[test]
 fn backspace_after_several_empty_paragraphs_deletes_only_one() {
     let mut model = cm("<p></p><p></p><p>|</p>");
-    model.backspace();
+    model.backspace().unwrap();
     assert_eq!(tx(&model), "<p>&nbsp;</p><p>&nbsp;|</p>");
 }
 
 #[test]
 fn new_line_at_start_of_link_does_not_extend_it() {
     let mut model = cm("<b><a href='test'>|Test</a></b>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<p>&nbsp;</p>\
@@ -446,7 +446,7 @@ fn new_line_at_start_of_link_does_not_extend_it() {
 #[test]
 fn new_line_at_end_of_link_does_not_extend_it() {
     let mut model = cm("<b><a href='test'>Test|</a></b>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<p>\
@@ -463,16 +463,16 @@ fn new_line_at_end_of_link_does_not_extend_it() {
 #[test]
 fn pressing_enter_after_wrapping_text_in_code_block_works() {
     let mut model = cm("|");
-    model.replace_text("Some code".into());
-    model.code_block();
-    model.enter();
+    model.replace_text("Some code".into()).unwrap();
+    model.code_block().unwrap();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<pre><code>Some code\n&nbsp;|</code></pre>")
 }
 
 #[test]
 fn pressing_enter_at_the_start_of_a_multiline_code_block() {
     let mut model = cm("<pre><code>|line_1\nline_2</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<pre><code>&nbsp;\n|line_1\nline_2</code></pre>"
@@ -482,7 +482,7 @@ fn pressing_enter_at_the_start_of_a_multiline_code_block() {
 #[test]
 fn pressing_enter_at_the_start_of_a_multiline_block_quote() {
     let mut model = cm("<blockquote><p>|line_1</p><p>line_2</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<blockquote><p>&nbsp;</p><p>|line_1</p><p>line_2</p></blockquote>"
@@ -493,7 +493,7 @@ fn pressing_enter_at_the_start_of_a_multiline_block_quote() {
 fn pressing_enter_in_the_middle_of_a_multiline_code_block_with_empty_starting_paragraphs(
 ) {
     let mut model = cm("<pre><code>|line_1\nline_2</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<pre><code>&nbsp;\n|line_1\nline_2</code></pre>"
@@ -503,7 +503,7 @@ fn pressing_enter_in_the_middle_of_a_multiline_code_block_with_empty_starting_pa
 #[test]
 fn pressing_enter_in_the_middle_of_a_multiline_code_block() {
     let mut model = cm("<pre><code>line_0\n|line_1\nline_2</code></pre>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(
         tx(&model),
         "<pre><code>line_0\n\n|line_1\nline_2</code></pre>"
@@ -515,7 +515,7 @@ fn pressing_enter_in_the_middle_of_a_multiline_block_quote_with_empty_starting_p
 ) {
     let mut model =
         cm("<blockquote><p></p><p>|line_1</p><p>line_2</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<blockquote><p>&nbsp;</p><p>&nbsp;</p><p>|line_1</p><p>line_2</p></blockquote>")
 }
 
@@ -523,15 +523,15 @@ fn pressing_enter_in_the_middle_of_a_multiline_block_quote_with_empty_starting_p
 fn pressing_enter_in_the_middle_of_a_multiline_block_quote() {
     let mut model =
         cm("<blockquote><p>line_0</p><p>|line_1</p><p>line_2</p></blockquote>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<blockquote><p>line_0</p><p>&nbsp;</p><p>|line_1</p><p>line_2</p></blockquote>")
 }
 
 #[test]
 fn several_new_lines_inside_block_node_then_text() {
     let mut model = cm("<blockquote><p>Ok|</p></blockquote>");
-    model.enter();
-    model.enter();
-    model.replace_text_in("o".into(), 4, 4);
+    model.enter().unwrap();
+    model.enter().unwrap();
+    model.replace_text_in("o".into(), 4, 4).unwrap();
     assert_eq!(tx(&model), "<blockquote><p>Ok</p></blockquote><p>o|</p>");
 }
@@ -12,23 +12,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use widestring::Utf16String;
+
 use crate::tests::testutils_composer_model::{cm, tx};
 
-use crate::{Location, TextUpdate};
+use crate::{ComposerModel, Direction, Granularity, Location, TextUpdate};
 
 #[test]
 fn selecting_ascii_characters() {
     let mut model = cm("abcdefgh|");
-    model.select(Location::from(0), Location::from(1));
+    model.select(Location::from(0), Location::from(1)).unwrap();
     assert_eq!(tx(&model), "{a}|bcdefgh");
 
-    model.select(Location::from(1), Location::from(3));
+    model.select(Location::from(1), Location::from(3)).unwrap();
     assert_eq!(tx(&model), "a{bc}|defgh");
 
-    model.select(Location::from(4), Location::from(8));
+    model.select(Location::from(4), Location::from(8)).unwrap();
     assert_eq!(tx(&model), "abcd{efgh}|");
 
-    model.select(Location::from(4), Location::from(9));
+    model.select(Location::from(4), Location::from(9)).unwrap();
     assert_eq!(tx(&model), "abcd{efgh}|");
 }
 
@@ -36,13 +38,13 @@ fn selecting_ascii_characters() {
 fn selecting_single_utf16_code_unit_characters() {
     let mut model = cm("\u{03A9}\u{03A9}\u{03A9}|");
 
-    model.select(Location::from(0), Location::from(1));
+    model.select(Location::from(0), Location::from(1)).unwrap();
     assert_eq!(tx(&model), "{\u{03A9}}|\u{03A9}\u{03A9}");
 
-    model.select(Location::from(0), Location::from(3));
+    model.select(Location::from(0), Location::from(3)).unwrap();
     assert_eq!(tx(&model), "{\u{03A9}\u{03A9}\u{03A9}}|");
 
-    model.select(Location::from(1), Location::from(2));
+    model.select(Location::from(1), Location::from(2)).unwrap();
     assert_eq!(tx(&model), "\u{03A9}{\u{03A9}}|\u{03A9}");
 }
 
@@ -50,13 +52,13 @@ fn selecting_single_utf16_code_unit_characters() {
 fn selecting_multiple_utf16_code_unit_characters() {
     let mut model = cm("\u{1F4A9}\u{1F4A9}\u{1F4A9}|");
 
-    model.select(Location::from(0), Location::from(2));
+    model.select(Location::from(0), Location::from(2)).unwrap();
     assert_eq!(tx(&model), "{\u{1F4A9}}|\u{1F4A9}\u{1F4A9}");
 
-    model.select(Location::from(0), Location::from(6));
+    model.select(Location::from(0), Location::from(6)).unwrap();
     assert_eq!(tx(&model), "{\u{1F4A9}\u{1F4A9}\u{1F4A9}}|");
 
-    model.select(Location::from(2), Location::from(4));
+    model.select(Location::from(2), Location::from(4)).unwrap();
     assert_eq!(tx(&model), "\u{1F4A9}{\u{1F4A9}}|\u{1F4A9}");
 }
 
@@ -64,25 +66,25 @@ fn selecting_multiple_utf16_code_unit_characters() {
 fn selecting_complex_characters() {
     let mut model = cm("aaa\u{03A9}bbb\u{1F469}\u{1F3FF}\u{200D}\u{1F680}ccc|");
 
-    model.select(Location::from(0), Location::from(3));
+    model.select(Location::from(0), Location::from(3)).unwrap();
     assert_eq!(
         tx(&model),
         "{aaa}|\u{03A9}bbb\u{1F469}\u{1F3FF}\u{200D}\u{1F680}ccc"
     );
 
-    model.select(Location::from(0), Location::from(4));
+    model.select(Location::from(0), Location::from(4)).unwrap();
     assert_eq!(
         tx(&model),
         "{aaa\u{03A9}}|bbb\u{1F469}\u{1F3FF}\u{200D}\u{1F680}ccc"
     );
 
-    model.select(Location::from(7), Location::from(14));
+    model.select(Location::from(7), Location::from(14)).unwrap();
     assert_eq!(
         tx(&model),
         "aaa\u{03A9}bbb{\u{1F469}\u{1F3FF}\u{200D}\u{1F680}}|ccc"
     );
 
-    model.select(Location::from(7), Location::from(15));
+    model.select(Location::from(7), Location::from(15)).unwrap();
     assert_eq!(
         tx(&model),
         "aaa\u{03A9}bbb{\u{1F469}\u{1F3FF}\u{200D}\u{1F680}c}|cc"
@@ -92,15 +94,15 @@ fn selecting_complex_characters() {
 #[test]
 fn selecting_within_a_tag() {
     let mut model = cm("ad|{asda}sf");
-    model.bold();
-    model.select(Location::from(3), Location::from(7));
+    model.bold().unwrap();
+    model.select(Location::from(3), Location::from(7)).unwrap();
     assert_eq!(tx(&model), "ad<strong>a{sda</strong>s}|f");
 }
 
 #[test]
 fn selecting_creates_a_selection_update() {
     let mut model = cm("abcdef|");
-    let update = model.select(Location::from(2), Location::from(6));
+    let update = model.select(Location::from(2), Location::from(6)).unwrap();
     if let TextUpdate::Select(s) = update.text_update {
         assert_eq!(s.start, Location::from(2));
         assert_eq!(s.end, Location::from(6));
@@ -108,3 +110,188 @@ fn selecting_creates_a_selection_update() {
         panic!("TextUpdate should be a selection")
     }
 }
+
+#[test]
+fn creating_from_html_keeps_the_selection_inside_the_content() {
+    let model = ComposerModel::<Utf16String>::from_html("abc", 2, 10).unwrap();
+    model.explicitly_assert_invariants();
+    assert_eq!(tx(&model), "ab{c}|");
+}
+
+#[test]
+fn select_all_selects_the_whole_document() {
+    let mut model = cm("<p>abc</p><p>d|ef</p>");
+    model.select_all().unwrap();
+    assert_eq!(tx(&model), "<p>{abc</p><p>def}|</p>");
+}
+
+#[test]
+fn select_word_at_selects_the_surrounding_word() {
+    let mut model = cm("hello wonderful world|");
+    model.select_word_at(Location::from(8)).unwrap();
+    assert_eq!(tx(&model), "hello {wonderful}| world");
+}
+
+#[test]
+fn select_word_at_end_of_word_selects_that_word() {
+    let mut model = cm("hello, world|");
+    model.select_word_at(Location::from(5)).unwrap();
+    assert_eq!(tx(&model), "{hello}|, world");
+}
+
+#[test]
+fn select_word_at_keeps_apostrophes_inside_words() {
+    let mut model = cm("we don't know|");
+    model.select_word_at(Location::from(4)).unwrap();
+    assert_eq!(tx(&model), "we {don't}| know");
+}
+
+#[test]
+fn select_word_at_does_not_cross_formatting_boundaries_inside_words() {
+    let mut model = cm("a <b>bo</b>ld move|");
+    model.select_word_at(Location::from(3)).unwrap();
+    assert_eq!(tx(&model), "a <b>{bo</b>ld}| move");
+}
+
+#[test]
+fn select_word_at_mention_selects_the_mention() {
+    let mut model = cm("hello |");
+    model
+        .insert_mention(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "Alice".into(),
+            vec![],
+        )
+        .unwrap();
+    model.select_word_at(Location::from(6)).unwrap();
+    assert_eq!(
+        tx(&model),
+        "hello {<a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:matrix.org\" contenteditable=\"false\">Alice</a>}|&nbsp;"
+    );
+}
+
+#[test]
+fn select_word_at_does_not_cross_line_breaks() {
+    let mut model = cm("<p>abc</p><p>def|</p>");
+    model.select_word_at(Location::from(3)).unwrap();
+    assert_eq!(tx(&model), "<p>{abc}|</p><p>def</p>");
+    model.select_word_at(Location::from(4)).unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>{def}|</p>");
+}
+
+#[test]
+fn select_sentence_at_selects_the_sentence_without_trailing_space() {
+    let mut model = cm("One thing. Another thing! Last|");
+    model.select_sentence_at(Location::from(14)).unwrap();
+    assert_eq!(tx(&model), "One thing. {Another thing!}| Last");
+}
+
+#[test]
+fn select_paragraph_at_selects_the_block() {
+    let mut model = cm("<p>abc</p><p>de <b>fg</b></p><p>hij|</p>");
+    model.select_paragraph_at(Location::from(5)).unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>{de <b>fg}|</b></p><p>hij</p>");
+}
+
+#[test]
+#[allow(deprecated)]
+fn select_paragraph_at_stops_at_line_breaks() {
+    let mut model = cm("abc|");
+    model.add_line_break().unwrap();
+    model.replace_text("def".into()).unwrap();
+    model.add_line_break().unwrap();
+    model.replace_text("ghi".into()).unwrap();
+    model.select_paragraph_at(Location::from(5)).unwrap();
+    assert_eq!(tx(&model), "abc<br />{def}|<br />ghi");
+}
+
+#[test]
+fn select_paragraph_at_in_an_empty_paragraph_places_the_cursor() {
+    let mut model = cm("<p>abc</p><p></p><p>def|</p>");
+    model.select_paragraph_at(Location::from(4)).unwrap();
+    assert_eq!(tx(&model), "<p>abc</p><p>&nbsp;|</p><p>def</p>");
+}
+
+#[test]
+fn extend_selection_by_word() {
+    let mut model = cm("|one two, three");
+    model
+        .extend_selection_by(Granularity::Word, Direction::Forwards)
+        .unwrap();
+    assert_eq!(tx(&model), "{one}| two, three");
+    model
+        .extend_selection_by(Granularity::Word, Direction::Forwards)
+        .unwrap();
+    assert_eq!(tx(&model), "{one two}|, three");
+    model
+        .extend_selection_by(Granularity::Word, Direction::Forwards)
+        .unwrap();
+    assert_eq!(tx(&model), "{one two, three}|");
+    model
+        .extend_selection_by(Granularity::Word, Direction::Backwards)
+        .unwrap();
+    assert_eq!(tx(&model), "{one two, }|three");
+}
+
+#[test]
+fn extend_selection_by_word_steps_through_neighbouring_blocks() {
+    let mut model =
+        cm("<p>one</p><p>two|</p><p></p><ul><li><p>three</p></li></ul>");
+    model
+        .extend_selection_by(Granularity::Word, Direction::Forwards)
+        .unwrap();
+    assert_eq!(
+        model.get_selection(),
+        (Location::from(7), Location::from(8))
+    );
+    model
+        .extend_selection_by(Granularity::Word, Direction::Forwards)
+        .unwrap();
+    assert_eq!(
+        model.get_selection(),
+        (Location::from(7), Location::from(14))
+    );
+    model.select(Location::from(4), Location::from(4)).unwrap();
+    model
+        .extend_selection_by(Granularity::Word, Direction::Backwards)
+        .unwrap();
+    assert_eq!(
+        model.get_selection(),
+        (Location::from(4), Location::from(0))
+    );
+}
+
+#[test]
+fn extend_selection_by_character_treats_emoji_as_one_character() {
+    let mut model = cm("a|\u{1F44D}\u{1F3FD}b");
+    model
+        .extend_selection_by(Granularity::Character, Direction::Forwards)
+        .unwrap();
+    assert_eq!(tx(&model), "a{\u{1F44D}\u{1F3FD}}|b");
+}
+
+#[test]
+fn extend_selection_by_paragraph_and_document() {
+    let mut model = cm("<p>a|bc</p><p>def</p>");
+    model
+        .extend_selection_by(Granularity::Paragraph, Direction::Forwards)
+        .unwrap();
+    assert_eq!(tx(&model), "<p>a{bc}|</p><p>def</p>");
+    model
+        .extend_selection_by(Granularity::Paragraph, Direction::Forwards)
+        .unwrap();
+    assert_eq!(tx(&model), "<p>a{bc</p><p>def}|</p>");
+    model
+        .extend_selection_by(Granularity::Document, Direction::Backwards)
+        .unwrap();
+    assert_eq!(tx(&model), "<p>|{a}bc</p><p>def</p>");
+}
+
+#[test]
+fn extend_selection_at_the_end_does_nothing() {
+    let mut model = cm("abc|");
+    let update = model
+        .extend_selection_by(Granularity::Character, Direction::Forwards)
+        .unwrap();
+    assert_eq!(update.text_update, TextUpdate::Keep);
+}
@@ -16,9 +16,8 @@ use indoc::indoc;
 use widestring::Utf16String;
 
 use crate::{
-    dom::DomCreationError,
     tests::{testutils_composer_model::tx, testutils_conversion::utf16},
-    HtmlParseError,
+    ComposerError, HtmlParseError,
 };
 
 use super::testutils_composer_model::cm;
@@ -38,7 +37,7 @@ fn set_content_from_html_invalid() {
         .unwrap_err();
     assert_eq!(
         error,
-        DomCreationError::HtmlParseError(HtmlParseError::new(vec![
+        ComposerError::HtmlParseError(HtmlParseError::new(vec![
             "Unexpected open tag at end of body".into()
         ]))
     );
@@ -156,6 +155,105 @@ fn set_content_from_html_single_br() {
     assert_eq!(tx(&model), "<p>test</p><p>test|</p>");
 }
 
+#[test]
+fn set_content_from_markdown_hard_break() {
+    let mut model = cm("|");
+    model.set_content_from_markdown(&utf16("a  \nb")).unwrap();
+    assert_eq!(tx(&model), "<p>a</p><p>b|</p>");
+}
+
+#[test]
+fn set_content_from_markdown_paragraphs_followed_by_list() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16("line1\nline2\n\n* item"))
+        .unwrap();
+    assert_eq!(
+        tx(&model),
+        "<p>line1</p><p>line2</p><ul><li>item|</li></ul>"
+    );
+}
+
+#[test]
+fn set_content_from_markdown_nested_lists() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16("1. a\n   * b\n   * c\n2. d"))
+        .unwrap();
+    assert_eq!(
+        tx(&model),
+        "<ol><li><p>a</p><ul><li>b</li><li>c</li></ul></li><li>d|</li></ol>"
+    );
+}
+
+#[test]
+fn set_content_from_markdown_heading_becomes_paragraph() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16("# Title\n\ntext"))
+        .unwrap();
+    assert_eq!(tx(&model), "<p>Title</p><p>&nbsp;</p><p>text|</p>");
+}
+
+#[test]
+fn set_content_from_markdown_link_to_user_becomes_mention() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16(
+            "hi [Alice](https://matrix.to/#/@alice:matrix.org)",
+        ))
+        .unwrap();
+    assert_eq!(
+        tx(&model),
+        "hi <a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:matrix.org\" contenteditable=\"false\">Alice</a>|"
+    );
+}
+
+#[test]
+fn set_content_from_markdown_mention_html_in_a_link_becomes_text() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16(
+            "[a <a data-mention-type=\"at-room\" href=\"#\">@room</a>](https://x.org)",
+        ))
+        .unwrap();
+    assert_eq!(tx(&model), "<a href=\"https://x.org\">a @room|</a>");
+}
+
+#[test]
+fn set_content_from_markdown_inline_html() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16("a <u>b **c**</u> d"))
+        .unwrap();
+    assert_eq!(tx(&model), "a <u>b <strong>c</strong></u> d|");
+}
+
+#[test]
+fn set_content_from_markdown_html_block() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16("<blockquote>quote</blockquote>"))
+        .unwrap();
+    assert_eq!(tx(&model), "<blockquote>quote|</blockquote>");
+}
+
+#[test]
+fn set_content_from_markdown_unclosed_html_is_kept_as_text() {
+    let mut model = cm("|");
+    model.set_content_from_markdown(&utf16("a<b>c")).unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "a<b>c");
+}
+
+#[test]
+fn set_content_from_markdown_drops_images() {
+    let mut model = cm("|");
+    model
+        .set_content_from_markdown(&utf16("a ![alt](img.png) b"))
+        .unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "a  b");
+}
+
 #[test]
 fn set_content_from_html_multiple_br() {
     let mut model = cm("|");
@@ -171,7 +269,7 @@ fn clear() {
     model
         .set_content_from_html(&Utf16String::from("content"))
         .unwrap();
-    model.clear();
+    model.clear().unwrap();
     assert_eq!(tx(&model), "|");
 }
 
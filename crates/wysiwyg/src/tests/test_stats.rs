@@ -0,0 +1,206 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::{
+    ComposerModel, ComposerStats, MaxLength, MaxLengthBehaviour,
+    MaxLengthExceeded,
+};
+
+fn model_with_max_length(
+    html: &str,
+    characters: usize,
+    behaviour: MaxLengthBehaviour,
+) -> ComposerModel<Utf16String> {
+    let mut model = cm(html);
+    model.set_max_length(Some(MaxLength {
+        characters,
+        behaviour,
+    }));
+    model
+}
+
+#[test]
+fn stats_of_an_empty_model() {
+    let model = cm("|");
+    assert_eq!(model.stats(), ComposerStats::default());
+}
+
+#[test]
+fn stats_count_characters_graphemes_and_words() {
+    let model = cm("Hello <b>wörld</b> 👨‍👩‍👧|");
+    let stats = model.stats();
+    assert_eq!(stats.characters, 17);
+    assert_eq!(stats.graphemes, 13);
+    assert_eq!(stats.words, 2);
+    assert_eq!(stats.body_bytes, "Hello wörld 👨‍👩‍👧".len());
+    assert_eq!(
+        stats.formatted_body_bytes,
+        "Hello <b>wörld</b> 👨‍👩‍👧".len()
+    );
+}
+
+#[test]
+fn stats_count_the_line_breaks_ending_paragraphs() {
+    let model = cm("<p>one</p><p>two|</p>");
+    let stats = model.stats();
+    // The plain text body is "one\ntwo\n"
+    assert_eq!(stats.characters, 8);
+    assert_eq!(stats.words, 2);
+    assert_eq!(stats.formatted_body_bytes, "one<br />two".len());
+}
+
+#[test]
+fn input_within_max_length_is_inserted() {
+    let mut model =
+        model_with_max_length("abc|", 5, MaxLengthBehaviour::Reject);
+    let update = model.replace_text("de".into()).unwrap();
+    assert_eq!(tx(&model), "abcde|");
+    assert_eq!(update.max_length_exceeded, None);
+}
+
+#[test]
+fn input_beyond_max_length_is_rejected() {
+    let mut model =
+        model_with_max_length("abc|", 5, MaxLengthBehaviour::Reject);
+    let update = model.replace_text("def".into()).unwrap();
+    assert_eq!(tx(&model), "abc|");
+    assert_eq!(
+        update.max_length_exceeded,
+        Some(MaxLengthExceeded::Rejected)
+    );
+    // Nothing was added to the undo history
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "abc|");
+}
+
+#[test]
+fn input_beyond_max_length_is_truncated() {
+    let mut model =
+        model_with_max_length("abc|", 5, MaxLengthBehaviour::Truncate);
+    let update = model.replace_text("defgh".into()).unwrap();
+    assert_eq!(tx(&model), "abcde|");
+    assert_eq!(
+        update.max_length_exceeded,
+        Some(MaxLengthExceeded::Truncated)
+    );
+}
+
+#[test]
+fn truncation_does_not_split_graphemes() {
+    let mut model =
+        model_with_max_length("a|", 4, MaxLengthBehaviour::Truncate);
+    // The flag is 2 characters, which would leave just enough room for
+    // half of the family emoji
+    model.replace_text("🇬🇧👨‍👩‍👧".into()).unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "a🇬🇧");
+}
+
+#[test]
+fn truncated_input_that_leaves_nothing_is_rejected() {
+    let mut model =
+        model_with_max_length("abcde|", 5, MaxLengthBehaviour::Truncate);
+    let update = model.replace_text("f".into()).unwrap();
+    assert_eq!(tx(&model), "abcde|");
+    assert_eq!(
+        update.max_length_exceeded,
+        Some(MaxLengthExceeded::Rejected)
+    );
+}
+
+#[test]
+fn replacing_a_selection_takes_the_removed_text_into_account() {
+    let mut model =
+        model_with_max_length("ab{cd}|", 4, MaxLengthBehaviour::Reject);
+    let update = model.replace_text("xy".into()).unwrap();
+    assert_eq!(tx(&model), "abxy|");
+    assert_eq!(update.max_length_exceeded, None);
+}
+
+#[test]
+fn replace_text_in_respects_max_length() {
+    let mut model =
+        model_with_max_length("abc|", 4, MaxLengthBehaviour::Truncate);
+    model.replace_text_in("xyz".into(), 0, 0).unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "xabc");
+}
+
+#[test]
+fn content_already_too_long_can_be_shortened_but_not_grown() {
+    let mut model =
+        model_with_max_length("abcdef|", 3, MaxLengthBehaviour::Reject);
+    model.replace_text("g".into()).unwrap();
+    assert_eq!(tx(&model), "abcdef|");
+    model.select(4.into(), 6.into()).unwrap();
+    model.replace_text("x".into()).unwrap();
+    assert_eq!(tx(&model), "abcdx|");
+}
+
+#[test]
+fn removing_the_max_length_allows_any_input() {
+    let mut model =
+        model_with_max_length("abc|", 3, MaxLengthBehaviour::Reject);
+    model.set_max_length(None);
+    model.replace_text("def".into()).unwrap();
+    assert_eq!(tx(&model), "abcdef|");
+}
+
+#[test]
+fn replacing_text_across_paragraphs_counts_the_removed_line_break() {
+    let mut model = model_with_max_length(
+        "<p>a{b</p><p>c}|d</p>",
+        6,
+        MaxLengthBehaviour::Reject,
+    );
+    model.replace_text("wxyz".into()).unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "ab\ncd\n");
+    model.replace_text("xyz".into()).unwrap();
+    assert_eq!(tx(&model), "<p>axyz|d</p>");
+}
+
+#[test]
+fn max_length_follows_edits_to_any_paragraph() {
+    let mut model = model_with_max_length(
+        "<p>ab|</p><p>cd</p>",
+        8,
+        MaxLengthBehaviour::Reject,
+    );
+    model.replace_text("x".into()).unwrap();
+    model.replace_text("y".into()).unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "abxy\ncd\n");
+    let update = model.replace_text("z".into()).unwrap();
+    assert_eq!(
+        update.max_length_exceeded,
+        Some(MaxLengthExceeded::Rejected)
+    );
+
+    model.select(9.into(), 9.into()).unwrap();
+    model.backspace().unwrap();
+    model.backspace().unwrap();
+    model.replace_text("z".into()).unwrap();
+    model.replace_text("z".into()).unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "abxy\nzz\n");
+    let update = model.replace_text("z".into()).unwrap();
+    assert_eq!(
+        update.max_length_exceeded,
+        Some(MaxLengthExceeded::Rejected)
+    );
+
+    model.undo().unwrap();
+    assert_eq!(model.get_content_as_plain_text(), "abxy\nz\n");
+    let update = model.replace_text("z".into()).unwrap();
+    assert_eq!(update.max_length_exceeded, None);
+}
@@ -12,17 +12,41 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::MenuAction;
+use crate::{MenuAction, PatternKey, SuggestionTrigger};
 
 use super::testutils_composer_model::{cm, tx};
 
 #[test]
 fn test_replace_text_suggestion() {
     let mut model = cm("|");
-    let update = model.replace_text("/".into());
+    let update = model.replace_text("/".into()).unwrap();
     let MenuAction::Suggestion(suggestion) = update.menu_action else {
         panic!("No suggestion pattern found")
     };
-    model.replace_text_suggestion("/invite".into(), suggestion, true);
+    model
+        .replace_text_suggestion("/invite".into(), suggestion, true)
+        .unwrap();
     assert_eq!(tx(&model), "/invite&nbsp;|");
 }
+
+#[test]
+fn test_replace_text_suggestion_from_trigger() {
+    let mut model = cm("|");
+    model.set_custom_suggestion_triggers(vec![SuggestionTrigger::with_regex(
+        '!', "[a-z]+",
+    )
+    .unwrap()]);
+    let update = model.replace_text("Hi".into()).unwrap();
+    assert_eq!(update.menu_action, MenuAction::None);
+    model.replace_text(" ".into()).unwrap();
+    let update = model.replace_text("!ki".into()).unwrap();
+    let MenuAction::Suggestion(suggestion) = update.menu_action else {
+        panic!("No suggestion pattern found")
+    };
+    assert_eq!(suggestion.key, PatternKey::Custom("!".into()));
+    assert_eq!(suggestion.text, "ki");
+    model
+        .replace_text_suggestion("!kick".into(), suggestion, true)
+        .unwrap();
+    assert_eq!(tx(&model), "Hi !kick&nbsp;|");
+}
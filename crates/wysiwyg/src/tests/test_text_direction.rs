@@ -0,0 +1,255 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::tests::testutils_conversion::utf16;
+use crate::{ComposerModel, TextDirection, ToHtml};
+
+#[test]
+fn setting_direction_wraps_inline_content_into_a_paragraph() {
+    let mut model = cm("abc|");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    assert_eq!(tx(&model), r#"<p dir="rtl">abc|</p>"#);
+}
+
+#[test]
+fn setting_direction_in_an_empty_model() {
+    let mut model = cm("|");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    assert_eq!(tx(&model), r#"<p dir="rtl">&nbsp;|</p>"#);
+}
+
+#[test]
+fn setting_direction_only_affects_the_paragraph_with_the_cursor() {
+    let mut model = cm("<p>a|</p><p>b</p>");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    assert_eq!(tx(&model), r#"<p dir="rtl">a|</p><p>b</p>"#);
+}
+
+#[test]
+fn setting_direction_affects_all_selected_paragraphs() {
+    let mut model = cm("<p>{a</p><p>b</p><p>c}|</p><p>d</p>");
+    model.set_text_direction(TextDirection::Ltr).unwrap();
+    assert_eq!(
+        tx(&model),
+        r#"<p dir="ltr">{a</p><p dir="ltr">b</p><p dir="ltr">c}|</p><p>d</p>"#
+    );
+}
+
+#[test]
+fn setting_direction_replaces_the_previous_one() {
+    let mut model = cm(r#"<p dir="rtl">a|</p>"#);
+    model.set_text_direction(TextDirection::Auto).unwrap();
+    assert_eq!(tx(&model), r#"<p dir="auto">a|</p>"#);
+}
+
+#[test]
+fn setting_direction_on_list_items() {
+    let mut model = cm("<ol><li>a|</li><li>b</li></ol>");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    assert_eq!(tx(&model), r#"<ol><li dir="rtl">a|</li><li>b</li></ol>"#);
+}
+
+#[test]
+fn setting_direction_on_a_code_block() {
+    let mut model = cm("<pre><code>a|</code></pre>");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    assert_eq!(tx(&model), r#"<pre dir="rtl"><code>a|</code></pre>"#);
+}
+
+#[test]
+fn setting_direction_in_a_quote_uses_the_innermost_block() {
+    let mut model = cm("<blockquote><p>a|</p></blockquote>");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    assert_eq!(
+        tx(&model),
+        r#"<blockquote><p dir="rtl">a|</p></blockquote>"#
+    );
+}
+
+#[test]
+fn setting_direction_can_be_undone() {
+    let mut model = cm("<p>a|</p>");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "<p>a|</p>");
+    model.redo().unwrap();
+    assert_eq!(tx(&model), r#"<p dir="rtl">a|</p>"#);
+}
+
+#[test]
+fn direction_is_kept_when_splitting_a_paragraph() {
+    let mut model = cm(r#"<p dir="rtl">ab|cd</p>"#);
+    model.enter().unwrap();
+    assert_eq!(tx(&model), r#"<p dir="rtl">ab</p><p dir="rtl">|cd</p>"#);
+}
+
+#[test]
+fn rtl_blocks_are_output_with_auto_direction() {
+    let model = cm("<p>שלום</p><p>hello|</p>");
+    assert_eq!(tx(&model), r#"<p dir="auto">שלום</p><p>hello|</p>"#);
+}
+
+#[test]
+fn auto_direction_uses_the_first_strong_character() {
+    let model = cm("<p>123 مرحبا hello|</p><p>123 hello مرحبا</p>");
+    assert_eq!(
+        tx(&model),
+        r#"<p dir="auto">123 مرحبا hello|</p><p>123 hello مرحبا</p>"#
+    );
+}
+
+#[test]
+fn auto_direction_is_only_added_to_the_innermost_blocks() {
+    let model = cm("<blockquote><p>שלום|</p></blockquote>");
+    assert_eq!(
+        tx(&model),
+        r#"<blockquote><p dir="auto">שלום|</p></blockquote>"#
+    );
+}
+
+#[test]
+fn explicit_direction_overrides_detection() {
+    let model = cm(r#"<p dir="ltr">שלום|</p>"#);
+    assert_eq!(tx(&model), r#"<p dir="ltr">שלום|</p>"#);
+}
+
+#[test]
+fn direction_is_parsed_from_html() {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model
+        .set_content_from_html(&utf16(
+            r#"<ul><li dir="rtl">a</li></ul><blockquote dir="ltr"><p>b</p></blockquote><pre dir="auto"><code>c</code></pre>"#,
+        ))
+        .unwrap();
+    assert_eq!(
+        model.get_content_as_html(),
+        utf16(
+            r#"<ul><li dir="rtl">a</li></ul><blockquote dir="ltr"><p>b</p></blockquote><pre dir="auto"><code>c</code></pre>"#
+        )
+    );
+}
+
+#[test]
+fn detected_direction_is_not_made_explicit_by_a_round_trip() {
+    let model = cm("<p>שלום|</p>");
+    let model = round_trip(&model);
+    assert_eq!(first_block_direction(&model), None);
+    assert_eq!(
+        model.get_content_as_html(),
+        utf16(r#"<p dir="auto">שלום</p>"#)
+    );
+}
+
+#[test]
+fn explicit_directions_are_kept_by_a_round_trip() {
+    let model = cm(
+        r#"<p dir="auto">hello</p><p dir="ltr">שלום</p><p dir="rtl">a|</p>"#,
+    );
+    let html = model.get_content_as_html();
+    assert_eq!(round_trip(&model).get_content_as_html(), html);
+    let directions: Vec<_> = round_trip(&model)
+        .state
+        .dom
+        .iter_containers()
+        .filter_map(|c| c.text_direction())
+        .collect();
+    assert_eq!(
+        directions,
+        [TextDirection::Auto, TextDirection::Ltr, TextDirection::Rtl]
+    );
+}
+
+#[test]
+fn detected_direction_follows_the_text_after_a_round_trip() {
+    let mut model = round_trip(&cm("<p>שלום|</p>"));
+    model.select(0.into(), 4.into()).unwrap();
+    model.replace_text(utf16("hello")).unwrap();
+    assert_eq!(tx(&model), "<p>hello|</p>");
+}
+
+#[test]
+fn invalid_direction_is_ignored_when_parsing() {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model
+        .set_content_from_html(&utf16(r#"<p dir="sideways">a</p>"#))
+        .unwrap();
+    assert_eq!(model.get_content_as_html(), utf16("<p>a</p>"));
+}
+
+#[test]
+fn message_output_puts_paragraph_directions_on_spans() {
+    let model = cm("<p>שלום</p><p>hello|</p>");
+    assert_eq!(
+        model.get_content_as_message_html(),
+        r#"<span dir="auto">שלום</span><br />hello"#
+    );
+}
+
+#[test]
+fn message_output_uses_line_breaks_whatever_the_direction() {
+    let mut model = cm("<p>a|</p><p>b</p>");
+    assert_eq!(model.get_content_as_message_html(), "a<br />b");
+    model.set_text_direction(TextDirection::Rtl).unwrap();
+    assert_eq!(
+        model.get_content_as_message_html(),
+        r#"<span dir="rtl">a</span><br />b"#
+    );
+    assert_eq!(
+        model.state.dom.to_message_html(),
+        utf16(r#"<span dir="rtl">a</span><br />b"#)
+    );
+}
+
+#[test]
+fn message_with_directions_can_be_edited() {
+    let mut model = ComposerModel::<Utf16String>::new();
+    model
+        .set_content_from_html(&utf16(
+            r#"<span dir="auto">שלום</span><br />hello"#,
+        ))
+        .unwrap();
+    assert_eq!(tx(&model), r#"<p dir="auto">שלום</p><p>hello|</p>"#);
+
+    model
+        .set_content_from_html(&utf16(r#"<span dir="rtl">a</span><br />b"#))
+        .unwrap();
+    assert_eq!(
+        model.get_content_as_html(),
+        utf16(r#"<p dir="rtl">a</p><p>b</p>"#)
+    );
+}
+
+fn round_trip(
+    model: &ComposerModel<Utf16String>,
+) -> ComposerModel<Utf16String> {
+    let mut parsed = ComposerModel::new();
+    parsed
+        .set_content_from_html(&model.get_content_as_html())
+        .unwrap();
+    parsed
+}
+
+fn first_block_direction(
+    model: &ComposerModel<Utf16String>,
+) -> Option<TextDirection> {
+    model
+        .state
+        .dom
+        .iter_containers()
+        .find(|c| !c.handle().is_root())
+        .and_then(|c| c.text_direction())
+}
@@ -0,0 +1,162 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::{TextTransform, TextUpdate};
+
+#[test]
+fn transforming_without_a_selection_does_nothing() {
+    let mut model = cm("abc|");
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(tx(&model), "abc|");
+}
+
+#[test]
+fn uppercase_selection() {
+    let mut model = cm("ab{cd}|ef");
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(tx(&model), "ab{CD}|ef");
+}
+
+#[test]
+fn lowercase_selection() {
+    let mut model = cm("{ABC DEF}|");
+    model.transform_selection(TextTransform::Lowercase).unwrap();
+    assert_eq!(tx(&model), "{abc def}|");
+}
+
+#[test]
+fn toggle_case_selection() {
+    let mut model = cm("{Hello World}|");
+    model
+        .transform_selection(TextTransform::ToggleCase)
+        .unwrap();
+    assert_eq!(tx(&model), "{hELLO wORLD}|");
+}
+
+#[test]
+fn title_case_selection() {
+    let mut model = cm("{the QUICK brown fox don't stop}|");
+    model.transform_selection(TextTransform::TitleCase).unwrap();
+    assert_eq!(tx(&model), "{The Quick Brown Fox Don't Stop}|");
+}
+
+#[test]
+fn title_case_in_the_middle_of_a_word_keeps_it_lowercase() {
+    let mut model = cm("he{LLO}| world");
+    model.transform_selection(TextTransform::TitleCase).unwrap();
+    assert_eq!(tx(&model), "he{llo}| world");
+}
+
+#[test]
+fn transform_keeps_formatting() {
+    let mut model = cm("{a<b>bc<i>de</i></b>f}|");
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(tx(&model), "{A<b>BC<i>DE</i></b>F}|");
+}
+
+#[test]
+fn title_case_words_across_formatting() {
+    let mut model = cm("{hel<b>LO wor</b>ld}|");
+    model.transform_selection(TextTransform::TitleCase).unwrap();
+    assert_eq!(tx(&model), "{Hel<b>lo Wor</b>ld}|");
+}
+
+#[test]
+fn transform_across_paragraphs() {
+    let mut model = cm("<p>a{bc</p><p>de}|f</p>");
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(tx(&model), "<p>a{BC</p><p>DE}|f</p>");
+}
+
+#[test]
+fn title_case_starts_a_new_word_in_each_paragraph() {
+    let mut model = cm("<p>{ab</p><p>cd}|</p>");
+    model.transform_selection(TextTransform::TitleCase).unwrap();
+    assert_eq!(tx(&model), "<p>{Ab</p><p>Cd}|</p>");
+}
+
+#[test]
+fn transform_skips_mentions() {
+    let mut model = cm("hello |");
+    model
+        .insert_mention(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "alice".into(),
+            vec![],
+        )
+        .unwrap();
+    model.replace_text("bye".into()).unwrap();
+    model.select(0.into(), 11.into()).unwrap();
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(
+        tx(&model),
+        "{HELLO <a data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:matrix.org\" contenteditable=\"false\">alice</a> BYE}|"
+    );
+}
+
+#[test]
+fn selection_grows_with_longer_text() {
+    let mut model = cm("{straße}| x");
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(tx(&model), "{STRASSE}| x");
+}
+
+#[test]
+fn reversed_selection_is_preserved() {
+    let mut model = cm("|{straße} x");
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(tx(&model), "|{STRASSE} x");
+}
+
+#[test]
+fn transform_is_undoable() {
+    let mut model = cm("{abc}|");
+    model.transform_selection(TextTransform::Uppercase).unwrap();
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "{abc}|");
+}
+
+#[test]
+fn transform_without_changes_keeps_the_history() {
+    let mut model = cm("a|");
+    model.replace_text("b".into()).unwrap();
+    model.select(0.into(), 2.into()).unwrap();
+    let update = model.transform_selection(TextTransform::Lowercase).unwrap();
+    assert_eq!(update.text_update, TextUpdate::Keep);
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "a|");
+}
+
+#[test]
+fn transforming_only_a_mention_does_nothing() {
+    let mut model = cm("|");
+    model
+        .insert_mention(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "alice".into(),
+            vec![],
+        )
+        .unwrap();
+    model.select(0.into(), 1.into()).unwrap();
+    let update = model.transform_selection(TextTransform::Uppercase).unwrap();
+    assert_eq!(update.text_update, TextUpdate::Keep);
+}
+
+#[test]
+fn title_case_looks_at_the_character_before_the_selection() {
+    let mut model = cm("<b>ab</b>{CD}|");
+    model.transform_selection(TextTransform::TitleCase).unwrap();
+    assert_eq!(tx(&model), "<b>ab</b>{cd}|");
+}
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::dom::Dom;
+use crate::tests::testutils_conversion::utf16;
 use crate::{
-    dom::parser::markdown::MarkdownHTMLParser,
-    tests::testutils_composer_model::tx, ComposerModel, ToMarkdown,
+    parse_markdown, tests::testutils_composer_model::tx, ComposerError,
+    ComposerModel, DomNode, ListType, ToHtml, ToMarkdown,
 };
 use widestring::Utf16String;
 
@@ -24,8 +26,13 @@ use super::testutils_composer_model::cm;
 fn text() {
     assert_to_message_md("abc", "abc");
     assert_to_message_md("abc def", "abc def");
-    // Internal spaces are preserved.
-    assert_to_message_md("abc   def", "abc   def");
+    // Internal spaces are preserved, and imported as non-breaking spaces
+    // so that they still show.
+    assert_to_message_md_importing_as(
+        "abc   def",
+        "abc   def",
+        "abc\u{a0}\u{a0} def",
+    );
 }
 
 // Markdown output contains unescaped special characters but this is ok.
@@ -147,7 +154,11 @@ fn text_with_inline_code() {
     // Inline code with a backtick at the start.
     assert_to_message_md("<code>`abc</code>", "`` `abc ``");
     assert_to_message_md("abc <code>def</code> ghi", "abc `` def `` ghi");
-    assert_to_message_md("abc<code> def </code>ghi", "abc``  def  ``ghi");
+    assert_to_message_md_importing_as(
+        "abc<code> def </code>ghi",
+        "abc``  def  ``ghi",
+        "abc<code>\u{a0}def\u{a0}</code>ghi",
+    );
 
     // It's impossible to get a line break inside an inline code with Markdown.
     assert_to_md_no_roundtrip(
@@ -213,8 +224,8 @@ fn list_ordered() {
     assert_to_md_no_roundtrip(
         r#"<ol><li>item1<ol><li>subitem1</li><li>subitem2</li></ol></li><li>item2</li></ol>"#,
         r#"1. item1
-  1. subitem1
-  2. subitem2
+   1. subitem1
+   2. subitem2
 2. item2"#,
     );
 }
@@ -224,8 +235,8 @@ fn list_ordered_and_unordered() {
     assert_to_md_no_roundtrip(
         r#"<ol><li>item1<ul><li>subitem1</li><li>subitem2</li></ul></li><li>item2</li></ol>"#,
         r#"1. item1
-  * subitem1
-  * subitem2
+   * subitem1
+   * subitem2
 2. item2"#,
     );
 }
@@ -264,7 +275,12 @@ fn room_mention_for_composer() {
 
 #[test]
 fn at_room_mention_for_message() {
-    assert_to_message_md("@room hello!", "@room hello!");
+    // The importer turns `@room` into a mention, as the composer does
+    assert_to_message_md_importing_as(
+        "@room hello!",
+        "@room hello!",
+        r##"<a data-mention-type="at-room" href="#" contenteditable="false">@room</a> hello!"##,
+    );
 }
 
 #[test]
@@ -273,8 +289,68 @@ fn at_room_mention_for_composer() {
 
     assert_eq!(tx(&model), "<a data-mention-type=\"at-room\" href=\"#\" contenteditable=\"false\">@room</a> hello!|");
 
-    assert_eq!(model.get_content_as_markdown(), "<a data-mention-type=\"at-room\" href=\"#\" contenteditable=\"false\">@room</a> hello!");
-    assert_eq!(model.get_content_as_message_markdown(), "@room hello!");
+    assert_eq!(model.get_content_as_markdown().unwrap(), "<a data-mention-type=\"at-room\" href=\"#\" contenteditable=\"false\">@room</a> hello!");
+    assert_eq!(
+        model.get_content_as_message_markdown().unwrap(),
+        "@room hello!"
+    );
+}
+
+#[test]
+fn markdown_for_quote_inside_list_item() {
+    assert_to_md_no_roundtrip(
+        "<ul><li><blockquote><p>a</p><p>b</p></blockquote></li></ul>",
+        "* > a\n  > b",
+    );
+}
+
+#[test]
+fn markdown_for_list_inside_quote() {
+    assert_to_md_no_roundtrip(
+        "<blockquote><ul><li>a</li><li>b</li></ul></blockquote>",
+        "> * a\n> * b\n",
+    );
+}
+
+#[test]
+fn markdown_for_nested_quotes() {
+    assert_to_md_no_roundtrip(
+        "<blockquote><blockquote><p>a</p></blockquote></blockquote>",
+        "> > a\n",
+    );
+}
+
+#[test]
+fn markdown_for_code_block_inside_quote() {
+    assert_to_md_no_roundtrip(
+        "<blockquote><pre><code>a\nb</code></pre></blockquote>",
+        "> ```\n> a\n> b\n> ```\n",
+    );
+}
+
+#[test]
+fn markdown_for_code_block_inside_ordered_list_item() {
+    assert_to_md_no_roundtrip(
+        "<ol><li><pre><code>a\nb</code></pre></li></ol>",
+        "1. ```\n   a\n   b\n   ```",
+    );
+}
+
+#[test]
+fn markdown_for_list_containing_a_paragraph_is_an_error() {
+    let mut model = cm("|");
+    model.state.dom = Dom::new(vec![DomNode::new_list(
+        ListType::Unordered,
+        vec![DomNode::new_paragraph(vec![DomNode::new_text(utf16("a"))])],
+    )]);
+    assert_eq!(
+        model.get_content_as_markdown(),
+        Err(ComposerError::MarkdownExportError(
+            "A list expects a list item as immediate child, received `p`"
+                .to_owned()
+        ))
+    );
+    assert!(model.get_content_as_message_markdown().is_err());
 }
 
 fn assert_to_md_no_roundtrip(html: &str, expected_markdown: &str) {
@@ -283,13 +359,22 @@ fn assert_to_md_no_roundtrip(html: &str, expected_markdown: &str) {
 }
 
 fn assert_to_message_md(html: &str, expected_markdown: &str) {
+    assert_to_message_md_importing_as(html, expected_markdown, html);
+}
+
+/// Like [assert_to_message_md], for markdown that is imported back as
+/// different HTML to `html`.
+fn assert_to_message_md_importing_as(
+    html: &str,
+    expected_markdown: &str,
+    expected_html: &str,
+) {
     let markdown = to_message_markdown(html);
     assert_eq!(markdown, expected_markdown);
 
-    let expected_html = html;
-    let html = MarkdownHTMLParser::to_html(&markdown).unwrap();
+    let html = parse_markdown(&markdown).unwrap().dom.to_html();
 
-    assert_eq!(html, expected_html);
+    assert_eq!(html, utf16(expected_html));
 }
 
 fn assert_to_message_md_no_roundtrip(html: &str, expected_markdown: &str) {
@@ -302,13 +387,14 @@ fn assert_to_composer_md(html: &str, expected_markdown: &str) {
     assert_eq!(markdown, expected_markdown);
 
     let expected_html = html;
-    let html = MarkdownHTMLParser::to_html(&markdown).unwrap();
+    let html = parse_markdown(&markdown).unwrap().dom.to_html();
 
-    assert_eq!(html, expected_html);
+    assert_eq!(html, utf16(expected_html));
 }
 
 fn to_message_markdown(html: &str) -> Utf16String {
     let markdown = ComposerModel::from_html(html, 0, 0)
+        .unwrap()
         .state
         .dom
         .to_message_markdown();
@@ -318,7 +404,11 @@ fn to_message_markdown(html: &str) -> Utf16String {
 }
 
 fn to_composer_markdown(html: &str) -> Utf16String {
-    let markdown = ComposerModel::from_html(html, 0, 0).state.dom.to_markdown();
+    let markdown = ComposerModel::from_html(html, 0, 0)
+        .unwrap()
+        .state
+        .dom
+        .to_markdown();
     assert!(markdown.is_ok());
 
     markdown.unwrap()
@@ -17,12 +17,12 @@ use crate::tests::testutils_composer_model::{cm, tx};
 #[test]
 fn outputs_paragraphs_as_line_breaks() {
     let mut model = cm("|");
-    model.replace_text("hello".into());
-    model.enter();
-    model.enter();
-    model.enter();
-    model.enter();
-    model.replace_text("Alice".into());
+    model.replace_text("hello".into()).unwrap();
+    model.enter().unwrap();
+    model.enter().unwrap();
+    model.enter().unwrap();
+    model.enter().unwrap();
+    model.replace_text("Alice".into()).unwrap();
 
     assert_eq!(
         tx(&model),
@@ -43,11 +43,13 @@ fn outputs_paragraphs_content_without_linebreak_when_followed_by_block() {
 #[test]
 fn only_outputs_href_attribute_on_user_mention() {
     let mut model = cm("|");
-    model.insert_mention(
-        "https://matrix.to/#/@alice:matrix.org".into(),
-        "inner text".into(),
-        vec![("style".into(), "some css".into())],
-    );
+    model
+        .insert_mention(
+            "https://matrix.to/#/@alice:matrix.org".into(),
+            "inner text".into(),
+            vec![("style".into(), "some css".into())],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a style=\"some css\" data-mention-type=\"user\" href=\"https://matrix.to/#/@alice:matrix.org\" contenteditable=\"false\">inner text</a>&nbsp;|");
 
     let message_output = model.get_content_as_message_html();
@@ -60,11 +62,13 @@ fn only_outputs_href_attribute_on_user_mention() {
 #[test]
 fn only_outputs_href_attribute_on_room_mention_and_uses_mx_id() {
     let mut model = cm("|");
-    model.insert_mention(
-        "https://matrix.to/#/#alice:matrix.org".into(),
-        "inner text".into(),
-        vec![("style".into(), "some css".into())],
-    );
+    model
+        .insert_mention(
+            "https://matrix.to/#/#alice:matrix.org".into(),
+            "inner text".into(),
+            vec![("style".into(), "some css".into())],
+        )
+        .unwrap();
     assert_eq!(tx(&model), "<a style=\"some css\" data-mention-type=\"room\" href=\"https://matrix.to/#/#alice:matrix.org\" contenteditable=\"false\">inner text</a>&nbsp;|");
 
     let message_output = model.get_content_as_message_html();
@@ -77,7 +81,9 @@ fn only_outputs_href_attribute_on_room_mention_and_uses_mx_id() {
 #[test]
 fn only_outputs_href_inner_text_for_at_room_mention() {
     let mut model = cm("|");
-    model.insert_at_room_mention(vec![("style".into(), "some css".into())]);
+    model
+        .insert_at_room_mention(vec![("style".into(), "some css".into())])
+        .unwrap();
     assert_eq!(tx(&model), "<a style=\"some css\" data-mention-type=\"at-room\" href=\"#\" contenteditable=\"false\">@room</a>&nbsp;|");
 
     let message_output = model.get_content_as_message_html();
@@ -258,6 +258,7 @@ fn assert_to_plain(html: &str, expected_plain_text: &str) {
 
 fn to_plain_text(html: &str) -> Utf16String {
     ComposerModel::from_html(html, 0, 0)
+        .unwrap()
         .state
         .dom
         .to_plain_text()
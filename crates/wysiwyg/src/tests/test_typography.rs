@@ -0,0 +1,179 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use widestring::Utf16String;
+
+use crate::tests::testutils_composer_model::{cm, tx};
+use crate::{ComposerModel, MaxLength, MaxLengthBehaviour, TypographyRule};
+
+fn typographic(html: &str) -> ComposerModel<Utf16String> {
+    let mut model = cm(html);
+    model.set_typography_rules(TypographyRule::defaults());
+    model
+}
+
+fn type_text(model: &mut ComposerModel<Utf16String>, text: &str) {
+    for c in text.chars() {
+        model.replace_text(c.to_string().as_str().into()).unwrap();
+    }
+}
+
+#[test]
+fn no_substitutions_by_default() {
+    let mut model = cm("|");
+    type_text(&mut model, "\"a\" -- b...");
+    assert_eq!(tx(&model), "\"a\" -- b...|");
+}
+
+#[test]
+fn typing_quotes_makes_them_curly() {
+    let mut model = typographic("|");
+    type_text(&mut model, "\"Don't\" she said");
+    assert_eq!(tx(&model), "“Don’t” she said|");
+}
+
+#[test]
+fn typing_two_hyphens_makes_an_em_dash() {
+    let mut model = typographic("|");
+    type_text(&mut model, "a--b");
+    assert_eq!(tx(&model), "a—b|");
+}
+
+#[test]
+fn typing_three_dots_makes_an_ellipsis() {
+    let mut model = typographic("|");
+    type_text(&mut model, "wait...");
+    assert_eq!(tx(&model), "wait…|");
+}
+
+#[test]
+fn substitutions_keep_formatting() {
+    let mut model = typographic("<b>a-|</b>");
+    type_text(&mut model, "-");
+    assert_eq!(tx(&model), "<b>a—|</b>");
+}
+
+#[test]
+fn substitution_is_a_separate_undo_step() {
+    let mut model = typographic("a-|");
+    type_text(&mut model, "-");
+    assert_eq!(tx(&model), "a—|");
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "a--|");
+    model.undo().unwrap();
+    assert_eq!(tx(&model), "a-|");
+    model.redo().unwrap();
+    model.redo().unwrap();
+    assert_eq!(tx(&model), "a—|");
+}
+
+#[test]
+fn pasted_text_is_not_substituted() {
+    let mut model = typographic("|");
+    model.replace_text("\"a\" -- b...".into()).unwrap();
+    assert_eq!(tx(&model), "\"a\" -- b...|");
+}
+
+#[test]
+fn no_substitutions_in_code_blocks() {
+    let mut model = typographic("<pre><code>a|</code></pre>");
+    type_text(&mut model, "--\"...");
+    assert_eq!(tx(&model), "<pre><code>a--\"...|</code></pre>");
+}
+
+#[test]
+fn no_substitutions_in_inline_code() {
+    let mut model = typographic("<code>a|</code>");
+    type_text(&mut model, "--\"");
+    assert_eq!(tx(&model), "<code>a--\"|</code>");
+}
+
+#[test]
+fn substitutions_resume_after_inline_code() {
+    let mut model = typographic("<code>a</code> |");
+    type_text(&mut model, "\"b\"");
+    assert_eq!(tx(&model), "<code>a</code> “b”|");
+}
+
+#[test]
+fn substitutions_do_not_cross_paragraphs() {
+    let mut model = typographic("<p>a-</p><p>|</p>");
+    type_text(&mut model, "-");
+    assert_eq!(tx(&model), "<p>a-</p><p>-|</p>");
+}
+
+#[test]
+fn custom_rules_are_applied() {
+    let mut model = cm("|");
+    model.set_typography_rules(vec![TypographyRule::Custom {
+        from: "->".into(),
+        to: "→".into(),
+    }]);
+    type_text(&mut model, "a -> b");
+    assert_eq!(tx(&model), "a → b|");
+}
+
+#[test]
+fn only_the_configured_rules_are_applied() {
+    let mut model = cm("|");
+    model.set_typography_rules(vec![TypographyRule::Ellipsis]);
+    type_text(&mut model, "\"a\"--...");
+    assert_eq!(tx(&model), "\"a\"--…|");
+}
+
+#[test]
+fn substitutions_do_not_cross_formatting() {
+    let mut model = typographic("<strong>a-|</strong>");
+    model.bold().unwrap();
+    type_text(&mut model, "-");
+    assert_eq!(tx(&model), "<strong>a-</strong>-|");
+}
+
+#[test]
+fn quotes_depend_on_the_text_before_the_formatting() {
+    let mut model = typographic("<em>don|</em>");
+    model.italic().unwrap();
+    type_text(&mut model, "'");
+    assert_eq!(tx(&model), "<em>don</em>’|");
+}
+
+#[test]
+fn quotes_after_a_space_in_other_formatting_open() {
+    let mut model = typographic("<em>said |</em>");
+    model.italic().unwrap();
+    type_text(&mut model, "\"");
+    assert_eq!(tx(&model), "<em>said&nbsp;</em>“|");
+}
+
+#[test]
+fn quotes_at_the_start_of_a_paragraph_open() {
+    let mut model = typographic("<p>a</p><p>|</p>");
+    type_text(&mut model, "'");
+    assert_eq!(tx(&model), "<p>a</p><p>‘|</p>");
+}
+
+#[test]
+fn replacements_that_exceed_the_max_length_are_not_applied() {
+    let mut model = cm("|");
+    model.set_typography_rules(vec![TypographyRule::Custom {
+        from: "->".into(),
+        to: "rightwards arrow".into(),
+    }]);
+    model.set_max_length(Some(MaxLength {
+        characters: 5,
+        behaviour: MaxLengthBehaviour::Truncate,
+    }));
+    type_text(&mut model, "a->");
+    assert_eq!(model.get_content_as_plain_text(), "a->");
+}
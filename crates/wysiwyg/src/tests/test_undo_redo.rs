@@ -14,7 +14,10 @@
 
 use crate::tests::testutils_composer_model::{cm, tx};
 
+use widestring::Utf16String;
+
 use crate::dom::nodes::{DomNode, TextNode};
+use crate::dom::Dom;
 use crate::{InlineFormatType, Location};
 
 use crate::tests::testutils_conversion::utf16;
@@ -28,7 +31,7 @@ fn undoing_action_restores_previous_state() {
         .append_at_end_of_document(DomNode::Text(prev_text_node));
     model.previous_states.push(prev.clone());
 
-    model.undo();
+    model.undo().unwrap();
 
     assert_eq!(prev.dom.children().len(), model.state.dom.children().len());
 }
@@ -38,7 +41,7 @@ fn inserting_text_creates_previous_state() {
     let mut model = cm("|");
     assert!(model.previous_states.is_empty());
 
-    model.replace_text(utf16("hello world!"));
+    model.replace_text(utf16("hello world!")).unwrap();
     assert!(!model.previous_states.is_empty());
 }
 
@@ -47,7 +50,7 @@ fn backspacing_text_creates_previous_state() {
     let mut model = cm("hello world!|");
     assert!(model.previous_states.is_empty());
 
-    model.backspace();
+    model.backspace().unwrap();
     assert!(!model.previous_states.is_empty());
 }
 
@@ -56,7 +59,7 @@ fn deleting_text_creates_previous_state() {
     let mut model = cm("hello |world!");
     assert!(model.previous_states.is_empty());
 
-    model.delete();
+    model.delete().unwrap();
     assert!(!model.previous_states.is_empty());
 }
 
@@ -65,7 +68,7 @@ fn formatting_text_creates_previous_state() {
     let mut model = cm("hello {world}|!");
     assert!(model.previous_states.is_empty());
 
-    model.bold();
+    model.bold().unwrap();
     assert!(!model.previous_states.is_empty());
 }
 
@@ -74,7 +77,7 @@ fn undoing_action_removes_last_previous_state() {
     let mut model = cm("hello {world}|!");
     model.previous_states.push(model.state.clone());
 
-    model.undo();
+    model.undo().unwrap();
 
     assert!(model.previous_states.is_empty());
 }
@@ -84,7 +87,7 @@ fn undoing_action_adds_popped_state_to_next_states() {
     let mut model = cm("hello {world}|!");
     model.previous_states.push(model.state.clone());
 
-    model.undo();
+    model.undo().unwrap();
 
     assert_eq!(model.next_states[0], model.state);
 }
@@ -94,7 +97,7 @@ fn redo_pops_state_from_next_states() {
     let mut model = cm("hello {world}|!");
     model.next_states.push(model.state.clone());
 
-    model.redo();
+    model.redo().unwrap();
 
     assert!(model.next_states.is_empty());
 }
@@ -104,7 +107,7 @@ fn redoing_action_adds_popped_state_to_previous_states() {
     let mut model = cm("hello {world}|!");
     model.next_states.push(model.state.clone());
 
-    model.redo();
+    model.redo().unwrap();
 
     assert_eq!(model.previous_states[0], model.state);
 }
@@ -112,15 +115,15 @@ fn redoing_action_adds_popped_state_to_previous_states() {
 #[test]
 fn undoing_restores_toggled_format_types() {
     let mut model = cm("|");
-    model.bold();
-    model.italic();
+    model.bold().unwrap();
+    model.italic().unwrap();
     assert_eq!(
         model.state.toggled_format_types,
         Vec::from([InlineFormatType::Bold, InlineFormatType::Italic,])
     );
-    model.replace_text(utf16("a"));
+    model.replace_text(utf16("a")).unwrap();
     assert_eq!(model.state.toggled_format_types, Vec::new());
-    model.undo();
+    model.undo().unwrap();
     assert_eq!(
         model.state.toggled_format_types,
         Vec::from([InlineFormatType::Bold, InlineFormatType::Italic,])
@@ -131,24 +134,24 @@ fn undoing_restores_toggled_format_types() {
 #[allow(deprecated)]
 fn can_undo_adding_line_break() {
     let mut model = cm("Test|");
-    model.add_line_break();
-    model.undo();
+    model.add_line_break().unwrap();
+    model.undo().unwrap();
     assert_eq!(tx(&model), "Test|");
 }
 
 #[test]
 fn can_undo_pressing_enter() {
     let mut model = cm("Test|");
-    model.enter();
-    model.undo();
+    model.enter().unwrap();
+    model.undo().unwrap();
     assert_eq!(tx(&model), "Test|");
 }
 
 #[test]
 fn can_undo_with_selection() {
     let mut model = cm("Test{foo}|bar");
-    model.enter();
-    model.undo();
+    model.enter().unwrap();
+    model.undo().unwrap();
     assert_eq!(tx(&model), "Test{foo}|bar");
 }
 
@@ -157,13 +160,13 @@ fn deleting_a_selection_with_enter_only_adds_one_to_undo_stack() {
     let mut model = cm("Test{foo}|bar");
 
     // Do 2 things
-    model.replace_text(utf16("baz"));
-    model.select(Location::from(4), Location::from(7));
-    model.enter();
+    model.replace_text(utf16("baz")).unwrap();
+    model.select(Location::from(4), Location::from(7)).unwrap();
+    model.enter().unwrap();
 
     // Undo twice
-    model.undo();
-    model.undo();
+    model.undo().unwrap();
+    model.undo().unwrap();
 
     // We should be back where we started
     assert_eq!(tx(&model), "Test{foo}|bar");
@@ -172,20 +175,40 @@ fn deleting_a_selection_with_enter_only_adds_one_to_undo_stack() {
 #[test]
 fn undoing_enter_only_undoes_one() {
     let mut model = cm("Test|");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>Test</p><p>&nbsp;|</p>");
-    model.enter();
+    model.enter().unwrap();
     assert_eq!(tx(&model), "<p>Test</p><p>&nbsp;</p><p>&nbsp;|</p>");
-    model.undo();
+    model.undo().unwrap();
     assert_eq!(tx(&model), "<p>Test</p><p>&nbsp;|</p>");
 }
 
 #[test]
 fn replacing_text_with_newlines_only_adds_one_to_undo_stack() {
     let mut model = cm("abc|");
-    model.replace_text(utf16("def\nghi"));
-    model.replace_text(utf16("\njkl\n"));
-    model.undo();
-    model.undo();
+    model.replace_text(utf16("def\nghi")).unwrap();
+    model.replace_text(utf16("\njkl\n")).unwrap();
+    model.undo().unwrap();
+    model.undo().unwrap();
     assert_eq!(tx(&model), "abc|");
 }
+
+#[test]
+fn typing_keeps_untouched_blocks_shared_with_the_undo_history() {
+    let mut model = cm("<p>abc</p><p>def</p><p>ghi|</p>");
+    model.replace_text(utf16("j")).unwrap();
+    model.backspace().unwrap();
+    model.enter().unwrap();
+    let ids = |dom: &Dom<Utf16String>| -> Vec<usize> {
+        dom.children()
+            .iter()
+            .map(|block| block.as_container().unwrap().children_id())
+            .collect()
+    };
+    let now = ids(&model.state.dom);
+    for state in &model.previous_states {
+        let before = ids(&state.dom);
+        // Only the paragraph being edited was copied
+        assert_eq!(before[..2], now[..2]);
+    }
+}
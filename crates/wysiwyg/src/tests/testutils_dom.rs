@@ -40,18 +40,21 @@ pub fn b<'a>(
     children: impl IntoIterator<Item = &'a DomNode<Utf16String>>,
 ) -> DomNode<Utf16String> {
     DomNode::new_formatting_from_tag(utf16("b"), clone_children(children))
+        .unwrap()
 }
 
 pub fn i<'a>(
     children: impl IntoIterator<Item = &'a DomNode<Utf16String>>,
 ) -> DomNode<Utf16String> {
     DomNode::new_formatting_from_tag(utf16("i"), clone_children(children))
+        .unwrap()
 }
 
 pub fn i_c<'a>(
     children: impl IntoIterator<Item = &'a DomNode<Utf16String>>,
 ) -> DomNode<Utf16String> {
     DomNode::new_formatting_from_tag(utf16("code"), clone_children(children))
+        .unwrap()
 }
 
 fn clone_children<'a>(
@@ -0,0 +1,115 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+
+/// The base direction of the text in a block, matching the values of the
+/// HTML `dir` attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextDirection {
+    /// Let the client pick the direction from the first strong character.
+    Auto,
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Ltr => "ltr",
+            Self::Rtl => "rtl",
+        }
+    }
+
+    /// Find the direction of the first strongly directional character in
+    /// `text`, if any. Digits, punctuation and whitespace are neutral.
+    pub fn detect(text: &str) -> Option<Self> {
+        Self::detect_in_chars(text.chars())
+    }
+
+    pub(crate) fn detect_in_chars(
+        mut chars: impl Iterator<Item = char>,
+    ) -> Option<Self> {
+        chars.find_map(|c| {
+            if is_strong_rtl(c) {
+                Some(Self::Rtl)
+            } else if c.is_alphabetic() {
+                Some(Self::Ltr)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl FromStr for TextDirection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "ltr" => Ok(Self::Ltr),
+            "rtl" => Ok(Self::Rtl),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Whether `c` belongs to one of the right-to-left scripts (Hebrew, Arabic,
+/// Syriac, Thaana, NKo, Samaritan, Mandaic and their presentation forms).
+fn is_strong_rtl(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0590}'..='\u{08FF}'
+            | '\u{FB1D}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}'
+            | '\u{10800}'..='\u{10FFF}'
+            | '\u{1E800}'..='\u{1EFFF}'
+    ) && c.is_alphabetic()
+}
+
+#[cfg(test)]
+mod test {
+    use super::TextDirection;
+
+    #[test]
+    fn detects_direction_from_first_strong_character() {
+        assert_eq!(TextDirection::detect("hello"), Some(TextDirection::Ltr));
+        assert_eq!(TextDirection::detect("שלום"), Some(TextDirection::Rtl));
+        assert_eq!(TextDirection::detect("مرحبا"), Some(TextDirection::Rtl));
+        assert_eq!(
+            TextDirection::detect("123, שלום world"),
+            Some(TextDirection::Rtl)
+        );
+        assert_eq!(
+            TextDirection::detect("(hi) שלום"),
+            Some(TextDirection::Ltr)
+        );
+    }
+
+    #[test]
+    fn neutral_text_has_no_direction() {
+        assert_eq!(TextDirection::detect(""), None);
+        assert_eq!(TextDirection::detect("123 !? 😀"), None);
+    }
+
+    #[test]
+    fn parses_dir_attribute_values() {
+        assert_eq!("rtl".parse(), Ok(TextDirection::Rtl));
+        assert_eq!("LTR".parse(), Ok(TextDirection::Ltr));
+        assert_eq!("auto".parse(), Ok(TextDirection::Auto));
+        assert_eq!("up".parse::<TextDirection>(), Err(()));
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// A change of case applied to the selected text by
+/// [crate::ComposerModel::transform_selection].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextTransform {
+    Uppercase,
+    Lowercase,
+    /// Capitalise the first letter of each word and lowercase the others.
+    TitleCase,
+    /// Swap the case of each letter.
+    ToggleCase,
+}
+
+impl TextTransform {
+    /// Transform `c`, given the character before it, if any.
+    pub(crate) fn apply(
+        &self,
+        c: char,
+        previous: Option<char>,
+        out: &mut String,
+    ) {
+        match self {
+            Self::Uppercase => out.extend(c.to_uppercase()),
+            Self::Lowercase => out.extend(c.to_lowercase()),
+            Self::TitleCase => {
+                if previous.map_or(true, |p| !is_word_char(p)) {
+                    out.extend(c.to_uppercase())
+                } else {
+                    out.extend(c.to_lowercase())
+                }
+            }
+            Self::ToggleCase => {
+                if c.is_uppercase() {
+                    out.extend(c.to_lowercase())
+                } else {
+                    out.extend(c.to_uppercase())
+                }
+            }
+        }
+    }
+}
+
+/// Apostrophes are part of words, so that "don't" becomes "Don't" rather
+/// than "Don'T".
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\'' || c == '’'
+}
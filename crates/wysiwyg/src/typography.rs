@@ -0,0 +1,133 @@
+// Copyright 2024 The Matrix.org Foundation C.I.C.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// A substitution applied to the text as it is typed, e.g. to turn `--` into
+/// an em dash. See [crate::ComposerModel::set_typography_rules].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypographyRule {
+    /// Turn straight quotes into opening or closing curly quotes, depending
+    /// on the character before them. Apostrophes become closing quotes.
+    SmartQuotes,
+    /// Turn `--` into `—`.
+    EmDash,
+    /// Turn `...` into `…`.
+    Ellipsis,
+    /// Turn `from` into `to` as soon as the last character of `from` is
+    /// typed.
+    Custom { from: String, to: String },
+}
+
+impl TypographyRule {
+    /// The quotes, dash and ellipsis rules.
+    pub fn defaults() -> Vec<Self> {
+        vec![Self::SmartQuotes, Self::EmDash, Self::Ellipsis]
+    }
+
+    /// If typing `typed` made `text`, the text before the cursor, match this
+    /// rule, return the matching suffix of `text` and its replacement.
+    pub(crate) fn substitution<'a>(
+        &self,
+        text: &'a str,
+        typed: char,
+    ) -> Option<(&'a str, String)> {
+        let (from, to) = match self {
+            Self::SmartQuotes => return smart_quote(text, typed),
+            Self::EmDash => ("--", "—"),
+            Self::Ellipsis => ("...", "…"),
+            Self::Custom { from, to } => (from.as_str(), to.as_str()),
+        };
+        if from.is_empty() || !from.ends_with(typed) || !text.ends_with(from) {
+            return None;
+        }
+        Some((&text[text.len() - from.len()..], to.to_owned()))
+    }
+}
+
+fn smart_quote(text: &str, typed: char) -> Option<(&str, String)> {
+    let (opening, closing) = match typed {
+        '"' => ('“', '”'),
+        '\'' => ('‘', '’'),
+        _ => return None,
+    };
+    let before = text.strip_suffix(typed)?;
+    let quote = match before.chars().next_back() {
+        None => opening,
+        Some(c) if c.is_whitespace() || "([{<“‘—–-/".contains(c) => {
+            opening
+        }
+        Some(_) => closing,
+    };
+    Some((&text[before.len()..], quote.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::TypographyRule;
+
+    fn substitute(rule: &TypographyRule, text: &str) -> Option<String> {
+        let typed = text.chars().next_back().unwrap();
+        rule.substitution(text, typed)
+            .map(|(matched, replacement)| {
+                format!(
+                    "{}{}",
+                    &text[..text.len() - matched.len()],
+                    replacement
+                )
+            })
+    }
+
+    #[test]
+    fn quotes_open_at_the_start_and_after_spaces() {
+        let rule = TypographyRule::SmartQuotes;
+        assert_eq!(substitute(&rule, "\""), Some("“".into()));
+        assert_eq!(substitute(&rule, "say \""), Some("say “".into()));
+        assert_eq!(substitute(&rule, "(\'"), Some("(‘".into()));
+    }
+
+    #[test]
+    fn quotes_close_after_other_characters() {
+        let rule = TypographyRule::SmartQuotes;
+        assert_eq!(substitute(&rule, "“hi\""), Some("“hi”".into()));
+        assert_eq!(substitute(&rule, "don\'"), Some("don’".into()));
+    }
+
+    #[test]
+    fn dashes_and_ellipses_need_the_whole_pattern() {
+        assert_eq!(substitute(&TypographyRule::EmDash, "a-"), None);
+        assert_eq!(
+            substitute(&TypographyRule::EmDash, "a--"),
+            Some("a—".into())
+        );
+        assert_eq!(substitute(&TypographyRule::Ellipsis, "a.."), None);
+        assert_eq!(
+            substitute(&TypographyRule::Ellipsis, "a..."),
+            Some("a…".into())
+        );
+    }
+
+    #[test]
+    fn custom_rules_match_when_their_last_character_is_typed() {
+        let rule = TypographyRule::Custom {
+            from: "(c)".into(),
+            to: "©".into(),
+        };
+        assert_eq!(substitute(&rule, "(c)"), Some("©".into()));
+        assert_eq!(substitute(&rule, "(c"), None);
+        let empty = TypographyRule::Custom {
+            from: "".into(),
+            to: "x".into(),
+        };
+        assert_eq!(substitute(&empty, "a"), None);
+    }
+}
@@ -18,10 +18,10 @@ use wysiwyg::{ComposerModel, Location, TextUpdate};
 #[test]
 fn can_instantiate_a_model_and_call_methods() {
     let mut model = ComposerModel::new();
-    model.replace_text(Utf16String::from_str("foo"));
-    model.select(Location::from(1), Location::from(2));
+    model.replace_text(Utf16String::from_str("foo")).unwrap();
+    model.select(Location::from(1), Location::from(2)).unwrap();
 
-    let update = model.bold();
+    let update = model.bold().unwrap();
 
     if let TextUpdate::ReplaceAll(r) = update.text_update {
         assert_eq!(r.replacement_html.to_string(), "f<strong>o</strong>o");
@@ -214,6 +214,7 @@ class MainActivity : ComponentActivity() {
                                     ComposerAction.UNINDENT -> state.unindent()
                                     ComposerAction.CODE_BLOCK -> state.toggleCodeBlock()
                                     ComposerAction.QUOTE -> state.toggleQuote()
+                                    ComposerAction.CLEAR_FORMATTING -> state.clearFormatting()
                                 }
                             }
                         })
@@ -190,6 +190,15 @@ class RichTextEditor : LinearLayout {
                         }
                     }
             }
+            is MenuAction.EmojiSuggestion -> {
+                val candidates = menuAction.candidates
+                suggestionAdapter.clear()
+                suggestionAdapter.addAll(candidates.map { "${it.emoji} :${it.shortcode}:" })
+                binding.menuSuggestion.onItemClickListener =
+                    OnItemClickListener { _, _, position, _ ->
+                        binding.richTextEditText.replaceTextSuggestion(candidates[position].emoji)
+                    }
+            }
         }
     }
 
@@ -20,7 +20,8 @@ object ComposerActions {
             ComposerAction.LINK to ActionState.ENABLED,
             ComposerAction.INLINE_CODE to ActionState.ENABLED,
             ComposerAction.CODE_BLOCK to ActionState.ENABLED,
-            ComposerAction.UNINDENT to ActionState.DISABLED
+            ComposerAction.UNINDENT to ActionState.DISABLED,
+            ComposerAction.CLEAR_FORMATTING to ActionState.DISABLED
         )
 }
 
@@ -172,6 +172,7 @@ private fun RealEditor(
                                     is ViewAction.ToggleList -> toggleList(it.ordered)
                                     is ViewAction.ToggleCodeBlock -> toggleCodeBlock()
                                     is ViewAction.ToggleQuote -> toggleQuote()
+                                    is ViewAction.ClearFormatting -> clearFormatting(it.includeBlocks)
                                     is ViewAction.Undo -> undo()
                                     is ViewAction.Redo -> redo()
                                     is ViewAction.Indent -> indent()
@@ -112,6 +112,15 @@ class RichTextEditorState(
         _viewActions.emit(ViewAction.ToggleQuote)
     }
 
+    /**
+     * Remove the inline formatting and links in the current selection.
+     *
+     * @param includeBlocks Whether lists, quotes and code blocks are turned back into paragraphs too.
+     */
+    suspend fun clearFormatting(includeBlocks: Boolean = false) {
+        _viewActions.emit(ViewAction.ClearFormatting(includeBlocks))
+    }
+
     /**
      * Set the HTML content of the editor.
      */
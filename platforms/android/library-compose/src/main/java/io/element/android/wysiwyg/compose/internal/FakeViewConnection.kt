@@ -49,6 +49,7 @@ internal class FakeViewActionCollector(
             is ViewAction.ToggleInlineFormat -> toggleInlineFormat(value.inlineFormat)
             is ViewAction.ToggleList -> toggleList(value.ordered)
             ViewAction.ToggleQuote -> toggleQuote()
+            is ViewAction.ClearFormatting -> clearFormatting()
             ViewAction.Undo -> undo()
             ViewAction.Unindent -> unindent()
             is ViewAction.ReplaceSuggestionText -> {
@@ -94,6 +95,12 @@ internal class FakeViewActionCollector(
         return true
     }
 
+    private fun clearFormatting() {
+        state.actions = state.actions.mapValues { (_, actionState) ->
+            if (actionState == ActionState.REVERSED) ActionState.ENABLED else actionState
+        }
+    }
+
     private fun undo() {
         updateActionState(ComposerAction.UNDO)
     }
@@ -7,6 +7,7 @@ internal sealed class ViewAction {
     data class ToggleList(val ordered: Boolean): ViewAction()
     data object ToggleCodeBlock: ViewAction()
     data object ToggleQuote: ViewAction()
+    data class ClearFormatting(val includeBlocks: Boolean): ViewAction()
     data object Undo: ViewAction()
     data object Redo: ViewAction()
     data object Indent: ViewAction()
@@ -132,6 +132,21 @@ class FakeRichTextEditorStateTest {
         }
     }
 
+    @Test
+    fun `clearFormatting updates the state`() = runTest {
+        moleculeFlow(RecompositionMode.Immediate) {
+            val state = fakeRichTextEditorState()
+            remember(state.actions) { state }
+        }.test {
+            val initialState = awaitItem()
+            initialState.toggleInlineFormat(InlineFormat.Bold)
+            awaitItem()
+            initialState.clearFormatting()
+            val actions = awaitItem().actions
+            assertThat(actions[ComposerAction.BOLD], equalTo(ActionState.ENABLED))
+        }
+    }
+
     @Test
     fun `undo updates the state`() = runTest {
         moleculeFlow(RecompositionMode.Immediate) {
@@ -625,7 +625,7 @@ class EditorEditTextInputTests {
             editor.setMarkdown("<u>*Test*</u>")
             ViewMatchers.assertThat(
                 editor.getContentAsMessageHtml(),
-                CoreMatchers.equalTo("<u><em>Test</em></u>")
+                CoreMatchers.equalTo("<em><u>Test</u></em>")
             )
         }
     }
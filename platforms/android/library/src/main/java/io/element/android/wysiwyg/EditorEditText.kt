@@ -402,6 +402,14 @@ class EditorEditText : AppCompatEditText {
         return true
     }
 
+    fun clearFormatting(includeBlocks: Boolean = false): Boolean {
+        val result = processInputAsTextResult(EditorInputAction.ClearFormatting(includeBlocks))
+            ?: return false
+        setTextFromComposerUpdate(result.text)
+        setSelectionFromComposerUpdate(result.selection.first, result.selection.last)
+        return true
+    }
+
     fun undo() {
         val result = processInputAsTextResult(EditorInputAction.Undo) ?: return
 
@@ -62,6 +62,12 @@ internal sealed interface EditorInputAction {
 
     object Quote: EditorInputAction
 
+    /**
+     * Removes the inline formatting and links in the current selection, and
+     * also its lists, quotes and code blocks if [includeBlocks] is true.
+     */
+    data class ClearFormatting(val includeBlocks: Boolean): EditorInputAction
+
     /**
      * Un-does the previous action, restoring the previous editor state.
      */
@@ -117,6 +117,7 @@ internal class EditorViewModel(
 
                 is EditorInputAction.CodeBlock -> composer?.codeBlock()
                 is EditorInputAction.Quote -> composer?.quote()
+                is EditorInputAction.ClearFormatting -> composer?.clearFormatting(action.includeBlocks)
                 is EditorInputAction.Indent -> composer?.indent()
                 is EditorInputAction.Unindent -> composer?.unindent()
                 is EditorInputAction.InsertMentionAtSuggestion -> insertMentionAtSuggestion(action)
@@ -292,9 +293,12 @@ internal class EditorViewModel(
     }
 
     private fun replaceTextSuggestion(action: EditorInputAction.ReplaceTextSuggestion): ComposerUpdate? {
-        val suggestion = (curMenuAction as? MenuAction.Suggestion)
-            ?.suggestionPattern
-            ?: return null
+        val suggestion = when (val menuAction = curMenuAction) {
+            is MenuAction.Suggestion -> menuAction.suggestionPattern
+            // The chosen emoji replaces the `:shortcode`
+            is MenuAction.EmojiSuggestion -> menuAction.suggestionPattern
+            MenuAction.Keep, MenuAction.None -> return null
+        }
 
         return runCatching {
             composer?.replaceTextSuggestion(
@@ -316,11 +320,7 @@ internal class EditorViewModel(
         // behaviour
         this.crashOnComposerFailure = false
 
-        runCatching {
-            composer?.debugPanic()
-        }.onFailure {
-            onComposerFailure(it)
-        }
+        onComposerFailure(InternalException("This should only happen in tests."))
 
         this.crashOnComposerFailure = crashOnComposerFailure
     }
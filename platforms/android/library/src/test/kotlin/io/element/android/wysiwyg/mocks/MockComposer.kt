@@ -99,6 +99,11 @@ class MockComposer {
         update: ComposerUpdate = MockComposerUpdateFactory.create(),
     ) = every { instance.insertAtRoomMentionAtSuggestion(suggestion = any()) } returns update
 
+    fun givenReplaceTextSuggestionResult(
+        text: String,
+        update: ComposerUpdate = MockComposerUpdateFactory.create(),
+    ) = every { instance.replaceTextSuggestion(newText = text, suggestion = any(), appendSpace = true) } returns update
+
     fun givenReplaceAllHtmlResult(
         html: String,
         update: ComposerUpdate = MockComposerUpdateFactory.create(),
@@ -133,6 +138,10 @@ class MockComposer {
         update: ComposerUpdate = MockComposerUpdateFactory.create(),
     ) = every { instance.quote() } returns update
 
+    fun givenClearFormatting(
+        update: ComposerUpdate = MockComposerUpdateFactory.create(),
+    ) = every { instance.clearFormatting(any()) } returns update
+
     fun givenErrorInUpdateSelection(
         throwable: Throwable = IllegalStateException("Invalid selection range"),
     ) = every { instance.select(any(), any()) } throws throwable
@@ -19,6 +19,7 @@ import org.junit.Before
 import org.junit.Test
 import uniffi.wysiwyg_composer.ActionState
 import uniffi.wysiwyg_composer.ComposerAction
+import uniffi.wysiwyg_composer.EmojiCandidate
 import uniffi.wysiwyg_composer.MenuAction
 import uniffi.wysiwyg_composer.MenuState
 import uniffi.wysiwyg_composer.PatternKey
@@ -330,6 +331,27 @@ internal class EditorViewModelTest {
         assertThat(result, equalTo(replaceTextResult))
     }
 
+    @Test
+    fun `when process replace text suggestion action after an emoji suggestion, it returns a text update`() {
+        val suggestionPattern =
+            SuggestionPattern(PatternKey.Custom(":"), text = "tada", 0.toUInt(), 5.toUInt())
+        composer.givenReplaceTextResult(MockComposerUpdateFactory.create(
+            menuAction = MenuAction.EmojiSuggestion(
+                suggestionPattern,
+                listOf(EmojiCandidate(shortcode = "tada", emoji = "🎉")),
+            )
+        ))
+        viewModel.processInput(EditorInputAction.ReplaceText(":tada"))
+
+        composer.givenReplaceTextSuggestionResult("🎉", composerStateUpdate)
+        val result = viewModel.processInput(EditorInputAction.ReplaceTextSuggestion("🎉"))
+
+        verify {
+            composer.instance.replaceTextSuggestion(newText = "🎉", suggestion = suggestionPattern, appendSpace = true)
+        }
+        assertThat(result, equalTo(replaceTextResult))
+    }
+
     @Test
     fun `when process replace all html action, it returns a text update`() {
         composer.givenReplaceAllHtmlResult("new html", composerStateUpdate)
@@ -435,6 +457,19 @@ internal class EditorViewModelTest {
         assertThat(result, equalTo(replaceTextResult))
     }
 
+    @Test
+    fun `when process clear formatting, it returns a text update`() {
+        composer.givenClearFormatting(composerStateUpdate)
+
+        val result = viewModel.processInput(EditorInputAction.ClearFormatting(includeBlocks = true))
+
+        verify {
+            composer.instance.clearFormatting(true)
+            actionsStatesCallback(actionStates)
+        }
+        assertThat(result, equalTo(replaceTextResult))
+    }
+
     @Test
     fun `given formatted text, getContentAsMessageHtml function returns formatted HTML`() {
         composer.givenGetContentAsMessageHtml(htmlParagraphs)
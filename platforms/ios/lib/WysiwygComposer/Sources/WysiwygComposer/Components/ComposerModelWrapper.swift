@@ -85,11 +85,11 @@ final class ComposerModelWrapper: ComposerModelWrapperProtocol {
     }
     
     func getContentAsMarkdown() -> String {
-        model.getContentAsMarkdown()
+        (try? model.getContentAsMarkdown()) ?? model.getContentAsPlainText()
     }
 
     func getContentAsMessageMarkdown() -> String {
-        model.getContentAsMessageMarkdown()
+        (try? model.getContentAsMessageMarkdown()) ?? model.getContentAsPlainText()
     }
     
     func getContentAsPlainText() -> String {
@@ -59,6 +59,8 @@ public class WysiwygComposerViewModel: WysiwygComposerViewModelProtocol, Observa
     @Published public var actionStates: [ComposerAction: ActionState] = [:]
     /// Published value for current detected suggestion pattern.
     @Published public var suggestionPattern: SuggestionPattern?
+    /// Published value for the emoji matching the current `:shortcode` suggestion pattern, if any.
+    @Published public var emojiCandidates: [EmojiCandidate] = []
     /// Published value for the composer maximised state.
     @Published public var maximised = false {
         didSet {
@@ -296,6 +298,16 @@ public extension WysiwygComposerViewModel {
         let update = model.replaceTextSuggestion(newText: name, suggestion: suggestionPattern)
         applyUpdate(update)
     }
+
+    /// Replace the current `:shortcode` suggestion pattern with an emoji.
+    ///
+    /// - Parameters:
+    ///   - emoji: The emoji, usually one of the `emojiCandidates`.
+    func setEmoji(_ emoji: String) {
+        guard let suggestionPattern, !emojiCandidates.isEmpty else { return }
+        let update = model.replaceTextSuggestion(newText: emoji, suggestion: suggestionPattern)
+        applyUpdate(update)
+    }
 }
 
 // MARK: - WysiwygComposerViewModelProtocol
@@ -594,8 +606,13 @@ private extension WysiwygComposerViewModel {
             break
         case .none:
             suggestionPattern = nil
+            emojiCandidates = []
         case let .suggestion(suggestionPattern: pattern):
             suggestionPattern = pattern
+            emojiCandidates = []
+        case let .emojiSuggestion(suggestionPattern: pattern, candidates: candidates):
+            suggestionPattern = pattern
+            emojiCandidates = candidates
         }
     }
 
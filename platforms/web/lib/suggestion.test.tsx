@@ -20,6 +20,7 @@ import init, {
     SuggestionPattern,
 } from '../generated/wysiwyg';
 import { SUGGESTIONS } from './constants';
+import { EmojiCandidate } from './types';
 import {
     getSuggestionChar,
     getSuggestionType,
@@ -99,6 +100,21 @@ describe('mapSuggestion', () => {
             text: suggestion.text,
         });
     });
+
+    it('returns an emoji suggestion when passed emoji candidates', () => {
+        const suggestion = {
+            key: { key_type: 3, custom_key_value: ':' },
+            text: 'smile',
+        } as unknown as SuggestionPattern;
+        const emojiCandidates = [{ shortcode: 'smile', emoji: '😄' }];
+
+        const mappedSuggestion = mapSuggestion(suggestion, emojiCandidates);
+        expect(mappedSuggestion).toMatchObject({
+            type: 'emoji',
+            text: 'smile',
+            emojiCandidates,
+        });
+    });
 });
 
 describe('suggestionPattern', () => {
@@ -127,3 +143,32 @@ describe('suggestionPattern', () => {
         );
     });
 });
+
+describe('emojiSuggestion', () => {
+    it('is only returned once emoji suggestions are enabled', () => {
+        // Given
+        const model = new_composer_model();
+        expect(
+            model.replace_text(':smi').menu_action().emoji_suggestion(),
+        ).toBeUndefined();
+        model.clear();
+        model.set_emoji_suggestions_enabled(true);
+
+        // When
+        const emojiSuggestion = model
+            .replace_text(':smi')
+            .menu_action()
+            .emoji_suggestion();
+
+        // Then
+        if (!emojiSuggestion) {
+            fail('There should be an emoji suggestion!');
+        }
+        expect(emojiSuggestion.suggestion_pattern.text).toBe('smi');
+        expect(
+            emojiSuggestion.candidates.map(
+                (candidate: EmojiCandidate) => candidate.emoji,
+            ),
+        ).toContain('😄');
+    });
+});
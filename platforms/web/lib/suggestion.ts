@@ -16,7 +16,12 @@ limitations under the License.
 
 import { SuggestionPattern } from '../generated/wysiwyg';
 import { SUGGESTIONS } from './constants';
-import { MappedSuggestion, SuggestionChar, SuggestionType } from './types';
+import {
+    EmojiCandidate,
+    MappedSuggestion,
+    SuggestionChar,
+    SuggestionType,
+} from './types';
 
 export function getSuggestionChar(
     suggestion: SuggestionPattern,
@@ -42,8 +47,17 @@ export function getSuggestionType(
 
 export function mapSuggestion(
     suggestion: SuggestionPattern | null,
+    emojiCandidates: EmojiCandidate[] | null = null,
 ): MappedSuggestion | null {
     if (suggestion === null) return suggestion;
+    if (emojiCandidates !== null) {
+        return {
+            text: suggestion.text,
+            keyChar: getSuggestionChar(suggestion),
+            type: 'emoji',
+            emojiCandidates,
+        };
+    }
     return {
         text: suggestion.text,
         keyChar: getSuggestionChar(suggestion),
@@ -66,12 +66,24 @@ export type InputEventProcessor = (
 ) => WysiwygEvent | null;
 
 export type SuggestionChar = (typeof SUGGESTIONS)[number] | '';
-export type SuggestionType = 'mention' | 'command' | 'custom' | 'unknown';
+export type SuggestionType =
+    | 'mention'
+    | 'command'
+    | 'custom'
+    | 'emoji'
+    | 'unknown';
+
+export type EmojiCandidate = {
+    shortcode: string;
+    emoji: string;
+};
 
 export type MappedSuggestion = {
     keyChar: SuggestionChar;
     text: string;
     type: SuggestionType;
+    // Only set for emoji suggestions, pass the chosen emoji to `command`
+    emojiCandidates?: EmojiCandidate[];
 };
 export type TraceAction = (
     update: ComposerUpdate | null,
@@ -58,6 +58,7 @@ export function useComposerModel(
     editorRef: RefObject<HTMLElement | null>,
     initialContent?: string,
     customSuggestionPatterns?: Array<string>,
+    isEmojiShortcodeSuggestionEnabled = false,
 ): {
     composerModel: ComposerModel | null;
     onError: (initialContent?: string) => Promise<void>;
@@ -111,6 +112,12 @@ export function useComposerModel(
         }
     }, [composerModel, customSuggestionPatterns]);
 
+    useEffect(() => {
+        composerModel?.set_emoji_suggestions_enabled(
+            isEmojiShortcodeSuggestionEnabled,
+        );
+    }, [composerModel, isEmojiShortcodeSuggestionEnabled]);
+
     useEffect(() => {
         if (editorRef.current) {
             initModel(initialContent);
@@ -29,6 +29,7 @@ import {
 } from '../dom';
 import {
     BlockType,
+    EmojiCandidate,
     FormattingFunctions,
     InputEventProcessor,
     WysiwygInputEvent,
@@ -164,6 +165,17 @@ export function extractActionStates(
     return mapToAllActionStates(menuStateUpdate.action_states);
 }
 
+/**
+ * Copy the emoji candidates out of the wasm objects
+ * @param {EmojiCandidate[]} candidates
+ * @returns {EmojiCandidate[]}
+ */
+function extractEmojiCandidates(
+    candidates: EmojiCandidate[],
+): EmojiCandidate[] {
+    return candidates.map(({ shortcode, emoji }) => ({ shortcode, emoji }));
+}
+
 /**
  * Event listener for WysiwygInputEvent
  * @param {WysiwygInputEvent} e
@@ -221,20 +233,26 @@ export function handleInput(
         }
 
         const menuStateUpdate = update.menu_state().update();
-        const menuActionUpdate = update
-            .menu_action()
-            .suggestion()?.suggestion_pattern;
+        const menuAction = update.menu_action();
+        const emojiSuggestion = menuAction.emoji_suggestion();
+        const menuActionUpdate =
+            menuAction.suggestion()?.suggestion_pattern ||
+            emojiSuggestion?.suggestion_pattern;
 
         const actionStates = menuStateUpdate
             ? extractActionStates(menuStateUpdate)
             : null;
 
         const suggestion = menuActionUpdate || null;
+        const emojiCandidates = emojiSuggestion
+            ? extractEmojiCandidates(emojiSuggestion.candidates)
+            : null;
 
         const res = {
             content: repl?.replacement_html,
             actionStates,
             suggestion,
+            emojiCandidates,
         };
 
         return res;
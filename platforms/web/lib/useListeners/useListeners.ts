@@ -22,6 +22,7 @@ import { handleInput, handleKeyDown, handleSelectionChange } from './event';
 import {
     FormattingFunctions,
     AllActionStates,
+    EmojiCandidate,
     InputEventProcessor,
     WysiwygInputEvent,
 } from '../types';
@@ -33,6 +34,7 @@ type State = {
     content: string | null;
     actionStates: AllActionStates;
     suggestion: SuggestionPattern | null;
+    emojiCandidates: EmojiCandidate[] | null;
 };
 
 export function useListeners(
@@ -49,11 +51,13 @@ export function useListeners(
     content: string | null;
     actionStates: AllActionStates;
     suggestion: SuggestionPattern | null;
+    emojiCandidates: EmojiCandidate[] | null;
 } {
     const [state, setState] = useState<State>({
         content: null,
         actionStates: createDefaultActionStates(),
         suggestion: null,
+        emojiCandidates: null,
     });
 
     const plainTextContentRef = useRef<string>();
@@ -68,6 +72,7 @@ export function useListeners(
                     composerModel.action_states(),
                 ),
                 suggestion: null,
+                emojiCandidates: null,
             });
             plainTextContentRef.current =
                 composerModel.get_content_as_plain_text();
@@ -109,11 +114,13 @@ export function useListeners(
 
                         // state.suggestion: update even if null
                         const suggestion = res.suggestion;
+                        const emojiCandidates = res.emojiCandidates;
 
                         return {
                             content,
                             actionStates,
                             suggestion,
+                            emojiCandidates,
                         };
                     });
                     plainTextContentRef.current =
@@ -185,10 +192,11 @@ export function useListeners(
                 );
 
                 if (actionStates) {
-                    setState(({ content, suggestion }) => ({
+                    setState(({ content, suggestion, emojiCandidates }) => ({
                         content,
                         actionStates,
                         suggestion,
+                        emojiCandidates,
                     }));
                 }
                 plainTextContentRef.current =
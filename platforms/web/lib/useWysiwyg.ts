@@ -61,6 +61,7 @@ export type WysiwygProps = {
     inputEventProcessor?: InputEventProcessor;
     initialContent?: string;
     emojiSuggestions?: Map<string, string>;
+    isEmojiShortcodeSuggestionEnabled?: boolean;
 };
 
 export type UseWysiwyg = {
@@ -98,6 +99,7 @@ export function useWysiwyg(wysiwygProps?: WysiwygProps): UseWysiwyg {
         ref,
         wysiwygProps?.initialContent,
         emojiKeys,
+        wysiwygProps?.isEmojiShortcodeSuggestionEnabled,
     );
     const { testRef, utilities: testUtilities } = useTestCases(
         ref,
@@ -106,23 +108,28 @@ export function useWysiwyg(wysiwygProps?: WysiwygProps): UseWysiwyg {
 
     const formattingFunctions = useFormattingFunctions(ref, composerModel);
 
-    const { content, actionStates, areListenersReady, suggestion } =
-        useListeners(
-            ref,
-            modelRef,
-            composerModel,
-            testUtilities,
-            formattingFunctions,
-            onError,
-            wysiwygProps?.inputEventProcessor,
-            wysiwygProps?.emojiSuggestions,
-        );
+    const {
+        content,
+        actionStates,
+        areListenersReady,
+        suggestion,
+        emojiCandidates,
+    } = useListeners(
+        ref,
+        modelRef,
+        composerModel,
+        testUtilities,
+        formattingFunctions,
+        onError,
+        wysiwygProps?.inputEventProcessor,
+        wysiwygProps?.emojiSuggestions,
+    );
 
     useEditorFocus(ref, wysiwygProps?.isAutoFocusEnabled);
 
     const memoisedMappedSuggestion = useMemo(
-        () => mapSuggestion(suggestion),
-        [suggestion],
+        () => mapSuggestion(suggestion, emojiCandidates),
+        [suggestion, emojiCandidates],
     );
 
     return {